@@ -1,6 +1,6 @@
-use std::collections::BTreeSet;
+use std::{collections::BTreeSet, path::Path};
 
-use anyhow::{Result, anyhow};
+use anyhow::{Result, anyhow, bail};
 use network_shapley::types::{Demand, Devices, PrivateLinks, PublicLinks};
 use tracing::{info, warn};
 
@@ -41,16 +41,28 @@ impl PreparedData {
     /// * `fetcher` - `Fetcher` instance (construct via settings)
     /// * `epoch` - Optional epoch, uses current - 1 if None
     /// * `require_shapley` - Attach shapley_inputs output if set to true
+    /// * `demand_override` - Optional JSON file with demands to use instead of computing them
     ///
     /// # Returns
     /// Result<PreparedData>
-    pub async fn new(fetcher: &Fetcher, epoch: Option<u64>, require_shapley: bool) -> Result<Self> {
+    pub async fn new(
+        fetcher: &Fetcher,
+        epoch: Option<u64>,
+        require_shapley: bool,
+        demand_override: Option<&Path>,
+    ) -> Result<Self> {
+        // Reject epochs that have not completed yet, they would silently produce empty inputs
+        if let Some(requested_epoch) = epoch {
+            let current_epoch = fetcher.dz_rpc_client.get_epoch_info().await?.epoch;
+            ensure_epoch_complete(requested_epoch, current_epoch)?;
+        }
+
         // NOTE: Always fetch current epoch's serviceability data first
         // This ensures we have the correct exchange_pk -> device -> location mappings
         let (fetch_epoch, mut fetch_data) = fetcher.fetch(epoch).await?;
 
         // Create cache for previous epoch data
-        let mut previous_epoch_cache = PreviousEpochCache::new();
+        let mut previous_epoch_cache = PreviousEpochCache::from_settings(&fetcher.settings);
         if fetcher
             .settings
             .telemetry_defaults
@@ -108,7 +120,8 @@ impl PreparedData {
         let (devices, device_ids) = build_and_log_devices(&fetcher.settings, &fetch_data)?;
 
         // Build private links
-        let private_links = build_and_log_private_links(&fetch_data, &device_ids);
+        let private_links =
+            build_and_log_private_links(&fetcher.settings, &fetch_data, &device_ids);
 
         // Build public links
         let public_links = build_and_log_public_links(
@@ -119,7 +132,8 @@ impl PreparedData {
         )?;
 
         // Build demands and city stats
-        let (demands, city_stats) = build_and_log_demands(fetcher, &fetch_data).await?;
+        let (demands, city_stats) =
+            build_and_log_demands(fetcher, &fetch_data, demand_override).await?;
 
         // Calculate city weights once for consistency
         let city_weights = calculate_city_weights(&city_stats);
@@ -181,6 +195,7 @@ impl PreparedData {
     /// * `snapshot` - Complete snapshot containing all epoch data
     /// * `settings` - Settings for processing configuration
     /// * `require_shapley` - Whether to build shapley inputs
+    /// * `demand_override` - Optional JSON file with demands to use instead of the leader schedule
     ///
     /// # Returns
     /// Result<PreparedData>
@@ -188,6 +203,7 @@ impl PreparedData {
         snapshot: &CompleteSnapshot,
         settings: &Settings,
         require_shapley: bool,
+        demand_override: Option<&Path>,
     ) -> Result<Self> {
         let fetch_epoch = snapshot.dz_epoch;
         let fetch_data = &snapshot.fetch_data;
@@ -214,7 +230,7 @@ impl PreparedData {
         let previous_epoch_cache = PreviousEpochCache::new();
 
         // Build private links
-        let private_links = build_and_log_private_links(fetch_data, &device_ids);
+        let private_links = build_and_log_private_links(settings, fetch_data, &device_ids);
 
         // Build public links
         let public_links = build_and_log_public_links(
@@ -224,18 +240,22 @@ impl PreparedData {
             &previous_epoch_cache,
         )?;
 
-        // Build demands using snapshot's leader schedule
-        let leader_schedule = snapshot
-            .leader_schedule
-            .as_ref()
-            .ok_or_else(|| anyhow!("Snapshot missing leader schedule for epoch {}", fetch_epoch))?;
+        // Build demands from the override file, or from the snapshot's leader schedule
+        let demand_output = match demand_override {
+            Some(path) => demand::load_from_file(path, settings, fetch_data)?,
+            None => {
+                let leader_schedule = snapshot.leader_schedule.as_ref().ok_or_else(|| {
+                    anyhow!("Snapshot missing leader schedule for epoch {}", fetch_epoch)
+                })?;
 
-        info!(
-            "Using leader schedule from snapshot (Solana epoch: {})",
-            leader_schedule.solana_epoch
-        );
+                info!(
+                    "Using leader schedule from snapshot (Solana epoch: {})",
+                    leader_schedule.solana_epoch
+                );
 
-        let demand_output = demand::build_with_schedule(settings, fetch_data, leader_schedule)?;
+                demand::build_with_schedule(settings, fetch_data, leader_schedule)?
+            }
+        };
         let demands = demand_output.demands;
         let city_stats = demand_output.city_stats;
 
@@ -291,6 +311,25 @@ impl PreparedData {
     }
 }
 
+/// Ensure the requested epoch has fully elapsed on the DZ ledger
+fn ensure_epoch_complete(requested_epoch: u64, current_epoch: u64) -> Result<()> {
+    if requested_epoch > current_epoch {
+        bail!(
+            "Requested epoch {} is in the future (current DZ epoch is {})",
+            requested_epoch,
+            current_epoch
+        );
+    }
+    if requested_epoch == current_epoch {
+        bail!(
+            "Requested epoch {} is still in progress, the latest complete DZ epoch is {}",
+            requested_epoch,
+            current_epoch.saturating_sub(1)
+        );
+    }
+    Ok(())
+}
+
 /// Process and aggregate device telemetry
 fn process_device_telemetry(fetch_data: &FetchData) -> Result<DZDTelemetryStatMap> {
     let stat_map = DZDTelemetryProcessor::process(fetch_data)?;
@@ -316,14 +355,18 @@ fn build_and_log_devices(
     settings: &Settings,
     fetch_data: &FetchData,
 ) -> Result<(Devices, DeviceIdMap)> {
-    let (devices, device_ids) = build_devices(fetch_data, &settings.network)?;
+    let (devices, device_ids) = build_devices(settings, fetch_data)?;
     info!("Devices:\n{}", print_devices(&devices));
     Ok((devices, device_ids))
 }
 
 /// Build private links and log output
-fn build_and_log_private_links(fetch_data: &FetchData, device_ids: &DeviceIdMap) -> PrivateLinks {
-    let private_links = build_private_links(fetch_data, device_ids);
+fn build_and_log_private_links(
+    settings: &Settings,
+    fetch_data: &FetchData,
+    device_ids: &DeviceIdMap,
+) -> PrivateLinks {
+    let private_links = build_private_links(settings, fetch_data, device_ids);
     info!("Private Links:\n{}", print_private_links(&private_links));
     private_links
 }
@@ -349,8 +392,9 @@ fn build_and_log_public_links(
 async fn build_and_log_demands(
     fetcher: &Fetcher,
     fetch_data: &FetchData,
+    demand_override: Option<&Path>,
 ) -> Result<(Vec<Demand>, CityStats)> {
-    build_demands(fetcher, fetch_data).await
+    build_demands(fetcher, fetch_data, demand_override).await
 }
 
 /// Calculate expected number of internet telemetry links
@@ -372,3 +416,26 @@ fn expected_inet_links(fetch_data: &FetchData) -> usize {
 
     unique_routes.len()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_future_epoch_is_rejected() {
+        let err = ensure_epoch_complete(101, 100).unwrap_err();
+        assert!(err.to_string().contains("in the future"));
+    }
+
+    #[test]
+    fn test_in_progress_epoch_is_rejected() {
+        let err = ensure_epoch_complete(100, 100).unwrap_err();
+        assert!(err.to_string().contains("still in progress"));
+    }
+
+    #[test]
+    fn test_completed_epoch_is_accepted() {
+        assert!(ensure_epoch_complete(99, 100).is_ok());
+        assert!(ensure_epoch_complete(0, 100).is_ok());
+    }
+}
@@ -183,6 +183,8 @@ mod tests {
             operator_uptime: 0.98,
             contiguity_bonus: 5.0,
             demand_multiplier: 1.2,
+            parallelism: 0,
+            device_edge_bandwidth_gbps: Default::default(),
         };
 
         let devices = vec![];
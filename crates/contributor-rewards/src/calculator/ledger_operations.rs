@@ -1,4 +1,4 @@
-use std::{fmt, fs, mem::size_of, path::PathBuf, time::Duration};
+use std::{fmt, fs, future::Future, mem::size_of, path::PathBuf, str::FromStr, time::Duration};
 
 use anyhow::{Result, anyhow, bail};
 use backon::{ExponentialBuilder, Retryable};
@@ -15,6 +15,7 @@ use solana_sdk::{
     signer::Signer, transaction::Transaction,
 };
 use tabled::{Table, Tabled, settings::Style};
+use thiserror::Error;
 use tracing::{debug, info, warn};
 
 use crate::{
@@ -131,6 +132,42 @@ impl WriteSummary {
     pub fn all_successful(&self) -> bool {
         self.failed_count() == 0
     }
+
+    /// Descriptions of the writes that failed, in the order they were attempted
+    pub fn failed_descriptions(&self) -> Vec<&str> {
+        self.results
+            .iter()
+            .filter_map(|r| match r {
+                WriteResult::Failed(description, _) => Some(description.as_str()),
+                WriteResult::Success(_, _) => None,
+            })
+            .collect()
+    }
+
+    /// Converts the summary into an error carrying it when any write failed
+    pub fn into_result(self) -> Result<Self, PartialWriteError> {
+        if self.all_successful() {
+            Ok(self)
+        } else {
+            Err(PartialWriteError { summary: self })
+        }
+    }
+}
+
+/// Returned when only some ledger writes succeeded
+///
+/// Carries the full `WriteSummary` so callers can see which writes landed and
+/// retry only the failures. Recover it from an `anyhow::Error` with
+/// `downcast_ref::<PartialWriteError>()`.
+#[derive(Debug, Error)]
+#[error(
+    "Some writes failed: {}/{} successful (failed: {})",
+    .summary.successful_count(),
+    .summary.total_count(),
+    .summary.failed_descriptions().join(", ")
+)]
+pub struct PartialWriteError {
+    pub summary: WriteSummary,
 }
 
 impl fmt::Display for WriteSummary {
@@ -380,11 +417,44 @@ pub async fn read_telemetry_aggregates(
     Ok(())
 }
 
+/// JSON output struct for read_reward_input
+#[derive(serde::Serialize)]
+pub struct RewardInputOutput {
+    pub epoch: u64,
+    pub timestamp: i64,
+    pub devices: usize,
+    pub private_links: usize,
+    pub public_links: usize,
+    pub demands: usize,
+    pub cities: usize,
+    pub operator_uptime: f64,
+    pub contiguity_bonus: f64,
+    pub demand_multiplier: f64,
+}
+
+impl From<&RewardInput> for RewardInputOutput {
+    fn from(input: &RewardInput) -> Self {
+        Self {
+            epoch: input.epoch,
+            timestamp: input.timestamp,
+            devices: input.devices.len(),
+            private_links: input.private_links.len(),
+            public_links: input.public_links.len(),
+            demands: input.demands.len(),
+            cities: input.city_summaries.len(),
+            operator_uptime: input.shapley_settings.operator_uptime,
+            contiguity_bonus: input.shapley_settings.contiguity_bonus,
+            demand_multiplier: input.shapley_settings.demand_multiplier,
+        }
+    }
+}
+
 /// Read reward input from the ledger
 pub async fn read_reward_input(
     settings: &Settings,
     epoch: u64,
     rewards_accountant: Option<Pubkey>,
+    json_output: bool,
 ) -> Result<()> {
     // Create fetcher
     let fetcher = Fetcher::from_settings(settings)?;
@@ -420,6 +490,12 @@ pub async fn read_reward_input(
         }
     };
 
+    if json_output {
+        let output = RewardInputOutput::from(&input_config);
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
     // Display the configuration using tabled
 
     #[derive(Tabled)]
@@ -759,6 +835,89 @@ pub async fn try_fetch_shapley_output(
     Ok(shapley_record.data)
 }
 
+/// Record accounts that can be targeted by `realloc_record` and `close_record`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordType {
+    DeviceTelemetry,
+    InternetTelemetry,
+    RewardInput,
+    /// The shapley output record, stored under the contributor rewards prefix
+    ContributorRewards,
+}
+
+impl RecordType {
+    /// Accepted type strings. `shapley-output` is an alias for `contributor-rewards`.
+    pub const VALID_NAMES: &[&str] = &[
+        "device-telemetry",
+        "internet-telemetry",
+        "reward-input",
+        "shapley-output",
+        "contributor-rewards",
+    ];
+
+    /// Every record written for an epoch, in the order they are written
+    pub const ALL: [Self; 4] = [
+        Self::DeviceTelemetry,
+        Self::InternetTelemetry,
+        Self::RewardInput,
+        Self::ContributorRewards,
+    ];
+
+    /// Canonical type string
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::DeviceTelemetry => "device-telemetry",
+            Self::InternetTelemetry => "internet-telemetry",
+            Self::RewardInput => "reward-input",
+            Self::ContributorRewards => "contributor-rewards",
+        }
+    }
+
+    /// Derive the record address for this type at the given epoch
+    pub fn record_key(&self, settings: &Settings, owner: &Pubkey, epoch: u64) -> Pubkey {
+        let epoch_bytes = epoch.to_le_bytes();
+        match self {
+            Self::DeviceTelemetry => {
+                let prefix = settings.get_device_telemetry_prefix();
+                let seeds: &[&[u8]] = &[&prefix, &epoch_bytes];
+                create_record_key(owner, seeds)
+            }
+            Self::InternetTelemetry => {
+                let prefix = settings.get_internet_telemetry_prefix();
+                let seeds: &[&[u8]] = &[&prefix, &epoch_bytes];
+                create_record_key(owner, seeds)
+            }
+            Self::RewardInput => {
+                let prefix = settings.get_reward_input_prefix();
+                let seeds: &[&[u8]] = &[&prefix, &epoch_bytes];
+                create_record_key(owner, seeds)
+            }
+            Self::ContributorRewards => {
+                let prefix = settings.get_contributor_rewards_prefix();
+                let seeds: &[&[u8]] = &[&prefix, &epoch_bytes, b"shapley_output"];
+                create_record_key(owner, seeds)
+            }
+        }
+    }
+}
+
+impl FromStr for RecordType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "device-telemetry" => Ok(Self::DeviceTelemetry),
+            "internet-telemetry" => Ok(Self::InternetTelemetry),
+            "reward-input" => Ok(Self::RewardInput),
+            "shapley-output" | "contributor-rewards" => Ok(Self::ContributorRewards),
+            _ => bail!(
+                "Invalid record type '{s}'. Must be one of: {}",
+                Self::VALID_NAMES.join(", ")
+            ),
+        }
+    }
+}
+
 /// NOTE: This is mostly just for debugging
 /// Realloc a record account
 pub async fn realloc_record(
@@ -769,6 +928,9 @@ pub async fn realloc_record(
     keypair_path: Option<PathBuf>,
     dry_run: bool,
 ) -> Result<()> {
+    // Reject unknown record types before touching the keypair or RPC
+    let record_type: RecordType = r#type.parse()?;
+
     // Load keypair
     let payer_signer = load_keypair(&keypair_path)?;
 
@@ -778,36 +940,11 @@ pub async fn realloc_record(
     // Validate keypair matches ProgramConfig
     validate_rewards_accountant_keypair(&fetcher.solana_write_client, &payer_signer).await?;
 
-    // Determine the prefix and compute the record address based on record type
-    let epoch_bytes = epoch.to_le_bytes();
-    let record_key = match r#type {
-        "device-telemetry" => {
-            let prefix = settings.get_device_telemetry_prefix();
-            let seeds: &[&[u8]] = &[&prefix, &epoch_bytes];
-            create_record_key(&payer_signer.pubkey(), seeds)
-        }
-        "internet-telemetry" => {
-            let prefix = settings.get_internet_telemetry_prefix();
-            let seeds: &[&[u8]] = &[&prefix, &epoch_bytes];
-            create_record_key(&payer_signer.pubkey(), seeds)
-        }
-        "reward-input" => {
-            let prefix = settings.get_reward_input_prefix();
-            let seeds: &[&[u8]] = &[&prefix, &epoch_bytes];
-            create_record_key(&payer_signer.pubkey(), seeds)
-        }
-        "contributor-rewards" => {
-            let prefix = settings.get_contributor_rewards_prefix();
-            let seeds: &[&[u8]] = &[&prefix, &epoch_bytes, b"shapley_output"];
-            create_record_key(&payer_signer.pubkey(), seeds)
-        }
-        _ => bail!(
-            "Invalid record type. Must be one of: device-telemetry, internet-telemetry, reward-input, contributor-rewards"
-        ),
-    };
+    // Compute the record address based on record type
+    let record_key = record_type.record_key(settings, &payer_signer.pubkey(), epoch);
 
     info!("Reallocating record account: {}", record_key);
-    info!("Record type: {}, Epoch: {}", r#type, epoch);
+    info!("Record type: {:?}, Epoch: {}", record_type, epoch);
 
     // Check if the account exists
     let maybe_account = (|| async {
@@ -873,6 +1010,9 @@ pub async fn close_record(
     keypair_path: Option<PathBuf>,
     dry_run: bool,
 ) -> Result<()> {
+    // Reject unknown record types before touching the keypair or RPC
+    let record_type: RecordType = r#type.parse()?;
+
     // Load keypair
     let payer_signer = load_keypair(&keypair_path)?;
 
@@ -882,42 +1022,26 @@ pub async fn close_record(
     // Validate keypair matches ProgramConfig
     validate_rewards_accountant_keypair(&fetcher.solana_write_client, &payer_signer).await?;
 
-    // Determine the prefix and compute the record address based on record type
-    let epoch_bytes = epoch.to_le_bytes();
-    let record_key = match r#type {
-        "device-telemetry" => {
-            let prefix = settings.get_device_telemetry_prefix();
-            let seeds: &[&[u8]] = &[&prefix, &epoch_bytes];
-            create_record_key(&payer_signer.pubkey(), seeds)
-        }
-        "internet-telemetry" => {
-            let prefix = settings.get_internet_telemetry_prefix();
-            let seeds: &[&[u8]] = &[&prefix, &epoch_bytes];
-            create_record_key(&payer_signer.pubkey(), seeds)
-        }
-        "reward-input" => {
-            let prefix = settings.get_reward_input_prefix();
-            let seeds: &[&[u8]] = &[&prefix, &epoch_bytes];
-            create_record_key(&payer_signer.pubkey(), seeds)
-        }
-        "contributor-rewards" => {
-            let prefix = settings.get_contributor_rewards_prefix();
-            let seeds: &[&[u8]] = &[&prefix, &epoch_bytes, b"shapley_output"];
-            create_record_key(&payer_signer.pubkey(), seeds)
-        }
-        _ => bail!(
-            "Invalid record type. Must be one of: device-telemetry, internet-telemetry, reward-input, contributor-rewards"
-        ),
-    };
+    // Compute the record address based on record type
+    let record_key = record_type.record_key(settings, &payer_signer.pubkey(), epoch);
 
     info!("Closing record account: {}", record_key);
-    info!("Record type: {}, Epoch: {}", r#type, epoch);
+    info!("Record type: {:?}, Epoch: {}", record_type, epoch);
 
     // Check if the account exists
+    if !record_exists(&fetcher, &record_key).await? {
+        bail!("Record account {record_key} does not exist");
+    }
+
+    close_record_account(&fetcher, &payer_signer, &record_key, dry_run).await
+}
+
+/// Whether a record account exists on the DZ ledger
+async fn record_exists(fetcher: &Fetcher, record_key: &Pubkey) -> Result<bool> {
     let maybe_account = (|| async {
         fetcher
             .dz_rpc_client
-            .get_account_with_commitment(&record_key, CommitmentConfig::confirmed())
+            .get_account_with_commitment(record_key, CommitmentConfig::confirmed())
             .await
     })
     .retry(&ExponentialBuilder::default().with_jitter())
@@ -926,13 +1050,19 @@ pub async fn close_record(
     })
     .await?;
 
-    if maybe_account.value.is_none() {
-        bail!("Record account {record_key} does not exist");
-    }
+    Ok(maybe_account.value.is_some())
+}
 
+/// Close a record account, returning its lamports to the payer
+async fn close_record_account(
+    fetcher: &Fetcher,
+    payer_signer: &Keypair,
+    record_key: &Pubkey,
+    dry_run: bool,
+) -> Result<()> {
     // Create close instruction
     let close_ix = record_ix::close_account(
-        &record_key,
+        record_key,
         &payer_signer.pubkey(),
         &payer_signer.pubkey(), // Return lamports to payer
     );
@@ -946,7 +1076,7 @@ pub async fn close_record(
         .await?;
 
     let message = Message::new(&[close_ix], Some(&payer_signer.pubkey()));
-    let transaction = Transaction::new(&[&payer_signer], message, recent_blockhash);
+    let transaction = Transaction::new(&[payer_signer], message, recent_blockhash);
 
     if !dry_run {
         let signature = (|| async {
@@ -972,12 +1102,157 @@ pub async fn close_record(
     Ok(())
 }
 
+/// What `close_all_records` did with a record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseOutcome {
+    Closed,
+    /// The record exists but was left open because of --dry-run
+    WouldClose,
+    AlreadyAbsent,
+}
+
+impl fmt::Display for CloseOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Closed => write!(f, "Closed"),
+            Self::WouldClose => write!(f, "Would close"),
+            Self::AlreadyAbsent => write!(f, "Already absent"),
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct CloseRecordInfo {
+    #[tabled(rename = "Type")]
+    record_type: &'static str,
+    #[tabled(rename = "Address")]
+    address: String,
+    #[tabled(rename = "Status")]
+    status: CloseOutcome,
+}
+
+/// Close every record of an epoch, skipping records that don't exist
+///
+/// Safe to re-run: absent records are reported rather than treated as errors.
+pub async fn close_all_records(
+    settings: &Settings,
+    epoch: u64,
+    keypair_path: Option<PathBuf>,
+    dry_run: bool,
+) -> Result<Vec<(RecordType, CloseOutcome)>> {
+    let payer_signer = load_keypair(&keypair_path)?;
+    let fetcher = Fetcher::from_settings(settings)?;
+    validate_rewards_accountant_keypair(&fetcher.solana_write_client, &payer_signer).await?;
+
+    let record_keys = RecordType::ALL.map(|record_type| {
+        (
+            record_type,
+            record_type.record_key(settings, &payer_signer.pubkey(), epoch),
+        )
+    });
+
+    info!("Closing all records for epoch {epoch}");
+
+    let outcomes = close_records_with(
+        &record_keys,
+        dry_run,
+        |record_key| {
+            let fetcher = &fetcher;
+            async move { record_exists(fetcher, &record_key).await }
+        },
+        |record_key| {
+            let fetcher = &fetcher;
+            let payer_signer = &payer_signer;
+            async move { close_record_account(fetcher, payer_signer, &record_key, false).await }
+        },
+    )
+    .await?;
+
+    let rows = record_keys
+        .iter()
+        .zip(&outcomes)
+        .map(
+            |((record_type, record_key), (_, outcome))| CloseRecordInfo {
+                record_type: record_type.name(),
+                address: record_key.to_string(),
+                status: *outcome,
+            },
+        )
+        .collect::<Vec<_>>();
+    println!(
+        "{}",
+        Table::new(rows).with(Style::psql().remove_horizontals())
+    );
+
+    Ok(outcomes)
+}
+
+/// Close each existing record in order, leaving absent records untouched
+async fn close_records_with<E, EFut, C, CFut>(
+    record_keys: &[(RecordType, Pubkey)],
+    dry_run: bool,
+    mut exists: E,
+    mut close: C,
+) -> Result<Vec<(RecordType, CloseOutcome)>>
+where
+    E: FnMut(Pubkey) -> EFut,
+    EFut: Future<Output = Result<bool>>,
+    C: FnMut(Pubkey) -> CFut,
+    CFut: Future<Output = Result<()>>,
+{
+    let mut outcomes = Vec::with_capacity(record_keys.len());
+
+    for (record_type, record_key) in record_keys {
+        let outcome = if !exists(*record_key).await? {
+            CloseOutcome::AlreadyAbsent
+        } else if dry_run {
+            CloseOutcome::WouldClose
+        } else {
+            close(*record_key).await.map_err(|e| {
+                anyhow!(
+                    "Failed to close {} record {record_key}: {e}",
+                    record_type.name()
+                )
+            })?;
+            CloseOutcome::Closed
+        };
+
+        info!("{} record {}: {}", record_type.name(), record_key, outcome);
+        outcomes.push((*record_type, outcome));
+    }
+
+    Ok(outcomes)
+}
+
+/// Status of a single record account, shared by table and JSON output
+#[derive(Tabled, serde::Serialize)]
+pub struct RecordInfo {
+    #[tabled(rename = "Type")]
+    pub record_type: String,
+    #[tabled(rename = "Address")]
+    pub address: String,
+    #[tabled(rename = "Data Size (bytes)")]
+    pub data_size: usize,
+    #[tabled(rename = "Header Size (bytes)")]
+    pub header_size: usize,
+    #[tabled(rename = "Status")]
+    pub status: String,
+}
+
+/// JSON output struct for inspect_records
+#[derive(serde::Serialize)]
+pub struct InspectRecordsOutput {
+    pub epoch: u64,
+    pub records: Vec<RecordInfo>,
+}
+
 /// Inspect record accounts for a given epoch
 pub async fn inspect_records(
     settings: &Settings,
     epoch: u64,
     rewards_accountant: Option<Pubkey>,
     record_type: Option<String>,
+    json_output: bool,
 ) -> Result<()> {
     let fetcher = Fetcher::from_settings(settings)?;
 
@@ -998,20 +1273,6 @@ pub async fn inspect_records(
         ]
     };
 
-    #[derive(Tabled)]
-    struct RecordInfo {
-        #[tabled(rename = "Type")]
-        record_type: String,
-        #[tabled(rename = "Address")]
-        address: String,
-        #[tabled(rename = "Data Size (bytes)")]
-        data_size: String,
-        #[tabled(rename = "Header Size (bytes)")]
-        header_size: String,
-        #[tabled(rename = "Status")]
-        status: String,
-    }
-
     let header_size = size_of::<RecordData>();
     let mut records = Vec::new();
 
@@ -1069,16 +1330,232 @@ pub async fn inspect_records(
         records.push(RecordInfo {
             record_type: r_type,
             address: record_key.to_string(),
-            data_size: data_size.to_string(),
-            header_size: header_size.to_string(),
+            data_size,
+            header_size,
             status,
         });
     }
 
-    println!(
-        "{}",
-        Table::new(records).with(Style::psql().remove_horizontals())
-    );
+    if json_output {
+        let output = InspectRecordsOutput { epoch, records };
+        println!("{}", serde_json::to_string(&output)?);
+    } else {
+        println!(
+            "{}",
+            Table::new(records).with(Style::psql().remove_horizontals())
+        );
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::{calculator::input::ShapleyInputs, settings::ShapleySettings};
+
+    #[test]
+    fn test_reward_input_json_output() {
+        let shapley_settings = ShapleySettings {
+            operator_uptime: 0.98,
+            contiguity_bonus: 5.0,
+            demand_multiplier: 1.2,
+            parallelism: 0,
+            device_edge_bandwidth_gbps: Default::default(),
+        };
+        let shapley_inputs = ShapleyInputs {
+            devices: vec![],
+            private_links: vec![],
+            public_links: vec![],
+            demands: vec![],
+            city_stats: BTreeMap::new(),
+            city_weights: BTreeMap::new(),
+        };
+        let input = RewardInput::new(42, shapley_settings, &shapley_inputs, b"device", b"inet");
+
+        let json = serde_json::to_value(RewardInputOutput::from(&input)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "epoch": 42,
+                "timestamp": input.timestamp,
+                "devices": 0,
+                "private_links": 0,
+                "public_links": 0,
+                "demands": 0,
+                "cities": 0,
+                "operator_uptime": 0.98,
+                "contiguity_bonus": 5.0,
+                "demand_multiplier": 1.2,
+            })
+        );
+    }
+
+    #[test]
+    fn test_inspect_records_json_output() {
+        let address = Pubkey::new_unique();
+        let output = InspectRecordsOutput {
+            epoch: 7,
+            records: vec![RecordInfo {
+                record_type: "reward-input".to_string(),
+                address: address.to_string(),
+                data_size: 1024,
+                header_size: size_of::<RecordData>(),
+                status: "Non Empty".to_string(),
+            }],
+        };
+
+        let json = serde_json::to_value(&output).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "epoch": 7,
+                "records": [{
+                    "record_type": "reward-input",
+                    "address": address.to_string(),
+                    "data_size": 1024,
+                    "header_size": size_of::<RecordData>(),
+                    "status": "Non Empty",
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn test_record_type_rejects_unknown_type() {
+        let err = "device-telemetery".parse::<RecordType>().unwrap_err();
+        let message = err.to_string();
+
+        assert!(message.contains("device-telemetery"));
+        for name in RecordType::VALID_NAMES {
+            assert!(message.contains(name), "missing {name} in: {message}");
+        }
+    }
+
+    #[test]
+    fn test_record_type_parses_known_types() {
+        assert_eq!(
+            "device-telemetry".parse::<RecordType>().unwrap(),
+            RecordType::DeviceTelemetry
+        );
+        assert_eq!(
+            "internet-telemetry".parse::<RecordType>().unwrap(),
+            RecordType::InternetTelemetry
+        );
+        assert_eq!(
+            "reward-input".parse::<RecordType>().unwrap(),
+            RecordType::RewardInput
+        );
+        assert_eq!(
+            "shapley-output".parse::<RecordType>().unwrap(),
+            RecordType::ContributorRewards
+        );
+        assert_eq!(
+            "contributor-rewards".parse::<RecordType>().unwrap(),
+            RecordType::ContributorRewards
+        );
+    }
+
+    #[tokio::test]
+    async fn test_close_all_records_is_idempotent() {
+        let record_keys = RecordType::ALL.map(|record_type| (record_type, Pubkey::new_unique()));
+
+        // Every record except the reward input exists
+        let existing = std::sync::Mutex::new(
+            record_keys
+                .iter()
+                .filter(|(record_type, _)| *record_type != RecordType::RewardInput)
+                .map(|(_, record_key)| *record_key)
+                .collect::<std::collections::HashSet<_>>(),
+        );
+
+        let run = |dry_run| {
+            close_records_with(
+                &record_keys,
+                dry_run,
+                |record_key| {
+                    let exists = existing.lock().unwrap().contains(&record_key);
+                    async move { Ok(exists) }
+                },
+                |record_key| {
+                    existing.lock().unwrap().remove(&record_key);
+                    async { Ok(()) }
+                },
+            )
+        };
+
+        let outcomes = run(true).await.unwrap();
+        assert_eq!(
+            outcomes,
+            vec![
+                (RecordType::DeviceTelemetry, CloseOutcome::WouldClose),
+                (RecordType::InternetTelemetry, CloseOutcome::WouldClose),
+                (RecordType::RewardInput, CloseOutcome::AlreadyAbsent),
+                (RecordType::ContributorRewards, CloseOutcome::WouldClose),
+            ]
+        );
+        assert_eq!(existing.lock().unwrap().len(), 3);
+
+        let outcomes = run(false).await.unwrap();
+        assert_eq!(
+            outcomes,
+            vec![
+                (RecordType::DeviceTelemetry, CloseOutcome::Closed),
+                (RecordType::InternetTelemetry, CloseOutcome::Closed),
+                (RecordType::RewardInput, CloseOutcome::AlreadyAbsent),
+                (RecordType::ContributorRewards, CloseOutcome::Closed),
+            ]
+        );
+        assert!(existing.lock().unwrap().is_empty());
+
+        // A second run finds everything already closed
+        let outcomes = run(false).await.unwrap();
+        assert!(
+            outcomes
+                .iter()
+                .all(|(_, outcome)| *outcome == CloseOutcome::AlreadyAbsent)
+        );
+    }
+
+    #[test]
+    fn test_partial_failure_carries_summary() {
+        let mut summary = WriteSummary::default();
+        summary.add_success_with_id(
+            "device telemetry aggregates".to_string(),
+            "device-record".to_string(),
+        );
+        summary.add_failure(
+            "internet telemetry aggregates".to_string(),
+            "blockhash not found".to_string(),
+        );
+        summary.add_success_with_id(
+            "reward calculation input".to_string(),
+            "reward-record".to_string(),
+        );
+        summary.add_success_with_id("merkle root posting".to_string(), "signature".to_string());
+
+        let err: anyhow::Error = summary.into_result().unwrap_err().into();
+        assert_eq!(
+            err.to_string(),
+            "Some writes failed: 3/4 successful (failed: internet telemetry aggregates)"
+        );
+
+        let partial = err.downcast_ref::<PartialWriteError>().unwrap();
+        assert_eq!(partial.summary.successful_count(), 3);
+        assert_eq!(
+            partial.summary.failed_descriptions(),
+            vec!["internet telemetry aggregates"]
+        );
+    }
+
+    #[test]
+    fn test_all_successful_returns_summary() {
+        let mut summary = WriteSummary::default();
+        summary.add_success("device telemetry aggregates".to_string());
+
+        let summary = summary.into_result().unwrap();
+        assert_eq!(summary.total_count(), 1);
+    }
+}
@@ -6,9 +6,17 @@ use tracing::{info, warn};
 
 use crate::{
     calculator::{
-        WriteConfig, data_prep::PreparedData, input::RewardInput, keypair_loader::load_keypair,
-        ledger_operations, proof::ShapleyOutputStorage,
-        revenue_distribution::post_rewards_merkle_root, shapley::evaluator::compute_shapley_values,
+        WriteConfig,
+        data_prep::PreparedData,
+        input::RewardInput,
+        keypair_loader::load_keypair,
+        ledger_operations,
+        proof::ShapleyOutputStorage,
+        revenue_distribution::{
+            check_staged_merkle_root, post_rewards_merkle_root,
+            try_fetch_staged_rewards_merkle_root, try_find_recipient_share_issues,
+        },
+        shapley::evaluator::compute_shapley_values,
     },
     cli::snapshot::CompleteSnapshot,
     ingestor::fetcher::Fetcher,
@@ -31,13 +39,24 @@ impl Orchestrator {
         &self.settings
     }
 
+    /// Calculates rewards for an epoch and writes the results to the ledger
+    ///
+    /// When only some writes succeed, the returned error is a
+    /// [`ledger_operations::PartialWriteError`] carrying the `WriteSummary`.
+    ///
+    /// Contributors receiving a reward without a valid recipient split are
+    /// warned about, or fail the calculation when `require_recipient_shares`
+    /// is set.
+    #[allow(clippy::too_many_arguments)]
     pub async fn calculate_rewards(
         &self,
         epoch: Option<u64>,
         keypair_path: Option<PathBuf>,
         snapshot_path: Option<PathBuf>,
+        demand_override: Option<PathBuf>,
         dry_run: bool,
         write_config: WriteConfig,
+        require_recipient_shares: bool,
     ) -> Result<ledger_operations::WriteSummary> {
         let epoch_start = Instant::now();
 
@@ -52,10 +71,15 @@ impl Orchestrator {
                 "Snapshot loaded: epoch {}, created at {}",
                 snapshot.dz_epoch, snapshot.metadata.created_at
             );
-            PreparedData::from_snapshot(&snapshot, &self.settings, true)?
+            PreparedData::from_snapshot(
+                &snapshot,
+                &self.settings,
+                true,
+                demand_override.as_deref(),
+            )?
         } else {
             let fetcher = Fetcher::from_settings(&self.settings)?;
-            PreparedData::new(&fetcher, epoch, true).await?
+            PreparedData::new(&fetcher, epoch, true, demand_override.as_deref()).await?
         };
 
         // Create fetcher for ledger writes (needed even in snapshot mode for non-dry-run)
@@ -106,6 +130,9 @@ impl Orchestrator {
             let merkle_root = shapley_storage.compute_merkle_root()?;
             info!("merkle_root: {:#?}", merkle_root);
 
+            self.check_recipient_shares(&fetcher, &shapley_storage, require_recipient_shares)
+                .await?;
+
             // Record payload sizes to monitor ledger write growth
             let reward_input_bytes = borsh::to_vec(&input_config)?;
             let shapley_storage_bytes = borsh::to_vec(&shapley_storage)?;
@@ -266,14 +293,8 @@ impl Orchestrator {
                 // Log final summary
                 info!("{}", summary);
 
-                // Return error if not all successful
-                if !summary.all_successful() {
-                    bail!(
-                        "Some writes failed: {}/{} successful",
-                        summary.successful_count(),
-                        summary.total_count()
-                    );
-                }
+                // Return an error carrying the summary if not all successful
+                summary = summary.into_result()?;
             } else if dry_run {
                 // Populate mock data in summary for Slack testing in dry-run mode
                 summary.add_success_with_id(
@@ -345,6 +366,52 @@ impl Orchestrator {
         Ok(summary)
     }
 
+    /// Pre-flight check that every rewarded contributor has a complete recipient split
+    async fn check_recipient_shares(
+        &self,
+        fetcher: &Fetcher,
+        shapley_storage: &ShapleyOutputStorage,
+        require_recipient_shares: bool,
+    ) -> Result<()> {
+        let contributor_keys = shapley_storage
+            .rewards()
+            .iter()
+            .filter(|reward| reward.unit_share > 0)
+            .map(|reward| reward.contributor_key)
+            .collect::<Vec<_>>();
+
+        let issues =
+            match try_find_recipient_share_issues(&fetcher.solana_write_client, &contributor_keys)
+                .await
+            {
+                Ok(issues) => issues,
+                Err(e) if !require_recipient_shares => {
+                    warn!("[WARN] Skipping recipient shares check: {e:#}");
+                    return Ok(());
+                }
+                Err(e) => return Err(e),
+            };
+
+        for (contributor_key, issue) in &issues {
+            warn!("[WARN] Contributor {contributor_key}: {issue}");
+        }
+
+        if require_recipient_shares && !issues.is_empty() {
+            bail!(
+                "{} of {} rewarded contributors have misconfigured recipient shares",
+                issues.len(),
+                contributor_keys.len()
+            );
+        }
+
+        info!(
+            "Recipient shares checked for {} rewarded contributors ({} misconfigured)",
+            contributor_keys.len(),
+            issues.len()
+        );
+        Ok(())
+    }
+
     pub async fn read_telemetry_aggregates(
         &self,
         epoch: u64,
@@ -393,8 +460,64 @@ impl Orchestrator {
         &self,
         epoch: u64,
         rewards_accountant: Option<Pubkey>,
+        json_output: bool,
     ) -> Result<()> {
-        ledger_operations::read_reward_input(&self.settings, epoch, rewards_accountant).await
+        ledger_operations::read_reward_input(&self.settings, epoch, rewards_accountant, json_output)
+            .await
+    }
+
+    /// Re-posts only the merkle root for an epoch, recomputed from the stored shapley output
+    ///
+    /// Bails if the Distribution account already has a different root staged.
+    pub async fn repost_merkle_root(
+        &self,
+        epoch: u64,
+        keypair_path: Option<PathBuf>,
+        dry_run: bool,
+    ) -> Result<()> {
+        let fetcher = Fetcher::from_settings(&self.settings)?;
+
+        let shapley_storage =
+            ledger_operations::read_shapley_output(&self.settings, epoch, None).await?;
+        let merkle_root = shapley_storage.compute_merkle_root()?;
+        let total_contributors = shapley_storage.total_contributors() as u32;
+
+        info!(
+            "Recomputed merkle root for epoch {}: {:?} ({} contributors)",
+            epoch, merkle_root, total_contributors
+        );
+
+        let staged =
+            try_fetch_staged_rewards_merkle_root(&fetcher.solana_write_client, epoch).await?;
+        if !check_staged_merkle_root(staged, merkle_root, total_contributors)? {
+            info!("Merkle root for epoch {epoch} is already staged, nothing to post");
+            return Ok(());
+        }
+
+        if dry_run {
+            info!("[DRY RUN] Would post merkle root for epoch {epoch}");
+            return Ok(());
+        }
+
+        let payer_signer = load_keypair(&keypair_path)?;
+        ledger_operations::validate_rewards_accountant_keypair(
+            &fetcher.solana_write_client,
+            &payer_signer,
+        )
+        .await?;
+
+        let signature = post_rewards_merkle_root(
+            &fetcher.solana_write_client,
+            &payer_signer,
+            epoch,
+            total_contributors,
+            merkle_root,
+            self.settings.scheduler.grace_period_max_wait_seconds,
+        )
+        .await?;
+
+        info!("[OK] Reposted merkle root for epoch {epoch}: {signature}");
+        Ok(())
     }
 
     pub async fn realloc_record(
@@ -419,6 +542,16 @@ impl Orchestrator {
         ledger_operations::close_record(&self.settings, &r#type, epoch, keypair_path, dry_run).await
     }
 
+    pub async fn close_all_records(
+        &self,
+        epoch: u64,
+        keypair_path: Option<PathBuf>,
+        dry_run: bool,
+    ) -> Result<()> {
+        ledger_operations::close_all_records(&self.settings, epoch, keypair_path, dry_run).await?;
+        Ok(())
+    }
+
     pub async fn write_telemetry_aggregates(
         &self,
         epoch: Option<u64>,
@@ -430,7 +563,7 @@ impl Orchestrator {
 
         // NOTE: Prepare telemetry data
         // This is same as calculate_rewards but without shapley_inputs
-        let prep_data = PreparedData::new(&fetcher, epoch, false).await?;
+        let prep_data = PreparedData::new(&fetcher, epoch, false, None).await?;
         let fetch_epoch = prep_data.epoch;
         let device_telemetry = prep_data.device_telemetry;
         let internet_telemetry = prep_data.internet_telemetry;
@@ -520,8 +653,15 @@ impl Orchestrator {
         epoch: u64,
         rewards_accountant: Option<Pubkey>,
         record_type: Option<String>,
+        json_output: bool,
     ) -> Result<()> {
-        ledger_operations::inspect_records(&self.settings, epoch, rewards_accountant, record_type)
-            .await
+        ledger_operations::inspect_records(
+            &self.settings,
+            epoch,
+            rewards_accountant,
+            record_type,
+            json_output,
+        )
+        .await
     }
 }
@@ -246,6 +246,20 @@ mod tests {
         assert_eq!(bob.unit_share, 250_000_000); // 0.25 * 1_000_000_000
     }
 
+    #[test]
+    fn test_recomputed_merkle_root_matches_stored_output() {
+        let output = create_test_shapley_output();
+        let storage = ShapleyOutputStorage::new(123, &output).unwrap();
+        let original_root = storage.compute_merkle_root().unwrap();
+
+        // Round-trip through borsh as the stored record is read back from the ledger
+        let bytes = borsh::to_vec(&storage).unwrap();
+        let stored: ShapleyOutputStorage = borsh::from_slice(&bytes).unwrap();
+
+        assert_eq!(stored.compute_merkle_root().unwrap(), original_root);
+        assert_eq!(stored.total_contributors(), storage.total_contributors());
+    }
+
     #[test]
     fn test_single_contributor_tree() {
         let output = create_single_contributor_output();
@@ -1,4 +1,7 @@
-use std::time::{Duration, Instant};
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
 
 use anyhow::{Context, Result, anyhow, bail};
 use doublezero_program_tools::instruction::try_build_instruction;
@@ -7,13 +10,16 @@ use doublezero_revenue_distribution::{
     instruction::{
         RevenueDistributionInstructionData, account::ConfigureDistributionRewardsAccounts,
     },
-    state::Distribution,
+    state::{ContributorRewards, Distribution},
     types::DoubleZeroEpoch,
 };
-use doublezero_solana_client_tools::rpc::try_fetch_zero_copy_data_with_commitment;
+use doublezero_solana_client_tools::{
+    account::zero_copy::ZeroCopyAccountOwnedData, rpc::try_fetch_zero_copy_data_with_commitment,
+};
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{
     message::{VersionedMessage, v0::Message},
+    pubkey::Pubkey,
     signature::{Keypair, Signature, Signer},
     transaction::VersionedTransaction,
 };
@@ -21,6 +27,103 @@ use svm_hash::sha2::Hash;
 use tokio::time::sleep;
 use tracing::{info, warn};
 
+/// Recipient shares are in basis points, so a fully configured split sums to 100%
+const FULL_RECIPIENT_SHARES: u32 = 10_000;
+
+/// Accounts fetched per `getMultipleAccounts` call
+const CONTRIBUTOR_REWARDS_FETCH_CHUNK_SIZE: usize = 100;
+
+/// Misconfiguration of a contributor's reward recipients
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecipientSharesIssue {
+    /// The ContributorRewards account does not exist or cannot be decoded
+    MissingAccount,
+    /// No recipients are configured
+    NoRecipients,
+    /// Recipient shares do not sum to 100% (total in basis points)
+    InvalidTotal(u32),
+}
+
+impl fmt::Display for RecipientSharesIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingAccount => write!(f, "contributor rewards account not found"),
+            Self::NoRecipients => write!(f, "no recipients configured"),
+            Self::InvalidTotal(total) => write!(
+                f,
+                "recipient shares sum to {:.2}% instead of 100%",
+                *total as f64 / 100.0
+            ),
+        }
+    }
+}
+
+/// Check a contributor's recipient shares, given in basis points
+///
+/// `None` means the ContributorRewards account is missing.
+pub fn check_recipient_shares(shares: Option<&[u16]>) -> Option<RecipientSharesIssue> {
+    let shares = match shares {
+        None => return Some(RecipientSharesIssue::MissingAccount),
+        Some([]) => return Some(RecipientSharesIssue::NoRecipients),
+        Some(shares) => shares,
+    };
+
+    let total = shares.iter().map(|share| u32::from(*share)).sum::<u32>();
+    (total != FULL_RECIPIENT_SHARES).then_some(RecipientSharesIssue::InvalidTotal(total))
+}
+
+/// Collect recipient share issues for contributors, paired with their fetched shares
+fn collect_recipient_share_issues(
+    contributor_keys: &[Pubkey],
+    shares: impl IntoIterator<Item = Option<Vec<u16>>>,
+) -> Vec<(Pubkey, RecipientSharesIssue)> {
+    contributor_keys
+        .iter()
+        .zip(shares)
+        .filter_map(|(contributor_key, shares)| {
+            check_recipient_shares(shares.as_deref()).map(|issue| (*contributor_key, issue))
+        })
+        .collect()
+}
+
+/// Fetch ContributorRewards accounts and report contributors whose recipients are misconfigured
+pub async fn try_find_recipient_share_issues(
+    rpc_client: &RpcClient,
+    contributor_keys: &[Pubkey],
+) -> Result<Vec<(Pubkey, RecipientSharesIssue)>> {
+    let mut issues = Vec::new();
+
+    for contributor_keys_chunk in contributor_keys.chunks(CONTRIBUTOR_REWARDS_FETCH_CHUNK_SIZE) {
+        let contributor_rewards_keys = contributor_keys_chunk
+            .iter()
+            .map(|contributor_key| ContributorRewards::find_address(contributor_key).0)
+            .collect::<Vec<_>>();
+        let accounts = rpc_client
+            .get_multiple_accounts(&contributor_rewards_keys)
+            .await
+            .context("Failed to fetch contributor rewards accounts")?;
+
+        let shares = accounts.iter().map(|account| {
+            let contributor_rewards =
+                ZeroCopyAccountOwnedData::<ContributorRewards>::from_account(account.as_ref()?)?;
+            Some(
+                contributor_rewards
+                    .recipient_shares
+                    .active_iter()
+                    .map(|share| u16::from(share.share))
+                    .collect::<Vec<_>>(),
+            )
+        });
+
+        issues.extend(collect_recipient_share_issues(
+            contributor_keys_chunk,
+            shares,
+        ));
+    }
+
+    Ok(issues)
+}
+
 /// Check if calculation is allowed for a given distribution based on current block timestamp
 async fn check_calculation_allowed(
     rpc_client: &RpcClient,
@@ -108,6 +211,58 @@ async fn wait_for_grace_period(
     }
 }
 
+/// Fetch the merkle root and contributor count already staged on the Distribution account
+///
+/// Returns `None` when no merkle root has been posted for the epoch yet.
+pub async fn try_fetch_staged_rewards_merkle_root(
+    rpc_client: &RpcClient,
+    epoch: u64,
+) -> Result<Option<(Hash, u32)>> {
+    let (distribution_key, _) = Distribution::find_address(DoubleZeroEpoch::new(epoch));
+
+    let distribution = try_fetch_zero_copy_data_with_commitment::<Distribution>(
+        rpc_client,
+        &distribution_key,
+        rpc_client.commitment(),
+    )
+    .await
+    .with_context(|| {
+        format!("Distribution account for epoch {epoch} does not exist at {distribution_key}")
+    })?;
+
+    if distribution.rewards_merkle_root == Hash::default() {
+        return Ok(None);
+    }
+
+    Ok(Some((
+        distribution.rewards_merkle_root,
+        distribution.total_contributors,
+    )))
+}
+
+/// Compare a recomputed merkle root against the one already staged, if any
+///
+/// Returns `true` when the root still needs to be posted and `false` when the
+/// staged values already match. Errors if a different root is staged.
+pub fn check_staged_merkle_root(
+    staged: Option<(Hash, u32)>,
+    merkle_root: Hash,
+    total_contributors: u32,
+) -> Result<bool> {
+    match staged {
+        None => Ok(true),
+        Some((staged_root, staged_contributors))
+            if staged_root == merkle_root && staged_contributors == total_contributors =>
+        {
+            Ok(false)
+        }
+        Some((staged_root, staged_contributors)) => bail!(
+            "Staged merkle root {staged_root:?} ({staged_contributors} contributors) does not match \
+             recomputed root {merkle_root:?} ({total_contributors} contributors)"
+        ),
+    }
+}
+
 /// Post the contributor rewards merkle root to the revenue distribution program
 pub async fn post_rewards_merkle_root(
     rpc_client: &RpcClient,
@@ -158,3 +313,60 @@ pub async fn post_rewards_merkle_root(
 
     Ok(signature)
 }
+
+#[cfg(test)]
+mod tests {
+    use svm_hash::sha2::double_hash;
+
+    use super::*;
+
+    #[test]
+    fn test_check_staged_merkle_root() {
+        let root = double_hash(b"root", b"", b"");
+        let other = double_hash(b"other", b"", b"");
+
+        // Nothing staged yet
+        assert!(check_staged_merkle_root(None, root, 3).unwrap());
+
+        // Already staged with the same values
+        assert!(!check_staged_merkle_root(Some((root, 3)), root, 3).unwrap());
+
+        // Different root or contributor count staged
+        assert!(check_staged_merkle_root(Some((other, 3)), root, 3).is_err());
+        assert!(check_staged_merkle_root(Some((root, 2)), root, 3).is_err());
+    }
+
+    #[test]
+    fn test_recipient_share_issues() {
+        let contributor_keys = (0..5).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+        let shares = vec![
+            // Single recipient with the full share
+            Some(vec![10_000]),
+            // Split across recipients
+            Some(vec![2_500, 7_500]),
+            // Missing ContributorRewards account
+            None,
+            // Account exists without recipients
+            Some(vec![]),
+            // Shares short of 100%
+            Some(vec![5_000, 4_000]),
+        ];
+
+        let issues = collect_recipient_share_issues(&contributor_keys, shares);
+        assert_eq!(
+            issues,
+            vec![
+                (contributor_keys[2], RecipientSharesIssue::MissingAccount),
+                (contributor_keys[3], RecipientSharesIssue::NoRecipients),
+                (
+                    contributor_keys[4],
+                    RecipientSharesIssue::InvalidTotal(9_000)
+                ),
+            ]
+        );
+        assert_eq!(
+            issues[2].1.to_string(),
+            "recipient shares sum to 90.00% instead of 100%"
+        );
+    }
+}
@@ -32,6 +32,11 @@ pub struct ShapleyComputeResult {
 /// Groups demands by source city, computes per-city Shapley values,
 /// and aggregates results using city weights.
 ///
+/// Cities are independent, so they are spread across a thread pool sized by
+/// `shapley_settings.parallelism`. Per-city results are collected into a
+/// `BTreeMap` before aggregation, so the output (and therefore the merkle
+/// root) is bit-identical regardless of thread count.
+///
 /// # Arguments
 /// * `shapley_inputs` - Network topology, demands, and city weights
 /// * `shapley_settings` - Computation parameters (uptime, bonus, multiplier)
@@ -54,64 +59,79 @@ pub fn compute_shapley_values(
     let demand_groups: Vec<(String, Vec<network_shapley::types::Demand>)> =
         demands_by_city.into_iter().collect();
 
-    // Collect per-city Shapley outputs in parallel
-    let per_city_shapley_outputs: BTreeMap<String, Vec<(String, f64)>> = demand_groups
-        .par_iter()
-        .map(|(city, demands)| {
-            let city_name = city.clone();
-            info!(
-                "City: {city_name}, Demand: \n{}",
-                print_demands(demands, 1_000_000)
-            );
-
-            // Build shapley inputs for this city
-            let input = ShapleyInput {
-                private_links: shapley_inputs.private_links.clone(),
-                devices: shapley_inputs.devices.clone(),
-                demands: demands.clone(),
-                public_links: shapley_inputs.public_links.clone(),
-                operator_uptime: shapley_settings.operator_uptime,
-                contiguity_bonus: shapley_settings.contiguity_bonus,
-                demand_multiplier: shapley_settings.demand_multiplier,
-            };
+    // 0 lets rayon pick one thread per CPU
+    let thread_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(shapley_settings.parallelism)
+        .build()
+        .context("failed to build Shapley thread pool")?;
 
-            // Compute Shapley values
-            let output = input
-                .compute()
-                .map_err(|err| {
+    // Collect per-city Shapley outputs in parallel
+    let per_city_shapley_outputs: BTreeMap<String, Vec<(String, f64)>> = thread_pool
+        .install(|| {
+            demand_groups
+                .par_iter()
+                .map(|(city, demands)| {
+                    let city_name = city.clone();
+                    info!(
+                        "City: {city_name}, Demand: \n{}",
+                        print_demands(demands, 1_000_000)
+                    );
+
+                    // Build shapley inputs for this city
+                    let input = ShapleyInput {
+                        private_links: shapley_inputs.private_links.clone(),
+                        devices: shapley_inputs.devices.clone(),
+                        demands: demands.clone(),
+                        public_links: shapley_inputs.public_links.clone(),
+                        operator_uptime: shapley_settings.operator_uptime,
+                        contiguity_bonus: shapley_settings.contiguity_bonus,
+                        demand_multiplier: shapley_settings.demand_multiplier,
+                    };
+
+                    // Compute Shapley values
+                    let output = input
+                        .compute()
+                        .map_err(|err| {
+                            metrics::counter!(
+                                "doublezero_contributor_rewards_shapley_computations_failed",
+                                "city" => city_name.clone()
+                            )
+                            .increment(1);
+                            warn!(
+                                error = ?err,
+                                city = %city_name,
+                                "Failed to compute Shapley values"
+                            );
+                            err
+                        })
+                        .with_context(|| {
+                            format!("failed to compute Shapley values for {city_name}")
+                        })?;
+
+                    // Track successful computation
                     metrics::counter!(
-                        "doublezero_contributor_rewards_shapley_computations_failed",
+                        "doublezero_contributor_rewards_shapley_computations",
                         "city" => city_name.clone()
                     )
                     .increment(1);
-                    warn!(error = ?err, city = %city_name, "Failed to compute Shapley values");
-                    err
+
+                    // Print per-city table
+                    let table = TableBuilder::from(output.clone())
+                        .build()
+                        .with(Style::psql().remove_horizontals())
+                        .to_string();
+                    info!("Shapley Output for {city_name}:\n{}", table);
+
+                    // Store raw values for aggregation
+                    let city_values: Vec<(String, f64)> = output
+                        .into_iter()
+                        .map(|(operator, shapley_value)| (operator, shapley_value.value))
+                        .collect();
+
+                    Ok((city_name, city_values))
                 })
-                .with_context(|| format!("failed to compute Shapley values for {city_name}"))?;
-
-            // Track successful computation
-            metrics::counter!(
-                "doublezero_contributor_rewards_shapley_computations",
-                "city" => city_name.clone()
-            )
-            .increment(1);
-
-            // Print per-city table
-            let table = TableBuilder::from(output.clone())
-                .build()
-                .with(Style::psql().remove_horizontals())
-                .to_string();
-            info!("Shapley Output for {city_name}:\n{}", table);
-
-            // Store raw values for aggregation
-            let city_values: Vec<(String, f64)> = output
-                .into_iter()
-                .map(|(operator, shapley_value)| (operator, shapley_value.value))
-                .collect();
-
-            Ok((city_name, city_values))
-        })
-        .collect::<Result<Vec<_>>>()?
+                .collect::<Result<Vec<_>>>()
+        })?
         .into_iter()
         .collect();
 
@@ -229,6 +249,8 @@ mod tests {
             operator_uptime: 0.98,
             contiguity_bonus: 5.0,
             demand_multiplier: 1.2,
+            parallelism: 0,
+            device_edge_bandwidth_gbps: Default::default(),
         };
 
         (inputs, settings)
@@ -251,6 +273,88 @@ mod tests {
         assert!(!result.aggregated_output.is_empty());
     }
 
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let (mut inputs, mut settings) = create_minimal_inputs();
+
+        // Add a third city so work is actually split across threads
+        inputs.devices.push(Device {
+            device: "LON01".to_string(),
+            edge: 100,
+            operator: "OperatorA".to_string(),
+        });
+        inputs.private_links.push(PrivateLink {
+            device1: "FRA01".to_string(),
+            device2: "LON01".to_string(),
+            latency: 15.0,
+            bandwidth: 10.0,
+            uptime: 1.0,
+            shared: None,
+        });
+        inputs.public_links.push(PublicLink {
+            city1: "FRA".to_string(),
+            city2: "LON".to_string(),
+            latency: 20.0,
+        });
+        inputs.public_links.push(PublicLink {
+            city1: "LON".to_string(),
+            city2: "NYC".to_string(),
+            latency: 90.0,
+        });
+        inputs.demands.extend([
+            Demand::new("LON".to_string(), "FRA".to_string(), 1, 1.0, 1.0, 1, false),
+            Demand::new("LON".to_string(), "NYC".to_string(), 1, 1.0, 1.0, 1, false),
+        ]);
+        inputs.city_stats.insert(
+            "LON".to_string(),
+            CityStat {
+                validator_count: 2,
+                total_stake_proxy: 700,
+            },
+        );
+        inputs.city_weights = calculate_city_weights(&inputs.city_stats);
+
+        settings.parallelism = 1;
+        let sequential = compute_shapley_values(&inputs, &settings).unwrap();
+
+        for parallelism in [0, 2, 4] {
+            settings.parallelism = parallelism;
+            let parallel = compute_shapley_values(&inputs, &settings).unwrap();
+
+            let to_bits = |values: &[(String, f64)]| {
+                values
+                    .iter()
+                    .map(|(operator, value)| (operator.clone(), value.to_bits()))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(
+                sequential.per_city_outputs.keys().collect::<Vec<_>>(),
+                parallel.per_city_outputs.keys().collect::<Vec<_>>()
+            );
+            for (city, values) in &sequential.per_city_outputs {
+                assert_eq!(to_bits(values), to_bits(&parallel.per_city_outputs[city]));
+            }
+
+            let aggregated_bits = |output: &ShapleyOutput| {
+                output
+                    .iter()
+                    .map(|(operator, val)| {
+                        (
+                            operator.clone(),
+                            val.value.to_bits(),
+                            val.proportion.to_bits(),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(
+                aggregated_bits(&sequential.aggregated_output),
+                aggregated_bits(&parallel.aggregated_output),
+                "parallelism = {parallelism} diverged from sequential output"
+            );
+        }
+    }
+
     #[test]
     fn test_aggregated_proportions_sum_to_one() {
         let (inputs, settings) = create_minimal_inputs();
@@ -1,4 +1,9 @@
-use std::collections::BTreeMap;
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+    fs,
+    path::{Path, PathBuf},
+};
 
 use anyhow::Result;
 use doublezero_serviceability::state::{
@@ -7,15 +12,16 @@ use doublezero_serviceability::state::{
 use network_shapley::types::{
     Demands, Device, Devices, PrivateLink, PrivateLinks, PublicLink, PublicLinks,
 };
+use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 use tabled::{Table, Tabled, settings::Style};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::{
     calculator::constants::{BPS_TO_GBPS, DEFAULT_EDGE_BANDWIDTH_GBPS, SEC_TO_MS},
     ingestor::{demand, fetcher::Fetcher, types::FetchData},
     processor::{
-        internet::InternetTelemetryStatMap, telemetry::DZDTelemetryStatMap, util::quantile_r_type7,
+        internet::InternetTelemetryStatMap, telemetry::DZDTelemetryStatMap, util::quantile,
     },
     settings::{Settings, network::Network},
 };
@@ -42,11 +48,45 @@ struct LinkPenalty {
     bandwidth_reduction_pct: f64,
 }
 
+/// Bump whenever the on-disk layout of `CachedEpochStats` changes, so stale
+/// cache files are ignored instead of misread
+const PREVIOUS_EPOCH_CACHE_VERSION: u32 = 2;
+
+/// Previous epoch stats as persisted in the cache directory
+#[derive(Serialize, Deserialize)]
+struct CachedEpochStats {
+    version: u32,
+    network: Network,
+    telemetry_program_id: String,
+    epoch: u64,
+    internet_stats: InternetTelemetryStatMap,
+    device_stats: DZDTelemetryStatMap,
+}
+
 /// Cache for previous epoch telemetry stats
 #[derive(Default)]
 pub struct PreviousEpochCache {
     pub internet_stats: Option<InternetTelemetryStatMap>,
     pub device_stats: Option<DZDTelemetryStatMap>,
+    /// Where processed stats are persisted across runs
+    cache_location: Option<CacheLocation>,
+}
+
+/// Cache directory plus the network and telemetry program the stats came from,
+/// so entries from another deployment are never mistaken for this one
+struct CacheLocation {
+    dir: PathBuf,
+    network: Network,
+    telemetry_program_id: String,
+}
+
+impl CacheLocation {
+    fn path(&self, epoch: u64) -> PathBuf {
+        self.dir.join(format!(
+            "{}-{}-epoch-{epoch}-telemetry-stats.json",
+            self.network, self.telemetry_program_id
+        ))
+    }
 }
 
 impl PreviousEpochCache {
@@ -54,33 +94,172 @@ impl PreviousEpochCache {
         Self::default()
     }
 
+    /// Create a cache persisted to the configured directory, if any
+    pub fn from_settings(settings: &Settings) -> Self {
+        match &settings.telemetry_defaults.previous_epoch_cache_dir {
+            Some(cache_dir) => Self::new().with_cache_dir(
+                cache_dir,
+                settings.network,
+                &settings.programs.telemetry_program_id,
+            ),
+            None => Self::new(),
+        }
+    }
+
+    /// Persist processed stats under `cache_dir`, keyed by network, telemetry
+    /// program and epoch
+    pub fn with_cache_dir(
+        mut self,
+        cache_dir: impl Into<PathBuf>,
+        network: Network,
+        telemetry_program_id: impl Into<String>,
+    ) -> Self {
+        self.cache_location = Some(CacheLocation {
+            dir: cache_dir.into(),
+            network,
+            telemetry_program_id: telemetry_program_id.into(),
+        });
+        self
+    }
+
     /// Fetch and cache previous epoch stats if not already cached
     pub async fn fetch_if_needed(&mut self, fetcher: &Fetcher, current_epoch: u64) -> Result<()> {
-        if self.internet_stats.is_none() || self.device_stats.is_none() {
-            let previous_epoch = current_epoch.saturating_sub(1);
-            if previous_epoch == 0 {
-                info!("No previous epoch available (current epoch is 1)");
-                return Ok(());
-            }
+        self.load_or_fetch(current_epoch, |previous_epoch| async move {
+            let (_epoch, prev_data) = fetcher.fetch(Some(previous_epoch)).await?;
+            Ok(prev_data)
+        })
+        .await
+    }
 
+    /// Populate the cache from disk, falling back to `fetch` on a miss
+    async fn load_or_fetch<F, Fut>(&mut self, current_epoch: u64, fetch: F) -> Result<()>
+    where
+        F: FnOnce(u64) -> Fut,
+        Fut: Future<Output = Result<FetchData>>,
+    {
+        if self.internet_stats.is_some() && self.device_stats.is_some() {
+            return Ok(());
+        }
+
+        let previous_epoch = current_epoch.saturating_sub(1);
+        if previous_epoch == 0 {
+            info!("No previous epoch available (current epoch is 1)");
+            return Ok(());
+        }
+
+        if let Some(cached) = self.read_cached(previous_epoch) {
             info!(
-                "Fetching previous epoch {} telemetry for default handling",
+                "Loaded previous epoch {} telemetry stats from disk cache",
                 previous_epoch
             );
+            self.device_stats = Some(cached.device_stats);
+            self.internet_stats = Some(cached.internet_stats);
+            return Ok(());
+        }
 
-            // Fetch previous epoch data
-            let (_epoch, prev_data) = fetcher.fetch(Some(previous_epoch)).await?;
+        info!(
+            "Fetching previous epoch {} telemetry for default handling",
+            previous_epoch
+        );
+
+        // Fetch previous epoch data
+        let prev_data = fetch(previous_epoch).await?;
+
+        // Process the telemetry data
+        use crate::processor::{
+            internet::InternetTelemetryProcessor, telemetry::DZDTelemetryProcessor,
+        };
+
+        let device_stats = DZDTelemetryProcessor::process(&prev_data)?;
+        let internet_stats = InternetTelemetryProcessor::process(&prev_data)?;
+
+        // A failed write only costs a refetch next run
+        if let Err(e) = self.write_cached(previous_epoch, &internet_stats, &device_stats) {
+            warn!(
+                "Failed to persist previous epoch {} telemetry stats: {}",
+                previous_epoch, e
+            );
+        }
+
+        self.device_stats = Some(device_stats);
+        self.internet_stats = Some(internet_stats);
 
-            // Process the telemetry data
-            use crate::processor::{
-                internet::InternetTelemetryProcessor, telemetry::DZDTelemetryProcessor,
-            };
+        info!("Cached previous epoch telemetry stats");
+        Ok(())
+    }
 
-            self.device_stats = Some(DZDTelemetryProcessor::process(&prev_data)?);
-            self.internet_stats = Some(InternetTelemetryProcessor::process(&prev_data)?);
+    fn cache_path(&self, epoch: u64) -> Option<PathBuf> {
+        self.cache_location
+            .as_ref()
+            .map(|location| location.path(epoch))
+    }
 
-            info!("Cached previous epoch telemetry stats");
+    /// Read cached stats for `epoch`, treating unreadable, outdated or foreign
+    /// files as a miss
+    fn read_cached(&self, epoch: u64) -> Option<CachedEpochStats> {
+        let location = self.cache_location.as_ref()?;
+        let path = location.path(epoch);
+        let contents = fs::read(&path).ok()?;
+
+        match serde_json::from_slice::<CachedEpochStats>(&contents) {
+            Ok(cached)
+                if cached.version == PREVIOUS_EPOCH_CACHE_VERSION
+                    && cached.network == location.network
+                    && cached.telemetry_program_id == location.telemetry_program_id
+                    && cached.epoch == epoch =>
+            {
+                Some(cached)
+            }
+            Ok(cached) => {
+                warn!(
+                    "Ignoring cached telemetry stats at {:?} (version {}, network {}, telemetry program {}, epoch {})",
+                    path, cached.version, cached.network, cached.telemetry_program_id, cached.epoch
+                );
+                None
+            }
+            Err(e) => {
+                warn!(
+                    "Ignoring unreadable telemetry stats cache {:?}: {}",
+                    path, e
+                );
+                None
+            }
         }
+    }
+
+    fn write_cached(
+        &self,
+        epoch: u64,
+        internet_stats: &InternetTelemetryStatMap,
+        device_stats: &DZDTelemetryStatMap,
+    ) -> Result<()> {
+        let Some(location) = &self.cache_location else {
+            return Ok(());
+        };
+        let path = location.path(epoch);
+
+        let cached = CachedEpochStats {
+            version: PREVIOUS_EPOCH_CACHE_VERSION,
+            network: location.network,
+            telemetry_program_id: location.telemetry_program_id.clone(),
+            epoch,
+            internet_stats: internet_stats.clone(),
+            device_stats: device_stats.clone(),
+        };
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        // Write to a temporary file first so a crash never leaves a partial cache entry
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, serde_json::to_vec(&cached)?)?;
+        fs::rename(&temp_path, &path)?;
+
+        debug!(
+            "Persisted previous epoch {} telemetry stats to {:?}",
+            epoch, path
+        );
         Ok(())
     }
 
@@ -101,13 +280,18 @@ impl PreviousEpochCache {
     }
 }
 
-pub fn build_devices(fetch_data: &FetchData, network: &Network) -> Result<(Devices, DeviceIdMap)> {
+/// Build shapley devices, taking each device's edge bandwidth from
+/// `shapley.device_edge_bandwidth_gbps` and falling back to the default
+pub fn build_devices(
+    settings: &Settings,
+    fetch_data: &FetchData,
+) -> Result<(Devices, DeviceIdMap)> {
     // First, collect all device metadata
     // R implementation merges devices with contributors
     // which reorders devices by contributor_pk before assigning city-based sequential IDs
 
-    // (device_pk, contributor_pk, city_code, owner)
-    let mut device_data: Vec<(Pubkey, Pubkey, String, String)> = Vec::new();
+    // (device_pk, contributor_pk, city_code, owner, edge)
+    let mut device_data: Vec<(Pubkey, Pubkey, String, String, u32)> = Vec::new();
 
     for (device_pk, device) in fetch_data.dz_serviceability.devices.iter() {
         let Some(contributor) = fetch_data
@@ -127,7 +311,7 @@ pub fn build_devices(fetch_data: &FetchData, network: &Network) -> Result<(Devic
             continue;
         };
 
-        let city_code = match network {
+        let city_code = match settings.network {
             Network::Testnet | Network::Devnet => exchange
                 .code
                 .strip_prefix('x')
@@ -136,23 +320,31 @@ pub fn build_devices(fetch_data: &FetchData, network: &Network) -> Result<(Devic
             Network::MainnetBeta | Network::Mainnet => exchange.code.clone(),
         };
 
+        let edge = settings
+            .shapley
+            .device_edge_bandwidth_gbps
+            .get(&device.code)
+            .copied()
+            .unwrap_or(DEFAULT_EDGE_BANDWIDTH_GBPS);
+
         device_data.push((
             *device_pk,
             device.contributor_pk,
             city_code,
             contributor.owner.to_string(),
+            edge,
         ));
     }
 
-    // Sort by contributor_pk only (matches R's merge operation)
-    // R's merge preserves insertion order within each contributor group
-    device_data.sort_by_key(|item| item.1);
+    // Sort by contributor_pk (matches R's merge operation), then by device_pk so
+    // devices sharing a contributor get stable IDs regardless of iteration order
+    device_data.sort_by_key(|item| (item.1, item.0));
 
     let mut devices = Vec::new();
     let mut device_ids: DeviceIdMap = DeviceIdMap::new();
     let mut city_counts: BTreeMap<String, u32> = BTreeMap::new();
 
-    for (device_pk, _contributor_pk, city_code, owner) in device_data {
+    for (device_pk, _contributor_pk, city_code, owner, edge) in device_data {
         let city_upper = city_code.to_uppercase();
         let counter = city_counts.entry(city_upper.clone()).or_insert(0);
         *counter += 1;
@@ -164,7 +356,7 @@ pub fn build_devices(fetch_data: &FetchData, network: &Network) -> Result<(Devic
 
         devices.push(Device {
             device: shapley_id,
-            edge: DEFAULT_EDGE_BANDWIDTH_GBPS,
+            edge,
             // Use owner pubkey as operator ID
             operator: owner,
         });
@@ -176,8 +368,12 @@ pub fn build_devices(fetch_data: &FetchData, network: &Network) -> Result<(Devic
 pub async fn build_demands(
     fetcher: &Fetcher,
     fetch_data: &FetchData,
+    demand_override: Option<&Path>,
 ) -> Result<(Demands, demand::CityStats)> {
-    let result = demand::build(fetcher, fetch_data).await?;
+    let result = match demand_override {
+        Some(path) => demand::load_from_file(path, &fetcher.settings, fetch_data)?,
+        None => demand::build(fetcher, fetch_data).await?,
+    };
     Ok((result.demands, result.city_stats))
 }
 
@@ -298,15 +494,105 @@ pub fn build_public_links(
     // Sort by city pairs for consistent output
     public_links.sort_by(|a, b| (&a.city1, &a.city2).cmp(&(&b.city1, &b.city2)));
 
+    metrics::counter!("doublezero_contributor_rewards_public_links_built")
+        .increment(public_links.len() as u64);
+
     Ok(public_links)
 }
 
-pub fn build_private_links(fetch_data: &FetchData, device_ids: &DeviceIdMap) -> PrivateLinks {
+/// Reason a candidate link was left out of the private links
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkExclusionReason {
+    InactiveLink,
+    InactiveDevice,
+    MissingDeviceId,
+    TooFewSamples,
+}
+
+impl Display for LinkExclusionReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InactiveLink => write!(f, "inactive link"),
+            Self::InactiveDevice => write!(f, "inactive device"),
+            Self::MissingDeviceId => write!(f, "missing device ID"),
+            Self::TooFewSamples => write!(f, "too few samples"),
+        }
+    }
+}
+
+impl LinkExclusionReason {
+    /// Label used for the `reason` dimension of link metrics
+    pub fn metric_label(&self) -> &'static str {
+        match self {
+            Self::InactiveLink => "inactive_link",
+            Self::InactiveDevice => "inactive_device",
+            Self::MissingDeviceId => "missing_device_id",
+            Self::TooFewSamples => "too_few_samples",
+        }
+    }
+}
+
+fn record_dropped_private_link(reason: &'static str) {
+    metrics::counter!(
+        "doublezero_contributor_rewards_private_links_dropped",
+        "reason" => reason
+    )
+    .increment(1);
+}
+
+/// Diagnostic entry for every candidate link considered by build_private_links
+#[derive(Debug, Clone, Tabled)]
+pub struct LinkReportEntry {
+    #[tabled(rename = "Link")]
+    pub code: String,
+    #[tabled(rename = "Pubkey")]
+    pub link_pk: Pubkey,
+    #[tabled(rename = "Valid Samples", display_with = "display_valid_samples")]
+    pub valid_samples: Option<usize>,
+    #[tabled(rename = "Excluded", display_with = "display_exclusion_reason")]
+    pub exclusion_reason: Option<LinkExclusionReason>,
+}
+
+fn display_valid_samples(valid_samples: &Option<usize>) -> String {
+    valid_samples.map_or_else(|| "-".to_string(), |count| count.to_string())
+}
+
+fn display_exclusion_reason(exclusion_reason: &Option<LinkExclusionReason>) -> String {
+    exclusion_reason.map_or_else(|| "-".to_string(), |reason| reason.to_string())
+}
+
+pub fn build_private_links(
+    settings: &Settings,
+    fetch_data: &FetchData,
+    device_ids: &DeviceIdMap,
+) -> PrivateLinks {
+    let (private_links, _link_report) =
+        build_private_links_with_report(settings, fetch_data, device_ids);
+    private_links
+}
+
+/// Same as build_private_links, but also reports why each excluded link was dropped
+pub fn build_private_links_with_report(
+    settings: &Settings,
+    fetch_data: &FetchData,
+    device_ids: &DeviceIdMap,
+) -> (PrivateLinks, Vec<LinkReportEntry>) {
     let mut private_links = Vec::new();
     let mut penalties = Vec::new();
+    let mut link_report = Vec::new();
 
     for (link_pk, link) in fetch_data.dz_serviceability.links.iter() {
+        let mut report_entry = LinkReportEntry {
+            code: link.code.clone(),
+            link_pk: *link_pk,
+            valid_samples: None,
+            exclusion_reason: None,
+        };
+
         if link.status != DZLinkStatus::Activated {
+            report_entry.exclusion_reason = Some(LinkExclusionReason::InactiveLink);
+            record_dropped_private_link(LinkExclusionReason::InactiveLink.metric_label());
+            link_report.push(report_entry);
             continue;
         }
 
@@ -317,13 +603,27 @@ pub fn build_private_links(fetch_data: &FetchData, device_ids: &DeviceIdMap) ->
             {
                 (f, t)
             }
-            _ => continue,
+            (Some(_), Some(_)) => {
+                report_entry.exclusion_reason = Some(LinkExclusionReason::InactiveDevice);
+                record_dropped_private_link(LinkExclusionReason::InactiveDevice.metric_label());
+                link_report.push(report_entry);
+                continue;
+            }
+            _ => {
+                report_entry.exclusion_reason = Some(LinkExclusionReason::MissingDeviceId);
+                record_dropped_private_link(LinkExclusionReason::MissingDeviceId.metric_label());
+                link_report.push(report_entry);
+                continue;
+            }
         };
 
-        let Some(from_id) = device_ids.get(&link.side_a_pk) else {
-            continue;
-        };
-        let Some(to_id) = device_ids.get(&link.side_z_pk) else {
+        let (Some(from_id), Some(to_id)) = (
+            device_ids.get(&link.side_a_pk),
+            device_ids.get(&link.side_z_pk),
+        ) else {
+            report_entry.exclusion_reason = Some(LinkExclusionReason::MissingDeviceId);
+            record_dropped_private_link(LinkExclusionReason::MissingDeviceId.metric_label());
+            link_report.push(report_entry);
             continue;
         };
 
@@ -350,6 +650,8 @@ pub fn build_private_links(fetch_data: &FetchData, device_ids: &DeviceIdMap) ->
             }
         }
 
+        report_entry.valid_samples = Some(combined_samples.len());
+
         // R implementation only includes links with >20 valid samples
         // Otherwise the link gets NA latency and is dropped
         if combined_samples.len() <= 20 {
@@ -359,13 +661,21 @@ pub fn build_private_links(fetch_data: &FetchData, device_ids: &DeviceIdMap) ->
                 to_device.code,
                 combined_samples.len()
             );
+            report_entry.exclusion_reason = Some(LinkExclusionReason::TooFewSamples);
+            record_dropped_private_link(LinkExclusionReason::TooFewSamples.metric_label());
+            link_report.push(report_entry);
             continue;
         }
 
         // Compute P95 from combined samples using R type 7 quantile (linear interpolation)
         // Matches R line 40: quantile(samples, 0.95) which defaults to type=7
         combined_samples.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
-        let latency_us = quantile_r_type7(&combined_samples, 0.95);
+        // Configurable for experimentation, defaults to type 7 at p95
+        let latency_us = quantile(
+            &combined_samples,
+            settings.telemetry_defaults.private_link_quantile,
+            settings.telemetry_defaults.private_link_quantile_type,
+        );
 
         // Convert latency from microseconds to milliseconds (R divides by 1e3 on line 40)
         let latency_ms = latency_us / 1000.0;
@@ -379,7 +689,15 @@ pub fn build_private_links(fetch_data: &FetchData, device_ids: &DeviceIdMap) ->
         };
 
         // Calculate penalized uptime
-        let uptime = penalized_uptime(true_uptime);
+        let uptime = settings
+            .telemetry_defaults
+            .uptime_penalty
+            .penalized_uptime(true_uptime);
+        if uptime == 0.0 {
+            // Kept for the shapley inputs, but contributes no bandwidth
+            metrics::counter!("doublezero_contributor_rewards_private_links_zero_uptime")
+                .increment(1);
+        }
 
         // Collect penalty information for links with reduced uptime
         if uptime < 1.0 {
@@ -404,6 +722,7 @@ pub fn build_private_links(fetch_data: &FetchData, device_ids: &DeviceIdMap) ->
             uptime,
             None,
         ));
+        link_report.push(report_entry);
     }
 
     // Print penalty table if any links were penalized
@@ -416,27 +735,222 @@ pub fn build_private_links(fetch_data: &FetchData, device_ids: &DeviceIdMap) ->
         );
     }
 
-    private_links
-}
-
-fn penalized_uptime(true_uptime: f64) -> f64 {
-    // Apply quadratic penalty formula for links with missing data (R line 92)
-    // uptime = pmin(pmax(-1578.9474 * true_uptime^2 + 3176.3158 * true_uptime - 1596.3684, 0), 1)
-    // This heavily penalizes links below 98% uptime:
-    // - 100% uptime -> 1.0 (no penalty)
-    // - 99% uptime -> 0.658 (~34% bandwidth reduction)
-    // - 98% uptime -> ~0 (threshold - effectively dropped)
-    // - <98% uptime -> 0 (link dropped from calculations)
-    const COEFF_A: f64 = -1578.9474;
-    const COEFF_B: f64 = 3176.3158;
-    const CONST_C: f64 = -1596.3684;
-    let uptime_raw = COEFF_A * true_uptime.powi(2) + COEFF_B * true_uptime + CONST_C;
-    uptime_raw.clamp(0.0, 1.0)
+    (private_links, link_report)
 }
 
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use anyhow::bail;
+
     use super::*;
+    use crate::{processor::telemetry::DZDTelemetryStats, settings::UptimePenaltySettings};
+
+    /// Penalty curve with the default coefficients
+    fn penalized_uptime(true_uptime: f64) -> f64 {
+        UptimePenaltySettings::default().penalized_uptime(true_uptime)
+    }
+
+    #[test]
+    fn test_penalized_uptime_alternate_coefficients() {
+        // Gentler curve that still yields 1.0 at 100% and drops links below 90%
+        // uptime = -50x^2 + 105x - 54
+        let penalty = UptimePenaltySettings {
+            quadratic_coeff: -50.0,
+            linear_coeff: 105.0,
+            constant: -54.0,
+        };
+
+        assert!((penalty.penalized_uptime(1.0) - 1.0).abs() < 1e-9);
+        assert!((penalty.penalized_uptime(0.95) - 0.625).abs() < 1e-9);
+        assert_eq!(penalty.penalized_uptime(0.85), 0.0);
+
+        // The same link is dropped by the default curve
+        assert!(penalized_uptime(0.95) < 0.001);
+    }
+
+    fn sample_device_stats() -> DZDTelemetryStatMap {
+        let stats = DZDTelemetryStats {
+            circuit: "device1->device2".to_string(),
+            link_pubkey: Pubkey::default(),
+            origin_device: Pubkey::default(),
+            target_device: Pubkey::default(),
+            rtt_mean_us: 5000.0,
+            rtt_median_us: 4500.0,
+            rtt_min_us: 1000.0,
+            rtt_max_us: 10000.0,
+            rtt_p90_us: 8500.0,
+            rtt_p95_us: 9000.0,
+            rtt_p99_us: 9900.0,
+            rtt_stddev_us: 1500.0,
+            jitter_ewma_us: 500.0,
+            avg_jitter_us: 500.0,
+            max_jitter_us: 1000.0,
+            packet_loss: 0.0,
+            loss_count: 0,
+            success_count: 100,
+            total_samples: 100,
+            missing_data_ratio: 0.0,
+        };
+        DZDTelemetryStatMap::from([(stats.circuit.clone(), stats)])
+    }
+
+    const TEST_TELEMETRY_PROGRAM_ID: &str = "11111111111111111111111111111111";
+
+    #[tokio::test]
+    async fn test_previous_epoch_cache_warm_skips_fetch() {
+        let cache_dir = tempfile::tempdir().unwrap();
+
+        PreviousEpochCache::new()
+            .with_cache_dir(
+                cache_dir.path(),
+                Network::Testnet,
+                TEST_TELEMETRY_PROGRAM_ID,
+            )
+            .write_cached(9, &InternetTelemetryStatMap::new(), &sample_device_stats())
+            .unwrap();
+
+        let fetches = AtomicUsize::new(0);
+        let mut cache = PreviousEpochCache::new().with_cache_dir(
+            cache_dir.path(),
+            Network::Testnet,
+            TEST_TELEMETRY_PROGRAM_ID,
+        );
+        cache
+            .load_or_fetch(10, |_| async {
+                fetches.fetch_add(1, Ordering::SeqCst);
+                bail!("fetch should not be called on a warm cache")
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(fetches.load(Ordering::SeqCst), 0);
+        assert_eq!(
+            cache.get_device_circuit_average("device1->device2"),
+            Some(9000.0)
+        );
+        assert!(cache.internet_stats.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_previous_epoch_cache_cold_fetches_and_persists() {
+        let cache_dir = tempfile::tempdir().unwrap();
+
+        let fetches = AtomicUsize::new(0);
+        let mut cache = PreviousEpochCache::new().with_cache_dir(
+            cache_dir.path(),
+            Network::Testnet,
+            TEST_TELEMETRY_PROGRAM_ID,
+        );
+        cache
+            .load_or_fetch(10, |epoch| {
+                assert_eq!(epoch, 9);
+                fetches.fetch_add(1, Ordering::SeqCst);
+                async { Ok(FetchData::default()) }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        assert!(
+            cache_dir
+                .path()
+                .join(format!(
+                    "testnet-{TEST_TELEMETRY_PROGRAM_ID}-epoch-9-telemetry-stats.json"
+                ))
+                .exists()
+        );
+
+        // A different epoch is a separate cache entry
+        assert!(cache.read_cached(8).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_previous_epoch_cache_ignores_outdated_version() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let cache = PreviousEpochCache::new().with_cache_dir(
+            cache_dir.path(),
+            Network::Testnet,
+            TEST_TELEMETRY_PROGRAM_ID,
+        );
+
+        let stale = serde_json::json!({
+            "version": PREVIOUS_EPOCH_CACHE_VERSION + 1,
+            "network": "testnet",
+            "telemetry_program_id": TEST_TELEMETRY_PROGRAM_ID,
+            "epoch": 9,
+            "internet_stats": {},
+            "device_stats": {},
+        });
+        fs::write(cache.cache_path(9).unwrap(), stale.to_string()).unwrap();
+        assert!(cache.read_cached(9).is_none());
+
+        fs::write(cache.cache_path(9).unwrap(), "not json").unwrap();
+        assert!(cache.read_cached(9).is_none());
+
+        let fetches = AtomicUsize::new(0);
+        let mut cache = cache;
+        cache
+            .load_or_fetch(10, |_| {
+                fetches.fetch_add(1, Ordering::SeqCst);
+                async { Ok(FetchData::default()) }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        assert!(cache.read_cached(9).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_previous_epoch_cache_scoped_to_network_and_program() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let testnet = PreviousEpochCache::new().with_cache_dir(
+            cache_dir.path(),
+            Network::Testnet,
+            TEST_TELEMETRY_PROGRAM_ID,
+        );
+        testnet
+            .write_cached(9, &InternetTelemetryStatMap::new(), &sample_device_stats())
+            .unwrap();
+        assert!(testnet.read_cached(9).is_some());
+
+        // Same directory and epoch, but another network or telemetry program
+        let mainnet = PreviousEpochCache::new().with_cache_dir(
+            cache_dir.path(),
+            Network::MainnetBeta,
+            TEST_TELEMETRY_PROGRAM_ID,
+        );
+        assert!(mainnet.read_cached(9).is_none());
+
+        let other_program_id = Pubkey::new_unique().to_string();
+        let other_program = PreviousEpochCache::new().with_cache_dir(
+            cache_dir.path(),
+            Network::Testnet,
+            &other_program_id,
+        );
+        assert!(other_program.read_cached(9).is_none());
+
+        // An entry whose contents disagree with its filename is ignored too
+        fs::copy(
+            testnet.cache_path(9).unwrap(),
+            mainnet.cache_path(9).unwrap(),
+        )
+        .unwrap();
+        assert!(mainnet.read_cached(9).is_none());
+
+        let fetches = AtomicUsize::new(0);
+        let mut mainnet = mainnet;
+        mainnet
+            .load_or_fetch(10, |_| {
+                fetches.fetch_add(1, Ordering::SeqCst);
+                async { Ok(FetchData::default()) }
+            })
+            .await
+            .unwrap();
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+    }
 
     #[test]
     fn test_penalized_uptime_perfect() {
@@ -189,7 +189,7 @@ async fn handle_export_shapley(
     );
 
     // Prepare data using shared function
-    let prep_data = PreparedData::from_snapshot(&snapshot, orchestrator.settings(), true)?;
+    let prep_data = PreparedData::from_snapshot(&snapshot, orchestrator.settings(), true, None)?;
     let epoch = prep_data.epoch;
 
     let shapley_inputs = prep_data
@@ -4,13 +4,14 @@ use anyhow::{Result, bail};
 use clap::Subcommand;
 use network_shapley::types::{Demand, Demands, Devices, PrivateLinks, PublicLinks};
 use solana_sdk::pubkey::Pubkey;
+use tabled::{Table, settings::Style};
 use tracing::{info, warn};
 
 use crate::{
     calculator::{
         orchestrator::Orchestrator,
         shapley::handler::{
-            PreviousEpochCache, build_devices, build_private_links, build_public_links,
+            PreviousEpochCache, build_devices, build_private_links_with_report, build_public_links,
         },
     },
     cli::{
@@ -35,7 +36,10 @@ pub enum InspectCommands {
     inspect rewards --epoch 123 --type device-telemetry
 
     # Inspect with specific rewards accountant
-    inspect rewards --epoch 123 --rewards-accountant <PUBKEY>"#
+    inspect rewards --epoch 123 --rewards-accountant <PUBKEY>
+
+    # Output as JSON
+    inspect rewards --epoch 123 --json"#
     )]
     Rewards {
         /// DZ epoch number to inspect records for
@@ -49,6 +53,10 @@ pub enum InspectCommands {
         /// Specific record type to inspect (shows all if not specified)
         #[arg(short = 't', long, value_name = "TYPE")]
         r#type: Option<String>,
+
+        /// Output as JSON instead of table
+        #[arg(long)]
+        json: bool,
     },
 
     #[command(
@@ -64,7 +72,10 @@ pub enum InspectCommands {
     inspect shapley -s mn-epoch-46-snapshot.json
 
     # Export ShapleyInputs to JSON
-    inspect shapley --epoch 9 --skip-users --output-format json-pretty --output-dir ./debug/"#
+    inspect shapley --epoch 9 --skip-users --output-format json-pretty --output-dir ./debug/
+
+    # Show why candidate private links were excluded
+    inspect shapley --epoch 9 --skip-users --link-report"#
     )]
     Shapley {
         /// DZ epoch to debug
@@ -94,6 +105,10 @@ pub enum InspectCommands {
         /// Specific output file path
         #[arg(long, value_name = "FILE")]
         output_file: Option<PathBuf>,
+
+        /// Print every candidate private link with its exclusion reason
+        #[arg(long)]
+        link_report: bool,
     },
 }
 
@@ -106,6 +121,7 @@ struct ShapleyInspectArgs {
     output_format: OutputFormat,
     output_dir: Option<PathBuf>,
     output_file: Option<PathBuf>,
+    link_report: bool,
 }
 
 /// Container for Shapley inputs using existing types
@@ -141,7 +157,8 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: InspectCommands) -> Result
             epoch,
             rewards_accountant,
             r#type,
-        } => handle_inspect_rewards(orchestrator, epoch, rewards_accountant, r#type).await,
+            json,
+        } => handle_inspect_rewards(orchestrator, epoch, rewards_accountant, r#type, json).await,
         InspectCommands::Shapley {
             epoch,
             snapshot,
@@ -150,6 +167,7 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: InspectCommands) -> Result
             output_format,
             output_dir,
             output_file,
+            link_report,
         } => {
             let args = ShapleyInspectArgs {
                 epoch,
@@ -159,6 +177,7 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: InspectCommands) -> Result
                 output_format,
                 output_dir,
                 output_file,
+                link_report,
             };
             handle_inspect_shapley(orchestrator, args).await
         }
@@ -170,9 +189,10 @@ async fn handle_inspect_rewards(
     epoch: u64,
     rewards_accountant: Option<Pubkey>,
     r#type: Option<String>,
+    json: bool,
 ) -> Result<()> {
     orchestrator
-        .inspect_records(epoch, rewards_accountant, r#type)
+        .inspect_records(epoch, rewards_accountant, r#type, json)
         .await
 }
 
@@ -249,8 +269,9 @@ async fn handle_inspect_shapley(
     // Create an empty cache since we're just inspecting, not applying defaults
     let previous_epoch_cache = PreviousEpochCache::new();
 
-    let (devices, device_ids) = build_devices(&fetch_data, &orchestrator.settings().network)?;
-    let private_links = build_private_links(&fetch_data, &device_ids);
+    let (devices, device_ids) = build_devices(orchestrator.settings(), &fetch_data)?;
+    let (private_links, link_report) =
+        build_private_links_with_report(orchestrator.settings(), &fetch_data, &device_ids);
     let public_links = build_public_links(
         orchestrator.settings(),
         &internet_stats,
@@ -331,6 +352,14 @@ async fn handle_inspect_shapley(
     println!("Public Links: {}", shapley_inputs.public_links.len());
     println!("Demands: {}", shapley_inputs.demands.len());
 
+    if args.link_report {
+        println!("\nPrivate Link Report:");
+        println!(
+            "{}",
+            Table::new(&link_report).with(Style::psql().remove_horizontals())
+        );
+    }
+
     Ok(())
 }
 
@@ -32,6 +32,9 @@ pub enum RewardsCommands {
     # Repost only merkle root (skip all DZ Ledger writes)
     calculate-rewards --snapshot mn-epoch-27-snapshot.json -k keypair.json --skip-device-telemetry --skip-internet-telemetry --skip-reward-input --skip-shapley-output
 
+    # What-if analysis with a fixed demand table
+    calculate-rewards --snapshot mn-epoch-27-snapshot.json --dry-run --demand-source demands.json
+
     # Create a snapshot first using the snapshot command
     snapshot all --epoch 27 --output-file mn-epoch-27-snapshot.json"#
     )]
@@ -45,6 +48,10 @@ pub enum RewardsCommands {
         )]
         snapshot: PathBuf,
 
+        /// JSON file with demands to use instead of deriving them from the leader schedule
+        #[arg(long, value_name = "FILE")]
+        demand_source: Option<PathBuf>,
+
         /// Skip writing to ledger and show what would be written
         #[arg(long)]
         dry_run: bool,
@@ -81,6 +88,11 @@ pub enum RewardsCommands {
         /// Send Slack notification after completion (requires Slack settings in config)
         #[arg(long)]
         slack_notify: bool,
+
+        /// Fail instead of warning when a rewarded contributor's recipient shares are
+        /// unset or don't sum to 100%
+        #[arg(long)]
+        require_recipient_shares: bool,
     },
     #[command(
         about = "Read and display telemetry aggregate statistics from the ledger",
@@ -147,7 +159,10 @@ pub enum RewardsCommands {
     read-reward-input --epoch 123
 
     # Read with specific rewards accountant
-    read-reward-input --epoch 123 --rewards-accountant <PUBKEY>"#
+    read-reward-input --epoch 123 --rewards-accountant <PUBKEY>
+
+    # Output as JSON
+    read-reward-input --epoch 123 --json"#
     )]
     ReadRewardInput {
         /// DZ epoch number to read configuration from
@@ -157,6 +172,10 @@ pub enum RewardsCommands {
         /// Rewards accountant public key (auto-fetched from ProgramConfig if not provided)
         #[arg(short = 'r', long, value_name = "PUBKEY")]
         rewards_accountant: Option<Pubkey>,
+
+        /// Output as JSON instead of table
+        #[arg(long)]
+        json: bool,
     },
     #[command(
         about = "Read and display all contributor rewards for an epoch",
@@ -183,6 +202,33 @@ pub enum RewardsCommands {
         #[arg(long)]
         json: bool,
     },
+    #[command(
+        about = "Re-post only the rewards merkle root, recomputed from the stored shapley output",
+        after_help = r#"Examples:
+    # Re-post the merkle root for epoch 123
+    repost-merkle-root --epoch 123 -k keypair.json
+
+    # Dry run to check the recomputed root against any staged root
+    repost-merkle-root --epoch 123 --dry-run"#
+    )]
+    RepostMerkleRoot {
+        /// DZ epoch number to re-post the merkle root for
+        #[arg(short, long, value_name = "EPOCH")]
+        epoch: u64,
+
+        /// Skip posting and only show the recomputed root
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Path to keypair file for signing transactions
+        #[arg(
+            short = 'k',
+            long,
+            value_name = "FILE",
+            required_unless_present = "dry_run"
+        )]
+        keypair: Option<PathBuf>,
+    },
     #[command(
         about = "Reallocate a record account to change its size",
         after_help = r#"Examples:
@@ -193,7 +239,8 @@ pub enum RewardsCommands {
     realloc-record --type internet-telemetry --epoch 123 --size 50000 --dry-run"#
     )]
     ReallocRecord {
-        /// Record type: 'device-telemetry', 'internet-telemetry', 'reward-input', or 'contributor-rewards'
+        /// Record type: 'device-telemetry', 'internet-telemetry', 'reward-input', or
+        /// 'contributor-rewards' (alias 'shapley-output')
         #[arg(short = 't', long, value_name = "TYPE")]
         r#type: String,
 
@@ -218,6 +265,37 @@ pub enum RewardsCommands {
         )]
         keypair: Option<PathBuf>,
     },
+    #[command(
+        about = "Close every record account for an epoch",
+        after_help = r#"Closes the device telemetry, internet telemetry, reward input and
+shapley output records. Records that are already closed are skipped, so the
+command is safe to re-run.
+
+Examples:
+    # Close all records for an epoch
+    close-all-records --epoch 100 -k keypair.json
+
+    # Dry run to see which records still exist
+    close-all-records --epoch 100 --dry-run"#
+    )]
+    CloseAllRecords {
+        /// DZ epoch number of the records to close
+        #[arg(short, long, value_name = "EPOCH")]
+        epoch: u64,
+
+        /// Skip the actual closures and show what would happen
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Path to keypair file for signing transactions
+        #[arg(
+            short = 'k',
+            long,
+            value_name = "FILE",
+            required_unless_present = "dry_run"
+        )]
+        keypair: Option<PathBuf>,
+    },
     #[command(
         about = "Close a record account and reclaim its rent",
         after_help = r#"Examples:
@@ -228,7 +306,8 @@ pub enum RewardsCommands {
     close-record --type contributor-rewards --epoch 100 --dry-run"#
     )]
     CloseRecord {
-        /// Record type: 'device-telemetry', 'internet-telemetry', 'reward-input', or 'contributor-rewards'
+        /// Record type: 'device-telemetry', 'internet-telemetry', 'reward-input', or
+        /// 'contributor-rewards' (alias 'shapley-output')
         #[arg(short = 't', long, value_name = "TYPE")]
         r#type: String,
 
@@ -290,6 +369,7 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: RewardsCommands) -> Result
     match cmd {
         RewardsCommands::CalculateRewards {
             snapshot,
+            demand_source,
             dry_run,
             keypair,
             skip_device_telemetry,
@@ -298,6 +378,7 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: RewardsCommands) -> Result
             skip_shapley_output,
             skip_merkle_root,
             slack_notify,
+            require_recipient_shares,
         } => {
             use tracing::warn;
 
@@ -333,7 +414,15 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: RewardsCommands) -> Result
             }
 
             let write_summary = orchestrator
-                .calculate_rewards(None, keypair, Some(snapshot.clone()), dry_run, write_config)
+                .calculate_rewards(
+                    None,
+                    keypair,
+                    Some(snapshot.clone()),
+                    demand_source,
+                    dry_run,
+                    write_config,
+                    require_recipient_shares,
+                )
                 .await?;
 
             // Send Slack notification if requested
@@ -411,9 +500,10 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: RewardsCommands) -> Result
         RewardsCommands::ReadRewardInput {
             epoch,
             rewards_accountant,
+            json,
         } => {
             orchestrator
-                .read_reward_input(epoch, rewards_accountant)
+                .read_reward_input(epoch, rewards_accountant, json)
                 .await
         }
         RewardsCommands::ReadRewards {
@@ -425,6 +515,15 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: RewardsCommands) -> Result
                 .read_all_rewards(epoch, rewards_accountant, json)
                 .await
         }
+        RewardsCommands::RepostMerkleRoot {
+            epoch,
+            dry_run,
+            keypair,
+        } => {
+            orchestrator
+                .repost_merkle_root(epoch, keypair, dry_run)
+                .await
+        }
         RewardsCommands::ReallocRecord {
             r#type,
             epoch,
@@ -446,6 +545,15 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: RewardsCommands) -> Result
                 .close_record(r#type, epoch, keypair, dry_run)
                 .await
         }
+        RewardsCommands::CloseAllRecords {
+            epoch,
+            dry_run,
+            keypair,
+        } => {
+            orchestrator
+                .close_all_records(epoch, keypair, dry_run)
+                .await
+        }
         RewardsCommands::WriteTelemAgg {
             epoch,
             dry_run,
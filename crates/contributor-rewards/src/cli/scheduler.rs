@@ -115,8 +115,10 @@ async fn start_scheduler(
     let storage = if let Some(local_dir) = local_dir_override {
         // Use local filesystem regardless of config
         info!("Using local storage override: {:?}", local_dir);
-        Box::new(storage::local::LocalFileStorage::new(local_dir))
-            as Box<dyn storage::SnapshotStorage>
+        Box::new(
+            storage::local::LocalFileStorage::new(local_dir)
+                .with_compression_level(settings.scheduler.snapshot_compression_level),
+        ) as Box<dyn storage::SnapshotStorage>
     } else {
         // Use storage backend from config
         info!(
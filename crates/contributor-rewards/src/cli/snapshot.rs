@@ -65,6 +65,10 @@ impl CompleteSnapshot {
         // Write to temporary file first (atomic write pattern)
         let temp_path = path.with_extension("tmp");
 
+        std::fs::write(
+            storage::checksum::sidecar_path(path),
+            storage::checksum::checksum(contents.as_bytes()),
+        )?;
         std::fs::write(&temp_path, contents)?;
 
         // Atomically rename temp file to final location
@@ -74,11 +78,12 @@ impl CompleteSnapshot {
         Ok(())
     }
 
-    /// Load and validate snapshot from file
+    /// Load and validate snapshot from file, decompressing `.snapshot.zst` files
     pub fn load_from_file(path: &std::path::Path) -> Result<Self> {
         info!("Loading snapshot from: {:?}", path);
-        let contents = std::fs::read_to_string(path)?;
-        let snapshot: Self = serde_json::from_str(&contents)?;
+        let contents = std::fs::read(path)?;
+        storage::checksum::verify_file(path, &contents)?;
+        let snapshot = storage::compression::decode(&path.to_string_lossy(), &contents)?;
         snapshot.validate()?;
         info!("Snapshot loaded and validated successfully");
         Ok(snapshot)
@@ -191,7 +196,7 @@ pub async fn create_snapshot(
 
     // Use PreparedData to apply same processing as calculate-rewards
     // This includes previous epoch cache lookups and internet telemetry accumulator
-    let prep_data = PreparedData::new(&fetcher, epoch, false).await?;
+    let prep_data = PreparedData::new(&fetcher, epoch, false, None).await?;
     let fetch_epoch = prep_data.epoch;
 
     info!("Processed data for DZ epoch {}", fetch_epoch);
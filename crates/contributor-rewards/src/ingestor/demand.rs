@@ -1,6 +1,10 @@
-use std::collections::BTreeMap;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+};
 
-use anyhow::{Result, anyhow, bail};
+use anyhow::{Context, Result, anyhow, bail};
 use doublezero_serviceability::state::user::User as DZUser;
 use network_shapley::types::{Demand, Demands};
 use rayon::prelude::*;
@@ -114,6 +118,99 @@ pub fn build_with_schedule(
     })
 }
 
+/// Loads a fixed demand table from a JSON file instead of deriving it from the leader schedule
+/// NOTE: Intended for testing and what-if analysis
+///
+/// Every demand must start and end in a city that has at least one device in `fetch_data`.
+/// City stats are reconstructed from the demand receivers and priorities so that city
+/// weights stay consistent with the supplied demands.
+pub fn load_from_file(
+    path: &Path,
+    settings: &Settings,
+    fetch_data: &FetchData,
+) -> Result<DemandBuildOutput> {
+    let json = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read demand file {}", path.display()))?;
+    let demands: Demands = serde_json::from_str(&json)
+        .with_context(|| format!("Failed to parse demand file {}", path.display()))?;
+
+    if demands.is_empty() {
+        bail!("Demand file {} contains no demands", path.display())
+    }
+
+    validate_cities(&demands, &known_cities(settings, fetch_data))?;
+
+    info!(
+        "Loaded {} demands from override file {}",
+        demands.len(),
+        path.display()
+    );
+
+    let city_stats = city_stats_from_demands(&demands);
+
+    Ok(DemandBuildOutput {
+        demands,
+        city_stats,
+    })
+}
+
+/// Collect the city codes of all devices, using the same naming as `build_city_stats`
+pub fn known_cities(settings: &Settings, fetch_data: &FetchData) -> BTreeSet<String> {
+    let serviceability = &fetch_data.dz_serviceability;
+
+    serviceability
+        .devices
+        .values()
+        .filter_map(|device| match settings.network {
+            Network::Testnet | Network::Devnet => serviceability
+                .locations
+                .get(&device.location_pk)
+                .map(|location| location.code.to_uppercase()),
+            Network::MainnetBeta | Network::Mainnet => serviceability
+                .exchanges
+                .get(&device.exchange_pk)
+                .map(|exchange| exchange.code.to_uppercase()),
+        })
+        .collect()
+}
+
+/// Ensure every demand endpoint refers to a known city
+pub fn validate_cities(demands: &Demands, known_cities: &BTreeSet<String>) -> Result<()> {
+    let unknown: BTreeSet<&str> = demands
+        .iter()
+        .flat_map(|demand| [demand.start.as_str(), demand.end.as_str()])
+        .filter(|city| !known_cities.contains(*city))
+        .collect();
+
+    if !unknown.is_empty() {
+        bail!(
+            "Demands reference unknown cities: {}",
+            unknown.into_iter().collect::<Vec<_>>().join(", ")
+        )
+    }
+
+    Ok(())
+}
+
+/// Reconstruct city stats from demand destinations (inverse of `generate`)
+fn city_stats_from_demands(demands: &Demands) -> CityStats {
+    let mut city_stats = CityStats::new();
+
+    for demand in demands {
+        let total_stake_proxy =
+            (demand.priority * SLOTS_IN_EPOCH * demand.receivers as f64).round() as usize;
+        city_stats.insert(
+            demand.end.clone(),
+            CityStat {
+                validator_count: demand.receivers as usize,
+                total_stake_proxy,
+            },
+        );
+    }
+
+    city_stats
+}
+
 /// Build city statistics from fetch data and leader schedule
 pub fn build_city_stats(
     settings: &Settings,
@@ -1,6 +1,8 @@
 use std::cmp::Ordering;
 
 use anyhow::{Result, ensure};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
 
 use crate::processor::constants::{PENALTY_JITTER_US, PENALTY_RTT_US};
 
@@ -108,6 +110,83 @@ pub fn quantile_r_type7(sorted_values: &[f64], p: f64) -> f64 {
     lower + fraction * (upper - lower)
 }
 
+/// Sample quantile algorithm, numbered as in R's `quantile(type = ...)`
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum QuantileType {
+    /// Inverse of the empirical CDF, no interpolation
+    Type1,
+    /// Linear interpolation on (n+1)p, as used by Minitab and SPSS
+    Type6,
+    /// Linear interpolation on (n-1)p, R's default
+    #[default]
+    Type7,
+}
+
+/// Calculate quantile `p` of pre-sorted values using the given algorithm
+pub fn quantile(sorted_values: &[f64], p: f64, quantile_type: QuantileType) -> f64 {
+    match quantile_type {
+        QuantileType::Type1 => quantile_r_type1(sorted_values, p),
+        QuantileType::Type6 => quantile_r_type6(sorted_values, p),
+        QuantileType::Type7 => quantile_r_type7(sorted_values, p),
+    }
+}
+
+/// R type 1: the smallest value whose empirical CDF is at least p
+fn quantile_r_type1(sorted_values: &[f64], p: f64) -> f64 {
+    // Same tolerance R uses so that e.g. 10 * 0.9 lands exactly on 9
+    const FUZZ: f64 = 4.0 * f64::EPSILON;
+
+    let n = sorted_values.len();
+    if n == 0 {
+        return f64::NAN;
+    }
+
+    let np = n as f64 * p;
+    let j = (np + FUZZ).floor() as usize;
+    let index = if np - j as f64 > FUZZ {
+        j
+    } else {
+        j.saturating_sub(1)
+    };
+
+    sorted_values[index.min(n - 1)]
+}
+
+/// R type 6: h = (n+1) * p (1-based continuous index)
+fn quantile_r_type6(sorted_values: &[f64], p: f64) -> f64 {
+    let n = sorted_values.len();
+    if n == 0 {
+        return f64::NAN;
+    }
+
+    let h = (n + 1) as f64 * p;
+    if h <= 1.0 {
+        return sorted_values[0];
+    }
+    if h >= n as f64 {
+        return sorted_values[n - 1];
+    }
+
+    let h_floor = h.floor() as usize;
+    let lower = sorted_values[h_floor - 1];
+    let upper = sorted_values[h_floor];
+    let fraction = h - h_floor as f64;
+
+    lower + fraction * (upper - lower)
+}
+
 pub fn calculate_rtt_statistics(values: &[f64]) -> Result<RttStats> {
     if values.is_empty() {
         return Ok(RttStats::new_dead());
@@ -314,6 +393,56 @@ pub fn calculate_packet_loss_stats(samples: &[u32]) -> PacketLossStats {
 mod tests {
     use super::*;
 
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn test_quantile_p90_p99() {
+        let values = (1..=10).map(f64::from).collect::<Vec<_>>();
+
+        // Values match R's quantile(1:10, c(0.9, 0.99), type = ...)
+        assert_close(quantile(&values, 0.90, QuantileType::Type7), 9.1);
+        assert_close(quantile(&values, 0.99, QuantileType::Type7), 9.91);
+
+        assert_close(quantile(&values, 0.90, QuantileType::Type6), 9.9);
+        assert_close(quantile(&values, 0.99, QuantileType::Type6), 10.0);
+
+        assert_close(quantile(&values, 0.90, QuantileType::Type1), 9.0);
+        assert_close(quantile(&values, 0.99, QuantileType::Type1), 10.0);
+    }
+
+    #[test]
+    fn test_quantile_default_matches_type7_p95() {
+        let values = (1..=10).map(f64::from).collect::<Vec<_>>();
+
+        assert_eq!(QuantileType::default(), QuantileType::Type7);
+        assert_eq!(
+            quantile(&values, 0.95, QuantileType::default()),
+            quantile_r_type7(&values, 0.95)
+        );
+        assert_close(quantile(&values, 0.95, QuantileType::default()), 9.55);
+    }
+
+    #[test]
+    fn test_quantile_bounds() {
+        let values = vec![3.0, 5.0, 8.0];
+
+        for quantile_type in [
+            QuantileType::Type1,
+            QuantileType::Type6,
+            QuantileType::Type7,
+        ] {
+            assert_eq!(quantile(&values, 0.0, quantile_type), 3.0);
+            assert_eq!(quantile(&values, 1.0, quantile_type), 8.0);
+            assert_eq!(quantile(&[4.0], 0.5, quantile_type), 4.0);
+            assert!(quantile(&[], 0.5, quantile_type).is_nan());
+        }
+    }
+
     #[test]
     fn test_rtt_statistics() {
         let values = vec![100.0, 200.0, 300.0, 400.0, 500.0];
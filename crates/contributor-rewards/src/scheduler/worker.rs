@@ -256,8 +256,10 @@ impl ScheduleWorker {
                     None,
                     self.keypair_path.clone(),
                     Some(snapshot_path),
+                    None,
                     false,
                     WriteConfig::default(),
+                    false,
                 )
                 .await?;
 
@@ -609,10 +611,10 @@ impl ScheduleWorker {
         let snapshot_location = self.storage.save(&snapshot, &filename).await?;
 
         // For calculate_rewards, we need a local file path
-        // If using S3, create a temp file; if local storage, use the path directly
+        // If using S3 or GCS, create a temp file; if local storage, use the path directly
         let (local_path, temp_file_guard) =
             match self.orchestrator.settings.scheduler.storage_backend {
-                StorageBackend::S3 => {
+                StorageBackend::S3 | StorageBackend::Gcs => {
                     // Create a named temp file that will be automatically cleaned up when dropped
                     let temp_file = NamedTempFile::new()
                         .map_err(|e| anyhow!("Failed to create temp file: {}", e))?;
@@ -30,6 +30,8 @@ pub enum StorageBackend {
     /// S3-compatible storage (AWS S3, minio, etc.)
     #[default]
     S3,
+    /// Google Cloud Storage
+    Gcs,
     /// Local filesystem storage
     LocalFile,
 }
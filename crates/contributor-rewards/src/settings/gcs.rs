@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// Google Cloud Storage configuration for snapshot storage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GcsSettings {
+    /// GCS bucket name
+    /// Environment variable: DZ__GCS__BUCKET
+    pub bucket: String,
+
+    /// Path to the service account JSON key file
+    /// Environment variable: DZ__GCS__CREDENTIALS_PATH
+    pub credentials_path: String,
+}
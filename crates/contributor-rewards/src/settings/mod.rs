@@ -1,17 +1,21 @@
 pub mod aws;
+pub mod gcs;
 pub mod network;
 pub mod validation;
 
-use std::{fmt, net::SocketAddr, path::Path};
+use std::{collections::BTreeMap, fmt, net::SocketAddr, path::Path};
 
 use anyhow::{Context, Result};
 use aws::{AwsSettings, StorageBackend};
 use borsh::{BorshDeserialize, BorshSerialize};
 use config::{Config as ConfigBuilder, Environment, File};
+use gcs::GcsSettings;
 use network::Network;
 use serde::{Deserialize, Serialize};
 use validation::validate_config;
 
+use crate::{processor::util::QuantileType, storage::compression::DEFAULT_COMPRESSION_LEVEL};
+
 /// Main settings configuration for contributor-rewards
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
@@ -37,6 +41,9 @@ pub struct Settings {
     pub metrics: Option<MetricsSettings>,
     /// AWS S3 configuration for snapshot storage (required when storage_backend = S3)
     pub aws: Option<AwsSettings>,
+    /// GCS configuration for snapshot storage (required when storage_backend = Gcs)
+    #[serde(default)]
+    pub gcs: Option<GcsSettings>,
     /// Slack notification settings
     #[serde(default)]
     pub slack: Option<SlackSettings>,
@@ -54,6 +61,18 @@ pub struct ShapleySettings {
     /// Multiplier for demand-based rewards
     /// Increases rewards in high-demand areas
     pub demand_multiplier: f64,
+    /// Number of threads used to compute per-city Shapley values
+    /// 0 = one per CPU, 1 = sequential. Results are identical for any value,
+    /// so this is not recorded in the on-chain reward input
+    #[serde(default)]
+    #[borsh(skip)]
+    pub parallelism: usize,
+    /// Edge bandwidth in Gbps keyed by device code, for devices whose capacity
+    /// differs from the default. The resolved edges are recorded with the
+    /// devices in the reward input, so the overrides themselves are not
+    #[serde(default)]
+    #[borsh(skip)]
+    pub device_edge_bandwidth_gbps: BTreeMap<String, u32>,
 }
 
 /// RPC endpoint configuration for blockchain interactions
@@ -128,6 +147,55 @@ pub struct TelemetryDefaultSettings {
     /// Enable previous epoch lookup for public links
     /// If true, fetches previous epoch's average when current has insufficient data
     pub enable_previous_epoch_lookup: bool,
+    /// Quantile of combined samples used as private link latency (0.0-1.0)
+    #[serde(default = "default_private_link_quantile")]
+    pub private_link_quantile: f64,
+    /// Quantile algorithm used for private link latency (default: type7)
+    #[serde(default)]
+    pub private_link_quantile_type: QuantileType,
+    /// Curve mapping private link true uptime to the uptime passed to Shapley
+    #[serde(default)]
+    pub uptime_penalty: UptimePenaltySettings,
+    /// Directory to persist processed previous epoch stats across runs
+    /// If unset, previous epoch telemetry is refetched on every run
+    #[serde(default)]
+    pub previous_epoch_cache_dir: Option<String>,
+}
+
+/// Quadratic penalty curve applied to private link uptime (R line 92)
+/// uptime = clamp(quadratic_coeff * x^2 + linear_coeff * x + constant, 0, 1)
+/// The defaults heavily penalize links below 98% uptime:
+/// - 100% uptime -> 1.0 (no penalty)
+/// - 99% uptime -> 0.658 (~34% bandwidth reduction)
+/// - 98% uptime -> ~0 (threshold - effectively dropped)
+#[derive(
+    Debug, Clone, Copy, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+#[serde(default)]
+pub struct UptimePenaltySettings {
+    pub quadratic_coeff: f64,
+    pub linear_coeff: f64,
+    pub constant: f64,
+}
+
+impl Default for UptimePenaltySettings {
+    fn default() -> Self {
+        Self {
+            quadratic_coeff: -1578.9474,
+            linear_coeff: 3176.3158,
+            constant: -1596.3684,
+        }
+    }
+}
+
+impl UptimePenaltySettings {
+    /// Penalized uptime for a link with the given true uptime (0.0-1.0)
+    pub fn penalized_uptime(&self, true_uptime: f64) -> f64 {
+        let uptime_raw = self.quadratic_coeff * true_uptime.powi(2)
+            + self.linear_coeff * true_uptime
+            + self.constant;
+        uptime_raw.clamp(0.0, 1.0)
+    }
 }
 
 /// Scheduler configuration for automated rewards calculation
@@ -147,12 +215,23 @@ pub struct SchedulerSettings {
     /// Maximum time to wait for grace period in seconds (default: 21600 = 6 hours)
     #[serde(default = "default_grace_period_max_wait_seconds")]
     pub grace_period_max_wait_seconds: u64,
+    /// zstd compression level for stored snapshots, 1-22 (default: 3)
+    #[serde(default = "default_snapshot_compression_level")]
+    pub snapshot_compression_level: i32,
+}
+
+fn default_private_link_quantile() -> f64 {
+    0.95
 }
 
 fn default_grace_period_max_wait_seconds() -> u64 {
     21600
 }
 
+fn default_snapshot_compression_level() -> i32 {
+    DEFAULT_COMPRESSION_LEVEL
+}
+
 /// Metrics configuration for Prometheus
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricsSettings {
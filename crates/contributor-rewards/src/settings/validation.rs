@@ -28,6 +28,15 @@ pub fn validate_config(settings: &Settings) -> Result<()> {
         );
     }
 
+    if let Some((code, _)) = settings
+        .shapley
+        .device_edge_bandwidth_gbps
+        .iter()
+        .find(|(_, bandwidth)| **bandwidth == 0)
+    {
+        bail!("Shapley device_edge_bandwidth_gbps for device {code} must be positive");
+    }
+
     // Validate RPC settings
     if settings.rpc.dz_url.is_empty() {
         bail!("DZ RPC URL cannot be empty");
@@ -138,6 +147,33 @@ pub fn validate_config(settings: &Settings) -> Result<()> {
         );
     }
 
+    if !(0.0..=1.0).contains(&settings.telemetry_defaults.private_link_quantile) {
+        bail!(
+            "Telemetry defaults private_link_quantile must be between 0.0 and 1.0, got {}",
+            settings.telemetry_defaults.private_link_quantile
+        );
+    }
+
+    // The penalty curve must leave links with perfect uptime unpenalized
+    let full_uptime = settings
+        .telemetry_defaults
+        .uptime_penalty
+        .penalized_uptime(1.0);
+    if (full_uptime - 1.0).abs() > 1e-6 {
+        bail!(
+            "Telemetry defaults uptime_penalty must yield 1.0 at 100% uptime, got {}",
+            full_uptime
+        );
+    }
+
+    // Validate scheduler settings
+    if !(1..=22).contains(&settings.scheduler.snapshot_compression_level) {
+        bail!(
+            "Scheduler snapshot_compression_level must be between 1 and 22, got {}",
+            settings.scheduler.snapshot_compression_level
+        );
+    }
+
     if let Some(metrics) = &settings.metrics
         && !validate_socket_addr(&metrics.addr)
     {
@@ -175,11 +211,14 @@ mod tests {
     use std::{net::SocketAddr, str::FromStr};
 
     use super::*;
-    use crate::settings::{
-        InetLookbackSettings, MetricsSettings, PrefixSettings, ProgramSettings, RpcSettings,
-        SchedulerSettings, ShapleySettings, TelemetryDefaultSettings,
-        aws::{AwsSettings, StorageBackend},
-        network::Network,
+    use crate::{
+        processor::util::QuantileType,
+        settings::{
+            InetLookbackSettings, MetricsSettings, PrefixSettings, ProgramSettings, RpcSettings,
+            SchedulerSettings, ShapleySettings, TelemetryDefaultSettings, UptimePenaltySettings,
+            aws::{AwsSettings, StorageBackend},
+            network::Network,
+        },
     };
 
     fn create_valid_config() -> Settings {
@@ -190,6 +229,8 @@ mod tests {
                 operator_uptime: 0.98,
                 contiguity_bonus: 5.0,
                 demand_multiplier: 1.2,
+                parallelism: 0,
+                device_edge_bandwidth_gbps: Default::default(),
             },
             rpc: RpcSettings {
                 dz_url: "https://api.mainnet-beta.solana.com".to_string(),
@@ -219,6 +260,10 @@ mod tests {
                 missing_data_threshold: 0.7,
                 private_default_latency_ms: 1000.0,
                 enable_previous_epoch_lookup: true,
+                previous_epoch_cache_dir: None,
+                private_link_quantile: 0.95,
+                private_link_quantile_type: QuantileType::Type7,
+                uptime_penalty: UptimePenaltySettings::default(),
             },
             scheduler: SchedulerSettings {
                 interval_seconds: 300,
@@ -227,6 +272,7 @@ mod tests {
                 enable_dry_run: false,
                 storage_backend: StorageBackend::LocalFile,
                 grace_period_max_wait_seconds: 21600,
+                snapshot_compression_level: 3,
             },
             metrics: Some(MetricsSettings {
                 addr: SocketAddr::from_str("127.0.0.1:9090").unwrap(),
@@ -238,6 +284,7 @@ mod tests {
                 secret_access_key: "dummy-secret".to_string(),
                 endpoint: None,
             }),
+            gcs: None,
             slack: None,
         }
     }
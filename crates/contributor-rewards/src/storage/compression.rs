@@ -0,0 +1,91 @@
+use anyhow::{Context, Result};
+
+use crate::cli::snapshot::CompleteSnapshot;
+
+/// File extension for zstd-compressed snapshots
+pub const COMPRESSED_SUFFIX: &str = ".snapshot.zst";
+
+/// Default zstd compression level
+pub const DEFAULT_COMPRESSION_LEVEL: i32 = 3;
+
+/// Name a snapshot is stored under once compressed, e.g.
+/// `tn-epoch-5-snapshot.json` becomes `tn-epoch-5-snapshot.snapshot.zst`
+pub fn compressed_name(filename: &str) -> String {
+    if is_compressed(filename) {
+        return filename.to_string();
+    }
+    let stem = filename.strip_suffix(".json").unwrap_or(filename);
+    format!("{stem}{COMPRESSED_SUFFIX}")
+}
+
+/// Whether a snapshot filename follows the compressed naming convention
+pub fn is_compressed(filename: &str) -> bool {
+    filename.ends_with(COMPRESSED_SUFFIX)
+}
+
+/// Serialize a snapshot to JSON and compress it
+pub fn compress(snapshot: &CompleteSnapshot, level: i32) -> Result<Vec<u8>> {
+    let json_data = serde_json::to_vec(snapshot).context("Failed to serialize snapshot to JSON")?;
+    zstd::encode_all(json_data.as_slice(), level).context("Failed to compress snapshot")
+}
+
+/// Decompress and deserialize a snapshot written by `compress`
+pub fn decompress(data: &[u8]) -> Result<CompleteSnapshot> {
+    let json_data = zstd::decode_all(data).context("Failed to decompress snapshot")?;
+    serde_json::from_slice(&json_data).context("Failed to deserialize snapshot")
+}
+
+/// Decode snapshot bytes, decompressing when `filename` is compressed
+pub fn decode(filename: &str, data: &[u8]) -> Result<CompleteSnapshot> {
+    if is_compressed(filename) {
+        decompress(data)
+    } else {
+        serde_json::from_slice(data).context("Failed to deserialize snapshot")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cli::snapshot::SnapshotMetadata, ingestor::types::FetchData};
+
+    #[test]
+    fn test_compressed_snapshot_round_trip() {
+        let snapshot = CompleteSnapshot {
+            dz_epoch: 42,
+            solana_epoch: Some(800),
+            fetch_data: FetchData::default(),
+            leader_schedule: None,
+            metadata: SnapshotMetadata {
+                created_at: "2025-01-01T00:00:00+00:00".to_string(),
+                network: "Testnet".to_string(),
+                exchanges_count: 1,
+                locations_count: 2,
+                devices_count: 3,
+                internet_samples_count: 4,
+                device_samples_count: 5,
+            },
+        };
+
+        let data = compress(&snapshot, DEFAULT_COMPRESSION_LEVEL).unwrap();
+        let loaded = decode("tn-epoch-42-snapshot.snapshot.zst", &data).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&loaded).unwrap(),
+            serde_json::to_value(&snapshot).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_compressed_name() {
+        assert_eq!(
+            compressed_name("tn-epoch-5-snapshot.json"),
+            "tn-epoch-5-snapshot.snapshot.zst"
+        );
+        assert_eq!(
+            compressed_name("tn-epoch-5.snapshot.zst"),
+            "tn-epoch-5.snapshot.zst"
+        );
+        assert_eq!(compressed_name("snapshot"), "snapshot.snapshot.zst");
+    }
+}
@@ -0,0 +1,219 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use backon::{ExponentialBuilder, Retryable};
+use google_cloud_storage::{
+    client::{Client as GcsClient, ClientConfig, google_cloud_auth::credentials::CredentialsFile},
+    http::{
+        Error as GcsError,
+        objects::{
+            download::Range,
+            get::GetObjectRequest,
+            upload::{Media, UploadObjectRequest, UploadType},
+        },
+    },
+};
+use tracing::{error, info, warn};
+
+use crate::{
+    cli::snapshot::CompleteSnapshot,
+    settings::gcs::GcsSettings,
+    storage::{
+        SnapshotStorage, checksum,
+        compression::{self, DEFAULT_COMPRESSION_LEVEL},
+    },
+};
+
+/// Object operations `GcsStorage` needs from a GCS client
+#[async_trait]
+pub trait GcsObjectStore: Send + Sync {
+    /// Upload `data` as a zstd-compressed object, replacing any existing object
+    async fn upload(&self, bucket: &str, object: &str, data: Vec<u8>) -> Result<()>;
+
+    /// Check whether an object exists
+    async fn exists(&self, bucket: &str, object: &str) -> Result<bool>;
+
+    /// Download an object's contents
+    async fn download(&self, bucket: &str, object: &str) -> Result<Vec<u8>>;
+}
+
+#[async_trait]
+impl GcsObjectStore for GcsClient {
+    async fn upload(&self, bucket: &str, object: &str, data: Vec<u8>) -> Result<()> {
+        let mut media = Media::new(object.to_string());
+        media.content_type = "application/zstd".into();
+        media.content_length = Some(data.len() as u64);
+
+        self.upload_object(
+            &UploadObjectRequest {
+                bucket: bucket.to_string(),
+                ..Default::default()
+            },
+            data,
+            &UploadType::Simple(media),
+        )
+        .await
+        .map_err(|e| anyhow!("GCS upload error: {}", e))?;
+
+        Ok(())
+    }
+
+    async fn exists(&self, bucket: &str, object: &str) -> Result<bool> {
+        match self
+            .get_object(&GetObjectRequest {
+                bucket: bucket.to_string(),
+                object: object.to_string(),
+                ..Default::default()
+            })
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(GcsError::Response(e)) if e.code == 404 => Ok(false),
+            Err(e) => Err(anyhow!("Failed to check if snapshot exists: {}", e)),
+        }
+    }
+
+    async fn download(&self, bucket: &str, object: &str) -> Result<Vec<u8>> {
+        self.download_object(
+            &GetObjectRequest {
+                bucket: bucket.to_string(),
+                object: object.to_string(),
+                ..Default::default()
+            },
+            &Range::default(),
+        )
+        .await
+        .map_err(|e| anyhow!("GCS download error: {}", e))
+    }
+}
+
+pub struct GcsStorage {
+    client: Arc<dyn GcsObjectStore>,
+    bucket: String,
+    compression_level: i32,
+}
+
+impl GcsStorage {
+    pub async fn new(config: GcsSettings) -> Result<Self> {
+        let credentials = CredentialsFile::new_from_file(config.credentials_path.clone())
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to load GCS service account credentials from {}",
+                    config.credentials_path
+                )
+            })?;
+        let client_config = ClientConfig::default()
+            .with_credentials(credentials)
+            .await
+            .context("Failed to configure GCS client")?;
+        let client = GcsClient::new(client_config);
+
+        info!("GCS storage initialized, bucket: {}", config.bucket);
+
+        Ok(Self::with_client(Arc::new(client), config.bucket))
+    }
+
+    /// Build storage on top of an existing object client
+    pub fn with_client(client: Arc<dyn GcsObjectStore>, bucket: String) -> Self {
+        Self {
+            client,
+            bucket,
+            compression_level: DEFAULT_COMPRESSION_LEVEL,
+        }
+    }
+
+    /// Set the zstd level snapshots are compressed with
+    pub fn with_compression_level(mut self, compression_level: i32) -> Self {
+        self.compression_level = compression_level;
+        self
+    }
+
+    /// Upload with retry logic
+    async fn upload_with_retry(&self, object: &str, data: Vec<u8>) -> Result<()> {
+        let upload_fn = || async {
+            self.client
+                .upload(&self.bucket, object, data.clone())
+                .await
+                .inspect_err(|e| error!("GCS upload failed: {}", e))
+        };
+
+        // Retry with exponential backoff: 1s, 2s, 4s, 8s, 16s
+        (upload_fn.retry(ExponentialBuilder::default().with_max_times(5)))
+            .await
+            .context("Failed to upload snapshot to GCS after retries")
+    }
+}
+
+#[async_trait]
+impl SnapshotStorage for GcsStorage {
+    async fn save(&self, snapshot: &CompleteSnapshot, filename: &str) -> Result<String> {
+        let object = compression::compressed_name(filename);
+        info!("Uploading snapshot to GCS: {}/{}", self.bucket, object);
+
+        // Serialize and compress
+        let data = compression::compress(snapshot, self.compression_level)?;
+
+        info!("Snapshot compressed: {} bytes", data.len());
+
+        // Store the checksum in a sidecar object next to the snapshot
+        let sha256 = checksum::checksum(&data);
+        self.upload_with_retry(&object, data).await?;
+        self.upload_with_retry(&checksum::sidecar_name(&object), sha256.into_bytes())
+            .await?;
+
+        let gcs_url = format!("gs://{}/{}", self.bucket, object);
+
+        info!("Snapshot uploaded successfully: {}", gcs_url);
+        Ok(gcs_url)
+    }
+
+    async fn exists(&self, filename: &str) -> Result<bool> {
+        // Snapshots uploaded before compression was introduced are plain JSON
+        Ok(self
+            .client
+            .exists(&self.bucket, &compression::compressed_name(filename))
+            .await?
+            || self.client.exists(&self.bucket, filename).await?)
+    }
+
+    async fn load(&self, filename: &str) -> Result<CompleteSnapshot> {
+        let compressed_object = compression::compressed_name(filename);
+        let object = if self.client.exists(&self.bucket, &compressed_object).await? {
+            compressed_object
+        } else {
+            filename.to_string()
+        };
+
+        info!("Loading snapshot from GCS: {}/{}", self.bucket, object);
+
+        let data = self
+            .client
+            .download(&self.bucket, &object)
+            .await
+            .context("Failed to download snapshot from GCS")?;
+
+        let sidecar = checksum::sidecar_name(&object);
+        if self.client.exists(&self.bucket, &sidecar).await? {
+            let expected = self
+                .client
+                .download(&self.bucket, &sidecar)
+                .await
+                .context("Failed to download snapshot checksum from GCS")?;
+            checksum::verify(&object, &data, &String::from_utf8_lossy(&expected))?;
+        } else {
+            warn!("No checksum found for snapshot {object}, skipping verification");
+        }
+
+        let snapshot =
+            compression::decode(&object, &data).context("Failed to decode snapshot from GCS")?;
+
+        info!("Snapshot loaded successfully from GCS");
+        Ok(snapshot)
+    }
+
+    fn storage_type(&self) -> &'static str {
+        "GCS"
+    }
+}
@@ -4,15 +4,31 @@ use anyhow::{Context, Result};
 use async_trait::async_trait;
 use tracing::{info, warn};
 
-use crate::{cli::snapshot::CompleteSnapshot, storage::SnapshotStorage};
+use crate::{
+    cli::snapshot::CompleteSnapshot,
+    storage::{
+        SnapshotStorage, checksum,
+        compression::{self, DEFAULT_COMPRESSION_LEVEL},
+    },
+};
 
 pub struct LocalFileStorage {
     base_dir: PathBuf,
+    compression_level: i32,
 }
 
 impl LocalFileStorage {
     pub fn new(base_dir: PathBuf) -> Self {
-        Self { base_dir }
+        Self {
+            base_dir,
+            compression_level: DEFAULT_COMPRESSION_LEVEL,
+        }
+    }
+
+    /// Set the zstd level snapshots are compressed with
+    pub fn with_compression_level(mut self, compression_level: i32) -> Self {
+        self.compression_level = compression_level;
+        self
     }
 
     fn resolve_path(&self, filename: &str) -> PathBuf {
@@ -23,7 +39,7 @@ impl LocalFileStorage {
 #[async_trait]
 impl SnapshotStorage for LocalFileStorage {
     async fn save(&self, snapshot: &CompleteSnapshot, filename: &str) -> Result<String> {
-        let path = self.resolve_path(filename);
+        let path = self.resolve_path(&compression::compressed_name(filename));
         info!("Saving snapshot to local file: {:?}", path);
 
         // Ensure directory exists
@@ -31,10 +47,11 @@ impl SnapshotStorage for LocalFileStorage {
             tokio::fs::create_dir_all(parent).await?;
         }
 
-        // Write atomically
-        let contents = serde_json::to_string_pretty(snapshot)?;
+        // Write atomically, with the checksum in a sidecar file
+        let contents = compression::compress(snapshot, self.compression_level)?;
         let temp_path = path.with_extension("tmp");
 
+        tokio::fs::write(checksum::sidecar_path(&path), checksum::checksum(&contents)).await?;
         tokio::fs::write(&temp_path, contents).await?;
         tokio::fs::rename(&temp_path, &path).await?;
 
@@ -43,28 +60,41 @@ impl SnapshotStorage for LocalFileStorage {
     }
 
     async fn exists(&self, filename: &str) -> Result<bool> {
-        let path = self.resolve_path(filename);
-        Ok(tokio::fs::try_exists(&path)
-            .await
-            .map_err(|e| {
-                warn!("Failed to check file existence: {}", e);
-                e
-            })
-            .unwrap_or(false))
+        // Snapshots saved before compression was introduced are plain JSON
+        for name in [compression::compressed_name(filename), filename.to_string()] {
+            let exists = tokio::fs::try_exists(self.resolve_path(&name))
+                .await
+                .map_err(|e| {
+                    warn!("Failed to check file existence: {}", e);
+                    e
+                })
+                .unwrap_or(false);
+            if exists {
+                return Ok(true);
+            }
+        }
+        Ok(false)
     }
 
     async fn load(&self, filename: &str) -> Result<CompleteSnapshot> {
-        let path = self.resolve_path(filename);
+        let compressed_path = self.resolve_path(&compression::compressed_name(filename));
+        let path = if tokio::fs::try_exists(&compressed_path)
+            .await
+            .unwrap_or(false)
+        {
+            compressed_path
+        } else {
+            self.resolve_path(filename)
+        };
         info!("Loading snapshot from local file: {:?}", path);
 
-        let contents = tokio::fs::read_to_string(&path)
+        let contents = tokio::fs::read(&path)
             .await
             .context("Failed to read snapshot file")?;
 
-        let snapshot: CompleteSnapshot =
-            serde_json::from_str(&contents).context("Failed to deserialize snapshot")?;
+        checksum::verify_file(&path, &contents)?;
 
-        Ok(snapshot)
+        compression::decode(&path.to_string_lossy(), &contents)
     }
 
     fn storage_type(&self) -> &'static str {
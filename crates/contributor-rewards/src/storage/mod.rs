@@ -1,4 +1,7 @@
+pub mod checksum;
+pub mod compression;
 pub mod credentials;
+pub mod gcs;
 pub mod local;
 pub mod s3;
 
@@ -15,13 +18,15 @@ use crate::{
 /// Trait for snapshot storage backends
 #[async_trait]
 pub trait SnapshotStorage: Send + Sync {
-    /// Upload/save a snapshot and return its location (path or URL)
+    /// Upload/save a zstd-compressed snapshot and return its location (path
+    /// or URL). The stored name follows `compression::compressed_name`, and a
+    /// SHA-256 checksum of the stored bytes is kept alongside it.
     async fn save(&self, snapshot: &CompleteSnapshot, filename: &str) -> Result<String>;
 
     /// Verify a snapshot exists at the given location
     async fn exists(&self, filename: &str) -> Result<bool>;
 
-    /// Load a snapshot from the given location
+    /// Load a snapshot from the given location, failing on checksum mismatch
     async fn load(&self, filename: &str) -> Result<CompleteSnapshot>;
 
     /// Get storage type name for logging
@@ -30,19 +35,34 @@ pub trait SnapshotStorage: Send + Sync {
 
 /// Factory for creating storage backends
 pub async fn create_storage(settings: &Settings) -> Result<Box<dyn SnapshotStorage>> {
+    let compression_level = settings.scheduler.snapshot_compression_level;
     match settings.scheduler.storage_backend {
         StorageBackend::S3 => {
             // Create S3 storage
             let aws_config = settings.aws.as_ref().ok_or_else(|| {
                 anyhow!("AWS configuration is required when storage_backend = S3")
             })?;
-            let storage = s3::S3Storage::new(aws_config.clone()).await?;
+            let storage = s3::S3Storage::new(aws_config.clone())
+                .await?
+                .with_compression_level(compression_level);
+            Ok(Box::new(storage))
+        }
+        StorageBackend::Gcs => {
+            // Create GCS storage
+            let gcs_config = settings.gcs.as_ref().ok_or_else(|| {
+                anyhow!("GCS configuration is required when storage_backend = Gcs")
+            })?;
+            let storage = gcs::GcsStorage::new(gcs_config.clone())
+                .await?
+                .with_compression_level(compression_level);
             Ok(Box::new(storage))
         }
         StorageBackend::LocalFile => {
             // Create local file storage
             let path = PathBuf::from(&settings.scheduler.snapshot_dir);
-            Ok(Box::new(local::LocalFileStorage::new(path)))
+            Ok(Box::new(
+                local::LocalFileStorage::new(path).with_compression_level(compression_level),
+            ))
         }
     }
 }
@@ -2,17 +2,25 @@ use anyhow::{Context, Result, anyhow};
 use async_trait::async_trait;
 use aws_sdk_s3::{Client as S3Client, primitives::ByteStream, types::ServerSideEncryption};
 use backon::{ExponentialBuilder, Retryable};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use crate::{
     cli::snapshot::CompleteSnapshot,
     settings::aws::AwsSettings,
-    storage::{SnapshotStorage, credentials::CredentialLoader},
+    storage::{
+        SnapshotStorage, checksum,
+        compression::{self, DEFAULT_COMPRESSION_LEVEL},
+        credentials::CredentialLoader,
+    },
 };
 
+/// Object metadata key holding the SHA-256 checksum of a snapshot
+const CHECKSUM_METADATA_KEY: &str = "sha256";
+
 pub struct S3Storage {
     client: S3Client,
     bucket: String,
+    compression_level: i32,
 }
 
 impl S3Storage {
@@ -25,7 +33,17 @@ impl S3Storage {
 
         info!("S3 storage initialized, bucket: {}", bucket);
 
-        Ok(Self { client, bucket })
+        Ok(Self {
+            client,
+            bucket,
+            compression_level: DEFAULT_COMPRESSION_LEVEL,
+        })
+    }
+
+    /// Set the zstd level snapshots are compressed with
+    pub fn with_compression_level(mut self, compression_level: i32) -> Self {
+        self.compression_level = compression_level;
+        self
     }
 
     /// Compute Content-MD5 for integrity verification
@@ -40,6 +58,7 @@ impl S3Storage {
         let bucket = self.bucket.clone();
         let key = key.to_string();
         let content_md5 = content_md5.to_string();
+        let sha256 = checksum::checksum(&data);
 
         let upload_fn = || async {
             client
@@ -47,8 +66,9 @@ impl S3Storage {
                 .bucket(&bucket)
                 .key(&key)
                 .body(ByteStream::from(data.clone()))
-                .content_type("application/json")
+                .content_type("application/zstd")
                 .content_md5(&content_md5)
+                .metadata(CHECKSUM_METADATA_KEY, &sha256)
                 .server_side_encryption(ServerSideEncryption::Aes256)
                 .send()
                 .await
@@ -66,6 +86,26 @@ impl S3Storage {
         Ok(())
     }
 
+    async fn object_exists(&self, key: &str) -> Result<bool> {
+        match self
+            .client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(e) => {
+                if e.to_string().contains("NotFound") {
+                    Ok(false)
+                } else {
+                    Err(anyhow!("Failed to check if snapshot exists: {}", e))
+                }
+            }
+        }
+    }
+
     /// Verify upload succeeded
     async fn verify_upload(&self, key: &str, expected_size: usize) -> Result<()> {
         let head = self
@@ -94,65 +134,64 @@ impl S3Storage {
 #[async_trait]
 impl SnapshotStorage for S3Storage {
     async fn save(&self, snapshot: &CompleteSnapshot, filename: &str) -> Result<String> {
-        info!("Uploading snapshot to S3: {}/{}", self.bucket, filename);
+        let key = compression::compressed_name(filename);
+        info!("Uploading snapshot to S3: {}/{}", self.bucket, key);
 
-        // Serialize to pretty JSON
-        let json_data =
-            serde_json::to_vec_pretty(snapshot).context("Failed to serialize snapshot to JSON")?;
+        // Serialize and compress
+        let data = compression::compress(snapshot, self.compression_level)?;
 
-        let data_size = json_data.len();
-        let content_md5 = Self::compute_md5(&json_data);
+        let data_size = data.len();
+        let content_md5 = Self::compute_md5(&data);
 
         info!(
-            "Snapshot serialized: {} bytes, MD5: {}",
+            "Snapshot compressed: {} bytes, MD5: {}",
             data_size, content_md5
         );
 
         // Upload with retry
-        self.upload_with_retry(filename, json_data, &content_md5)
-            .await?;
+        self.upload_with_retry(&key, data, &content_md5).await?;
 
         // Verify upload
-        self.verify_upload(filename, data_size).await?;
+        self.verify_upload(&key, data_size).await?;
 
-        let s3_url = format!("https://{}.s3.amazonaws.com/{}", self.bucket, filename);
+        let s3_url = format!("https://{}.s3.amazonaws.com/{}", self.bucket, key);
 
         info!("Snapshot uploaded successfully: {}", s3_url);
         Ok(s3_url)
     }
 
     async fn exists(&self, filename: &str) -> Result<bool> {
-        match self
-            .client
-            .head_object()
-            .bucket(&self.bucket)
-            .key(filename)
-            .send()
-            .await
-        {
-            Ok(_) => Ok(true),
-            Err(e) => {
-                if e.to_string().contains("NotFound") {
-                    Ok(false)
-                } else {
-                    Err(anyhow!("Failed to check if snapshot exists: {}", e))
-                }
-            }
-        }
+        // Snapshots uploaded before compression was introduced are plain JSON
+        Ok(self
+            .object_exists(&compression::compressed_name(filename))
+            .await?
+            || self.object_exists(filename).await?)
     }
 
     async fn load(&self, filename: &str) -> Result<CompleteSnapshot> {
-        info!("Loading snapshot from S3: {}/{}", self.bucket, filename);
+        let compressed_key = compression::compressed_name(filename);
+        let key = if self.object_exists(&compressed_key).await? {
+            compressed_key
+        } else {
+            filename.to_string()
+        };
+
+        info!("Loading snapshot from S3: {}/{}", self.bucket, key);
 
         let response = self
             .client
             .get_object()
             .bucket(&self.bucket)
-            .key(filename)
+            .key(&key)
             .send()
             .await
             .context("Failed to download snapshot from S3")?;
 
+        let expected_checksum = response
+            .metadata()
+            .and_then(|metadata| metadata.get(CHECKSUM_METADATA_KEY))
+            .cloned();
+
         let data = response
             .body
             .collect()
@@ -160,8 +199,13 @@ impl SnapshotStorage for S3Storage {
             .context("Failed to read snapshot data")?
             .into_bytes();
 
-        let snapshot: CompleteSnapshot =
-            serde_json::from_slice(&data).context("Failed to deserialize snapshot from S3")?;
+        match expected_checksum {
+            Some(expected) => checksum::verify(&key, &data, &expected)?,
+            None => warn!("No checksum found for snapshot {key}, skipping verification"),
+        }
+
+        let snapshot =
+            compression::decode(&key, &data).context("Failed to decode snapshot from S3")?;
 
         info!("Snapshot loaded successfully from S3");
         Ok(snapshot)
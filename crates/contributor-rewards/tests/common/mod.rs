@@ -13,6 +13,8 @@ pub fn create_test_settings(
             operator_uptime: 0.98,
             contiguity_bonus: 5.0,
             demand_multiplier: 1.2,
+            parallelism: 0,
+            device_edge_bandwidth_gbps: Default::default(),
         },
         rpc: settings::RpcSettings {
             dz_url: "https://test.com".to_string(),
@@ -42,6 +44,11 @@ pub fn create_test_settings(
             missing_data_threshold: missing_threshold,
             private_default_latency_ms: private_default_ms,
             enable_previous_epoch_lookup: enable_previous,
+            previous_epoch_cache_dir: None,
+            private_link_quantile: 0.95,
+            private_link_quantile_type:
+                doublezero_contributor_rewards::processor::util::QuantileType::Type7,
+            uptime_penalty: settings::UptimePenaltySettings::default(),
         },
         scheduler: settings::SchedulerSettings {
             interval_seconds: 300,
@@ -50,6 +57,7 @@ pub fn create_test_settings(
             enable_dry_run: false,
             storage_backend: settings::aws::StorageBackend::LocalFile,
             grace_period_max_wait_seconds: 21600,
+            snapshot_compression_level: 3,
         },
         metrics: Some(settings::MetricsSettings {
             addr: "127.0.0.1:9090".parse().unwrap(),
@@ -61,6 +69,7 @@ pub fn create_test_settings(
             secret_access_key: "dummy-secret".to_string(),
             endpoint: None,
         }),
+        gcs: None,
         slack: None,
     }
 }
@@ -1,11 +1,13 @@
 mod common;
 
-use std::{fs, path::Path};
+use std::{fs, io::Write, path::Path};
 
 use anyhow::Result;
 use common::create_test_settings;
 use doublezero_contributor_rewards::ingestor::{demand, epoch::LeaderSchedule, types::FetchData};
+use network_shapley::types::Demand;
 use serde_json::Value;
+use tempfile::NamedTempFile;
 
 fn load_test_data() -> Result<FetchData> {
     let data_path = Path::new("tests/testnet_snapshot.json");
@@ -103,4 +105,74 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_demand_override_is_used_when_provided() -> Result<()> {
+        let settings = create_test_settings(0.7, 1000.0, false);
+        let fetch_data = load_test_data()?;
+
+        let demands = vec![
+            Demand {
+                start: "NYC".to_string(),
+                end: "LON".to_string(),
+                receivers: 3,
+                traffic: 0.05,
+                priority: 0.001,
+                kind: 1,
+                multicast: false,
+            },
+            Demand {
+                start: "LON".to_string(),
+                end: "NYC".to_string(),
+                receivers: 2,
+                traffic: 0.05,
+                priority: 0.002,
+                kind: 1,
+                multicast: false,
+            },
+        ];
+
+        let mut file = NamedTempFile::new()?;
+        file.write_all(serde_json::to_string(&demands)?.as_bytes())?;
+
+        let result = demand::load_from_file(file.path(), &settings, &fetch_data)?;
+
+        assert_eq!(result.demands.len(), 2);
+        assert_eq!(result.demands[0].start, "NYC");
+        assert_eq!(result.demands[0].end, "LON");
+        assert_eq!(result.demands[1].receivers, 2);
+
+        // City stats are derived from the override, not the leader schedule
+        assert_eq!(result.city_stats.len(), 2);
+        assert_eq!(result.city_stats["LON"].validator_count, 3);
+        assert_eq!(result.city_stats["LON"].total_stake_proxy, 1296);
+        assert_eq!(result.city_stats["NYC"].validator_count, 2);
+        assert_eq!(result.city_stats["NYC"].total_stake_proxy, 1728);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_demand_override_rejects_unknown_cities() -> Result<()> {
+        let settings = create_test_settings(0.7, 1000.0, false);
+        let fetch_data = load_test_data()?;
+
+        let demands = vec![Demand {
+            start: "NYC".to_string(),
+            end: "XYZ".to_string(),
+            receivers: 1,
+            traffic: 0.05,
+            priority: 0.001,
+            kind: 1,
+            multicast: false,
+        }];
+
+        let mut file = NamedTempFile::new()?;
+        file.write_all(serde_json::to_string(&demands)?.as_bytes())?;
+
+        let err = demand::load_from_file(file.path(), &settings, &fetch_data).unwrap_err();
+        assert!(err.to_string().contains("XYZ"), "unexpected error: {err}");
+
+        Ok(())
+    }
 }
@@ -0,0 +1,167 @@
+// Tests for GCS storage against an in-memory object store
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use doublezero_contributor_rewards::{
+    cli::snapshot::{CompleteSnapshot, SnapshotMetadata},
+    ingestor::types::FetchData,
+    storage::{
+        SnapshotStorage,
+        compression::compressed_name,
+        gcs::{GcsObjectStore, GcsStorage},
+    },
+};
+
+/// In-memory stand-in for a GCS bucket
+#[derive(Default)]
+struct MockGcsClient {
+    objects: Mutex<HashMap<(String, String), Vec<u8>>>,
+}
+
+#[async_trait]
+impl GcsObjectStore for MockGcsClient {
+    async fn upload(&self, bucket: &str, object: &str, data: Vec<u8>) -> Result<()> {
+        self.objects
+            .lock()
+            .unwrap()
+            .insert((bucket.to_string(), object.to_string()), data);
+        Ok(())
+    }
+
+    async fn exists(&self, bucket: &str, object: &str) -> Result<bool> {
+        Ok(self
+            .objects
+            .lock()
+            .unwrap()
+            .contains_key(&(bucket.to_string(), object.to_string())))
+    }
+
+    async fn download(&self, bucket: &str, object: &str) -> Result<Vec<u8>> {
+        self.objects
+            .lock()
+            .unwrap()
+            .get(&(bucket.to_string(), object.to_string()))
+            .cloned()
+            .ok_or_else(|| anyhow!("No such object: gs://{bucket}/{object}"))
+    }
+}
+
+fn test_snapshot(dz_epoch: u64) -> CompleteSnapshot {
+    CompleteSnapshot {
+        dz_epoch,
+        solana_epoch: Some(dz_epoch + 1),
+        fetch_data: FetchData::default(),
+        leader_schedule: None,
+        metadata: SnapshotMetadata {
+            created_at: chrono::Utc::now().to_rfc3339(),
+            network: "Testnet".to_string(),
+            exchanges_count: 0,
+            locations_count: 0,
+            devices_count: 0,
+            internet_samples_count: 0,
+            device_samples_count: 0,
+        },
+    }
+}
+
+#[tokio::test]
+async fn test_gcs_save_load_round_trip() {
+    let client = Arc::new(MockGcsClient::default());
+    let storage = GcsStorage::with_client(client.clone(), "test-bucket".to_string());
+
+    assert_eq!(storage.storage_type(), "GCS");
+
+    let snapshot = test_snapshot(777);
+    let filename = "test-gcs-snapshot-epoch-777.json";
+
+    let location = storage
+        .save(&snapshot, filename)
+        .await
+        .expect("Failed to save snapshot to GCS");
+    let object = compressed_name(filename);
+    assert_eq!(location, format!("gs://test-bucket/{object}"));
+
+    // The compressed object lands in the configured bucket
+    assert!(client.exists("test-bucket", &object).await.unwrap());
+    assert!(!client.exists("test-bucket", filename).await.unwrap());
+    assert!(!client.exists("other-bucket", &object).await.unwrap());
+
+    let loaded = storage
+        .load(filename)
+        .await
+        .expect("Failed to load snapshot from GCS");
+    assert_eq!(loaded.dz_epoch, snapshot.dz_epoch);
+    assert_eq!(loaded.solana_epoch, snapshot.solana_epoch);
+    assert_eq!(loaded.metadata.network, snapshot.metadata.network);
+    assert_eq!(
+        serde_json::to_value(&loaded).unwrap(),
+        serde_json::to_value(&snapshot).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_gcs_exists() {
+    let storage = GcsStorage::with_client(
+        Arc::new(MockGcsClient::default()),
+        "test-bucket".to_string(),
+    );
+    let filename = "test-gcs-snapshot-epoch-778.json";
+
+    assert!(!storage.exists(filename).await.unwrap());
+
+    storage
+        .save(&test_snapshot(778), filename)
+        .await
+        .expect("Failed to save snapshot to GCS");
+
+    assert!(storage.exists(filename).await.unwrap());
+    assert!(!storage.exists("missing.json").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_gcs_load_missing_object_fails() {
+    let storage = GcsStorage::with_client(
+        Arc::new(MockGcsClient::default()),
+        "test-bucket".to_string(),
+    );
+
+    assert!(storage.load("missing.json").await.is_err());
+}
+
+#[tokio::test]
+async fn test_gcs_load_rejects_corrupted_snapshot() {
+    let client = Arc::new(MockGcsClient::default());
+    let storage = GcsStorage::with_client(client.clone(), "test-bucket".to_string());
+    let filename = "test-gcs-snapshot-epoch-779.json";
+
+    storage
+        .save(&test_snapshot(779), filename)
+        .await
+        .expect("Failed to save snapshot to GCS");
+
+    // The checksum sidecar sits next to the snapshot and the snapshot verifies
+    let object = compressed_name(filename);
+    assert!(
+        client
+            .exists("test-bucket", &format!("{object}.sha256"))
+            .await
+            .unwrap()
+    );
+    assert!(storage.load(filename).await.is_ok());
+
+    // Flip a byte in the stored payload
+    client
+        .objects
+        .lock()
+        .unwrap()
+        .get_mut(&("test-bucket".to_string(), object))
+        .unwrap()[8] ^= 0xff;
+
+    let err = storage.load(filename).await.unwrap_err();
+    assert!(format!("{err:#}").contains("Checksum mismatch"));
+}
@@ -1,3 +1,5 @@
+mod recorder;
+
 use std::{collections::HashMap, fs, path::Path};
 
 use anyhow::Result;
@@ -7,6 +9,7 @@ use doublezero_contributor_rewards::{
     processor::internet::InternetTelemetryProcessor,
     settings,
 };
+use recorder::CountingRecorder;
 use serde_json::Value;
 
 fn load_test_data() -> Result<FetchData> {
@@ -28,6 +31,8 @@ fn test_settings() -> settings::Settings {
             operator_uptime: 0.98,
             contiguity_bonus: 5.0,
             demand_multiplier: 1.2,
+            parallelism: 0,
+            device_edge_bandwidth_gbps: Default::default(),
         },
         rpc: settings::RpcSettings {
             dz_url: "https://test.com".to_string(),
@@ -57,6 +62,11 @@ fn test_settings() -> settings::Settings {
             missing_data_threshold: 0.7,
             private_default_latency_ms: 1000.0,
             enable_previous_epoch_lookup: true,
+            previous_epoch_cache_dir: None,
+            private_link_quantile: 0.95,
+            private_link_quantile_type:
+                doublezero_contributor_rewards::processor::util::QuantileType::Type7,
+            uptime_penalty: settings::UptimePenaltySettings::default(),
         },
         scheduler: settings::SchedulerSettings {
             interval_seconds: 300,
@@ -65,6 +75,7 @@ fn test_settings() -> settings::Settings {
             enable_dry_run: false,
             storage_backend: settings::aws::StorageBackend::LocalFile,
             grace_period_max_wait_seconds: 21600,
+            snapshot_compression_level: 3,
         },
         metrics: Some(settings::MetricsSettings {
             addr: "127.0.0.1:9090".parse().unwrap(),
@@ -76,6 +87,7 @@ fn test_settings() -> settings::Settings {
             secret_access_key: "dummy-secret".to_string(),
             endpoint: None,
         }),
+        gcs: None,
         slack: None,
     }
 }
@@ -293,4 +305,30 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_public_links_built_metric() -> Result<()> {
+        let fetch_data = load_test_data()?;
+        let settings = test_settings();
+        let internet_stats = InternetTelemetryProcessor::process(&fetch_data)?;
+        let previous_epoch_cache = PreviousEpochCache::new();
+
+        let recorder = CountingRecorder::default();
+        let public_links = metrics::with_local_recorder(&recorder, || {
+            build_public_links(
+                &settings,
+                &internet_stats,
+                &fetch_data,
+                &previous_epoch_cache,
+            )
+        })?;
+
+        assert!(!public_links.is_empty());
+        assert_eq!(
+            recorder.counter("doublezero_contributor_rewards_public_links_built"),
+            public_links.len() as u64
+        );
+
+        Ok(())
+    }
 }
@@ -1,13 +1,28 @@
-use std::{collections::HashMap, fs, path::Path};
+mod recorder;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::Path,
+};
 
 use anyhow::Result;
 use doublezero_contributor_rewards::{
-    calculator::shapley::handler::{build_devices, build_private_links},
+    calculator::{
+        constants::DEFAULT_EDGE_BANDWIDTH_GBPS,
+        shapley::handler::{
+            LinkExclusionReason, build_devices, build_private_links,
+            build_private_links_with_report,
+        },
+    },
     ingestor::types::FetchData,
     processor::telemetry::DZDTelemetryProcessor,
     settings,
 };
+use doublezero_serviceability::state::{device::DeviceStatus, link::LinkStatus};
+use recorder::CountingRecorder;
 use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
 
 fn load_test_data() -> Result<FetchData> {
     let data_path = Path::new("tests/testnet_snapshot.json");
@@ -102,6 +117,8 @@ fn test_settings() -> settings::Settings {
             operator_uptime: 0.98,
             contiguity_bonus: 5.0,
             demand_multiplier: 1.2,
+            parallelism: 0,
+            device_edge_bandwidth_gbps: Default::default(),
         },
         rpc: settings::RpcSettings {
             dz_url: "https://test.com".to_string(),
@@ -131,6 +148,11 @@ fn test_settings() -> settings::Settings {
             missing_data_threshold: 0.7,
             private_default_latency_ms: 1000.0,
             enable_previous_epoch_lookup: true,
+            previous_epoch_cache_dir: None,
+            private_link_quantile: 0.95,
+            private_link_quantile_type:
+                doublezero_contributor_rewards::processor::util::QuantileType::Type7,
+            uptime_penalty: settings::UptimePenaltySettings::default(),
         },
         scheduler: settings::SchedulerSettings {
             interval_seconds: 300,
@@ -139,6 +161,7 @@ fn test_settings() -> settings::Settings {
             enable_dry_run: false,
             storage_backend: settings::aws::StorageBackend::LocalFile,
             grace_period_max_wait_seconds: 21600,
+            snapshot_compression_level: 3,
         },
         metrics: Some(settings::MetricsSettings {
             addr: "127.0.0.1:9090".parse().unwrap(),
@@ -150,6 +173,7 @@ fn test_settings() -> settings::Settings {
             secret_access_key: "dummy-secret".to_string(),
             endpoint: None,
         }),
+        gcs: None,
         slack: None,
     }
 }
@@ -187,7 +211,7 @@ mod tests {
         println!("Processed {} device telemetry stats", telemetry_stats.len());
 
         // Build devices to obtain the shapley device identifiers and mapping back to original codes
-        let (devices, device_ids) = build_devices(&fetch_data, &settings.network)?;
+        let (devices, device_ids) = build_devices(&settings, &fetch_data)?;
         println!("Constructed {} shapley device identifiers", devices.len());
 
         let mut shapley_to_original: HashMap<String, String> = HashMap::new();
@@ -198,7 +222,7 @@ mod tests {
         }
 
         // Generate private links
-        let private_links = build_private_links(&fetch_data, &device_ids);
+        let private_links = build_private_links(&settings, &fetch_data, &device_ids);
 
         // Print results for verification
         println!("\nPrivate Links Generated:");
@@ -323,6 +347,162 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_private_link_report_exclusion_reasons() -> Result<()> {
+        let mut fetch_data = load_test_data()?;
+        let settings = test_settings();
+
+        let link_pk_by_code = |fetch_data: &FetchData, code: &str| -> Pubkey {
+            *fetch_data
+                .dz_serviceability
+                .links
+                .iter()
+                .find(|(_, link)| link.code == code)
+                .unwrap()
+                .0
+        };
+        let device_pk_by_code = |fetch_data: &FetchData, code: &str| -> Pubkey {
+            *fetch_data
+                .dz_serviceability
+                .devices
+                .iter()
+                .find(|(_, device)| device.code == code)
+                .unwrap()
+                .0
+        };
+
+        // Inactive link
+        let tyo_lax_pk = link_pk_by_code(&fetch_data, "tyo-dz001:lax-dz001");
+        fetch_data
+            .dz_serviceability
+            .links
+            .get_mut(&tyo_lax_pk)
+            .unwrap()
+            .status = LinkStatus::Pending;
+
+        // Inactive device (ams-dz001 only has the ams-dz001:lon-dz001 link)
+        let ams_pk = device_pk_by_code(&fetch_data, "ams-dz001");
+        fetch_data
+            .dz_serviceability
+            .devices
+            .get_mut(&ams_pk)
+            .unwrap()
+            .status = DeviceStatus::Pending;
+
+        // Missing device ID
+        let (_devices, mut device_ids) = build_devices(&settings, &fetch_data)?;
+        let nyc_pk = device_pk_by_code(&fetch_data, "nyc-dz001");
+        device_ids.remove(&nyc_pk);
+
+        let (private_links, link_report) =
+            build_private_links_with_report(&settings, &fetch_data, &device_ids);
+
+        // Every candidate link is reported
+        assert_eq!(link_report.len(), fetch_data.dz_serviceability.links.len());
+        assert_eq!(
+            link_report
+                .iter()
+                .filter(|entry| entry.exclusion_reason.is_none())
+                .count(),
+            private_links.len()
+        );
+
+        let reason_for = |code: &str| {
+            link_report
+                .iter()
+                .find(|entry| entry.code == code)
+                .unwrap()
+                .exclusion_reason
+        };
+
+        assert_eq!(
+            reason_for("tyo-dz001:lax-dz001"),
+            Some(LinkExclusionReason::InactiveLink)
+        );
+        assert_eq!(
+            reason_for("ams-dz001:lon-dz001"),
+            Some(LinkExclusionReason::InactiveDevice)
+        );
+        assert_eq!(
+            reason_for("nyc-dz001:lon-dz001"),
+            Some(LinkExclusionReason::MissingDeviceId)
+        );
+        assert_eq!(
+            reason_for("lax-dz001:nyc-dz001"),
+            Some(LinkExclusionReason::MissingDeviceId)
+        );
+        assert_eq!(
+            reason_for("fra-dz001:fra-dz-001-x"),
+            Some(LinkExclusionReason::TooFewSamples)
+        );
+        assert_eq!(reason_for("lon-dz001:fra-dz001"), None);
+
+        let too_few_samples = link_report
+            .iter()
+            .find(|entry| entry.code == "fra-dz001:fra-dz-001-x")
+            .unwrap();
+        assert_eq!(too_few_samples.valid_samples, Some(0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_device_ids_are_deterministic() -> Result<()> {
+        let fetch_data = load_test_data()?;
+        let settings = test_settings();
+
+        let (first_devices, first_device_ids) = build_devices(&settings, &fetch_data)?;
+        let (_, second_device_ids) = build_devices(&settings, &fetch_data)?;
+        assert_eq!(first_device_ids, second_device_ids);
+
+        // Same devices in reverse insertion order must produce the same IDs
+        let mut reversed_fetch_data = fetch_data.clone();
+        reversed_fetch_data.dz_serviceability.devices.reverse();
+        let (reversed_devices, reversed_device_ids) =
+            build_devices(&settings, &reversed_fetch_data)?;
+        assert_eq!(first_device_ids, reversed_device_ids);
+        assert_eq!(first_devices.len(), reversed_devices.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_device_edge_bandwidth_overrides() -> Result<()> {
+        let fetch_data = load_test_data()?;
+        let mut settings = test_settings();
+
+        // Pick two devices that make it into the shapley devices
+        let (_, device_ids) = build_devices(&settings, &fetch_data)?;
+        let included = device_ids
+            .iter()
+            .filter_map(|(device_pk, shapley_id)| {
+                let device = fetch_data.dz_serviceability.devices.get(device_pk)?;
+                Some((device.code.clone(), shapley_id.clone()))
+            })
+            .take(2)
+            .collect::<Vec<_>>();
+        assert_eq!(included.len(), 2);
+        let (small_code, small_id) = included[0].clone();
+        let (large_code, large_id) = included[1].clone();
+
+        settings.shapley.device_edge_bandwidth_gbps =
+            BTreeMap::from([(small_code, 10), (large_code, 400)]);
+        let (devices, _) = build_devices(&settings, &fetch_data)?;
+
+        for device in &devices {
+            let expected = if device.device == small_id {
+                10
+            } else if device.device == large_id {
+                400
+            } else {
+                DEFAULT_EDGE_BANDWIDTH_GBPS
+            };
+            assert_eq!(device.edge, expected, "edge for {}", device.device);
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_link_data_integrity() -> Result<()> {
         let fetch_data = load_test_data()?;
@@ -435,4 +615,135 @@ mod tests {
             "99.9% uptime calculation failed"
         );
     }
+
+    #[test]
+    fn test_private_links_dropped_metric_by_reason() -> Result<()> {
+        let mut fetch_data = load_test_data()?;
+        let settings = test_settings();
+
+        let tyo_lax_pk = *fetch_data
+            .dz_serviceability
+            .links
+            .iter()
+            .find(|(_, link)| link.code == "tyo-dz001:lax-dz001")
+            .unwrap()
+            .0;
+        fetch_data
+            .dz_serviceability
+            .links
+            .get_mut(&tyo_lax_pk)
+            .unwrap()
+            .status = LinkStatus::Pending;
+
+        let (_devices, device_ids) = build_devices(&settings, &fetch_data)?;
+
+        let recorder = CountingRecorder::default();
+        let (_private_links, link_report) = metrics::with_local_recorder(&recorder, || {
+            build_private_links_with_report(&settings, &fetch_data, &device_ids)
+        });
+
+        let reported = |reason: LinkExclusionReason| {
+            link_report
+                .iter()
+                .filter(|entry| entry.exclusion_reason == Some(reason))
+                .count() as u64
+        };
+
+        for reason in [
+            LinkExclusionReason::InactiveLink,
+            LinkExclusionReason::InactiveDevice,
+            LinkExclusionReason::MissingDeviceId,
+            LinkExclusionReason::TooFewSamples,
+        ] {
+            assert_eq!(
+                recorder.counter(&format!(
+                    "doublezero_contributor_rewards_private_links_dropped{{reason={}}}",
+                    reason.metric_label()
+                )),
+                reported(reason),
+                "unexpected count for {reason}"
+            );
+        }
+
+        assert_eq!(
+            recorder.counter(
+                "doublezero_contributor_rewards_private_links_dropped{reason=inactive_link}"
+            ),
+            1
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_private_links_zero_uptime_counted_separately() -> Result<()> {
+        let mut fetch_data = load_test_data()?;
+        let settings = test_settings();
+
+        let tyo_lax_pk = *fetch_data
+            .dz_serviceability
+            .links
+            .iter()
+            .find(|(_, link)| link.code == "tyo-dz001:lax-dz001")
+            .unwrap()
+            .0;
+
+        // Pad the link's samples with an equal number of losses so only half
+        // are valid, which the default penalty curve takes down to zero uptime
+        for sample in fetch_data
+            .dz_telemetry
+            .device_latency_samples
+            .iter_mut()
+            .filter(|sample| sample.link_pk == tyo_lax_pk)
+        {
+            let sample_count = sample.samples.len();
+            sample.samples.extend(std::iter::repeat_n(0, sample_count));
+        }
+
+        let (_devices, device_ids) = build_devices(&settings, &fetch_data)?;
+
+        let recorder = CountingRecorder::default();
+        let (private_links, link_report) = metrics::with_local_recorder(&recorder, || {
+            build_private_links_with_report(&settings, &fetch_data, &device_ids)
+        });
+
+        let tyo_lax = &fetch_data.dz_serviceability.links[&tyo_lax_pk];
+        let (tyo_id, lax_id) = (
+            &device_ids[&tyo_lax.side_a_pk],
+            &device_ids[&tyo_lax.side_z_pk],
+        );
+        let padded_link = private_links
+            .iter()
+            .find(|link| &link.device1 == tyo_id && &link.device2 == lax_id)
+            .expect("zero-uptime link should be kept");
+        assert_eq!(padded_link.uptime, 0.0);
+
+        let zero_uptime_links = private_links
+            .iter()
+            .filter(|link| link.uptime == 0.0)
+            .count() as u64;
+        assert_eq!(
+            recorder.counter("doublezero_contributor_rewards_private_links_zero_uptime"),
+            zero_uptime_links
+        );
+
+        // Zero-uptime links are kept, so they are not counted as dropped
+        let excluded = link_report
+            .iter()
+            .filter(|entry| entry.exclusion_reason.is_some())
+            .count();
+        assert_eq!(
+            private_links.len() + excluded,
+            link_report.len(),
+            "every reported link is either kept or excluded"
+        );
+        assert_eq!(
+            recorder.counter(
+                "doublezero_contributor_rewards_private_links_dropped{reason=zero_uptime}"
+            ),
+            0
+        );
+
+        Ok(())
+    }
 }
@@ -9,7 +9,11 @@ use doublezero_contributor_rewards::{
         aws::{AwsSettings, StorageBackend},
         network::Network,
     },
-    storage::create_storage,
+    storage::{
+        checksum::sidecar_path,
+        compression::{COMPRESSED_SUFFIX, compressed_name},
+        create_storage,
+    },
 };
 
 /// Helper function to create dummy AWS settings for tests that don't use S3
@@ -39,12 +43,15 @@ fn create_test_settings(
             enable_dry_run: false,
             storage_backend,
             grace_period_max_wait_seconds: 21600,
+            snapshot_compression_level: 3,
         },
         aws,
         shapley: doublezero_contributor_rewards::settings::ShapleySettings {
             operator_uptime: 0.98,
             contiguity_bonus: 5.0,
             demand_multiplier: 1.2,
+            parallelism: 0,
+            device_edge_bandwidth_gbps: Default::default(),
         },
         rpc: doublezero_contributor_rewards::settings::RpcSettings {
             dz_url: "https://test.com".to_string(),
@@ -74,8 +81,15 @@ fn create_test_settings(
             missing_data_threshold: 0.7,
             private_default_latency_ms: 1000.0,
             enable_previous_epoch_lookup: true,
+            previous_epoch_cache_dir: None,
+            private_link_quantile: 0.95,
+            private_link_quantile_type:
+                doublezero_contributor_rewards::processor::util::QuantileType::Type7,
+            uptime_penalty:
+                doublezero_contributor_rewards::settings::UptimePenaltySettings::default(),
         },
         metrics: None,
+        gcs: None,
         slack: None,
     }
 }
@@ -193,7 +207,7 @@ async fn test_local_file_storage() {
         .expect("Failed to save snapshot locally");
 
     println!("Snapshot saved to: {}", location);
-    assert!(location.contains(filename));
+    assert!(location.ends_with(&compressed_name(filename)));
 
     // Test exists
     let exists = storage
@@ -212,3 +226,195 @@ async fn test_local_file_storage() {
 
     println!("✓ Local file storage test passed!");
 }
+
+#[tokio::test]
+async fn test_gcs_storage_requires_gcs_settings() {
+    let settings = create_test_settings(
+        StorageBackend::Gcs,
+        "/tmp/snapshots".to_string(),
+        create_dummy_aws_settings(),
+    );
+
+    let err = create_storage(&settings)
+        .await
+        .err()
+        .expect("GCS backend without [gcs] settings should fail");
+    assert!(
+        err.to_string()
+            .contains("GCS configuration is required when storage_backend = Gcs")
+    );
+}
+
+#[tokio::test]
+async fn test_local_file_storage_compressed_round_trip() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+
+    let mut settings = create_test_settings(
+        StorageBackend::LocalFile,
+        temp_dir.path().to_string_lossy().to_string(),
+        create_dummy_aws_settings(),
+    );
+    settings.scheduler.snapshot_compression_level = 19;
+
+    let storage = create_storage(&settings)
+        .await
+        .expect("Failed to create storage");
+
+    let snapshot = CompleteSnapshot {
+        dz_epoch: 889,
+        solana_epoch: Some(901),
+        fetch_data: FetchData::default(),
+        leader_schedule: None,
+        metadata: SnapshotMetadata {
+            created_at: chrono::Utc::now().to_rfc3339(),
+            network: "Testnet".to_string(),
+            exchanges_count: 1,
+            locations_count: 2,
+            devices_count: 3,
+            internet_samples_count: 4,
+            device_samples_count: 5,
+        },
+    };
+
+    let filename = "test-local-snapshot-epoch-889.json";
+    let location = storage
+        .save(&snapshot, filename)
+        .await
+        .expect("Failed to save snapshot locally");
+
+    // Only the compressed file is written, and it is zstd rather than JSON
+    assert!(location.ends_with(COMPRESSED_SUFFIX));
+    assert!(!temp_dir.path().join(filename).exists());
+    let bytes = std::fs::read(&location).expect("Failed to read compressed snapshot");
+    assert_eq!(&bytes[..4], &[0x28, 0xb5, 0x2f, 0xfd]);
+
+    let loaded = storage
+        .load(filename)
+        .await
+        .expect("Failed to load compressed snapshot");
+    assert_eq!(
+        serde_json::to_value(&loaded).unwrap(),
+        serde_json::to_value(&snapshot).unwrap()
+    );
+
+    // Loading by the stored name works too
+    let loaded = storage
+        .load(&compressed_name(filename))
+        .await
+        .expect("Failed to load compressed snapshot by its stored name");
+    assert_eq!(loaded.dz_epoch, snapshot.dz_epoch);
+}
+
+#[tokio::test]
+async fn test_local_file_storage_loads_uncompressed_snapshot() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+
+    let settings = create_test_settings(
+        StorageBackend::LocalFile,
+        temp_dir.path().to_string_lossy().to_string(),
+        create_dummy_aws_settings(),
+    );
+
+    let storage = create_storage(&settings)
+        .await
+        .expect("Failed to create storage");
+
+    let snapshot = CompleteSnapshot {
+        dz_epoch: 890,
+        solana_epoch: None,
+        fetch_data: FetchData::default(),
+        leader_schedule: None,
+        metadata: SnapshotMetadata {
+            created_at: chrono::Utc::now().to_rfc3339(),
+            network: "Testnet".to_string(),
+            exchanges_count: 0,
+            locations_count: 0,
+            devices_count: 0,
+            internet_samples_count: 0,
+            device_samples_count: 0,
+        },
+    };
+
+    // Snapshots written before compression was introduced are plain JSON
+    let filename = "test-local-snapshot-epoch-890.json";
+    std::fs::write(
+        temp_dir.path().join(filename),
+        serde_json::to_string_pretty(&snapshot).unwrap(),
+    )
+    .unwrap();
+
+    assert!(storage.exists(filename).await.unwrap());
+    let loaded = storage
+        .load(filename)
+        .await
+        .expect("Failed to load uncompressed snapshot");
+    assert_eq!(loaded.dz_epoch, snapshot.dz_epoch);
+}
+
+#[tokio::test]
+async fn test_local_file_storage_rejects_corrupted_snapshot() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+
+    let settings = create_test_settings(
+        StorageBackend::LocalFile,
+        temp_dir.path().to_string_lossy().to_string(),
+        create_dummy_aws_settings(),
+    );
+
+    let storage = create_storage(&settings)
+        .await
+        .expect("Failed to create storage");
+
+    let snapshot = CompleteSnapshot {
+        dz_epoch: 891,
+        solana_epoch: Some(902),
+        fetch_data: FetchData::default(),
+        leader_schedule: None,
+        metadata: SnapshotMetadata {
+            created_at: chrono::Utc::now().to_rfc3339(),
+            network: "Testnet".to_string(),
+            exchanges_count: 0,
+            locations_count: 0,
+            devices_count: 0,
+            internet_samples_count: 0,
+            device_samples_count: 0,
+        },
+    };
+
+    let filename = "test-local-snapshot-epoch-891.json";
+    let location = storage
+        .save(&snapshot, filename)
+        .await
+        .expect("Failed to save snapshot locally");
+
+    // A valid snapshot loads and has a checksum sidecar
+    let location = std::path::PathBuf::from(location);
+    assert!(sidecar_path(&location).exists());
+    let loaded = storage
+        .load(filename)
+        .await
+        .expect("Failed to load valid snapshot");
+    assert_eq!(loaded.dz_epoch, snapshot.dz_epoch);
+
+    // Flip a byte in the stored payload
+    let mut bytes = std::fs::read(&location).unwrap();
+    bytes[8] ^= 0xff;
+    std::fs::write(&location, bytes).unwrap();
+
+    let err = storage
+        .load(filename)
+        .await
+        .expect_err("Corrupted snapshot should fail to load");
+    assert!(format!("{err:#}").contains("Checksum mismatch"));
+
+    // Loading the file directly, as `--snapshot` does, fails the same way
+    let err = CompleteSnapshot::load_from_file(&location)
+        .expect_err("Corrupted snapshot should fail to load from file");
+    assert!(format!("{err:#}").contains("Checksum mismatch"));
+}
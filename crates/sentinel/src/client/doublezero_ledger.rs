@@ -25,6 +25,8 @@ use crate::{Result, new_transaction};
 #[automock]
 #[async_trait]
 pub trait DzRpcClientType {
+    fn serviceability_id(&self) -> Pubkey;
+
     async fn issue_access_pass(
         &self,
         service_key: &Pubkey,
@@ -41,6 +43,10 @@ pub struct DzRpcClient {
 
 #[async_trait]
 impl DzRpcClientType for DzRpcClient {
+    fn serviceability_id(&self) -> Pubkey {
+        self.serviceability_id
+    }
+
     async fn issue_access_pass(
         &self,
         service_key: &Pubkey,
@@ -26,6 +26,7 @@ use solana_client::{
         RpcTransactionConfig,
     },
     rpc_filter::{Memcmp, RpcFilterType},
+    rpc_response::RpcLeaderSchedule,
 };
 use solana_commitment_config::{CommitmentConfig, CommitmentLevel};
 use solana_sdk::{
@@ -45,7 +46,7 @@ use crate::{AccessId, Error, Result, new_transaction};
 
 const ACCESS_REQUEST_ACCOUNT_INDEX: usize = 2;
 
-const SLOTS_PER_EPOCH: u64 = 432_000;
+pub(crate) const SLOTS_PER_EPOCH: u64 = 432_000;
 
 #[automock]
 #[async_trait]
@@ -72,6 +73,11 @@ pub trait SolRpcClientType {
     ) -> Result<bool>;
 
     async fn get_validator_ip(&self, validator_id: &Pubkey) -> Result<Option<Ipv4Addr>>;
+
+    async fn get_slot(&self) -> Result<u64>;
+
+    /// Full leader schedule of the epoch containing `slot`
+    async fn get_leader_schedule(&self, slot: u64) -> Result<Option<RpcLeaderSchedule>>;
 }
 
 pub struct SolRpcClient {
@@ -117,6 +123,14 @@ impl SolRpcClientType for SolRpcClient {
     async fn get_validator_ip(&self, validator_id: &Pubkey) -> Result<Option<Ipv4Addr>> {
         self.get_validator_ip(validator_id).await
     }
+
+    async fn get_slot(&self) -> Result<u64> {
+        Ok(self.client.get_slot().await?)
+    }
+
+    async fn get_leader_schedule(&self, slot: u64) -> Result<Option<RpcLeaderSchedule>> {
+        Ok(self.client.get_leader_schedule(Some(slot)).await?)
+    }
 }
 
 impl SolRpcClient {
@@ -362,7 +376,7 @@ fn is_request_access_instruction(ix: &CompiledInstruction, static_account_keys:
             == PassportInstructionData::REQUEST_ACCESS
 }
 
-struct PreviousEpochSlots(u64);
+pub(crate) struct PreviousEpochSlots(pub u64);
 
 impl Iterator for PreviousEpochSlots {
     type Item = u64;
@@ -29,6 +29,8 @@ pub enum Error {
     BorshIo(#[from] borsh::io::Error),
     #[error("deserialization error: {0}")]
     Deserialize(String),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
     #[error("instruction not found in transaction: {0}")]
     InstructionNotFound(Signature),
     #[error("invalid instruction data: {0}")]
@@ -45,6 +47,8 @@ pub enum Error {
     ReqChannel(#[from] tokio::sync::mpsc::error::SendError<Signature>),
     #[error("rpc client error: {0}")]
     RpcClient(Box<ClientError>),
+    #[error("json serialization error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
     #[error("invalid transaction signature: {0}")]
     SignatureInvalid(#[from] ParseSignatureError),
     #[error("access request signature did not verify")]
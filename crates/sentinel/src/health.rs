@@ -0,0 +1,194 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    time::timeout,
+};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::Result;
+
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Completion time of the last poll loop, shared between the poller and the
+/// readiness probe
+#[derive(Clone, Debug, Default)]
+pub struct PollHealth {
+    last_poll: Arc<Mutex<Option<Instant>>>,
+}
+
+impl PollHealth {
+    pub fn record_poll(&self) {
+        *self.last_poll.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// Ready once a poll loop has completed within `threshold`
+    pub fn is_ready(&self, threshold: Duration) -> bool {
+        self.last_poll
+            .lock()
+            .unwrap()
+            .is_some_and(|last_poll| last_poll.elapsed() <= threshold)
+    }
+}
+
+/// Serve `/healthz` (process up) and `/readyz` (last poll loop completed
+/// within `ready_threshold`) until shutdown
+pub async fn serve(
+    listener: TcpListener,
+    health: PollHealth,
+    ready_threshold: Duration,
+    shutdown_listener: CancellationToken,
+) -> Result<()> {
+    info!(addr = %listener.local_addr()?, "health endpoint listening");
+
+    loop {
+        tokio::select! {
+            biased;
+            _ = shutdown_listener.cancelled() => break,
+            accepted = listener.accept() => {
+                let (stream, peer) = match accepted {
+                    Ok(accepted) => accepted,
+                    Err(err) => {
+                        warn!(?err, "failed to accept health connection");
+                        continue;
+                    }
+                };
+
+                let health = health.clone();
+                tokio::spawn(async move {
+                    if let Err(err) = handle_connection(stream, &health, ready_threshold).await {
+                        debug!(?err, %peer, "health connection failed");
+                    }
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    health: &PollHealth,
+    ready_threshold: Duration,
+) -> Result<()> {
+    let path = match timeout(REQUEST_READ_TIMEOUT, read_request_path(&mut stream)).await {
+        Ok(path) => path?,
+        Err(_) => None,
+    };
+
+    let (status, body) = match path.as_deref() {
+        Some("/healthz") => ("200 OK", "ok"),
+        Some("/readyz") if health.is_ready(ready_threshold) => ("200 OK", "ready"),
+        Some("/readyz") => ("503 Service Unavailable", "not ready"),
+        Some(_) => ("404 Not Found", "not found"),
+        None => ("400 Bad Request", "bad request"),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+/// Read the request head and return the path of a GET request
+async fn read_request_path(stream: &mut TcpStream) -> Result<Option<String>> {
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 || request.len() + n > MAX_REQUEST_BYTES {
+            return Ok(None);
+        }
+        request.extend_from_slice(&buf[..n]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let mut request_line = request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace();
+
+    match (request_line.next(), request_line.next()) {
+        (Some("GET"), Some(target)) => {
+            // Ignore any query string
+            let path = target.split('?').next().unwrap_or(target);
+            Ok(Some(path.to_string()))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn get(addr: std::net::SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(format!("GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").as_bytes())
+            .await
+            .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_health_and_readiness_endpoints() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let health = PollHealth::default();
+        let shutdown_listener = CancellationToken::new();
+
+        let server = tokio::spawn(serve(
+            listener,
+            health.clone(),
+            Duration::from_secs(60),
+            shutdown_listener.clone(),
+        ));
+
+        // No poll has completed yet
+        assert!(get(addr, "/healthz").await.starts_with("HTTP/1.1 200 OK"));
+        assert!(
+            get(addr, "/readyz")
+                .await
+                .starts_with("HTTP/1.1 503 Service Unavailable")
+        );
+
+        health.record_poll();
+        let response = get(addr, "/readyz").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with("ready"));
+
+        // The last poll is older than the threshold
+        *health.last_poll.lock().unwrap() = Some(Instant::now() - Duration::from_secs(120));
+        assert!(
+            get(addr, "/readyz")
+                .await
+                .starts_with("HTTP/1.1 503 Service Unavailable")
+        );
+
+        assert!(
+            get(addr, "/metrics")
+                .await
+                .starts_with("HTTP/1.1 404 Not Found")
+        );
+
+        shutdown_listener.cancel();
+        server.await.unwrap().unwrap();
+    }
+}
@@ -13,6 +13,7 @@ use solana_sdk::{
 pub mod client;
 pub mod constants;
 mod error;
+pub mod health;
 pub mod sentinel;
 pub mod settings;
 
@@ -1,12 +1,15 @@
+use std::time::Duration;
+
 use clap::Parser;
 use doublezero_ledger_sentinel::{
     constants::ENV_PREVIOUS_LEADER_EPOCHS,
+    health,
     sentinel::PollingSentinel,
     settings::{AppArgs, Settings},
 };
 use metrics_exporter_prometheus::PrometheusBuilder;
 use solana_sdk::signer::Signer;
-use tokio::signal;
+use tokio::{net::TcpListener, signal};
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -30,13 +33,21 @@ async fn main() -> anyhow::Result<()> {
     let sol_rpc_url = settings.sol_rpc();
     let dz_rpc_url = settings.dz_rpc();
     let keypair = settings.keypair();
-    let serviceability_id = settings.serviceability_program_id()?;
+    let serviceability_ids = settings.serviceability_program_ids()?;
+    anyhow::ensure!(
+        !serviceability_ids.is_empty(),
+        "at least one serviceability program must be configured"
+    );
 
     info!(
         %sol_rpc_url,
         %dz_rpc_url,
         poll_interval_secs = args.poll_interval,
+        max_backoff_secs = settings.max_backoff_secs,
+        max_concurrent_verifications = args.max_concurrent_verifications,
+        results_dir = ?args.results_dir,
         pubkey = %keypair.pubkey(),
+        ?serviceability_ids,
         "DoubleZero Ledger Sentinel starting"
     );
 
@@ -44,14 +55,36 @@ async fn main() -> anyhow::Result<()> {
         dz_rpc_url,
         sol_rpc_url,
         keypair,
-        serviceability_id,
+        serviceability_ids,
         args.poll_interval,
+        settings.max_backoff_secs,
         ENV_PREVIOUS_LEADER_EPOCHS,
+        args.max_concurrent_verifications as usize,
+        args.results_dir,
     )
     .await?;
 
+    if args.once {
+        let summary = polling_sentinel.poll_once().await?;
+        println!("{summary}");
+        if !summary.all_verified() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     let shutdown_listener = shutdown_listener();
 
+    let health_listener = TcpListener::bind(settings.health_addr()).await?;
+    let ready_threshold =
+        Duration::from_secs(args.poll_interval) * settings.ready_poll_intervals.max(1);
+    tokio::spawn(health::serve(
+        health_listener,
+        polling_sentinel.health(),
+        ready_threshold,
+        shutdown_listener.clone(),
+    ));
+
     tokio::select! {
         biased;
         _ = shutdown_listener.cancelled() => {
@@ -1,4 +1,5 @@
 pub mod poller;
+pub mod results;
 pub mod verification;
 
 pub use poller::PollingSentinel;
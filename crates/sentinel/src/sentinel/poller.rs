@@ -1,10 +1,15 @@
 use std::{
+    fmt,
     net::Ipv4Addr,
+    path::PathBuf,
     sync::Arc,
     time::{Duration, Instant},
 };
 
+use backon::{BackoffBuilder, ExponentialBackoff, ExponentialBuilder};
+use chrono::Utc;
 use doublezero_passport::instruction::AccessMode;
+use futures::{StreamExt, stream};
 use retainer::Cache;
 use solana_sdk::{pubkey::Pubkey, signature::Keypair};
 use tokio::time::interval;
@@ -14,9 +19,17 @@ use url::Url;
 
 use crate::{
     AccessId, Result,
-    client::{doublezero_ledger::DzRpcClient, solana::SolRpcClient},
+    client::{
+        doublezero_ledger::{DzRpcClient, DzRpcClientType},
+        solana::{SolRpcClient, SolRpcClientType},
+    },
     error::rpc_with_retry,
-    sentinel::ValidatorVerifier,
+    health::PollHealth,
+    sentinel::{
+        ValidatorVerifier,
+        results::{PollResults, VerificationOutcome, VerificationResult},
+        verification::LeaderScheduleCache,
+    },
 };
 
 // cache ttl: 5 minutes
@@ -24,22 +37,63 @@ const CACHE_TTL: Duration = Duration::from_secs(300);
 // cache monitoring interval, every 60s
 const CACHE_MONITOR_INTERVAL: Duration = Duration::from_secs(60);
 
-pub struct PollingSentinel {
-    dz_rpc_client: DzRpcClient,
-    sol_rpc_client: SolRpcClient,
+/// Outcome counts of a single poll
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PollSummary {
+    pub granted: usize,
+    pub denied: usize,
+    pub failed: usize,
+    /// Requests skipped because they were recently processed
+    pub duplicates: usize,
+}
+
+impl PollSummary {
+    /// Whether every checked validator passed verification
+    pub fn all_verified(&self) -> bool {
+        self.denied == 0 && self.failed == 0
+    }
+}
+
+impl fmt::Display for PollSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "checked {} access requests: {} granted, {} denied, {} failed ({} recently processed skipped)",
+            self.granted + self.denied + self.failed,
+            self.granted,
+            self.denied,
+            self.failed,
+            self.duplicates
+        )
+    }
+}
+
+pub struct PollingSentinel<Sol = SolRpcClient, Dz = DzRpcClient> {
+    /// One client per monitored serviceability program
+    dz_rpc_clients: Vec<Dz>,
+    sol_rpc_client: Sol,
     processed_cache: Arc<Cache<Pubkey, Instant>>,
+    leader_schedule_cache: LeaderScheduleCache,
+    health: PollHealth,
     poll_interval: Duration,
+    max_backoff: Duration,
     previous_leader_epochs: u8,
+    max_concurrent_verifications: usize,
+    results_dir: Option<PathBuf>,
 }
 
 impl PollingSentinel {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         dz_rpc: Url,
         sol_rpc: Url,
         keypair: Arc<Keypair>,
-        serviceability_id: Pubkey,
+        serviceability_ids: Vec<Pubkey>,
         poll_interval_secs: u64,
+        max_backoff_secs: u64,
         previous_leader_epochs: u8,
+        max_concurrent_verifications: usize,
+        results_dir: Option<PathBuf>,
     ) -> Result<Self> {
         // Create cache with automatic background cleanup
         let processed_cache = Arc::new(Cache::new());
@@ -52,16 +106,34 @@ impl PollingSentinel {
         });
 
         Ok(Self {
-            dz_rpc_client: DzRpcClient::new(dz_rpc, keypair.clone(), serviceability_id),
+            dz_rpc_clients: serviceability_ids
+                .into_iter()
+                .map(|serviceability_id| {
+                    DzRpcClient::new(dz_rpc.clone(), keypair.clone(), serviceability_id)
+                })
+                .collect(),
             sol_rpc_client: SolRpcClient::new(sol_rpc, keypair),
             processed_cache,
+            leader_schedule_cache: LeaderScheduleCache::default(),
+            health: PollHealth::default(),
             poll_interval: Duration::from_secs(poll_interval_secs),
+            max_backoff: Duration::from_secs(max_backoff_secs),
             previous_leader_epochs,
+            max_concurrent_verifications: max_concurrent_verifications.max(1),
+            results_dir,
         })
     }
+}
+
+impl<Sol: SolRpcClientType, Dz: DzRpcClientType> PollingSentinel<Sol, Dz> {
+    /// Poll state for the readiness probe
+    pub fn health(&self) -> PollHealth {
+        self.health.clone()
+    }
 
     pub async fn run(&mut self, shutdown_listener: CancellationToken) -> Result<()> {
         let mut poll_timer = interval(self.poll_interval);
+        let mut backoff = PollBackoff::new(self.poll_interval, self.max_backoff, true);
 
         loop {
             tokio::select! {
@@ -71,88 +143,147 @@ impl PollingSentinel {
                     break;
                 }
                 _ = poll_timer.tick() => {
-                    let access_ids = match rpc_with_retry(
-                        || async {
-                            self.sol_rpc_client.get_access_requests().await
-                        },
-                        "get_access_requests",
-                    ).await {
-                        Ok(ids) => ids,
+                    match self.poll_once().await {
+                        Ok(_) => {
+                            backoff.record_success();
+                            metrics::gauge!("doublezero_sentinel_consecutive_failures").set(0);
+                        }
                         Err(err) => {
-                            error!(?err, "failed to fetch access requests; will retry in next cycle");
+                            let retry_in = backoff.record_failure();
+                            error!(
+                                ?err,
+                                ?retry_in,
+                                consecutive_failures = backoff.consecutive_failures(),
+                                "failed to fetch access requests; backing off"
+                            );
                             metrics::counter!("doublezero_sentinel_poll_failed").increment(1);
-                            continue;
-                        }
-                    };
-
-                    // Filter out already-processed requests
-                    let mut new_requests = Vec::new();
-                    let mut duplicate_count = 0;
-
-                    for access_id in access_ids {
-                        if let Some(processed_at) = self.processed_cache.get(&access_id.request_pda).await {
-                            duplicate_count += 1;
-                            let age = processed_at.elapsed();
-                            metrics::counter!("doublezero_sentinel_duplicate_request_filtered").increment(1);
-                            metrics::histogram!("doublezero_sentinel_duplicate_age_seconds").record(age.as_secs_f64());
-                        } else {
-                            new_requests.push(access_id);
+                            metrics::gauge!("doublezero_sentinel_consecutive_failures")
+                                .set(backoff.consecutive_failures());
+                            poll_timer.reset_after(retry_in);
                         }
                     }
+                }
+            }
+        }
 
-                    if duplicate_count > 0 {
-                        info!(
-                            duplicates = duplicate_count,
-                            "filtered out recently processed requests"
-                        );
-                    }
+        Ok(())
+    }
 
-                    info!(count = new_requests.len(), "processing unhandled access requests");
-
-                    for access_id in new_requests {
-                        let request_pda = access_id.request_pda;
-                        match self.handle_access_request(access_id).await {
-                            Ok(_) => {
-                                // Only cache after successful processing
-                                self.processed_cache.insert(request_pda, Instant::now(), CACHE_TTL).await;
-                            }
-                            Err(err) => {
-                                error!(?err, "error encountered validating network access request; will retry on next poll");
-                                // Don't cache failures - allow retry on next poll cycle
-                            }
-                        }
+    /// Run a single poll iteration: fetch pending access requests, then
+    /// verify and grant or deny each one not recently processed. Fails only
+    /// if the access requests cannot be fetched.
+    pub async fn poll_once(&self) -> Result<PollSummary> {
+        let access_ids = rpc_with_retry(
+            || async { self.sol_rpc_client.get_access_requests().await },
+            "get_access_requests",
+        )
+        .await?;
+
+        // Filter out already-processed requests
+        let mut new_requests = Vec::new();
+        let mut duplicate_count = 0;
+
+        for access_id in access_ids {
+            if let Some(processed_at) = self.processed_cache.get(&access_id.request_pda).await {
+                duplicate_count += 1;
+                let age = processed_at.elapsed();
+                metrics::counter!("doublezero_sentinel_duplicate_request_filtered").increment(1);
+                metrics::histogram!("doublezero_sentinel_duplicate_age_seconds")
+                    .record(age.as_secs_f64());
+            } else {
+                new_requests.push(access_id);
+            }
+        }
+
+        if duplicate_count > 0 {
+            info!(
+                duplicates = duplicate_count,
+                "filtered out recently processed requests"
+            );
+        }
+
+        info!(
+            count = new_requests.len(),
+            "processing unhandled access requests"
+        );
+
+        let mut summary = PollSummary {
+            duplicates: duplicate_count,
+            ..Default::default()
+        };
+
+        if !new_requests.is_empty() {
+            let polled_at = Utc::now();
+            let results = stream::iter(new_requests)
+                .map(|access_id| self.process_access_request(access_id))
+                .buffer_unordered(self.max_concurrent_verifications)
+                .collect::<Vec<_>>()
+                .await;
+
+            for result in &results {
+                match result.outcome {
+                    VerificationOutcome::Granted => summary.granted += 1,
+                    VerificationOutcome::Denied => summary.denied += 1,
+                    VerificationOutcome::Failed => summary.failed += 1,
+                }
+            }
+
+            if let Some(results_dir) = &self.results_dir {
+                match PollResults::new(polled_at, results).write_to_dir(results_dir) {
+                    Ok(path) => info!(path = %path.display(), "wrote poll verification results"),
+                    Err(err) => {
+                        error!(?err, "failed to write poll verification results");
+                        metrics::counter!("doublezero_sentinel_results_write_failed").increment(1);
                     }
                 }
             }
         }
 
-        Ok(())
+        self.health.record_poll();
+
+        Ok(summary)
     }
 
-    async fn handle_access_request(&self, access_id: AccessId) -> Result<()> {
-        let service_key = match &access_id.mode {
-            AccessMode::SolanaValidator(a) => a.service_key,
-            AccessMode::SolanaValidatorWithBackupIds { attestation, .. } => attestation.service_key,
-        };
+    async fn process_access_request(&self, access_id: AccessId) -> VerificationResult {
+        let request_pda = access_id.request_pda;
+        let service_key = service_key(&access_id.mode);
+
+        match self.handle_access_request(access_id).await {
+            Ok(validator_ids) => {
+                // Only cache after successful processing
+                self.processed_cache
+                    .insert(request_pda, Instant::now(), CACHE_TTL)
+                    .await;
+
+                if validator_ids.is_empty() {
+                    VerificationResult::denied(&request_pda, &service_key)
+                } else {
+                    VerificationResult::granted(&request_pda, &service_key, &validator_ids)
+                }
+            }
+            Err(err) => {
+                error!(
+                    ?err,
+                    "error encountered validating network access request; will retry on next poll"
+                );
+                // Don't cache failures - allow retry on next poll cycle
+                VerificationResult::failed(&request_pda, &service_key, err.to_string())
+            }
+        }
+    }
+
+    /// Grant or deny the access request, returning the validators issued an
+    /// access pass (empty when denied)
+    async fn handle_access_request(&self, access_id: AccessId) -> Result<Vec<Pubkey>> {
+        let service_key = service_key(&access_id.mode);
 
         info!(%service_key, request_pda = %access_id.request_pda, "handling access request");
 
         let validator_ips = self.verify_qualifiers(&access_id.mode).await?;
+        let validator_ids = validator_ips.iter().map(|(id, _)| *id).collect();
 
         if !validator_ips.is_empty() {
-            // Issue access passes for all validators (primary + backups)
-            for (validator_id, validator_ip) in validator_ips {
-                rpc_with_retry(
-                    || async {
-                        self.dz_rpc_client
-                            .issue_access_pass(&service_key, &validator_ip, &validator_id)
-                            .await
-                    },
-                    "issue_access_pass",
-                )
-                .await?;
-                info!(%validator_id, %validator_ip, user = %service_key, "access pass issued");
-            }
+            issue_access_passes(&self.dz_rpc_clients, &service_key, &validator_ips).await?;
 
             let signature = rpc_with_retry(
                 || async {
@@ -179,21 +310,248 @@ impl PollingSentinel {
             metrics::counter!("doublezero_sentinel_access_denied").increment(1);
         }
 
-        Ok(())
+        Ok(validator_ids)
     }
 
     async fn verify_qualifiers(&self, access_mode: &AccessMode) -> Result<Vec<(Pubkey, Ipv4Addr)>> {
-        let verifier = ValidatorVerifier::new(&self.sol_rpc_client, self.previous_leader_epochs);
+        let verifier = ValidatorVerifier::new(
+            &self.sol_rpc_client,
+            &self.leader_schedule_cache,
+            self.previous_leader_epochs,
+        );
         verifier.verify_qualifiers(access_mode).await
     }
 }
 
+/// Delay before the next poll after consecutive failures
+///
+/// The first failure retries after the regular poll interval and each further
+/// consecutive failure doubles the delay, up to `max`. A success resets the
+/// schedule.
+struct PollBackoff {
+    builder: ExponentialBuilder,
+    backoff: ExponentialBackoff,
+    max: Duration,
+    consecutive_failures: u32,
+}
+
+impl PollBackoff {
+    fn new(base: Duration, max: Duration, jitter: bool) -> Self {
+        let max = max.max(base);
+        let mut builder = ExponentialBuilder::default()
+            .with_min_delay(base)
+            .with_max_delay(max)
+            .without_max_times();
+        if jitter {
+            builder = builder.with_jitter();
+        }
+
+        Self {
+            builder,
+            backoff: builder.build(),
+            max,
+            consecutive_failures: 0,
+        }
+    }
+
+    fn record_failure(&mut self) -> Duration {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        // Jitter is added on top of the delay, so clamp again to honor the cap
+        self.backoff.next().unwrap_or(self.max).min(self.max)
+    }
+
+    fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.backoff = self.builder.build();
+    }
+
+    fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+}
+
+/// Issue access passes for all validators (primary + backups) on every
+/// monitored serviceability program
+async fn issue_access_passes<DzRpcClient: DzRpcClientType>(
+    dz_rpc_clients: &[DzRpcClient],
+    service_key: &Pubkey,
+    validator_ips: &[(Pubkey, Ipv4Addr)],
+) -> Result<()> {
+    for dz_rpc_client in dz_rpc_clients {
+        let serviceability_id = dz_rpc_client.serviceability_id().to_string();
+
+        for (validator_id, validator_ip) in validator_ips {
+            let result = rpc_with_retry(
+                || async {
+                    dz_rpc_client
+                        .issue_access_pass(service_key, validator_ip, validator_id)
+                        .await
+                },
+                "issue_access_pass",
+            )
+            .await;
+
+            if result.is_err() {
+                metrics::counter!(
+                    "doublezero_sentinel_access_pass_failed",
+                    "serviceability_id" => serviceability_id.clone()
+                )
+                .increment(1);
+            }
+            result?;
+
+            info!(%validator_id, %validator_ip, user = %service_key, %serviceability_id, "access pass issued");
+            metrics::counter!(
+                "doublezero_sentinel_access_pass_issued",
+                "serviceability_id" => serviceability_id.clone()
+            )
+            .increment(1);
+        }
+    }
+
+    Ok(())
+}
+
+fn service_key(access_mode: &AccessMode) -> Pubkey {
+    match access_mode {
+        AccessMode::SolanaValidator(a) => a.service_key,
+        AccessMode::SolanaValidatorWithBackupIds { attestation, .. } => attestation.service_key,
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use doublezero_passport::instruction::SolanaValidatorAttestation;
-    use solana_sdk::pubkey::Pubkey;
+    use std::collections::HashMap;
+
+    use doublezero_passport::{instruction::SolanaValidatorAttestation, state::AccessRequest};
+    use solana_sdk::{
+        offchain_message::OffchainMessage, pubkey::Pubkey, signature::Signature, signer::Signer,
+    };
 
     use super::*;
+    use crate::client::{
+        doublezero_ledger::MockDzRpcClientType,
+        solana::{MockSolRpcClientType, SLOTS_PER_EPOCH},
+    };
+
+    /// Access request signed by the returned validator identity
+    fn signed_access_id() -> (AccessId, Pubkey) {
+        let validator_id = Keypair::new();
+        let mut attestation = SolanaValidatorAttestation {
+            validator_id: validator_id.pubkey(),
+            service_key: Pubkey::new_unique(),
+            ed25519_signature: [0; 64],
+        };
+
+        let raw_message =
+            AccessRequest::access_request_message(&AccessMode::SolanaValidator(attestation));
+        let offchain_msg = OffchainMessage::new(0, raw_message.as_bytes()).unwrap();
+        attestation.ed25519_signature = validator_id
+            .sign_message(&offchain_msg.serialize().unwrap())
+            .into();
+
+        let access_id = AccessId {
+            request_pda: Pubkey::new_unique(),
+            rent_beneficiary_key: Pubkey::new_unique(),
+            mode: AccessMode::SolanaValidator(attestation),
+        };
+        (access_id, validator_id.pubkey())
+    }
+
+    /// Sentinel over a mocked Solana connection whose leader schedule
+    /// contains `leaders`
+    fn mocked_sentinel(
+        access_ids: Vec<AccessId>,
+        leaders: Vec<Pubkey>,
+        dz_rpc_client: MockDzRpcClientType,
+    ) -> PollingSentinel<MockSolRpcClientType, MockDzRpcClientType> {
+        let mut sol_rpc_client = MockSolRpcClientType::new();
+        sol_rpc_client
+            .expect_get_access_requests()
+            .times(1)
+            .returning(move || Ok(access_ids.clone()));
+        sol_rpc_client
+            .expect_get_slot()
+            .returning(|| Ok(10 * SLOTS_PER_EPOCH));
+        sol_rpc_client
+            .expect_get_leader_schedule()
+            .returning(move |_| {
+                Ok(Some(
+                    leaders
+                        .iter()
+                        .map(|leader| (leader.to_string(), vec![0]))
+                        .collect::<HashMap<_, _>>(),
+                ))
+            });
+        sol_rpc_client
+            .expect_get_validator_ip()
+            .returning(|_| Ok(Some(Ipv4Addr::new(10, 0, 0, 1))));
+        sol_rpc_client
+            .expect_grant_access()
+            .returning(|_, _| Ok(Signature::default()));
+        sol_rpc_client
+            .expect_deny_access()
+            .returning(|_| Ok(Signature::default()));
+
+        PollingSentinel {
+            dz_rpc_clients: vec![dz_rpc_client],
+            sol_rpc_client,
+            processed_cache: Arc::new(Cache::new()),
+            leader_schedule_cache: LeaderScheduleCache::default(),
+            health: PollHealth::default(),
+            poll_interval: Duration::from_secs(15),
+            max_backoff: Duration::from_secs(600),
+            previous_leader_epochs: 1,
+            max_concurrent_verifications: 1,
+            results_dir: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_poll_once_summarizes_single_pass() {
+        let (access_id, validator_id) = signed_access_id();
+
+        let mut dz_rpc_client = MockDzRpcClientType::new();
+        dz_rpc_client
+            .expect_serviceability_id()
+            .return_const(Pubkey::new_unique());
+        dz_rpc_client
+            .expect_issue_access_pass()
+            .times(1)
+            .returning(|_, _, _| Ok(Signature::default()));
+
+        let sentinel = mocked_sentinel(vec![access_id], vec![validator_id], dz_rpc_client);
+        let summary = sentinel.poll_once().await.unwrap();
+
+        assert_eq!(
+            summary,
+            PollSummary {
+                granted: 1,
+                ..Default::default()
+            }
+        );
+        assert!(summary.all_verified());
+        assert!(sentinel.health().is_ready(Duration::from_secs(60)));
+    }
+
+    #[tokio::test]
+    async fn test_poll_once_reports_unverified_validator() {
+        let (access_id, _) = signed_access_id();
+
+        // Not in the leader schedule, so no access pass is issued
+        let mut dz_rpc_client = MockDzRpcClientType::new();
+        dz_rpc_client.expect_issue_access_pass().never();
+
+        let sentinel = mocked_sentinel(vec![access_id], vec![Pubkey::new_unique()], dz_rpc_client);
+        let summary = sentinel.poll_once().await.unwrap();
+
+        assert_eq!(summary.denied, 1);
+        assert!(!summary.all_verified());
+        assert_eq!(
+            summary.to_string(),
+            "checked 1 access requests: 0 granted, 1 denied, 0 failed (0 recently processed skipped)"
+        );
+    }
 
     #[tokio::test]
     async fn test_cache_prevents_duplicate_processing() {
@@ -263,6 +621,71 @@ mod tests {
         assert!(cache.get(&pda3).await.is_none());
     }
 
+    #[test]
+    fn test_poll_backoff_schedule() {
+        let base = Duration::from_secs(30);
+        let mut backoff = PollBackoff::new(base, Duration::from_secs(200), false);
+
+        let delays = (0..5).map(|_| backoff.record_failure()).collect::<Vec<_>>();
+        assert_eq!(
+            delays,
+            vec![
+                Duration::from_secs(30),
+                Duration::from_secs(60),
+                Duration::from_secs(120),
+                Duration::from_secs(200),
+                Duration::from_secs(200),
+            ]
+        );
+        assert_eq!(backoff.consecutive_failures(), 5);
+
+        // A success resets to the base interval
+        backoff.record_success();
+        assert_eq!(backoff.consecutive_failures(), 0);
+        assert_eq!(backoff.record_failure(), base);
+        assert_eq!(backoff.record_failure(), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_poll_backoff_jitter_respects_cap() {
+        let max = Duration::from_secs(200);
+        let mut backoff = PollBackoff::new(Duration::from_secs(30), max, true);
+
+        for _ in 0..10 {
+            let delay = backoff.record_failure();
+            assert!(delay >= Duration::from_secs(30) && delay <= max);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_access_passes_issued_on_every_serviceability_program() {
+        let service_key = Pubkey::new_unique();
+        let validator_ips = vec![
+            (Pubkey::new_unique(), Ipv4Addr::new(10, 0, 0, 1)),
+            (Pubkey::new_unique(), Ipv4Addr::new(10, 0, 0, 2)),
+        ];
+
+        let dz_rpc_clients = [Pubkey::new_unique(), Pubkey::new_unique()]
+            .into_iter()
+            .map(|serviceability_id| {
+                let mut dz_rpc_client = MockDzRpcClientType::new();
+                dz_rpc_client
+                    .expect_serviceability_id()
+                    .return_const(serviceability_id);
+                dz_rpc_client
+                    .expect_issue_access_pass()
+                    .withf(move |key, _, _| *key == service_key)
+                    .times(validator_ips.len())
+                    .returning(|_, _, _| Ok(Signature::default()));
+                dz_rpc_client
+            })
+            .collect::<Vec<_>>();
+
+        issue_access_passes(&dz_rpc_clients, &service_key, &validator_ips)
+            .await
+            .unwrap();
+    }
+
     #[tokio::test]
     async fn test_verify_qualifiers_signature_verify_error_returns_empty() {
         // Build a real PollingSentinel; it won't hit network because we short-circuit on signature
@@ -272,11 +695,16 @@ mod tests {
         let serviceability_id = Pubkey::new_unique();
 
         let sentinel = PollingSentinel {
-            dz_rpc_client: DzRpcClient::new(dz_rpc, keypair.clone(), serviceability_id),
+            dz_rpc_clients: vec![DzRpcClient::new(dz_rpc, keypair.clone(), serviceability_id)],
             sol_rpc_client: SolRpcClient::new(sol_rpc, keypair),
             processed_cache: Arc::new(Cache::new()),
+            leader_schedule_cache: LeaderScheduleCache::default(),
+            health: PollHealth::default(),
             poll_interval: Duration::from_secs(15),
+            max_backoff: Duration::from_secs(600),
             previous_leader_epochs: 0,
+            max_concurrent_verifications: 1,
+            results_dir: None,
         };
 
         // Invalid signature -> verify_access_request(...) should return Error::SignatureVerify
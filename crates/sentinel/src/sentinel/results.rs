@@ -0,0 +1,130 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::Result;
+
+/// Final decision for a single access request in a poll
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationOutcome {
+    Granted,
+    Denied,
+    /// Processing failed and the request will be retried on the next poll
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct VerificationResult {
+    pub request_pda: String,
+    pub service_key: String,
+    pub outcome: VerificationOutcome,
+    /// Validators issued an access pass, empty unless the request was granted
+    pub validator_ids: Vec<String>,
+    pub error: Option<String>,
+}
+
+impl VerificationResult {
+    pub fn granted(request_pda: &Pubkey, service_key: &Pubkey, validator_ids: &[Pubkey]) -> Self {
+        Self {
+            request_pda: request_pda.to_string(),
+            service_key: service_key.to_string(),
+            outcome: VerificationOutcome::Granted,
+            validator_ids: validator_ids.iter().map(ToString::to_string).collect(),
+            error: None,
+        }
+    }
+
+    pub fn denied(request_pda: &Pubkey, service_key: &Pubkey) -> Self {
+        Self {
+            request_pda: request_pda.to_string(),
+            service_key: service_key.to_string(),
+            outcome: VerificationOutcome::Denied,
+            validator_ids: vec![],
+            error: None,
+        }
+    }
+
+    pub fn failed(request_pda: &Pubkey, service_key: &Pubkey, error: String) -> Self {
+        Self {
+            request_pda: request_pda.to_string(),
+            service_key: service_key.to_string(),
+            outcome: VerificationOutcome::Failed,
+            validator_ids: vec![],
+            error: Some(error),
+        }
+    }
+}
+
+/// Verification results of one poll, persisted for auditing
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PollResults {
+    pub polled_at: DateTime<Utc>,
+    pub results: Vec<VerificationResult>,
+}
+
+impl PollResults {
+    pub fn new(polled_at: DateTime<Utc>, results: Vec<VerificationResult>) -> Self {
+        Self { polled_at, results }
+    }
+
+    /// Write the results to a timestamped JSON file in `dir`, returning its path
+    pub fn write_to_dir(&self, dir: &Path) -> Result<PathBuf> {
+        fs::create_dir_all(dir)?;
+
+        let path = dir.join(format!(
+            "poll-{}.json",
+            self.polled_at.format("%Y%m%dT%H%M%S%.3fZ")
+        ));
+        let contents = serde_json::to_vec_pretty(self)?;
+        fs::write(&path, contents)?;
+
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poll_writes_results_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let results_dir = dir.path().join("results");
+
+        let service_key = Pubkey::new_unique();
+        let validator_id = Pubkey::new_unique();
+        let poll_results = PollResults::new(
+            "2025-10-01T12:30:45.123Z".parse().unwrap(),
+            vec![
+                VerificationResult::granted(&Pubkey::new_unique(), &service_key, &[validator_id]),
+                VerificationResult::denied(&Pubkey::new_unique(), &service_key),
+                VerificationResult::failed(
+                    &Pubkey::new_unique(),
+                    &service_key,
+                    "rpc client error".to_string(),
+                ),
+            ],
+        );
+
+        let path = poll_results.write_to_dir(&results_dir).unwrap();
+        assert_eq!(
+            path,
+            results_dir.join("poll-20251001T123045.123Z.json"),
+            "results file should be named after the poll timestamp"
+        );
+
+        let written: PollResults = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        assert_eq!(written, poll_results);
+        assert_eq!(
+            written.results[0].validator_ids,
+            vec![validator_id.to_string()]
+        );
+        assert_eq!(written.results[2].outcome, VerificationOutcome::Failed);
+    }
+}
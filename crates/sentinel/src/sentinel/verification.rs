@@ -1,23 +1,112 @@
-use std::net::Ipv4Addr;
+use std::{
+    collections::{BTreeMap, HashSet},
+    net::Ipv4Addr,
+    str::FromStr,
+    sync::Arc,
+};
 
 use doublezero_passport::instruction::AccessMode;
 use solana_sdk::pubkey::Pubkey;
-use tracing::info;
+use tokio::sync::Mutex;
+use tracing::{debug, info};
 
 use crate::{
-    Error, Result, client::solana::SolRpcClientType, error::rpc_with_retry, verify_access_request,
+    Error, Result,
+    client::solana::{PreviousEpochSlots, SLOTS_PER_EPOCH, SolRpcClientType},
+    error::rpc_with_retry,
+    verify_access_request,
 };
 
+/// Number of epochs whose leader schedules are kept in memory
+pub const LEADER_SCHEDULE_CACHE_EPOCHS: usize = 4;
+
+/// Leaders of each epoch
+type EpochLeaders = Arc<HashSet<Pubkey>>;
+
+/// Leader schedules keyed by epoch. An epoch's schedule never changes, so
+/// once fetched it is reused until it falls out of the most recent
+/// `max_epochs` epochs.
+pub struct LeaderScheduleCache {
+    schedules: Mutex<BTreeMap<u64, EpochLeaders>>,
+    max_epochs: usize,
+}
+
+impl Default for LeaderScheduleCache {
+    fn default() -> Self {
+        Self::new(LEADER_SCHEDULE_CACHE_EPOCHS)
+    }
+}
+
+impl LeaderScheduleCache {
+    pub fn new(max_epochs: usize) -> Self {
+        Self {
+            schedules: Default::default(),
+            max_epochs: max_epochs.max(1),
+        }
+    }
+
+    /// Leaders of the epoch containing `slot`, fetching the schedule on a
+    /// cache miss. Missing schedules are not cached.
+    async fn get_or_try_fetch<SolRpcClient: SolRpcClientType>(
+        &self,
+        sol_rpc_client: &SolRpcClient,
+        slot: u64,
+    ) -> Result<Option<EpochLeaders>> {
+        let epoch = slot / SLOTS_PER_EPOCH;
+
+        // Hold the lock while fetching so concurrent verifications wait for
+        // a single fetch of the same epoch
+        let mut schedules = self.schedules.lock().await;
+
+        if let Some(leaders) = schedules.get(&epoch) {
+            metrics::counter!("doublezero_sentinel_leader_schedule_cache_hit").increment(1);
+            return Ok(Some(leaders.clone()));
+        }
+        metrics::counter!("doublezero_sentinel_leader_schedule_cache_miss").increment(1);
+
+        let Some(schedule) = rpc_with_retry(
+            || async { sol_rpc_client.get_leader_schedule(slot).await },
+            "get_leader_schedule",
+        )
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let leaders = Arc::new(
+            schedule
+                .into_iter()
+                .filter(|(_, slot_indices)| !slot_indices.is_empty())
+                .filter_map(|(identity, _)| Pubkey::from_str(&identity).ok())
+                .collect::<HashSet<_>>(),
+        );
+        debug!(epoch, leaders = leaders.len(), "cached leader schedule");
+
+        schedules.insert(epoch, leaders.clone());
+        while schedules.len() > self.max_epochs {
+            schedules.pop_first();
+        }
+
+        Ok(Some(leaders))
+    }
+}
+
 /// Shared validator verification logic used by both WebSocket and polling modes
 pub struct ValidatorVerifier<'a, SolRpcClient: SolRpcClientType> {
     sol_rpc_client: &'a SolRpcClient,
+    leader_schedule_cache: &'a LeaderScheduleCache,
     previous_leader_epochs: u8,
 }
 
 impl<'a, SolRpcClient: SolRpcClientType> ValidatorVerifier<'a, SolRpcClient> {
-    pub fn new(sol_rpc_client: &'a SolRpcClient, previous_leader_epochs: u8) -> Self {
+    pub fn new(
+        sol_rpc_client: &'a SolRpcClient,
+        leader_schedule_cache: &'a LeaderScheduleCache,
+        previous_leader_epochs: u8,
+    ) -> Self {
         Self {
             sol_rpc_client,
+            leader_schedule_cache,
             previous_leader_epochs,
         }
     }
@@ -104,17 +193,36 @@ impl<'a, SolRpcClient: SolRpcClientType> ValidatorVerifier<'a, SolRpcClient> {
         Ok(ips)
     }
 
-    /// Check that a validator is in the leader schedule
+    /// Check that a validator is in the leader schedule of each of the last
+    /// `previous_leader_epochs` epochs
+    ///
+    /// NOTE: If previous_leader_epochs is 0, there are no leader schedules to
+    /// evaluate, so this returns false.
     async fn check_validator_in_leader_schedule(&self, validator_id: &Pubkey) -> Result<bool> {
-        rpc_with_retry(
-            || async {
-                self.sol_rpc_client
-                    .is_scheduled_leader(validator_id, self.previous_leader_epochs)
-                    .await
-            },
-            "is_scheduled_leader",
-        )
-        .await
+        if self.previous_leader_epochs == 0 {
+            return Ok(false);
+        }
+
+        let slot = rpc_with_retry(|| self.sol_rpc_client.get_slot(), "get_slot").await?;
+
+        // We want to ensure that the number of leader schedules evaluated is
+        // equal to the number of epochs requested.
+        let mut schedule_count = 0;
+
+        for slot in PreviousEpochSlots(slot).take(self.previous_leader_epochs as usize) {
+            let leaders = self
+                .leader_schedule_cache
+                .get_or_try_fetch(self.sol_rpc_client, slot)
+                .await?;
+
+            if !leaders.is_some_and(|leaders| leaders.contains(validator_id)) {
+                return Ok(false);
+            }
+
+            schedule_count += 1;
+        }
+
+        Ok(schedule_count == self.previous_leader_epochs)
     }
 
     /// Get and validate a validator's IP from gossip
@@ -129,3 +237,71 @@ impl<'a, SolRpcClient: SolRpcClientType> ValidatorVerifier<'a, SolRpcClient> {
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::client::solana::MockSolRpcClientType;
+
+    #[tokio::test]
+    async fn test_leader_schedule_fetched_once_per_epoch() {
+        let validator_id = Pubkey::new_unique();
+        let slot = 10 * SLOTS_PER_EPOCH + 1_234;
+
+        let mut sol_rpc_client = MockSolRpcClientType::new();
+        sol_rpc_client.expect_get_slot().returning(move || Ok(slot));
+        sol_rpc_client
+            .expect_get_leader_schedule()
+            .withf(move |requested_slot| *requested_slot == slot)
+            .times(1)
+            .returning(move |_| {
+                Ok(Some(HashMap::from([
+                    (validator_id.to_string(), vec![0, 1, 2, 3]),
+                    (Pubkey::new_unique().to_string(), vec![4, 5, 6, 7]),
+                ])))
+            });
+
+        let leader_schedule_cache = LeaderScheduleCache::default();
+        let verifier = ValidatorVerifier::new(&sol_rpc_client, &leader_schedule_cache, 1);
+
+        assert!(
+            verifier
+                .check_validator_in_leader_schedule(&validator_id)
+                .await
+                .unwrap()
+        );
+        assert!(
+            verifier
+                .check_validator_in_leader_schedule(&validator_id)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !verifier
+                .check_validator_in_leader_schedule(&Pubkey::new_unique())
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_leader_schedule_cache_is_bounded() {
+        let mut sol_rpc_client = MockSolRpcClientType::new();
+        sol_rpc_client
+            .expect_get_leader_schedule()
+            .returning(|_| Ok(Some(HashMap::new())));
+
+        let leader_schedule_cache = LeaderScheduleCache::new(2);
+        for epoch in 5..10 {
+            leader_schedule_cache
+                .get_or_try_fetch(&sol_rpc_client, epoch * SLOTS_PER_EPOCH)
+                .await
+                .unwrap();
+        }
+
+        let schedules = leader_schedule_cache.schedules.lock().await;
+        assert_eq!(schedules.keys().copied().collect::<Vec<_>>(), vec![8, 9]);
+    }
+}
@@ -28,6 +28,20 @@ pub struct AppArgs {
     /// Recommended: 30-120 seconds for production.
     #[arg(long)]
     pub poll_interval: u64,
+
+    /// Maximum number of access requests verified concurrently in a poll
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+    pub max_concurrent_verifications: u64,
+
+    /// Directory in which each poll writes a timestamped JSON file of its
+    /// verification results, for auditing
+    #[arg(long, value_name = "DIR")]
+    pub results_dir: Option<PathBuf>,
+
+    /// Run a single verification pass and exit. The exit status is non-zero
+    /// unless every checked validator verified.
+    #[arg(long)]
+    pub once: bool,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -36,8 +50,9 @@ pub struct Settings {
     #[serde(default = "default_log")]
     pub log: String,
 
-    /// The DZ ledger environment with which to interface
-    pub env: String,
+    /// The DZ ledger environment(s) or serviceability program ID(s) with which
+    /// to interface. Accepts a single value or a list.
+    pub env: OneOrMany,
 
     /// Connection URIs for the DZ ledger RPC endpoint
     dz_rpc: String,
@@ -52,6 +67,36 @@ pub struct Settings {
     /// metrics listening endpoint
     #[serde(default = "default_metrics_addr")]
     metrics_addr: String,
+
+    /// Upper bound in seconds on the poll interval while backing off after
+    /// consecutive RPC failures
+    #[serde(default = "default_max_backoff_secs")]
+    pub max_backoff_secs: u64,
+
+    /// Health and readiness probe listening endpoint
+    #[serde(default = "default_health_addr")]
+    health_addr: String,
+
+    /// Number of poll intervals without a completed poll loop after which the
+    /// sentinel reports as not ready
+    #[serde(default = "default_ready_poll_intervals")]
+    pub ready_poll_intervals: u32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl OneOrMany {
+    pub fn values(&self) -> &[String] {
+        match self {
+            Self::One(value) => std::slice::from_ref(value),
+            Self::Many(values) => values,
+        }
+    }
 }
 
 impl Settings {
@@ -101,17 +146,36 @@ impl Settings {
             .expect("invalid metrics network address and port")
     }
 
-    pub fn serviceability_program_id(
+    pub fn health_addr(&self) -> SocketAddr {
+        self.health_addr
+            .parse()
+            .expect("invalid health network address and port")
+    }
+
+    /// Serviceability program IDs of every configured environment, without
+    /// duplicates
+    pub fn serviceability_program_ids(
         &self,
-    ) -> Result<Pubkey, solana_sdk::pubkey::ParsePubkeyError> {
-        match self.env.to_lowercase().as_str() {
-            "local" => Pubkey::from_str("7CTniUa88iJKUHTrCkB4TjAoG6TD7AMivhQeuqN2LPtX"),
-            "devnet" => Ok(devnet::program_id::id()),
-            "testnet" => Ok(testnet::program_id::id()),
-            "mainnet" => Ok(mainnet::program_id::id()),
-            "mainnet-beta" => Ok(mainnet::program_id::id()),
-            other => Pubkey::from_str(other),
+    ) -> Result<Vec<Pubkey>, solana_sdk::pubkey::ParsePubkeyError> {
+        let mut program_ids = Vec::new();
+        for env in self.env.values() {
+            let program_id = serviceability_program_id(env)?;
+            if !program_ids.contains(&program_id) {
+                program_ids.push(program_id);
+            }
         }
+        Ok(program_ids)
+    }
+}
+
+fn serviceability_program_id(env: &str) -> Result<Pubkey, solana_sdk::pubkey::ParsePubkeyError> {
+    match env.to_lowercase().as_str() {
+        "local" => Pubkey::from_str("7CTniUa88iJKUHTrCkB4TjAoG6TD7AMivhQeuqN2LPtX"),
+        "devnet" => Ok(devnet::program_id::id()),
+        "testnet" => Ok(testnet::program_id::id()),
+        "mainnet" => Ok(mainnet::program_id::id()),
+        "mainnet-beta" => Ok(mainnet::program_id::id()),
+        other => Pubkey::from_str(other),
     }
 }
 
@@ -122,3 +186,50 @@ fn default_log() -> String {
 fn default_metrics_addr() -> String {
     "127.0.0.1:2112".to_string()
 }
+
+fn default_max_backoff_secs() -> u64 {
+    600
+}
+
+fn default_health_addr() -> String {
+    "127.0.0.1:2113".to_string()
+}
+
+fn default_ready_poll_intervals() -> u32 {
+    3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings_with_env(env: serde_json::Value) -> Settings {
+        serde_json::from_value(serde_json::json!({
+            "env": env,
+            "dz_rpc": "http://localhost:8899",
+            "sol_rpc": "localhost",
+            "keypair": "keypair.json",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_env_accepts_single_value_or_list() {
+        let settings = settings_with_env(serde_json::json!("devnet"));
+        assert_eq!(
+            settings.serviceability_program_ids().unwrap(),
+            vec![devnet::program_id::id()]
+        );
+
+        let other_id = Pubkey::new_unique();
+        let settings = settings_with_env(serde_json::json!([
+            "devnet",
+            other_id.to_string(),
+            "devnet"
+        ]));
+        assert_eq!(
+            settings.serviceability_program_ids().unwrap(),
+            vec![devnet::program_id::id(), other_id]
+        );
+    }
+}
@@ -1,8 +1,8 @@
 use anyhow::Result;
-use reqwest::{Body, Client};
+use reqwest::Client;
 use tabled::{builder::Builder as TableBuilder, settings::Style};
 
-use crate::slack::build_message_request;
+use crate::slack::post_message;
 
 /// Post detailed reward cycle completion notification to Slack
 /// Displays a table with Type | Value | Identifier format showing all write operations
@@ -54,11 +54,13 @@ pub async fn post_detailed_completion(
         "text": message_text
     });
 
-    let body = Body::from(serde_json::to_string(&payload)?);
-    let request = build_message_request(&client, body, webhook_url.to_string())?;
-    let _resp = request.send().await?;
-
-    Ok(())
+    post_message(
+        &client,
+        webhook_url.to_string(),
+        serde_json::to_string(&payload)?,
+        None, // max_attempts
+    )
+    .await
 }
 
 /// Map internal description to user-friendly Type name
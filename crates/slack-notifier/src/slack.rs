@@ -1,14 +1,31 @@
-use std::{env, fs};
+use std::{env, fs, time::Duration};
 
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, Result, anyhow, bail};
 use reqwest::{
-    Body, Client, RequestBuilder,
-    header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE},
+    Body, Client, RequestBuilder, Response, StatusCode,
+    header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE, RETRY_AFTER},
 };
 use serde::{Deserialize, Serialize};
 
+/// Attempts made to post a Slack message unless the caller sets them, overridable
+/// with `SLACK_MAX_ATTEMPTS`
+pub const DEFAULT_MAX_ATTEMPTS: usize = 3;
+
+/// Delay before the first retry when Slack does not send `Retry-After`
+const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(500);
+
 #[derive(Debug, Serialize)]
 pub struct SlackMessage {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub blocks: Vec<Block>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+}
+
+/// Slack only draws a color bar for blocks nested in an attachment
+#[derive(Debug, Serialize)]
+pub struct Attachment {
+    pub color: String,
     pub blocks: Vec<Block>,
 }
 
@@ -92,6 +109,85 @@ pub fn build_message_request(
     Ok(msg_request)
 }
 
+/// Post a message payload to a webhook, making up to `max_attempts` attempts.
+/// Without it, `SLACK_MAX_ATTEMPTS` or [`DEFAULT_MAX_ATTEMPTS`] applies.
+pub async fn post_message(
+    client: &Client,
+    webhook: String,
+    payload: String,
+    max_attempts: Option<usize>,
+) -> Result<()> {
+    let max_attempts = max_attempts.unwrap_or_else(default_max_attempts);
+    post_message_with_retry(client, &webhook, &payload, max_attempts)
+        .await
+        .context("Failed to post message to Slack")
+}
+
+/// Post a message payload to a webhook, retrying rate limits (429), server
+/// errors (5xx) and connection failures with exponential backoff. A
+/// `Retry-After` header from Slack takes precedence over the backoff delay.
+pub async fn post_message_with_retry(
+    client: &Client,
+    webhook: &str,
+    payload: &str,
+    max_attempts: usize,
+) -> Result<()> {
+    let mut delay = INITIAL_RETRY_DELAY;
+    let mut attempt = 1;
+
+    loop {
+        let request =
+            build_message_request(client, Body::from(payload.to_string()), webhook.to_string())?;
+
+        let (err, retry_after) = match request.send().await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) if is_retryable_status(response.status()) => (
+                anyhow!("Slack responded with {}", response.status()),
+                retry_after(&response),
+            ),
+            Ok(response) => bail!("Slack responded with {}", response.status()),
+            Err(err) => (err.into(), None),
+        };
+
+        if attempt >= max_attempts {
+            return Err(err.context(format!("Giving up after {attempt} attempts")));
+        }
+
+        let wait = retry_after.unwrap_or(delay);
+        tracing::warn!(
+            "Slack post attempt {attempt}/{max_attempts} failed: {err:#}, retrying in {wait:?}"
+        );
+        tokio::time::sleep(wait).await;
+
+        delay *= 2;
+        attempt += 1;
+    }
+}
+
+fn default_max_attempts() -> usize {
+    env::var("SLACK_MAX_ATTEMPTS")
+        .ok()
+        .and_then(|attempts| attempts.parse().ok())
+        .filter(|&attempts| attempts > 0)
+        .unwrap_or(DEFAULT_MAX_ATTEMPTS)
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
 pub async fn upload_file(filepath: String, channel_id: String) -> anyhow::Result<Option<String>> {
     let created_csv = fs::metadata(filepath.clone())?;
     let file_size = created_csv.len();
@@ -173,7 +269,10 @@ pub fn build_multi_row_table(
 
     body.push(table);
 
-    Ok(SlackMessage { blocks: body })
+    Ok(SlackMessage {
+        blocks: body,
+        attachments: Vec::new(),
+    })
 }
 
 pub fn build_table(
@@ -228,10 +327,66 @@ pub fn build_table(
     };
     body.push(table);
 
-    let slack_message = SlackMessage { blocks: body };
+    let slack_message = SlackMessage {
+        blocks: body,
+        attachments: Vec::new(),
+    };
     Ok(slack_message)
 }
 
+/// Slack rejects section blocks with more than ten fields
+const MAX_SECTION_FIELDS: usize = 10;
+
+/// Build a Block Kit message with a header and labelled fields laid out in
+/// sections, with a `color` bar (hex, e.g. "#2eb886") down the side.
+pub fn build_fields_message(
+    header: String,
+    color: &str,
+    fields: Vec<(String, String)>,
+) -> SlackMessage {
+    let mut blocks: Vec<Block> = Vec::new();
+
+    blocks.push(Block {
+        column_settings: None,
+        block_type: "header".to_string(),
+        fields: None,
+        rows: None,
+        text: Some(Text {
+            text_type: "plain_text".to_string(),
+            text: Some(header),
+            emoji: Some(true),
+        }),
+    });
+
+    let fields: Vec<Text> = fields
+        .into_iter()
+        .map(|(label, value)| Text {
+            text_type: "mrkdwn".to_string(),
+            text: Some(format!("*{label}*\n{value}")),
+            emoji: None,
+        })
+        .collect();
+
+    let mut fields = fields.into_iter().peekable();
+    while fields.peek().is_some() {
+        blocks.push(Block {
+            column_settings: None,
+            block_type: "section".to_string(),
+            fields: Some(fields.by_ref().take(MAX_SECTION_FIELDS).collect()),
+            rows: None,
+            text: None,
+        });
+    }
+
+    SlackMessage {
+        blocks: Vec::new(),
+        attachments: vec![Attachment {
+            color: color.to_string(),
+            blocks,
+        }],
+    }
+}
+
 async fn complete_file_upload(
     client: &Client,
     filename: String,
@@ -310,3 +465,104 @@ fn slack_access_token() -> Result<String> {
         Err(_) => bail!("SLACK_ACCESS_TOKEN env var not set"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+
+    use super::*;
+
+    /// Serves one canned response per connection and counts requests.
+    async fn mock_slack(responses: Vec<&'static str>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let counter = requests.clone();
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                read_request(&mut stream).await;
+                counter.fetch_add(1, Ordering::SeqCst);
+                stream.write_all(response.as_bytes()).await.unwrap();
+                stream.shutdown().await.unwrap();
+            }
+        });
+
+        (url, requests)
+    }
+
+    async fn read_request(stream: &mut TcpStream) {
+        let mut request = Vec::new();
+        let mut buf = [0; 4096];
+        loop {
+            let n = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..n]);
+
+            let text = String::from_utf8_lossy(&request);
+            if let Some(header_end) = text.find("\r\n\r\n") {
+                let content_length = text[..header_end]
+                    .lines()
+                    .find_map(|line| {
+                        let (name, value) = line.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length")
+                            .then(|| value.trim().parse::<usize>().ok())?
+                    })
+                    .unwrap_or(0);
+                if request.len() >= header_end + 4 + content_length {
+                    return;
+                }
+            }
+            if n == 0 {
+                return;
+            }
+        }
+    }
+
+    const TOO_MANY_REQUESTS: &str = "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    const SERVICE_UNAVAILABLE: &str = "HTTP/1.1 503 Service Unavailable\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    const OK: &str = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok";
+
+    #[tokio::test]
+    async fn test_post_message_retries_after_rate_limit() {
+        let (url, requests) = mock_slack(vec![TOO_MANY_REQUESTS, OK]).await;
+
+        post_message_with_retry(&Client::new(), &url, r#"{"text":"hi"}"#, 3)
+            .await
+            .unwrap();
+
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_post_message_gives_up_after_max_attempts() {
+        let (url, requests) = mock_slack(vec![SERVICE_UNAVAILABLE, SERVICE_UNAVAILABLE]).await;
+
+        let err = post_message_with_retry(&Client::new(), &url, r#"{"text":"hi"}"#, 2)
+            .await
+            .unwrap_err();
+
+        assert!(format!("{err:#}").contains("503"));
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_post_message_returns_error() {
+        let (url, requests) = mock_slack(vec![SERVICE_UNAVAILABLE]).await;
+
+        let err = post_message(&Client::new(), url, r#"{"text":"hi"}"#.to_string(), Some(1))
+            .await
+            .unwrap_err();
+
+        assert!(format!("{err:#}").contains("Failed to post message to Slack"));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+}
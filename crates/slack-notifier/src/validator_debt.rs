@@ -1,14 +1,25 @@
 use std::env;
 
-use anyhow::{Result, bail};
-use reqwest::{Body, Client};
+use anyhow::{Context, Result, bail};
+use reqwest::{Client, Url};
 
 use crate::slack;
 
 const VALIDATOR_DEBT_CHANNEL_ID: &str = "C09LES1Q127"; // #tmp-validator-debt
 
+/// Where validator debt reports are posted and how many attempts each post
+/// gets
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SlackTarget<'a> {
+    /// Webhook to post to instead of the `VALIDATOR_SLACK_WEBHOOK` env var
+    pub webhook_override: Option<&'a str>,
+    /// Attempts per message instead of the `SLACK_MAX_ATTEMPTS` env var
+    pub max_attempts: Option<usize>,
+}
+
 pub async fn post_distribution_to_slack(
     filepath: Option<String>,
+    target: SlackTarget<'_>,
     dz_epoch: u64,
     solana_epoch: u64,
     dry_run: bool,
@@ -39,7 +50,15 @@ pub async fn post_distribution_to_slack(
         transaction.unwrap_or("No transaction details".to_string()),
     ];
 
-    post_to_slack(filepath, &client, header, table_header, table_values).await?;
+    post_to_slack(
+        filepath,
+        target,
+        &client,
+        header,
+        table_header,
+        table_values,
+    )
+    .await?;
 
     Ok(())
 }
@@ -48,6 +67,7 @@ pub async fn post_finalized_distribution_to_slack(
     finalized_sig: String,
     dz_epoch: u64,
     dry_run: bool,
+    target: SlackTarget<'_>,
 ) -> Result<()> {
     let client = reqwest::Client::new();
     let header = if dry_run {
@@ -60,13 +80,14 @@ pub async fn post_finalized_distribution_to_slack(
 
     let table_values = vec![dz_epoch.to_string(), finalized_sig.to_string()];
 
-    post_to_slack(None, &client, header, table_header, table_values).await?;
+    post_to_slack(None, target, &client, header, table_header, table_values).await?;
 
     Ok(())
 }
 
 pub async fn post_debt_collections_to_slack(
     client: &Client,
+    target: SlackTarget<'_>,
     header: &str,
     table_header: Vec<String>,
     table_values: Vec<Vec<String>>,
@@ -74,15 +95,140 @@ pub async fn post_debt_collections_to_slack(
     let table = slack::build_multi_row_table(header.to_string(), table_header, table_values)?;
 
     let payload = serde_json::to_string(&table)?;
-    let body = Body::from(payload);
-    let request = slack::build_message_request(client, body, slack_webhook()?)?;
-    let _resp = request.send().await?;
+    slack::post_message(
+        client,
+        slack_webhook(target.webhook_override)?,
+        payload,
+        target.max_attempts,
+    )
+    .await
+}
 
-    Ok(())
+/// Percentage paid below which a debt collection summary is flagged red
+pub const LOW_COLLECTION_PERCENTAGE: f64 = 50.0;
+
+/// Totals across a debt collection run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DebtCollectionSummary {
+    pub total_paid: u64,
+    pub total_debt: u64,
+    pub insufficient_funds_count: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectionStatus {
+    /// All debt has been paid
+    Complete,
+    /// Some debt is outstanding
+    Partial,
+    /// Less than `LOW_COLLECTION_PERCENTAGE` of the debt has been paid
+    Low,
+}
+
+impl CollectionStatus {
+    pub fn color(self) -> &'static str {
+        match self {
+            CollectionStatus::Complete => "#2eb886",
+            CollectionStatus::Partial => "#daa038",
+            CollectionStatus::Low => "#a30200",
+        }
+    }
+}
+
+impl DebtCollectionSummary {
+    pub fn total_outstanding(&self) -> u64 {
+        self.total_debt.saturating_sub(self.total_paid)
+    }
+
+    pub fn percentage_paid(&self) -> f64 {
+        if self.total_debt == 0 {
+            return 100.0;
+        }
+        self.total_paid as f64 / self.total_debt as f64 * 100.0
+    }
+
+    pub fn status(&self) -> CollectionStatus {
+        if self.total_outstanding() == 0 {
+            CollectionStatus::Complete
+        } else if self.percentage_paid() < LOW_COLLECTION_PERCENTAGE {
+            CollectionStatus::Low
+        } else {
+            CollectionStatus::Partial
+        }
+    }
+
+    fn fields(&self) -> Vec<(String, String)> {
+        vec![
+            ("Total Paid".to_string(), format_sol(self.total_paid)),
+            ("Total Debt".to_string(), format_sol(self.total_debt)),
+            (
+                "Total Outstanding".to_string(),
+                format_sol(self.total_outstanding()),
+            ),
+            (
+                "Total Percentage Paid".to_string(),
+                format!("{:.2}%", self.percentage_paid()),
+            ),
+            (
+                "Total Insufficient Funds Count".to_string(),
+                self.insufficient_funds_count.to_string(),
+            ),
+        ]
+    }
+}
+
+fn format_sol(lamports: u64) -> String {
+    format!("{:.9} SOL", lamports as f64 * 1e-9)
+}
+
+/// Build the debt collection summary as a table message
+pub fn build_debt_collection_summary_table(
+    header: &str,
+    summary: &DebtCollectionSummary,
+) -> Result<slack::SlackMessage> {
+    let (table_header, table_values): (Vec<_>, Vec<_>) = summary.fields().into_iter().unzip();
+    slack::build_table(header.to_string(), table_header, table_values)
+}
+
+/// Build the debt collection summary as Block Kit fields, colored green when
+/// fully paid, yellow when partially paid and red when collection is low.
+pub fn build_debt_collection_summary_blocks(
+    header: &str,
+    summary: &DebtCollectionSummary,
+) -> slack::SlackMessage {
+    slack::build_fields_message(
+        header.to_string(),
+        summary.status().color(),
+        summary.fields(),
+    )
+}
+
+pub async fn post_debt_collection_summary_to_slack(
+    client: &Client,
+    target: SlackTarget<'_>,
+    header: &str,
+    summary: &DebtCollectionSummary,
+    use_blocks: bool,
+) -> Result<()> {
+    let msg = if use_blocks {
+        build_debt_collection_summary_blocks(header, summary)
+    } else {
+        build_debt_collection_summary_table(header, summary)?
+    };
+
+    let payload = serde_json::to_string(&msg)?;
+    slack::post_message(
+        client,
+        slack_webhook(target.webhook_override)?,
+        payload,
+        target.max_attempts,
+    )
+    .await
 }
 
 pub async fn post_to_slack(
     filepath: Option<String>,
+    target: SlackTarget<'_>,
     client: &Client,
     header: &str,
     mut table_header: Vec<String>,
@@ -99,16 +245,141 @@ pub async fn post_to_slack(
     let msg = slack::build_table(header.to_string(), table_header, table_values)?;
 
     let payload = serde_json::to_string(&msg)?;
-    let body = Body::from(payload);
-    let request = slack::build_message_request(client, body, slack_webhook()?)?;
-    let _resp = request.send().await?;
-
-    Ok(())
+    slack::post_message(
+        client,
+        slack_webhook(target.webhook_override)?,
+        payload,
+        target.max_attempts,
+    )
+    .await
 }
 
-fn slack_webhook() -> Result<String> {
+/// Resolve the webhook to post to. An explicit override takes precedence
+/// over the `VALIDATOR_SLACK_WEBHOOK` env var.
+pub fn slack_webhook(webhook_override: Option<&str>) -> Result<String> {
+    if let Some(webhook) = webhook_override {
+        return validate_webhook_url(webhook);
+    }
+
     match env::var("VALIDATOR_SLACK_WEBHOOK") {
         Ok(webhook) => Ok(webhook),
         Err(_) => bail!("VALIDATOR_SLACK_WEBHOOK env var not set"),
     }
 }
+
+/// Check that a webhook is an absolute http(s) URL with a host.
+pub fn validate_webhook_url(webhook: &str) -> Result<String> {
+    let url =
+        Url::parse(webhook).with_context(|| format!("Invalid Slack webhook URL: {webhook}"))?;
+
+    if !matches!(url.scheme(), "https" | "http") || url.host_str().is_none() {
+        bail!("Invalid Slack webhook URL: {webhook} (expected an http(s) URL)");
+    }
+
+    Ok(webhook.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use super::*;
+
+    #[test]
+    fn test_slack_webhook_override_takes_precedence() {
+        let env_webhook = "https://hooks.slack.com/services/ENV/WEBHOOK";
+        let override_webhook = "https://hooks.slack.com/services/CLI/OVERRIDE";
+
+        // SAFETY: no other test in this crate reads or writes this env var.
+        unsafe { env::set_var("VALIDATOR_SLACK_WEBHOOK", env_webhook) };
+
+        assert_eq!(
+            slack_webhook(Some(override_webhook)).unwrap(),
+            override_webhook
+        );
+        assert_eq!(slack_webhook(None).unwrap(), env_webhook);
+
+        unsafe { env::remove_var("VALIDATOR_SLACK_WEBHOOK") };
+    }
+
+    #[test]
+    fn test_validate_webhook_url() {
+        assert!(validate_webhook_url("https://hooks.slack.com/services/T/B/X").is_ok());
+        assert!(validate_webhook_url("hooks.slack.com/services/T/B/X").is_err());
+        assert!(validate_webhook_url("ftp://hooks.slack.com/services/T/B/X").is_err());
+        assert!(validate_webhook_url("not a url").is_err());
+    }
+
+    #[test]
+    fn test_debt_collection_summary_status() {
+        let summary = |total_paid| DebtCollectionSummary {
+            total_paid,
+            total_debt: 1_000,
+            insufficient_funds_count: 0,
+        };
+
+        assert_eq!(summary(1_000).status(), CollectionStatus::Complete);
+        assert_eq!(summary(900).status(), CollectionStatus::Partial);
+        assert_eq!(summary(500).status(), CollectionStatus::Partial);
+        assert_eq!(summary(499).status(), CollectionStatus::Low);
+    }
+
+    #[test]
+    fn test_debt_collection_summary_blocks_structure() {
+        let summary = DebtCollectionSummary {
+            total_paid: 750_000_000,
+            total_debt: 1_000_000_000,
+            insufficient_funds_count: 3,
+        };
+
+        let msg = build_debt_collection_summary_blocks("Total Debt Collection", &summary);
+        let value: Value = serde_json::to_value(&msg).unwrap();
+
+        assert_eq!(
+            value,
+            json!({
+                "attachments": [{
+                    "color": "#daa038",
+                    "blocks": [
+                        {
+                            "type": "header",
+                            "text": {
+                                "type": "plain_text",
+                                "text": "Total Debt Collection",
+                                "emoji": true,
+                            },
+                        },
+                        {
+                            "type": "section",
+                            "fields": [
+                                { "type": "mrkdwn", "text": "*Total Paid*\n0.750000000 SOL" },
+                                { "type": "mrkdwn", "text": "*Total Debt*\n1.000000000 SOL" },
+                                { "type": "mrkdwn", "text": "*Total Outstanding*\n0.250000000 SOL" },
+                                { "type": "mrkdwn", "text": "*Total Percentage Paid*\n75.00%" },
+                                { "type": "mrkdwn", "text": "*Total Insufficient Funds Count*\n3" },
+                            ],
+                        },
+                    ],
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn test_debt_collection_summary_table_still_available() {
+        let summary = DebtCollectionSummary {
+            total_paid: 1_000,
+            total_debt: 1_000,
+            insufficient_funds_count: 0,
+        };
+
+        let msg = build_debt_collection_summary_table("Total Debt Collection", &summary).unwrap();
+        let value: Value = serde_json::to_value(&msg).unwrap();
+
+        assert!(value.get("attachments").is_none());
+        assert_eq!(value["blocks"][0]["type"], "header");
+        assert_eq!(value["blocks"][1]["type"], "table");
+        assert_eq!(value["blocks"][1]["rows"][0][0]["text"], "Total Paid");
+        assert_eq!(value["blocks"][1]["rows"][1][3]["text"], "100.00%");
+    }
+}
@@ -0,0 +1,198 @@
+use anyhow::Result;
+use clap::{Args, Subcommand, ValueEnum};
+use doublezero_solana_sdk::{
+    passport::state::{AccessRequest, ProgramConfig as PassportProgramConfig},
+    revenue_distribution::{
+        state::{
+            Distribution, Journal, ProgramConfig as RevenueDistributionProgramConfig,
+            SolanaValidatorDeposit, find_2z_token_pda_address, find_swap_authority_address,
+        },
+        types::DoubleZeroEpoch,
+    },
+    sol_conversion::state::ProgramState as SolConversionProgramState,
+};
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(Debug, Args)]
+pub struct DerivePdaCommand {
+    #[command(subcommand)]
+    pda: PdaKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PdaProgram {
+    RevenueDistribution,
+    Passport,
+    SolConversion,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Subcommand)]
+pub enum PdaKind {
+    /// Program config account. For SOL Conversion, this is its program state.
+    ProgramConfig {
+        #[arg(long, value_enum, default_value = "revenue-distribution")]
+        program: PdaProgram,
+    },
+
+    /// Revenue Distribution distribution account for a DZ epoch.
+    Distribution {
+        #[arg(value_name = "EPOCH")]
+        dz_epoch: u64,
+    },
+
+    /// Revenue Distribution journal account.
+    Journal,
+
+    /// Revenue Distribution swap authority.
+    SwapAuthority,
+
+    /// 2Z token PDA owned by a Revenue Distribution account, like the program
+    /// config, journal, swap authority or a distribution.
+    #[command(name = "2z-token")]
+    Token2z {
+        #[arg(value_name = "KEY")]
+        owner_key: Pubkey,
+    },
+
+    /// Solana validator deposit account for a node ID.
+    ValidatorDeposit {
+        #[arg(value_name = "NODE_ID")]
+        node_id: Pubkey,
+    },
+
+    /// Passport access request account for a service key.
+    AccessRequest {
+        #[arg(value_name = "SERVICE_KEY")]
+        service_key: Pubkey,
+    },
+}
+
+impl PdaKind {
+    fn find_address(&self) -> (Pubkey, u8) {
+        match self {
+            Self::ProgramConfig { program } => match program {
+                PdaProgram::RevenueDistribution => RevenueDistributionProgramConfig::find_address(),
+                PdaProgram::Passport => PassportProgramConfig::find_address(),
+                PdaProgram::SolConversion => SolConversionProgramState::find_address(),
+            },
+            Self::Distribution { dz_epoch } => {
+                Distribution::find_address(DoubleZeroEpoch::new(*dz_epoch))
+            }
+            Self::Journal => Journal::find_address(),
+            Self::SwapAuthority => find_swap_authority_address(),
+            Self::Token2z { owner_key } => find_2z_token_pda_address(owner_key),
+            Self::ValidatorDeposit { node_id } => SolanaValidatorDeposit::find_address(node_id),
+            Self::AccessRequest { service_key } => AccessRequest::find_address(service_key),
+        }
+    }
+}
+
+impl DerivePdaCommand {
+    pub async fn try_into_execute(self) -> Result<()> {
+        let (address, bump) = self.pda.find_address();
+
+        println!("Address: {address}");
+        println!("Bump:    {bump}");
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    #[derive(Debug, Parser)]
+    struct TestCli {
+        #[command(subcommand)]
+        pda: PdaKind,
+    }
+
+    fn parse(args: &[&str]) -> PdaKind {
+        TestCli::try_parse_from(std::iter::once("derive-pda").chain(args.iter().copied()))
+            .unwrap()
+            .pda
+    }
+
+    #[test]
+    fn test_program_config_pdas() {
+        assert_eq!(
+            parse(&["program-config"]).find_address(),
+            RevenueDistributionProgramConfig::find_address()
+        );
+        assert_eq!(
+            parse(&["program-config", "--program", "passport"]).find_address(),
+            PassportProgramConfig::find_address()
+        );
+        assert_eq!(
+            parse(&["program-config", "--program", "sol-conversion"]).find_address(),
+            SolConversionProgramState::find_address()
+        );
+
+        // SOL Conversion seeds are defined in this workspace, so the bump can
+        // be checked against the raw derivation.
+        let (address, bump) =
+            parse(&["program-config", "--program", "sol-conversion"]).find_address();
+        assert_eq!(
+            Pubkey::create_program_address(
+                &[SolConversionProgramState::SEED_PREFIX, &[bump]],
+                &doublezero_solana_sdk::sol_conversion::ID,
+            )
+            .unwrap(),
+            address
+        );
+    }
+
+    #[test]
+    fn test_distribution_pda() {
+        let (address, bump) = parse(&["distribution", "42"]).find_address();
+        assert_eq!(
+            (address, bump),
+            Distribution::find_address(DoubleZeroEpoch::new(42))
+        );
+        assert_ne!(
+            address,
+            parse(&["distribution", "43"]).find_address().0,
+            "each epoch has its own distribution"
+        );
+        assert!(!address.is_on_curve());
+    }
+
+    #[test]
+    fn test_journal_and_swap_authority_pdas() {
+        assert_eq!(parse(&["journal"]).find_address(), Journal::find_address());
+        assert_eq!(
+            parse(&["swap-authority"]).find_address(),
+            find_swap_authority_address()
+        );
+    }
+
+    #[test]
+    fn test_2z_token_pda() {
+        let (journal_key, _) = Journal::find_address();
+        let (address, bump) = parse(&["2z-token", &journal_key.to_string()]).find_address();
+        assert_eq!((address, bump), find_2z_token_pda_address(&journal_key));
+        assert!(!address.is_on_curve());
+    }
+
+    #[test]
+    fn test_validator_deposit_pda() {
+        let node_id = Pubkey::new_unique();
+        assert_eq!(
+            parse(&["validator-deposit", &node_id.to_string()]).find_address(),
+            SolanaValidatorDeposit::find_address(&node_id)
+        );
+    }
+
+    #[test]
+    fn test_access_request_pda() {
+        let service_key = Pubkey::new_unique();
+        let (address, bump) = parse(&["access-request", &service_key.to_string()]).find_address();
+        assert_eq!((address, bump), AccessRequest::find_address(&service_key));
+        assert!(!address.is_on_curve());
+
+        assert!(TestCli::try_parse_from(["derive-pda", "access-request", "not-a-pubkey"]).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+mod derive_pda;
 mod passport;
 mod revenue_distribution;
 
@@ -13,6 +14,10 @@ pub enum DoubleZeroSolanaCommand {
 
     /// Revenue distribution program commands.
     RevenueDistribution(revenue_distribution::RevenueDistributionCommand),
+
+    /// Print the address and bump of a program PDA.
+    #[command(hide = true)]
+    DerivePda(derive_pda::DerivePdaCommand),
 }
 
 impl DoubleZeroSolanaCommand {
@@ -22,30 +27,7 @@ impl DoubleZeroSolanaCommand {
             Self::RevenueDistribution(revenue_distribution) => {
                 revenue_distribution.command.try_into_execute().await
             }
-        }
-    }
-}
-
-fn try_prompt_proceed_confirmation(prompt_message: String, abort_message: String) -> Result<()> {
-    loop {
-        println!("⚠️  {prompt_message}. Proceed? [y/N]");
-
-        let mut input = String::new();
-        std::io::stdin().read_line(&mut input).unwrap();
-
-        let first_char = input
-            .trim()
-            .chars()
-            .next()
-            .map(|c| c.to_lowercase().next().unwrap());
-
-        match first_char {
-            Some('y') => return Ok(()),
-            Some('n') | None => anyhow::bail!("{abort_message}"),
-            _ => {
-                println!("Invalid input. Please enter 'y' for yes or 'n' for no.");
-                continue;
-            }
+            Self::DerivePda(command) => command.try_into_execute().await,
         }
     }
 }
@@ -1,4 +1,6 @@
-use anyhow::{Result, bail};
+use std::collections::HashSet;
+
+use anyhow::{Result, bail, ensure};
 use doublezero_ledger_sentinel::{
     client::solana::SolRpcClientType, constants::ENV_PREVIOUS_LEADER_EPOCHS,
 };
@@ -8,6 +10,10 @@ use solana_sdk::pubkey::Pubkey;
 
 use crate::utils::find_node_by_node_id;
 
+/// Each epoch checked is a separate leader schedule lookup, so keep the
+/// number of RPC calls bounded.
+pub const MAX_LEADER_SCHEDULE_EPOCHS: u8 = 10;
+
 pub async fn validate_validator_access<C>(
     connection: &SolanaConnection,
     sol_client: &C,
@@ -18,6 +24,9 @@ pub async fn validate_validator_access<C>(
 where
     C: SolRpcClientType + Sync,
 {
+    try_validate_validator_ids(primary_validator_id, backup_validator_ids)?;
+    let leader_schedule_epochs = checked_leader_schedule_epochs(leader_schedule_epochs);
+
     let nodes = connection.get_cluster_nodes().await?;
     if nodes.is_empty() {
         bail!("Unable to fetch cluster nodes. Is your RPC endpoint correct?");
@@ -28,11 +37,63 @@ where
         sol_client,
         primary_validator_id,
         backup_validator_ids,
-        leader_schedule_epochs,
+        Some(leader_schedule_epochs),
     )
     .await
 }
 
+/// Falls back to [ENV_PREVIOUS_LEADER_EPOCHS] and clamps to
+/// [MAX_LEADER_SCHEDULE_EPOCHS].
+pub fn checked_leader_schedule_epochs(leader_schedule_epochs: Option<u8>) -> u8 {
+    match leader_schedule_epochs {
+        None => ENV_PREVIOUS_LEADER_EPOCHS,
+        Some(0) => {
+            crate::plain_println!(
+                "⚠️  Leader schedule epochs is 0. No leader schedule will be checked, so the primary validator cannot pass validation"
+            );
+            0
+        }
+        Some(epochs) if epochs > MAX_LEADER_SCHEDULE_EPOCHS => {
+            crate::plain_println!(
+                "⚠️  Leader schedule epochs {epochs} exceeds the maximum. Using {MAX_LEADER_SCHEDULE_EPOCHS}"
+            );
+            MAX_LEADER_SCHEDULE_EPOCHS
+        }
+        Some(epochs) => epochs,
+    }
+}
+
+/// Validator IDs must be set and no ID can be used more than once. Unlike the
+/// gossip and leader schedule checks, these cannot be overridden with
+/// --force.
+pub fn try_validate_validator_ids(
+    primary_validator_id: &Pubkey,
+    backup_validator_ids: &[Pubkey],
+) -> Result<()> {
+    ensure!(
+        *primary_validator_id != Pubkey::default(),
+        "Primary validator ID cannot be the zero pubkey"
+    );
+
+    let mut seen_ids = HashSet::from([primary_validator_id]);
+    for backup_id in backup_validator_ids {
+        ensure!(
+            *backup_id != Pubkey::default(),
+            "Backup validator ID cannot be the zero pubkey"
+        );
+        ensure!(
+            backup_id != primary_validator_id,
+            "Backup validator ID ({backup_id}) is the same as the primary validator ID"
+        );
+        ensure!(
+            seen_ids.insert(backup_id),
+            "Backup validator ID ({backup_id}) is given more than once"
+        );
+    }
+
+    Ok(())
+}
+
 pub async fn validate_validator_access_with_nodes<C>(
     nodes: &[RpcContactInfo],
     sol_client: &C,
@@ -46,9 +107,9 @@ where
     let mut errors = Vec::<String>::new();
     let leader_schedule_epochs = leader_schedule_epochs.unwrap_or(ENV_PREVIOUS_LEADER_EPOCHS);
 
-    println!("Primary validator 🖥️  💎:\n  ID: {primary_validator_id} ");
+    crate::plain_println!("Primary validator 🖥️  💎:\n  ID: {primary_validator_id} ");
     if let Some(node) = find_node_by_node_id(nodes, primary_validator_id) {
-        println!(
+        crate::plain_println!(
             "  Gossip: ✅ OK ({})",
             node.gossip.as_ref().map(|g| g.ip()).unwrap()
         );
@@ -58,16 +119,16 @@ where
             .is_scheduled_leader(primary_validator_id, leader_schedule_epochs)
             .await?
         {
-            print!(" ✅ OK ");
+            crate::plain_print!(" ✅ OK ");
         } else {
-            print!(" ❌ Invalid ");
+            crate::plain_print!(" ❌ Invalid ");
             errors.push(format!(
                 "Primary validator ID ({}) is not an active staked validator. The primary must have stake delegated and be participating in the leader scheduler.",
                 primary_validator_id
             ));
         }
     } else {
-        println!(" ❌ Gossip Fail",);
+        crate::plain_println!(" ❌ Gossip Fail",);
         errors.push(format!(
             "Primary validator ID ({}) is not visible in gossip. The primary validator must appear in gossip to be considered active.",
             primary_validator_id
@@ -76,29 +137,29 @@ where
     println!();
 
     if !backup_validator_ids.is_empty() {
-        println!("\nBackup validator 🖥️  🛟: ");
+        crate::plain_println!("\nBackup validator 🖥️  🛟: ");
 
         for backup_id in backup_validator_ids {
             print!("  ID: {backup_id}\n  Gossip: ");
 
             if let Some(ip) = sol_client.get_validator_ip(backup_id).await? {
-                println!(" ✅ OK ({})", ip);
+                crate::plain_println!(" ✅ OK ({})", ip);
                 print!("  Leader scheduler: ");
 
                 if sol_client
                     .is_scheduled_leader(backup_id, leader_schedule_epochs)
                     .await?
                 {
-                    println!(" ❌ Fail (on leader scheduler)");
+                    crate::plain_println!(" ❌ Fail (on leader scheduler)");
                     errors.push(format!(
                         "Backup validator ID ({}) should not be on leader scheduler. It must be a non-leader scheduled validator.",
                         backup_id
                     ));
                 } else {
-                    println!(" ✅ OK (not a leader scheduled validator)");
+                    crate::plain_println!(" ✅ OK (not a leader scheduled validator)");
                 }
             } else {
-                println!("❌ Gossip Fail",);
+                crate::plain_println!("❌ Gossip Fail",);
                 errors.push(format!(
                     "Backup validator ID ({}) is not visible in gossip. Backup validators must appear in gossip to be considered valid.",
                     backup_id
@@ -138,7 +199,10 @@ mod tests {
     use solana_client::rpc_response::RpcContactInfo;
     use solana_sdk::pubkey::Pubkey;
 
-    use super::validate_validator_access_with_nodes;
+    use super::{
+        MAX_LEADER_SCHEDULE_EPOCHS, checked_leader_schedule_epochs, try_validate_validator_ids,
+        validate_validator_access_with_nodes,
+    };
     use crate::command::passport::access_validation::should_continue_after_validation;
 
     fn make_contact_info(pubkey: &Pubkey, gossip: Option<SocketAddr>) -> RpcContactInfo {
@@ -249,6 +313,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn leader_schedule_epochs_are_clamped() {
+        assert_eq!(
+            checked_leader_schedule_epochs(None),
+            ENV_PREVIOUS_LEADER_EPOCHS
+        );
+        assert_eq!(checked_leader_schedule_epochs(Some(0)), 0);
+        assert_eq!(checked_leader_schedule_epochs(Some(3)), 3);
+        assert_eq!(
+            checked_leader_schedule_epochs(Some(MAX_LEADER_SCHEDULE_EPOCHS)),
+            MAX_LEADER_SCHEDULE_EPOCHS
+        );
+        assert_eq!(
+            checked_leader_schedule_epochs(Some(u8::MAX)),
+            MAX_LEADER_SCHEDULE_EPOCHS
+        );
+    }
+
+    #[test]
+    fn validator_ids_must_be_distinct_and_nonzero() {
+        let primary = Pubkey::new_unique();
+        let backup = Pubkey::new_unique();
+
+        assert!(try_validate_validator_ids(&primary, &[]).is_ok());
+        assert!(try_validate_validator_ids(&primary, &[backup]).is_ok());
+
+        let err = try_validate_validator_ids(&Pubkey::default(), &[backup]).unwrap_err();
+        assert!(err.to_string().contains("Primary validator ID cannot be"));
+
+        let err = try_validate_validator_ids(&primary, &[Pubkey::default()]).unwrap_err();
+        assert!(err.to_string().contains("Backup validator ID cannot be"));
+
+        let err = try_validate_validator_ids(&primary, &[primary]).unwrap_err();
+        assert!(err.to_string().contains("same as the primary"));
+
+        let err = try_validate_validator_ids(&primary, &[backup, backup]).unwrap_err();
+        assert!(err.to_string().contains("more than once"));
+    }
+
     #[test]
     fn should_continue_respects_force_flag() {
         let errors = vec!["some error".to_string()];
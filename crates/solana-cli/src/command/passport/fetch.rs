@@ -1,8 +1,12 @@
 use anyhow::Result;
 use clap::Args;
-use doublezero_solana_client_tools::rpc::{SolanaConnection, SolanaConnectionOptions};
-use doublezero_solana_sdk::passport::instruction::AccessMode;
-use solana_sdk::pubkey::Pubkey;
+use doublezero_solana_client_tools::{
+    account::zero_copy::ZeroCopyAccountOwnedData,
+    rpc::{SolanaConnection, SolanaConnectionOptions, try_fetch_multiple_accounts},
+};
+use doublezero_solana_sdk::passport::{instruction::AccessMode, state::AccessRequest};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use tabled::{Table, Tabled, settings::Style};
 
 #[derive(Debug, Args)]
 pub struct FetchCommand {
@@ -12,6 +16,15 @@ pub struct FetchCommand {
     #[arg(long, value_name = "DOUBLEZERO_PUBKEY")]
     access_request: Option<Pubkey>,
 
+    /// Show the access request status of each DoubleZero address. Can be
+    /// repeated or given as a comma-separated list.
+    #[arg(
+        long = "doublezero-address",
+        value_name = "DOUBLEZERO_PUBKEY,DOUBLEZERO_PUBKEY",
+        value_delimiter = ','
+    )]
+    doublezero_addresses: Vec<Pubkey>,
+
     #[command(flatten)]
     solana_connection_options: SolanaConnectionOptions,
 }
@@ -21,6 +34,7 @@ impl FetchCommand {
         let FetchCommand {
             config,
             access_request,
+            doublezero_addresses,
             solana_connection_options,
         } = self;
 
@@ -86,24 +100,135 @@ impl FetchCommand {
                 "Request fee          | {:.9} SOL",
                 access_request.request_fee_lamports as f64 * 1e-9
             );
-            match access_request.checked_access_mode() {
-                Some(access_mode) => {
-                    let access_mode_str = match access_mode {
-                        AccessMode::SolanaValidator(_) => "Solana validator",
-                        AccessMode::SolanaValidatorWithBackupIds { .. } => {
-                            "Solana validator with backup IDs"
-                        }
-                    };
-                    println!("Access mode          | {access_mode_str}");
-                }
-                None => {
-                    println!("Access mode          | Unknown");
-                }
-            }
+            println!(
+                "Access mode          | {}",
+                access_mode_label(&access_request)
+            );
 
             println!();
         }
 
+        if !doublezero_addresses.is_empty() {
+            let access_request_keys = doublezero_addresses
+                .iter()
+                .map(|service_key| AccessRequest::find_address(service_key).0)
+                .collect::<Vec<_>>();
+            let accounts = try_fetch_multiple_accounts(&connection, &access_request_keys).await?;
+
+            let rows = access_status_rows(&doublezero_addresses, accounts);
+            println!("{}", Table::new(rows).with(Style::markdown()));
+        }
+
         Ok(())
     }
 }
+
+#[derive(Debug, Tabled)]
+struct AccessStatusRow {
+    doublezero_address: Pubkey,
+    status: &'static str,
+    access_mode: &'static str,
+    request_fee: String,
+}
+
+fn access_mode_label(access_request: &AccessRequest) -> &'static str {
+    match access_request.checked_access_mode() {
+        Some(AccessMode::SolanaValidator(_)) => "Solana validator",
+        Some(AccessMode::SolanaValidatorWithBackupIds { .. }) => "Solana validator with backup IDs",
+        None => "Unknown",
+    }
+}
+
+/// One row per service key. Accounts are expected in the same order as the
+/// service keys. A missing account means there is no outstanding request,
+/// either because none was made or because the sentinel already handled it.
+fn access_status_rows(
+    service_keys: &[Pubkey],
+    accounts: Vec<Option<Account>>,
+) -> Vec<AccessStatusRow> {
+    service_keys
+        .iter()
+        .zip(accounts)
+        .map(|(service_key, account)| {
+            let access_request = account
+                .as_ref()
+                .and_then(ZeroCopyAccountOwnedData::<AccessRequest>::from_account);
+
+            match (account, access_request) {
+                (None, _) => AccessStatusRow {
+                    doublezero_address: *service_key,
+                    status: "Not found",
+                    access_mode: "-",
+                    request_fee: "-".to_string(),
+                },
+                (Some(_), None) => AccessStatusRow {
+                    doublezero_address: *service_key,
+                    status: "Invalid account",
+                    access_mode: "-",
+                    request_fee: "-".to_string(),
+                },
+                (Some(_), Some(access_request)) => AccessStatusRow {
+                    doublezero_address: *service_key,
+                    status: "Pending",
+                    access_mode: access_mode_label(&access_request),
+                    request_fee: format!(
+                        "{:.9} SOL",
+                        access_request.request_fee_lamports as f64 * 1e-9
+                    ),
+                },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use doublezero_solana_sdk::{PrecomputedDiscriminator, passport::ID};
+
+    use super::*;
+
+    fn access_request_account(service_key: &Pubkey, request_fee_lamports: u64) -> Account {
+        let mut access_request: AccessRequest = bytemuck::Zeroable::zeroed();
+        access_request.service_key = *service_key;
+        access_request.request_fee_lamports = request_fee_lamports;
+
+        let mut data = AccessRequest::discriminator_slice().to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&access_request));
+
+        Account {
+            lamports: 1_000_000,
+            data,
+            owner: ID,
+            executable: false,
+            rent_epoch: u64::MAX,
+        }
+    }
+
+    #[test]
+    fn test_access_status_rows() {
+        let service_keys = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let accounts = vec![
+            Some(access_request_account(&service_keys[0], 100_000_000)),
+            None,
+            Some(Account::default()),
+        ];
+
+        let rows = access_status_rows(&service_keys, accounts);
+        assert_eq!(rows.len(), 3);
+
+        assert_eq!(rows[0].doublezero_address, service_keys[0]);
+        assert_eq!(rows[0].status, "Pending");
+        assert_eq!(rows[0].request_fee, "0.100000000 SOL");
+
+        assert_eq!(rows[1].doublezero_address, service_keys[1]);
+        assert_eq!(rows[1].status, "Not found");
+        assert_eq!(rows[1].access_mode, "-");
+
+        assert_eq!(rows[2].doublezero_address, service_keys[2]);
+        assert_eq!(rows[2].status, "Invalid account");
+    }
+}
@@ -7,6 +7,7 @@ use doublezero_ledger_sentinel::{
 };
 use doublezero_sdk::get_doublezero_pubkey;
 use doublezero_solana_client_tools::rpc::{SolanaConnection, SolanaConnectionOptions};
+use serde::Serialize;
 use solana_client::rpc_response::RpcContactInfo;
 use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
 use url::Url;
@@ -21,19 +22,38 @@ pub struct FindValidatorCommand {
     #[arg(long, value_name = "IP_ADDRESS")]
     gossip_ip: Option<String>,
 
+    /// Print the validator as JSON instead of human-readable output.
+    #[arg(long)]
+    json: bool,
+
     #[command(flatten)]
     solana_connection_options: SolanaConnectionOptions,
 }
 
+/// Structured output of `find-validator --json`. Fields the RPC cannot
+/// provide are null.
+#[derive(Debug, Serialize)]
+struct FindValidatorOutput {
+    identity: String,
+    gossip_ip: Option<String>,
+    vote_account: Option<String>,
+    activated_stake: Option<u64>,
+    leader_slots_recent: Option<usize>,
+    is_leader_scheduled: bool,
+}
+
 impl FindValidatorCommand {
     pub async fn try_into_execute(self) -> Result<()> {
         let FindValidatorCommand {
             validator_id,
             gossip_ip,
+            json,
             solana_connection_options,
         } = self;
 
-        println!("DoubleZero Passport - Find Validator");
+        if !json {
+            println!("DoubleZero Passport - Find Validator");
+        }
 
         // Establish a connection to the Solana cluster
         let connection = SolanaConnection::from(solana_connection_options);
@@ -42,12 +62,14 @@ impl FindValidatorCommand {
             Arc::new(Keypair::new()),
         );
 
-        // Identify the cluster
-        let cluster = identify_cluster(&connection).await;
-        println!("Connected to Solana: {:}\n", cluster);
+        if !json {
+            // Identify the cluster
+            let cluster = identify_cluster(&connection).await;
+            println!("Connected to Solana: {:}\n", cluster);
 
-        if let Ok(kp) = get_doublezero_pubkey() {
-            println!("DoubleZero ID: {}", kp.pubkey())
+            if let Ok(kp) = get_doublezero_pubkey() {
+                println!("DoubleZero ID: {}", kp.pubkey())
+            }
         }
 
         // Fetch the cluster nodes
@@ -60,11 +82,9 @@ impl FindValidatorCommand {
         if let Some(node_id) = validator_id {
             // Search by node_id
             if let Some(node) = find_node_by_node_id(&nodes, &node_id) {
-                print_node_info(node, &sol_client).await?;
+                output_node_info(node, &connection, &sol_client, json).await?;
             } else {
-                println!(
-                    "⚠️  Warning: Your node ID is not appearing in gossip. Your validator must be visible in gossip in order to connect to DoubleZero."
-                );
+                warn_not_in_gossip("node ID", json)?;
             }
         } else if let Some(ip_str) = gossip_ip {
             // Search by server_ip
@@ -76,17 +96,17 @@ impl FindValidatorCommand {
                 }
             };
             if let Some(node) = find_node_by_ip(&nodes, server_ip) {
-                print_node_info(node, &sol_client).await?;
+                output_node_info(node, &connection, &sol_client, json).await?;
             } else {
-                println!(
-                    "⚠️  Warning: Your IP is not appearing in gossip. Your validator must be visible in gossip in order to connect to DoubleZero."
-                );
+                warn_not_in_gossip("IP", json)?;
             }
         } else {
             // Neither node_id nor server_ip provided, attempt to detect public IP
             match try_get_public_ipv4() {
                 Ok(ip) => {
-                    println!("Detected public IP: {ip}");
+                    if !json {
+                        println!("Detected public IP: {ip}");
+                    }
                     let server_ip: Ipv4Addr = match ip.parse() {
                         Ok(addr) => addr,
                         Err(e) => {
@@ -95,11 +115,9 @@ impl FindValidatorCommand {
                         }
                     };
                     if let Some(node) = find_node_by_ip(&nodes, server_ip) {
-                        print_node_info(node, &sol_client).await?;
+                        output_node_info(node, &connection, &sol_client, json).await?;
                     } else {
-                        println!(
-                            "⚠️  Warning: Your IP is not appearing in gossip. Your validator must be visible in gossip in order to connect to DoubleZero."
-                        );
+                        warn_not_in_gossip("IP", json)?;
                     }
                 }
                 Err(e) => println!("Failed to get public IP: {e}"),
@@ -110,6 +128,74 @@ impl FindValidatorCommand {
     }
 }
 
+/// Warn that the validator is not in gossip. JSON output has nothing to
+/// print, so the warning becomes an error instead.
+fn warn_not_in_gossip(lookup: &str, json: bool) -> Result<()> {
+    let message = format!(
+        "Your {lookup} is not appearing in gossip. Your validator must be visible in gossip in order to connect to DoubleZero."
+    );
+
+    if json {
+        anyhow::bail!(message);
+    }
+
+    crate::plain_println!("⚠️  Warning: {message}");
+    Ok(())
+}
+
+async fn output_node_info(
+    node: &RpcContactInfo,
+    connection: &SolanaConnection,
+    sol_client: &SolRpcClient,
+    json: bool,
+) -> Result<()> {
+    if !json {
+        return print_node_info(node, sol_client).await;
+    }
+
+    let pubkey = node.pubkey.parse::<Pubkey>().expect("Invalid pubkey");
+
+    let is_leader_scheduled = sol_client
+        .is_scheduled_leader(&pubkey, ENV_PREVIOUS_LEADER_EPOCHS)
+        .await?;
+
+    // Vote account and leader schedule are best effort, since not every RPC
+    // serves them.
+    let vote_account = connection
+        .get_vote_accounts()
+        .await
+        .ok()
+        .and_then(|status| {
+            status
+                .current
+                .into_iter()
+                .chain(status.delinquent)
+                .find(|vote_account| vote_account.node_pubkey == node.pubkey)
+        });
+
+    let leader_slots_recent = connection
+        .get_leader_schedule(None)
+        .await
+        .ok()
+        .flatten()
+        .map(|schedule| schedule.get(&node.pubkey).map_or(0, Vec::len));
+
+    let output = FindValidatorOutput {
+        identity: node.pubkey.clone(),
+        gossip_ip: node.gossip.map(|gossip| gossip.ip().to_string()),
+        vote_account: vote_account
+            .as_ref()
+            .map(|vote_account| vote_account.vote_pubkey.clone()),
+        activated_stake: vote_account.map(|vote_account| vote_account.activated_stake),
+        leader_slots_recent,
+        is_leader_scheduled,
+    };
+
+    println!("{}", serde_json::to_string_pretty(&output)?);
+
+    Ok(())
+}
+
 async fn print_node_info(node: &RpcContactInfo, sol_client: &SolRpcClient) -> Result<()> {
     println!("Validator ID: {}", node.pubkey);
     match &node.gossip {
@@ -124,14 +210,56 @@ async fn print_node_info(node: &RpcContactInfo, sol_client: &SolRpcClient) -> Re
         .await?
     {
         println!("In Leader scheduler");
-        println!(
+        crate::plain_println!(
             "✅ This validator can connect as a primary in DoubleZero 🖥️  💎. It is a leader scheduled validator."
         );
     } else {
-        println!(
+        crate::plain_println!(
             "✅ This validator can only connect as a backup in DoubleZero 🖥️  🛟. It is not leader scheduled and cannot act as a primary validator."
         );
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_validator_output_json() {
+        let output = FindValidatorOutput {
+            identity: "11111111111111111111111111111112".to_string(),
+            gossip_ip: Some("10.0.0.1".to_string()),
+            vote_account: Some("11111111111111111111111111111113".to_string()),
+            activated_stake: Some(42_000_000_000),
+            leader_slots_recent: Some(16),
+            is_leader_scheduled: true,
+        };
+
+        let value = serde_json::to_value(&output).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "identity": "11111111111111111111111111111112",
+                "gossip_ip": "10.0.0.1",
+                "vote_account": "11111111111111111111111111111113",
+                "activated_stake": 42_000_000_000u64,
+                "leader_slots_recent": 16,
+                "is_leader_scheduled": true,
+            })
+        );
+
+        // Unavailable fields are null rather than omitted.
+        let output = FindValidatorOutput {
+            vote_account: None,
+            activated_stake: None,
+            leader_slots_recent: None,
+            ..output
+        };
+        let value = serde_json::to_value(&output).unwrap();
+        assert!(value["vote_account"].is_null());
+        assert!(value["activated_stake"].is_null());
+        assert!(value["leader_slots_recent"].is_null());
+    }
+}
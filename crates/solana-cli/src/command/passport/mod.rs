@@ -9,6 +9,7 @@ pub mod fetch;
 pub mod find_validator;
 pub mod prepare_access;
 pub mod request_access;
+pub mod revoke_access;
 
 #[derive(Debug, Args, Clone)]
 pub struct SharedAccessArgs {
@@ -21,7 +22,7 @@ pub struct SharedAccessArgs {
     /// Optional backup validator IDs (identity pubkeys)
     #[arg(long, value_name = "PUBKEY,PUBKEY,PUBKEY", value_delimiter = ',')]
     pub backup_validator_ids: Vec<Pubkey>,
-    /// Number of previous epochs to check when evaluating the leader schedule (defaults to ENV_PREVIOUS_LEADER_EPOCHS)
+    /// Number of previous epochs to check when evaluating the leader schedule (defaults to ENV_PREVIOUS_LEADER_EPOCHS, at most 10)
     #[arg(long, hide = true)]
     pub leader_schedule_epochs: Option<u8>,
 }
@@ -42,6 +43,8 @@ pub enum PassportSubcommand {
     PrepareValidatorAccess(prepare_access::PrepareValidatorAccessCommand),
     /// Request access as a Solana Validator
     RequestValidatorAccess(request_access::RequestValidatorAccessCommand),
+    /// Revoke an outstanding access request (sentinel only)
+    RevokeValidatorAccess(revoke_access::RevokeValidatorAccessCommand),
 }
 
 impl PassportSubcommand {
@@ -51,6 +54,7 @@ impl PassportSubcommand {
             Self::FindValidator(command) => command.try_into_execute().await,
             Self::PrepareValidatorAccess(command) => command.try_into_execute().await,
             Self::RequestValidatorAccess(command) => command.try_into_execute().await,
+            Self::RevokeValidatorAccess(command) => command.try_into_execute().await,
         }
     }
 }
@@ -17,7 +17,9 @@ use doublezero_solana_sdk::{
 };
 use solana_sdk::{
     compute_budget::ComputeBudgetInstruction,
+    instruction::Instruction,
     offchain_message::OffchainMessage,
+    pubkey::Pubkey,
     signature::{Keypair, Signature},
 };
 use url::Url;
@@ -87,10 +89,15 @@ impl RequestValidatorAccessCommand {
 
         let tx_sig = self.request_access(&wallet).await?;
 
-        if let TransactionOutcome::Executed(tx_sig) = tx_sig {
-            println!("Request Solana validator access: {tx_sig}");
+        match tx_sig {
+            TransactionOutcome::Executed(tx_sig) => {
+                println!("Request Solana validator access: {tx_sig}");
 
-            wallet.print_verbose_output(&[tx_sig]).await?;
+                wallet.print_verbose_output(&[tx_sig]).await?;
+            }
+            TransactionOutcome::Simulated(_) => {
+                println!("\nSimulated only (--dry-run). Access request {address} was not created");
+            }
         }
 
         Ok(())
@@ -144,7 +151,8 @@ impl RequestValidatorAccessCommand {
             &PassportInstructionData::RequestAccess(access_mode),
         )?;
 
-        let (_, bump) = AccessRequest::find_address(&self.shared.doublezero_address);
+        let (access_request_key, bump) =
+            AccessRequest::find_address(&self.shared.doublezero_address);
 
         let mut compute_unit_limit = 10_000;
         compute_unit_limit += Wallet::compute_units_for_bump_seed(bump);
@@ -158,8 +166,127 @@ impl RequestValidatorAccessCommand {
             instructions.push(compute_unit_price_ix.clone());
         }
 
-        let transaction = wallet.new_transaction(&instructions).await?;
+        try_send_or_simulate_request_access(wallet, &instructions, &access_request_key).await
+    }
+}
+
+/// With --dry-run, print the accounts the request would touch before the
+/// wallet prints the simulated compute units and program logs.
+async fn try_send_or_simulate_request_access(
+    wallet: &Wallet,
+    instructions: &[Instruction],
+    access_request_key: &Pubkey,
+) -> Result<TransactionOutcome> {
+    let transaction = wallet.new_transaction(instructions).await?;
+
+    if wallet.dry_run {
+        println!("Access request PDA: {access_request_key}");
+        println!("Accounts:");
+        for account_meta in &instructions[0].accounts {
+            let mode = match (account_meta.is_signer, account_meta.is_writable) {
+                (true, true) => "signer, writable",
+                (true, false) => "signer",
+                (false, true) => "writable",
+                (false, false) => "read-only",
+            };
+            println!("  {} ({mode})", account_meta.pubkey);
+        }
+        println!();
+    }
+
+    wallet.send_or_simulate_transaction(&transaction).await
+}
+
+#[cfg(test)]
+mod tests {
+    use doublezero_solana_client_tools::rpc::SolanaConnection;
+    use solana_sdk::hash::Hash;
+    use wiremock::{Mock, MockServer, ResponseTemplate, matchers};
+
+    use super::*;
+
+    fn rpc_response(value: serde_json::Value) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "jsonrpc": "2.0",
+            "result": {
+                "context": { "slot": 1 },
+                "value": value
+            },
+            "id": 1
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_does_not_send() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(matchers::method("POST"))
+            .and(matchers::body_partial_json(
+                serde_json::json!({ "method": "getLatestBlockhash" }),
+            ))
+            .respond_with(rpc_response(serde_json::json!({
+                "blockhash": Hash::default().to_string(),
+                "lastValidBlockHeight": 100
+            })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(matchers::method("POST"))
+            .and(matchers::body_partial_json(
+                serde_json::json!({ "method": "simulateTransaction" }),
+            ))
+            .respond_with(rpc_response(serde_json::json!({
+                "err": null,
+                "logs": ["Program log: Instruction: RequestAccess"],
+                "accounts": null,
+                "unitsConsumed": 8_000
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(matchers::method("POST"))
+            .and(matchers::body_partial_json(
+                serde_json::json!({ "method": "sendTransaction" }),
+            ))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let wallet = Wallet {
+            connection: SolanaConnection::new(mock_server.uri()),
+            signer: Keypair::new(),
+            compute_unit_price_ix: None,
+            verbose: false,
+            fee_payer: None,
+            dry_run: true,
+            tx_rate_limiter: None,
+        };
+
+        let service_key = Pubkey::new_unique();
+        let (access_request_key, _) = AccessRequest::find_address(&service_key);
+        let instructions = [
+            try_build_instruction(
+                &ID,
+                RequestAccessAccounts::new(&wallet.pubkey(), &service_key),
+                &PassportInstructionData::RequestAccess(AccessMode::SolanaValidator(
+                    SolanaValidatorAttestation {
+                        validator_id: Pubkey::new_unique(),
+                        service_key,
+                        ed25519_signature: [0; 64],
+                    },
+                )),
+            )
+            .unwrap(),
+            ComputeBudgetInstruction::set_compute_unit_limit(10_000),
+        ];
+
+        let outcome =
+            try_send_or_simulate_request_access(&wallet, &instructions, &access_request_key)
+                .await
+                .unwrap();
+        assert!(matches!(outcome, TransactionOutcome::Simulated(_)));
 
-        wallet.send_or_simulate_transaction(&transaction).await
+        // The simulation ran once and nothing was sent.
+        mock_server.verify().await;
     }
 }
@@ -0,0 +1,129 @@
+use anyhow::{Result, ensure};
+use clap::Args;
+use doublezero_solana_client_tools::{
+    payer::{SolanaPayerOptions, TransactionOutcome, Wallet},
+    rpc::SolanaConnection,
+};
+use doublezero_solana_sdk::{
+    passport::{
+        ID,
+        instruction::{PassportInstructionData, account::DenyAccessAccounts},
+    },
+    try_build_instruction,
+};
+use solana_sdk::{compute_budget::ComputeBudgetInstruction, pubkey::Pubkey};
+
+/*
+   doublezero-solana passport revoke-validator-access --doublezero-address SSSS
+*/
+
+#[derive(Debug, Args)]
+pub struct RevokeValidatorAccessCommand {
+    /// The DoubleZero service key whose access request should be revoked
+    #[arg(long)]
+    doublezero_address: Pubkey,
+
+    #[command(flatten)]
+    solana_payer_options: SolanaPayerOptions,
+}
+
+impl RevokeValidatorAccessCommand {
+    pub async fn try_into_execute(self) -> Result<()> {
+        let Self {
+            doublezero_address,
+            solana_payer_options,
+        } = self;
+
+        let wallet = Wallet::try_from(solana_payer_options)?;
+        let wallet_key = wallet.pubkey();
+
+        println!("DoubleZero Passport - Revoke Validator Access");
+        println!("\nDoubleZero Address: {doublezero_address}\n");
+
+        let access_request_key =
+            try_revoke_preflight(&wallet.connection, &doublezero_address, &wallet_key).await?;
+
+        // Revoking is the same as the sentinel denying the request, which
+        // closes the access request account.
+        let revoke_access_ix = try_build_instruction(
+            &ID,
+            DenyAccessAccounts::new(&wallet_key, &access_request_key),
+            &PassportInstructionData::DenyAccess,
+        )?;
+
+        let mut instructions = vec![
+            revoke_access_ix,
+            ComputeBudgetInstruction::set_compute_unit_limit(12_000),
+        ];
+
+        if let Some(ref compute_unit_price_ix) = wallet.compute_unit_price_ix {
+            instructions.push(compute_unit_price_ix.clone());
+        }
+
+        let transaction = wallet.new_transaction(&instructions).await?;
+        let tx_sig = wallet.send_or_simulate_transaction(&transaction).await?;
+
+        if let TransactionOutcome::Executed(tx_sig) = tx_sig {
+            println!("Revoke Solana validator access: {tx_sig}");
+
+            wallet.print_verbose_output(&[tx_sig]).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Make sure there is an access request to revoke and that the signer is
+/// allowed to revoke it. Returns the access request key.
+async fn try_revoke_preflight(
+    connection: &SolanaConnection,
+    service_key: &Pubkey,
+    authority_key: &Pubkey,
+) -> Result<Pubkey> {
+    let (access_request_key, _) = super::fetch_access_request(connection, service_key).await?;
+
+    let (_, program_config) = super::fetch_program_config(connection).await?;
+    ensure!(
+        program_config.sentinel_key == *authority_key,
+        "Only the sentinel ({}) can revoke access requests. Signer is {authority_key}",
+        program_config.sentinel_key
+    );
+
+    Ok(access_request_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{Mock, MockServer, ResponseTemplate, matchers};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_revoke_preflight_no_access_request() {
+        let mock_server = MockServer::start().await;
+
+        // Every account lookup comes back empty.
+        Mock::given(matchers::method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "result": {
+                    "context": { "slot": 1 },
+                    "value": null
+                },
+                "id": 1
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let connection = SolanaConnection::new(mock_server.uri());
+        let service_key = Pubkey::new_unique();
+
+        let err = try_revoke_preflight(&connection, &service_key, &Pubkey::new_unique())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("Access request not found for service key {service_key}")
+        );
+    }
+}
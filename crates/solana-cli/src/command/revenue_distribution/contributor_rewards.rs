@@ -1,9 +1,16 @@
-use anyhow::{Result, bail};
+use std::collections::HashSet;
+
+use anyhow::{Context, Result, bail, ensure};
 use clap::Args;
-use doublezero_solana_client_tools::payer::{SolanaPayerOptions, TransactionOutcome, Wallet};
+use doublezero_solana_client_tools::{
+    payer::{SolanaPayerOptions, TransactionOutcome, Wallet},
+    rpc::{DoubleZeroLedgerConnection, DoubleZeroLedgerEnvironmentOverride, SolanaConnection},
+};
 use doublezero_solana_sdk::{
+    DOUBLEZERO_MINT_DECIMALS,
     revenue_distribution::{
         ID,
+        fetch::{try_fetch_config, try_fetch_distribution},
         instruction::{
             RevenueDistributionInstructionData, account::InitializeContributorRewardsAccounts,
         },
@@ -12,6 +19,15 @@ use doublezero_solana_sdk::{
     try_build_instruction,
 };
 use solana_sdk::{compute_budget::ComputeBudgetInstruction, pubkey::Pubkey};
+use tabled::Tabled;
+
+use crate::command::revenue_distribution::{
+    fetch::{TableOptions, print_table},
+    try_fetch_shapley_record,
+};
+
+/// Recipient shares are in basis points, so a complete split sums to 100%.
+const RECIPIENT_SHARES_TOTAL: u16 = 10_000;
 
 #[derive(Debug, Args)]
 pub struct ContributorRewardsCommand {
@@ -20,20 +36,62 @@ pub struct ContributorRewardsCommand {
     #[arg(long)]
     initialize: bool,
 
+    /// Comma-separated recipient split as <PUBKEY>:<BASIS_POINTS>, summing to
+    /// 10000. Only used with --simulate.
+    #[arg(long, value_name = "RECIPIENTS", requires = "simulate")]
+    recipients: Option<String>,
+
+    /// Instead of sending a transaction, print what each recipient would have
+    /// received for the most recent finalized epoch.
+    #[arg(long, requires = "recipients", conflicts_with = "initialize")]
+    simulate: bool,
+
+    #[arg(hide = true, long)]
+    rewards_accountant: Option<Pubkey>,
+
+    #[command(flatten)]
+    dz_env: DoubleZeroLedgerEnvironmentOverride,
+
     #[command(flatten)]
     solana_payer_options: SolanaPayerOptions,
 }
 
+#[derive(Debug, Tabled)]
+struct SimulatedPayoutTableRow {
+    dz_epoch: u64,
+    recipient: Pubkey,
+    proportion: String,
+    payout: String,
+}
+
 impl ContributorRewardsCommand {
     pub async fn try_into_execute(self) -> Result<()> {
         let ContributorRewardsCommand {
             service_key,
             initialize,
+            recipients,
+            simulate,
+            rewards_accountant: rewards_accountant_key,
+            dz_env,
             solana_payer_options,
         } = self;
 
+        if simulate {
+            let recipients = parse_recipients(recipients.as_deref().unwrap_or_default())?;
+            validate_recipients(&recipients)?;
+
+            return try_print_simulated_payouts(
+                SolanaConnection::from(solana_payer_options.connection_options),
+                dz_env,
+                rewards_accountant_key,
+                &service_key,
+                &recipients,
+            )
+            .await;
+        }
+
         if !initialize {
-            bail!("Nothing to do. Please specify `--initialize`");
+            bail!("Nothing to do. Please specify `--initialize` or `--simulate`");
         }
 
         let wallet = Wallet::try_from(solana_payer_options)?;
@@ -71,3 +129,173 @@ impl ContributorRewardsCommand {
         Ok(())
     }
 }
+
+//
+
+async fn try_print_simulated_payouts(
+    solana_connection: SolanaConnection,
+    dz_env: DoubleZeroLedgerEnvironmentOverride,
+    rewards_accountant_key: Option<Pubkey>,
+    service_key: &Pubkey,
+    recipients: &[(Pubkey, u16)],
+) -> Result<()> {
+    let network_env = solana_connection.try_network_environment().await?;
+    let dz_connection = DoubleZeroLedgerConnection::from(dz_env.dz_env.unwrap_or(network_env));
+
+    let (_, config) = try_fetch_config(&solana_connection).await?;
+    let dz_epoch_value = config.next_completed_dz_epoch.value().saturating_sub(1);
+
+    let (_, distribution) = try_fetch_distribution(&solana_connection, dz_epoch_value).await?;
+    ensure!(
+        distribution.is_rewards_calculation_finalized(),
+        "Rewards calculation for epoch {dz_epoch_value} is not finalized yet"
+    );
+
+    let shapley_record = try_fetch_shapley_record(
+        &dz_connection,
+        &rewards_accountant_key.unwrap_or(config.rewards_accountant_key),
+        dz_epoch_value,
+    )
+    .await?;
+
+    let reward_share = shapley_record
+        .rewards
+        .iter()
+        .find(|reward_share| reward_share.contributor_key == *service_key)
+        .with_context(|| format!("No reward for {service_key} in epoch {dz_epoch_value}"))?;
+
+    // TODO: Revisit when economic burn rate is introduced.
+    let collected_rewards = distribution.total_collected_2z_tokens();
+    let burnable_rewards = distribution
+        .community_burn_rate
+        .mul_scalar(collected_rewards);
+    let distributable_rewards = collected_rewards - burnable_rewards;
+
+    let reward = reward_share
+        .checked_unit_share()
+        .context("Invalid reward unit share")?
+        .mul_scalar(distributable_rewards);
+
+    let rows = recipients
+        .iter()
+        .zip(project_recipient_payouts(reward, recipients))
+        .map(|((recipient, share), payout)| SimulatedPayoutTableRow {
+            dz_epoch: dz_epoch_value,
+            recipient: *recipient,
+            proportion: format!("{:.2}%", *share as f64 / 100.0),
+            payout: format!(
+                "{:.1} 2Z",
+                payout as f64 / f64::powi(10.0, DOUBLEZERO_MINT_DECIMALS as i32)
+            ),
+        })
+        .collect::<Vec<_>>();
+
+    print_table(
+        rows,
+        TableOptions {
+            columns_aligned_right: Some(&[0, 2, 3]),
+        },
+    );
+
+    Ok(())
+}
+
+/// Parse a comma-separated list of `<PUBKEY>:<BASIS_POINTS>` recipients.
+fn parse_recipients(recipients: &str) -> Result<Vec<(Pubkey, u16)>> {
+    recipients
+        .split(',')
+        .map(str::trim)
+        .filter(|recipient| !recipient.is_empty())
+        .map(|recipient| {
+            let (key, share) = recipient
+                .split_once(':')
+                .with_context(|| format!("Expected <PUBKEY>:<BASIS_POINTS>, got {recipient}"))?;
+            let key = key
+                .parse::<Pubkey>()
+                .with_context(|| format!("Invalid recipient pubkey {key}"))?;
+            let share = share
+                .parse::<u16>()
+                .with_context(|| format!("Invalid basis points {share} for {key}"))?;
+
+            Ok((key, share))
+        })
+        .collect()
+}
+
+/// Ensure recipients are unique, each has a nonzero share and the shares sum
+/// to 100%.
+fn validate_recipients(recipients: &[(Pubkey, u16)]) -> Result<()> {
+    ensure!(!recipients.is_empty(), "At least one recipient is required");
+
+    let mut seen = HashSet::new();
+    for (key, share) in recipients {
+        ensure!(seen.insert(key), "Duplicate recipient {key}");
+        ensure!(*share > 0, "Recipient {key} has a zero share");
+    }
+
+    let total = recipients
+        .iter()
+        .map(|(_, share)| u32::from(*share))
+        .sum::<u32>();
+    ensure!(
+        total == u32::from(RECIPIENT_SHARES_TOTAL),
+        "Recipient shares must sum to {RECIPIENT_SHARES_TOTAL} basis points, got {total}"
+    );
+
+    Ok(())
+}
+
+/// Split a contributor's reward by basis-point shares. Rounding dust goes to
+/// the first recipient so the payouts sum to the reward.
+fn project_recipient_payouts(reward: u64, recipients: &[(Pubkey, u16)]) -> Vec<u64> {
+    let mut payouts = recipients
+        .iter()
+        .map(|(_, share)| {
+            (u128::from(reward) * u128::from(*share) / u128::from(RECIPIENT_SHARES_TOTAL)) as u64
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(first) = payouts.first_mut() {
+        let projected = payouts.iter().sum::<u64>();
+        *first += reward - projected;
+    }
+
+    payouts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_projected_payouts_sum_to_reward() {
+        let recipients = parse_recipients(&format!(
+            "{}:3333, {}:3333,{}:3334",
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique()
+        ))
+        .unwrap();
+        validate_recipients(&recipients).unwrap();
+
+        for reward in [0, 1, 999, 1_000_000_007, u64::MAX / 3] {
+            let payouts = project_recipient_payouts(reward, &recipients);
+            assert_eq!(payouts.len(), recipients.len());
+            assert_eq!(payouts.iter().sum::<u64>(), reward);
+        }
+
+        let payouts = project_recipient_payouts(10_000, &recipients);
+        assert_eq!(payouts, vec![3_333, 3_333, 3_334]);
+    }
+
+    #[test]
+    fn test_validate_recipients() {
+        let key = Pubkey::new_unique();
+
+        assert!(validate_recipients(&[]).is_err());
+        assert!(validate_recipients(&[(key, 9_999)]).is_err());
+        assert!(validate_recipients(&[(key, 5_000), (key, 5_000)]).is_err());
+        assert!(validate_recipients(&[(key, 10_000), (Pubkey::new_unique(), 0)]).is_err());
+        assert!(parse_recipients(&format!("{key}=10000")).is_err());
+    }
+}
@@ -1,28 +1,82 @@
 use anyhow::{Context, Result, ensure};
-use clap::Args;
+use clap::{Args, ValueEnum};
 use doublezero_solana_client_tools::{
     instruction::take_instruction,
     payer::{SolanaPayerOptions, TransactionOutcome, Wallet},
-    rpc::SolanaConnection,
+    rpc::{NetworkEnvironment, SolanaConnection},
 };
 use doublezero_solana_sdk::{
     revenue_distribution::{env::mainnet::DOUBLEZERO_MINT_KEY, fetch::SolConversionState},
     sol_conversion::{
         ID,
         instruction::{SolConversionInstructionData, account::BuySolAccounts},
-        oracle,
+        oracle::{self, OraclePriceData},
     },
     try_build_instruction,
 };
+use serde::Serialize;
+use solana_client::{
+    client_error::ClientError,
+    rpc_config::{RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig},
+    rpc_response::RpcSimulateTransactionResult,
+};
 use solana_sdk::{
-    compute_budget::ComputeBudgetInstruction, instruction::Instruction, program_pack::Pack,
+    compute_budget::ComputeBudgetInstruction,
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
     pubkey::Pubkey,
+    signature::Signature,
+    transaction::TransactionError,
 };
 
-use crate::command::{
-    revenue_distribution::try_request_oracle_conversion_price, try_prompt_proceed_confirmation,
+use crate::{
+    command::revenue_distribution::{OracleOptions, try_request_oracle_conversion_price},
+    terminal::try_prompt_proceed_confirmation,
 };
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// Receipt printed with `--output json`. SOL amounts are in lamports, 2Z
+/// amounts in the token's base units and the limit price in oracle rate
+/// precision.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct Convert2zReceipt {
+    /// Whether the transaction was only simulated
+    simulated: bool,
+    /// Transaction signature, absent when simulated
+    signature: Option<String>,
+    tokens_spent: u64,
+    sol_received: u64,
+    limit_price: u64,
+    token_balance_before: u64,
+    token_balance_after: u64,
+}
+
+impl Convert2zReceipt {
+    fn new(
+        signature: Option<Signature>,
+        token_balance_before: u64,
+        token_balance_after: u64,
+        sol_received: u64,
+        limit_price: u64,
+    ) -> Self {
+        Self {
+            simulated: signature.is_none(),
+            signature: signature.map(|signature| signature.to_string()),
+            tokens_spent: token_balance_before.saturating_sub(token_balance_after),
+            sol_received,
+            limit_price,
+            token_balance_before,
+            token_balance_after,
+        }
+    }
+}
+
 #[derive(Debug, Args, Clone)]
 pub struct Convert2zCommand {
     /// Limit price defaults to the current SOL/2Z oracle price.
@@ -39,6 +93,18 @@ pub struct Convert2zCommand {
     #[arg(long, value_name = "SOL")]
     checked_sol_amount: Option<String>,
 
+    /// Output format. JSON prints a receipt of the conversion.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+
+    #[command(flatten)]
+    oracle_options: OracleOptions,
+
+    /// If the conversion fails because the oracle price moved after it was
+    /// fetched, retry with a fresh price up to this many times.
+    #[arg(long, value_name = "COUNT", default_value_t = 2)]
+    max_price_retries: usize,
+
     #[command(flatten)]
     solana_payer_options: SolanaPayerOptions,
 }
@@ -49,10 +115,14 @@ impl Convert2zCommand {
             limit_price: limit_price_str,
             source_2z_account: source_token_account_key,
             checked_sol_amount: checked_sol_amount_str,
+            output,
+            oracle_options,
+            max_price_retries,
             solana_payer_options,
         } = self;
+        let json = output == OutputFormat::Json;
 
-        let mut wallet = Wallet::try_from(solana_payer_options)?;
+        let wallet = Wallet::try_from(solana_payer_options)?;
 
         let checked_lamports = match checked_sol_amount_str {
             Some(checked_sol_amount_str) => {
@@ -72,58 +142,198 @@ impl Convert2zCommand {
             None => None,
         };
 
-        let sol_conversion_state = SolConversionState::try_fetch(&wallet.connection).await?;
-        let fixed_fill_quantity = sol_conversion_state.fixed_fill_quantity;
-
-        let mut convert_2z_context = Convert2zContext::try_prepare(
-            &wallet,
-            &sol_conversion_state,
-            limit_price_str,
-            source_token_account_key,
-            checked_lamports,
-        )
-        .await?;
-        let buy_sol_ix = take_instruction(&mut convert_2z_context.instruction);
-
-        let balance_before = convert_2z_context
-            .try_token_balance(&wallet.connection)
+        let network_env = Convert2zContext::try_ensure_supported_network(&wallet).await?;
+        let oracle_endpoint = oracle_options.endpoint(network_env);
+
+        let wallet = &wallet;
+        let oracle_endpoint = &oracle_endpoint;
+        let limit_price_str = &limit_price_str;
+
+        // Each attempt fetches the conversion state again, since the discount
+        // depends on the slot of the last trade.
+        let (convert_2z_context, fixed_fill_quantity, balance_before, tx_outcome) =
+            try_with_price_retries(
+                max_price_retries,
+                move || try_request_oracle_conversion_price(oracle_endpoint),
+                move |oracle_price_data| async move {
+                    let sol_conversion_state =
+                        SolConversionState::try_fetch(&wallet.connection).await?;
+
+                    let mut convert_2z_context = Convert2zContext::try_prepare_with_price(
+                        wallet,
+                        &sol_conversion_state,
+                        limit_price_str.clone(),
+                        source_token_account_key,
+                        checked_lamports,
+                        oracle_price_data,
+                    )
+                    .await?;
+                    let buy_sol_ix = take_instruction(&mut convert_2z_context.instruction);
+
+                    let balance_before = convert_2z_context
+                        .try_token_balance(&wallet.connection)
+                        .await?;
+
+                    // Must match BUY_SOL_INSTRUCTION_INDEX.
+                    let mut instructions = vec![
+                        buy_sol_ix,
+                        ComputeBudgetInstruction::set_compute_unit_limit(
+                            Convert2zContext::BUY_SOL_COMPUTE_UNIT_LIMIT,
+                        ),
+                    ];
+
+                    if let Some(compute_unit_price_ix) = &wallet.compute_unit_price_ix {
+                        instructions.push(compute_unit_price_ix.clone());
+                    }
+
+                    let transaction = wallet.new_transaction(&instructions).await?;
+                    let tx_outcome = wallet
+                        .send_or_simulate_transaction_with_configs(
+                            &transaction,
+                            wallet.default_send_transaction_config(),
+                            RpcSimulateTransactionConfig {
+                                accounts: Some(RpcSimulateTransactionAccountsConfig {
+                                    encoding: Default::default(),
+                                    addresses: vec![
+                                        wallet.pubkey().to_string(),
+                                        convert_2z_context.user_token_account_key.to_string(),
+                                    ],
+                                }),
+                                ..wallet.default_simulate_transaction_config()
+                            },
+                        )
+                        .await?;
+
+                    Ok((
+                        convert_2z_context,
+                        sol_conversion_state.fixed_fill_quantity,
+                        balance_before,
+                        tx_outcome,
+                    ))
+                },
+            )
             .await?;
-        println!("2Z token balance: {:.8}", balance_before as f64 * 1e-8);
 
-        let mut instructions = vec![
-            buy_sol_ix,
-            ComputeBudgetInstruction::set_compute_unit_limit(
-                Convert2zContext::BUY_SOL_COMPUTE_UNIT_LIMIT,
-            ),
-        ];
+        if !json {
+            println!("2Z token balance: {:.8}", balance_before as f64 * 1e-8);
+        }
 
-        if let Some(compute_unit_price_ix) = wallet.compute_unit_price_ix.as_mut() {
-            instructions.push(take_instruction(compute_unit_price_ix));
+        match tx_outcome {
+            TransactionOutcome::Executed(tx_sig) => {
+                let balance_after = convert_2z_context
+                    .try_token_balance(&wallet.connection)
+                    .await?;
+
+                if json {
+                    let receipt = Convert2zReceipt::new(
+                        Some(tx_sig),
+                        balance_before,
+                        balance_after,
+                        fixed_fill_quantity,
+                        convert_2z_context.limit_price,
+                    );
+                    println!("{}", serde_json::to_string_pretty(&receipt)?);
+                } else {
+                    println!("Converted 2Z to SOL: {tx_sig}");
+                    println!(
+                        "Converted {:.8} 2Z tokens to {:.9} SOL",
+                        (balance_before - balance_after) as f64 * 1e-8,
+                        (fixed_fill_quantity as f64 * 1e-9)
+                    );
+
+                    wallet.print_verbose_output(&[tx_sig]).await?;
+                }
+            }
+            TransactionOutcome::Simulated(simulation_response) => {
+                if json {
+                    let simulated_balances = try_parse_simulated_balances(simulation_response)?;
+                    let receipt = Convert2zReceipt::new(
+                        None,
+                        balance_before,
+                        simulated_balances.token_amount,
+                        fixed_fill_quantity,
+                        convert_2z_context.limit_price,
+                    );
+                    println!("{}", serde_json::to_string_pretty(&receipt)?);
+                }
+            }
         }
 
-        let transaction = wallet.new_transaction(&instructions).await?;
-        let tx_sig = wallet.send_or_simulate_transaction(&transaction).await?;
+        Ok(())
+    }
+}
 
-        if let TransactionOutcome::Executed(tx_sig) = tx_sig {
-            println!("Converted 2Z to SOL: {tx_sig}");
+//
 
-            let balance_after = convert_2z_context
-                .try_token_balance(&wallet.connection)
-                .await?;
-            println!(
-                "Converted {:.8} 2Z tokens to {:.9} SOL",
-                (balance_before - balance_after) as f64 * 1e-8,
-                (fixed_fill_quantity as f64 * 1e-9)
-            );
+/// Index of the buy SOL instruction in the convert-2z transaction.
+const BUY_SOL_INSTRUCTION_INDEX: u8 = 0;
+
+/// Runs `attempt` with a price from `fetch_price`. If the buy SOL
+/// instruction fails and the oracle price has moved since it was fetched,
+/// the attempt is retried with the new price, up to `max_price_retries`
+/// times. Other failures are returned right away.
+async fn try_with_price_retries<T, F, FFut, A, AFut>(
+    max_price_retries: usize,
+    mut fetch_price: F,
+    mut attempt: A,
+) -> Result<T>
+where
+    F: FnMut() -> FFut,
+    FFut: Future<Output = Result<OraclePriceData>>,
+    A: FnMut(OraclePriceData) -> AFut,
+    AFut: Future<Output = Result<T>>,
+{
+    let mut oracle_price_data = fetch_price().await?;
+    let mut price_retries = 0;
+
+    loop {
+        let err = match attempt(oracle_price_data.clone()).await {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
 
-            wallet.print_verbose_output(&[tx_sig]).await?;
+        if !is_buy_sol_program_error(&err) {
+            return Err(err);
         }
 
-        Ok(())
+        let latest_oracle_price_data = fetch_price().await?;
+        if latest_oracle_price_data == oracle_price_data {
+            return Err(err.context("Buy SOL failed, but the oracle price has not moved"));
+        }
+
+        if price_retries == max_price_retries {
+            return Err(err.context(format!(
+                "Oracle price moved after {price_retries} retries. Try again or increase \
+                 --max-price-retries"
+            )));
+        }
+
+        price_retries += 1;
+        eprintln!(
+            "Oracle price moved from {:.8} to {:.8}. Retrying ({price_retries}/{max_price_retries})",
+            oracle_price_data.swap_rate as f64 * 1e-8,
+            latest_oracle_price_data.swap_rate as f64 * 1e-8,
+        );
+        oracle_price_data = latest_oracle_price_data;
     }
 }
 
-//
+/// Whether the buy SOL instruction itself failed, like when the limit price
+/// check fails, as opposed to the transaction failing to land.
+fn is_buy_sol_program_error(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|err| err.downcast_ref::<ClientError>())
+        .filter_map(ClientError::get_transaction_error)
+        .any(|tx_err| {
+            matches!(
+                tx_err,
+                TransactionError::InstructionError(
+                    BUY_SOL_INSTRUCTION_INDEX,
+                    InstructionError::Custom(_)
+                )
+            )
+        })
+}
 
 fn parse_limit_price_to_u64(bid_price_str: String) -> Result<u64> {
     const RATE_PRECISION: f64 =
@@ -153,6 +363,42 @@ fn parse_limit_price_to_u64(bid_price_str: String) -> Result<u64> {
     Ok((bid_price * RATE_PRECISION).round() as u64)
 }
 
+/// Balances after a simulation that returned the signer and its 2Z token
+/// account, in that order
+pub struct SimulatedBalances {
+    pub lamports: u64,
+    pub token_amount: u64,
+}
+
+pub fn try_parse_simulated_balances(
+    simulation_response: RpcSimulateTransactionResult,
+) -> Result<SimulatedBalances> {
+    let post_simulation_account_infos = simulation_response
+        .accounts
+        .context("Simulation did not return account infos")?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    ensure!(
+        post_simulation_account_infos.len() == 2,
+        "Expected 2 accounts after simulation, got {}",
+        post_simulation_account_infos.len()
+    );
+
+    let token_account_data = post_simulation_account_infos[1]
+        .data
+        .decode()
+        .context("Failed to decode ATA account info")?;
+    let token_amount = spl_token_interface::state::Account::unpack(&token_account_data)
+        .context("Simulated account is not a token account")?
+        .amount;
+
+    Ok(SimulatedBalances {
+        lamports: post_simulation_account_infos[0].lamports,
+        token_amount,
+    })
+}
+
 pub fn unwrap_token_account_or_ata(
     wallet: &Wallet,
     source_token_account_key: Option<Pubkey>,
@@ -181,12 +427,42 @@ impl Convert2zContext {
         limit_price_str: Option<String>,
         source_token_account_key: Option<Pubkey>,
         checked_lamports: Option<u64>,
+        oracle_options: &OracleOptions,
     ) -> Result<Self> {
+        let network_env = Self::try_ensure_supported_network(wallet).await?;
+        let oracle_price_data =
+            try_request_oracle_conversion_price(&oracle_options.endpoint(network_env)).await?;
+
+        Self::try_prepare_with_price(
+            wallet,
+            sol_conversion_state,
+            limit_price_str,
+            source_token_account_key,
+            checked_lamports,
+            oracle_price_data,
+        )
+        .await
+    }
+
+    pub async fn try_ensure_supported_network(wallet: &Wallet) -> Result<NetworkEnvironment> {
         let network_env = wallet.connection.try_network_environment().await?;
         ensure!(
             network_env.is_mainnet_beta(),
             "2Z conversion is only supported on mainnet-beta"
         );
+        Ok(network_env)
+    }
+
+    /// Same as [Self::try_prepare], with an oracle price that was already
+    /// fetched. The network is not checked.
+    pub async fn try_prepare_with_price(
+        wallet: &Wallet,
+        sol_conversion_state: &SolConversionState,
+        limit_price_str: Option<String>,
+        source_token_account_key: Option<Pubkey>,
+        checked_lamports: Option<u64>,
+        oracle_price_data: OraclePriceData,
+    ) -> Result<Self> {
         let wallet_key = wallet.pubkey();
 
         let SolConversionState {
@@ -214,7 +490,6 @@ impl Convert2zContext {
         let user_token_account_key = unwrap_token_account_or_ata(wallet, source_token_account_key);
 
         let current_slot = wallet.connection.get_slot().await?;
-        let oracle_price_data = try_request_oracle_conversion_price().await?;
 
         // Compute discount.
         let discount_params = oracle::DiscountParameters::from_configuration_registry(
@@ -268,3 +543,144 @@ impl Convert2zContext {
             .with_context(|| format!("Account {user_token_account_key} not token account"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use anyhow::anyhow;
+
+    use super::*;
+
+    fn oracle_price_data(swap_rate: u64, timestamp: i64) -> OraclePriceData {
+        OraclePriceData {
+            swap_rate,
+            timestamp,
+            signature: Default::default(),
+        }
+    }
+
+    fn buy_sol_program_error() -> anyhow::Error {
+        ClientError::from(TransactionError::InstructionError(
+            BUY_SOL_INSTRUCTION_INDEX,
+            InstructionError::Custom(6_000),
+        ))
+        .into()
+    }
+
+    #[tokio::test]
+    async fn test_price_retry_after_stale_price() {
+        let prices = [oracle_price_data(2_500, 1), oracle_price_data(2_600, 2)];
+        let fetches = AtomicUsize::new(0);
+        let attempts = &Mutex::new(Vec::new());
+
+        let value = try_with_price_retries(
+            2,
+            || async { Ok(prices[fetches.fetch_add(1, Ordering::SeqCst)].clone()) },
+            move |oracle_price_data| async move {
+                let mut attempts = attempts.lock().unwrap();
+                attempts.push(oracle_price_data.swap_rate);
+                if attempts.len() == 1 {
+                    Err(buy_sol_program_error().context("Failed to send transaction"))
+                } else {
+                    Ok(42)
+                }
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(value, 42);
+        assert_eq!(*attempts.lock().unwrap(), vec![2_500, 2_600]);
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_price_retry_distinguishes_other_failures() {
+        let attempts = AtomicUsize::new(0);
+
+        // The price has not moved, so the failure is not stale pricing.
+        let err = try_with_price_retries(
+            2,
+            || async { Ok(oracle_price_data(2_500, 1)) },
+            |_| async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(buy_sol_program_error())
+            },
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("has not moved"));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        // Not a buy SOL failure.
+        let attempts = AtomicUsize::new(0);
+        let err = try_with_price_retries(
+            2,
+            || async { Ok(oracle_price_data(2_500, 1)) },
+            |_| async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(anyhow!("2Z token account not found"))
+            },
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.to_string(), "2Z token account not found");
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        // The price keeps moving.
+        let fetches = AtomicUsize::new(0);
+        let attempts = AtomicUsize::new(0);
+        let err = try_with_price_retries(
+            1,
+            || async {
+                let timestamp = fetches.fetch_add(1, Ordering::SeqCst) as i64;
+                Ok(oracle_price_data(2_500, timestamp))
+            },
+            |_| async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(buy_sol_program_error())
+            },
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("--max-price-retries"));
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_convert_2z_receipt_json() {
+        let tx_sig = Signature::from([3; 64]);
+        let receipt =
+            Convert2zReceipt::new(Some(tx_sig), 500_000_000, 120_000_000, 1_000_000_000, 2_500);
+        assert_eq!(
+            serde_json::to_value(&receipt).unwrap(),
+            serde_json::json!({
+                "simulated": false,
+                "signature": tx_sig.to_string(),
+                "tokens_spent": 380_000_000,
+                "sol_received": 1_000_000_000,
+                "limit_price": 2_500,
+                "token_balance_before": 500_000_000,
+                "token_balance_after": 120_000_000,
+            })
+        );
+
+        let receipt = Convert2zReceipt::new(None, 500_000_000, 120_000_000, 1_000_000_000, 2_500);
+        assert_eq!(
+            serde_json::to_value(&receipt).unwrap(),
+            serde_json::json!({
+                "simulated": true,
+                "signature": null,
+                "tokens_spent": 380_000_000,
+                "sol_received": 1_000_000_000,
+                "limit_price": 2_500,
+                "token_balance_before": 500_000_000,
+                "token_balance_after": 120_000_000,
+            })
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use anyhow::Result;
+use clap::Args;
+use doublezero_solana_client_tools::rpc::{SolanaConnection, SolanaConnectionOptions};
+use doublezero_solana_sdk::revenue_distribution::state::SolanaValidatorDeposit;
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(Debug, Args)]
+pub struct DeriveDepositCommand {
+    /// Node ID of the Solana validator.
+    #[arg(value_name = "NODE_ID")]
+    node_id: Pubkey,
+
+    #[command(flatten)]
+    connection_options: SolanaConnectionOptions,
+}
+
+impl DeriveDepositCommand {
+    pub async fn try_into_execute(self) -> Result<()> {
+        let Self {
+            node_id,
+            connection_options,
+        } = self;
+
+        let (deposit_key, bump) = SolanaValidatorDeposit::find_address(&node_id);
+
+        let connection = SolanaConnection::from(connection_options);
+        let (_, deposit, balance) =
+            super::try_fetch_solana_validator_deposit(&connection, &node_id).await?;
+
+        for line in deposit_derivation_lines(&node_id, &deposit_key, bump, deposit.map(|_| balance))
+        {
+            println!("{line}");
+        }
+
+        Ok(())
+    }
+}
+
+//
+
+/// Derived deposit PDA and bump for a node ID, with the deposit balance if the
+/// account is initialized.
+fn deposit_derivation_lines(
+    node_id: &Pubkey,
+    deposit_key: &Pubkey,
+    bump: u8,
+    balance: Option<u64>,
+) -> Vec<String> {
+    vec![
+        format!("Node ID:     {node_id}"),
+        format!("Deposit PDA: {deposit_key}"),
+        format!("Bump:        {bump}"),
+        match balance {
+            Some(balance) => format!("Balance:     {:.9} SOL", balance as f64 * 1e-9),
+            None => "Balance:     deposit account not initialized".to_string(),
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_deposit_derivation_lines() {
+        let node_id = Pubkey::from_str("6WgdYhhGE53WrZ7ywJA15hBVkw7CRbQ8yDBBTwmBtAHN").unwrap();
+        let (deposit_key, bump) = SolanaValidatorDeposit::find_address(&node_id);
+
+        // The derivation is deterministic and lands off the ed25519 curve.
+        assert_eq!(
+            SolanaValidatorDeposit::find_address(&node_id),
+            (deposit_key, bump)
+        );
+        assert!(!deposit_key.is_on_curve());
+        assert_ne!(
+            SolanaValidatorDeposit::find_address(&Pubkey::new_unique()).0,
+            deposit_key
+        );
+
+        assert_eq!(
+            deposit_derivation_lines(&node_id, &deposit_key, bump, Some(1_500_000_000)),
+            vec![
+                "Node ID:     6WgdYhhGE53WrZ7ywJA15hBVkw7CRbQ8yDBBTwmBtAHN".to_string(),
+                format!("Deposit PDA: {deposit_key}"),
+                format!("Bump:        {bump}"),
+                "Balance:     1.500000000 SOL".to_string(),
+            ]
+        );
+        assert_eq!(
+            deposit_derivation_lines(&node_id, &deposit_key, bump, None)[3],
+            "Balance:     deposit account not initialized"
+        );
+    }
+}
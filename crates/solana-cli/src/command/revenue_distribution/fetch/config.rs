@@ -4,6 +4,9 @@ use doublezero_solana_client_tools::rpc::{SolanaConnection, SolanaConnectionOpti
 use doublezero_solana_sdk::revenue_distribution::{
     fetch::try_fetch_config, state::CommunityBurnRateMode,
 };
+use serde::Serialize;
+
+use super::FetchOutput;
 
 #[derive(Debug, Args)]
 pub struct ConfigCommand {
@@ -11,7 +14,7 @@ pub struct ConfigCommand {
     connection_options: SolanaConnectionOptions,
 }
 
-#[derive(Debug, tabled::Tabled)]
+#[derive(Debug, tabled::Tabled, Serialize)]
 struct ConfigTableRow {
     field: &'static str,
     value: String,
@@ -19,15 +22,22 @@ struct ConfigTableRow {
 }
 
 impl ConfigCommand {
-    pub async fn try_into_execute(self) -> Result<()> {
+    pub async fn try_into_execute(self, output: FetchOutput) -> Result<()> {
         let Self { connection_options } = self;
 
         let connection = SolanaConnection::from(connection_options);
         let (config_key, config) = try_fetch_config(&connection).await?;
 
         if config.is_paused() {
-            println!("⚠️  Warning: Program is paused");
-            println!();
+            let warning_message = crate::terminal::plain("⚠️  Warning: Program is paused");
+
+            // Keep stdout parseable when printing JSON.
+            if output.is_json() {
+                eprintln!("{warning_message}");
+            } else {
+                println!("{warning_message}");
+                println!();
+            }
         }
 
         let distribution_parameters = &config.distribution_parameters;
@@ -216,14 +226,12 @@ impl ConfigCommand {
             note: write_off_note,
         });
 
-        super::print_table(
+        output.print(
             value_rows,
             super::TableOptions {
                 columns_aligned_right: Some(&[1]),
             },
-        );
-
-        Ok(())
+        )
     }
 }
 
@@ -247,7 +255,7 @@ pub struct ValidatorFeesCommand {
 }
 
 impl ValidatorFeesCommand {
-    pub async fn try_into_execute(self) -> Result<()> {
+    pub async fn try_into_execute(self, output: FetchOutput) -> Result<()> {
         let Self { connection_options } = self;
         let connection = SolanaConnection::from(connection_options);
         let (_, config) = try_fetch_config(&connection).await?;
@@ -303,19 +311,18 @@ impl ValidatorFeesCommand {
         }
 
         if value_rows.is_empty() {
-            println!("... Solana validator fee parameters not configured yet");
+            output.print_empty("... Solana validator fee parameters not configured yet");
             return Ok(());
         }
 
-        super::print_table(value_rows, Default::default());
-
-        Ok(())
+        output.print(value_rows, Default::default())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::command::revenue_distribution::fetch::first_json_row;
 
     #[test]
     fn test_format_write_off_activation_epoch_disabled() {
@@ -337,4 +344,16 @@ mod tests {
         assert_eq!(value, "epoch 42");
         assert_eq!(note, "Active");
     }
+
+    #[test]
+    fn test_json_rows() {
+        let row = first_json_row(vec![ConfigTableRow {
+            field: "Next distribution",
+            value: "10".to_string(),
+            note: "Current DoubleZero Ledger epoch".to_string(),
+        }]);
+        assert_eq!(row["field"], "Next distribution");
+        assert_eq!(row["value"], "10");
+        assert_eq!(row["note"], "Current DoubleZero Ledger epoch");
+    }
 }
@@ -8,6 +8,7 @@ use doublezero_solana_sdk::{
     PrecomputedDiscriminator, environment_2z_token_mint_key,
     revenue_distribution::{self, state::ContributorRewards},
 };
+use serde::Serialize;
 use solana_account_decoder_client_types::UiAccountEncoding;
 use solana_client::{
     rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
@@ -17,6 +18,8 @@ use solana_sdk::pubkey::Pubkey;
 use spl_associated_token_account_interface::address::get_associated_token_address_and_bump_seed;
 use tabled::Tabled;
 
+use super::{FetchOutput, serialize_pubkey_as_string};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
 pub enum ContributorRewardsViewMode {
     #[default]
@@ -39,24 +42,27 @@ pub struct ContributorRewardsCommand {
     connection_options: SolanaConnectionOptions,
 }
 
-#[derive(Debug, Tabled)]
+#[derive(Debug, Tabled, Serialize)]
 struct ContributorRewardsSummaryRow {
+    #[serde(serialize_with = "serialize_pubkey_as_string")]
     service_key: Pubkey,
     manager: String,
     blocks_protocol_management: &'static str,
     recipients_configured_count: u8,
 }
 
-#[derive(Debug, Tabled)]
+#[derive(Debug, Tabled, Serialize)]
 struct ContributorRewardsRecipientRow {
     index: usize,
+    #[serde(serialize_with = "serialize_pubkey_as_string")]
     recipient: Pubkey,
+    #[serde(serialize_with = "serialize_pubkey_as_string")]
     ata: Pubkey,
     proportion: String,
 }
 
 impl ContributorRewardsCommand {
-    pub async fn try_into_execute(self) -> Result<()> {
+    pub async fn try_into_execute(self, output: FetchOutput) -> Result<()> {
         let Self {
             service_key,
             manager,
@@ -78,10 +84,10 @@ impl ContributorRewardsCommand {
 
         match view {
             ContributorRewardsViewMode::Summary => {
-                try_print_summary_view(&connection, service_key, manager).await
+                try_print_summary_view(&connection, service_key, manager, output).await
             }
             ContributorRewardsViewMode::Recipients => {
-                try_print_recipients_view(&connection, service_key.unwrap()).await
+                try_print_recipients_view(&connection, service_key.unwrap(), output).await
             }
         }
     }
@@ -91,6 +97,7 @@ async fn try_print_summary_view(
     connection: &SolanaConnection,
     service_key: Option<Pubkey>,
     manager_filter: Option<Pubkey>,
+    output: FetchOutput,
 ) -> Result<()> {
     let accounts = if let Some(service_key) = service_key {
         let (pda_key, _) = ContributorRewards::find_address(&service_key);
@@ -170,19 +177,18 @@ async fn try_print_summary_view(
     // Sort by service_key for consistent output
     rows.sort_by_key(|row| row.service_key.to_string());
 
-    super::print_table(
+    output.print(
         rows,
         super::TableOptions {
             columns_aligned_right: Some(&[2, 3]),
         },
-    );
-
-    Ok(())
+    )
 }
 
 async fn try_print_recipients_view(
     connection: &SolanaConnection,
     service_key: Pubkey,
+    output: FetchOutput,
 ) -> Result<()> {
     let (pda_key, _) = ContributorRewards::find_address(&service_key);
 
@@ -221,14 +227,12 @@ async fn try_print_recipients_view(
         bail!("No recipients configured for service key {service_key}");
     }
 
-    super::print_table(
+    output.print(
         rows,
         super::TableOptions {
             columns_aligned_right: Some(&[0, 3]),
         },
-    );
-
-    Ok(())
+    )
 }
 
 #[cfg(test)]
@@ -236,6 +240,7 @@ mod tests {
     use doublezero_solana_client_tools::rpc::SolanaConnectionOptions;
 
     use super::*;
+    use crate::command::revenue_distribution::fetch::first_json_row;
 
     const UNIT_SHARE16_MAX: u16 = 10_000;
 
@@ -267,6 +272,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_json_rows() {
+        let service_key = Pubkey::new_unique();
+        let summary_row = first_json_row(vec![ContributorRewardsSummaryRow {
+            service_key,
+            manager: Default::default(),
+            blocks_protocol_management: "no",
+            recipients_configured_count: 2,
+        }]);
+        assert_eq!(summary_row["service_key"], service_key.to_string());
+        assert_eq!(summary_row["recipients_configured_count"], 2);
+
+        let recipient = Pubkey::new_unique();
+        let recipient_row = first_json_row(vec![ContributorRewardsRecipientRow {
+            index: 0,
+            recipient,
+            ata: Pubkey::new_unique(),
+            proportion: format_proportion(UNIT_SHARE16_MAX),
+        }]);
+        assert_eq!(recipient_row["recipient"], recipient.to_string());
+        assert_eq!(recipient_row["proportion"], "100.00%");
+    }
+
     #[tokio::test]
     async fn test_recipients_view_requires_service_key() {
         // Construct the command with Recipients view but no service_key
@@ -278,7 +306,7 @@ mod tests {
         };
 
         // Call the real execute method - validation happens before any RPC calls
-        let result = cmd.try_into_execute().await;
+        let result = cmd.try_into_execute(FetchOutput::Table).await;
 
         // Must be an error
         assert!(
@@ -309,7 +337,7 @@ mod tests {
         };
 
         // Call the real execute method - validation happens before any RPC calls
-        let result = cmd.try_into_execute().await;
+        let result = cmd.try_into_execute(FetchOutput::Table).await;
 
         assert!(
             result.is_err(),
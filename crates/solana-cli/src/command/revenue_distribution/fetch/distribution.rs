@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, ops::RangeInclusive};
 
 use anyhow::{Context, Result, ensure};
 use clap::{Args, ValueEnum};
@@ -17,6 +17,8 @@ use doublezero_solana_sdk::{
         types::UnitShare32,
     },
 };
+use futures::{StreamExt, TryStreamExt, stream};
+use serde::Serialize;
 use solana_client::{
     rpc_config::RpcProgramAccountsConfig,
     rpc_filter::{Memcmp, RpcFilterType},
@@ -25,11 +27,14 @@ use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey};
 use tabled::Tabled;
 
 use crate::command::revenue_distribution::{
-    fetch::{TableOptions, print_table},
+    fetch::{FetchOutput, TableOptions},
     try_distribution_rewards_iter, try_distribution_solana_validator_debt_iter,
     try_fetch_shapley_record,
 };
 
+/// Distributions fetched at once for an epoch range.
+const MAX_CONCURRENT_DISTRIBUTION_FETCHES: usize = 8;
+
 #[derive(Debug, Clone, PartialEq, Eq, ValueEnum)]
 pub enum DistributionViewMode {
     Summary,
@@ -44,6 +49,19 @@ pub struct DistributionCommand {
     #[arg(long, short = 'e')]
     dz_epoch: Option<u64>,
 
+    /// First DZ epoch of a range to summarize with one row per epoch.
+    #[arg(
+        long,
+        value_name = "EPOCH",
+        requires = "to_epoch",
+        conflicts_with = "dz_epoch"
+    )]
+    from_epoch: Option<u64>,
+
+    /// Last DZ epoch (inclusive) of a range to summarize.
+    #[arg(long, value_name = "EPOCH", requires = "from_epoch")]
+    to_epoch: Option<u64>,
+
     #[arg(long, value_enum, default_value = "summary")]
     view: DistributionViewMode,
 
@@ -60,14 +78,71 @@ pub struct DistributionCommand {
     dz_env: DoubleZeroLedgerEnvironmentOverride,
 }
 
-#[derive(Debug, Tabled)]
+#[derive(Debug, Tabled, Serialize)]
 struct DistributionSummaryTableRow {
     field: &'static str,
     value: String,
     note: String,
 }
 
-#[derive(Debug, Tabled)]
+#[derive(Debug, PartialEq, Eq, Tabled, Serialize)]
+struct DistributionRangeTableRow {
+    dz_epoch: u64,
+    total_debt: String,
+    collected: String,
+    paid: String,
+    rewards_finalized: &'static str,
+}
+
+/// Distribution fields summarized per epoch of a range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DistributionProgress {
+    dz_epoch: u64,
+    total_solana_validator_debt: u64,
+    collected_solana_validator_payments: u64,
+    is_rewards_calculation_finalized: bool,
+}
+
+impl From<&Distribution> for DistributionProgress {
+    fn from(distribution: &Distribution) -> Self {
+        Self {
+            dz_epoch: distribution.dz_epoch.value(),
+            total_solana_validator_debt: distribution.total_solana_validator_debt,
+            collected_solana_validator_payments: distribution.collected_solana_validator_payments,
+            is_rewards_calculation_finalized: distribution.is_rewards_calculation_finalized(),
+        }
+    }
+}
+
+impl From<DistributionProgress> for DistributionRangeTableRow {
+    fn from(progress: DistributionProgress) -> Self {
+        let DistributionProgress {
+            dz_epoch,
+            total_solana_validator_debt,
+            collected_solana_validator_payments,
+            is_rewards_calculation_finalized,
+        } = progress;
+
+        Self {
+            dz_epoch,
+            total_debt: format_sol(total_solana_validator_debt),
+            collected: format_sol(collected_solana_validator_payments),
+            paid: collected_debt_pct(
+                total_solana_validator_debt,
+                collected_solana_validator_payments,
+            )
+            .map(|pct| format!("{pct:.3}%"))
+            .unwrap_or_else(|| "-".to_string()),
+            rewards_finalized: if is_rewards_calculation_finalized {
+                "yes"
+            } else {
+                "no"
+            },
+        }
+    }
+}
+
+#[derive(Debug, Tabled, Serialize)]
 struct DistributionSolanaValidatorDebtTableRow {
     dz_epoch: u64,
     solana_epoch: String,
@@ -80,7 +155,7 @@ struct DistributionSolanaValidatorDebtTableRow {
     note: String,
 }
 
-#[derive(Debug, Tabled)]
+#[derive(Debug, Tabled, Serialize)]
 struct DistributionRewardsTableRow {
     dz_epoch: u64,
     index: usize,
@@ -91,9 +166,11 @@ struct DistributionRewardsTableRow {
 }
 
 impl DistributionCommand {
-    pub async fn try_into_execute(self) -> Result<()> {
+    pub async fn try_into_execute(self, output: FetchOutput) -> Result<()> {
         let Self {
             dz_epoch,
+            from_epoch,
+            to_epoch,
             view: view_mode,
             solana_connection_options,
             debt_accountant: debt_accountant_key,
@@ -107,6 +184,35 @@ impl DistributionCommand {
         let dz_env = dz_env.dz_env.unwrap_or(network_env);
         let dz_connection = DoubleZeroLedgerConnection::from(dz_env);
 
+        if let Some(from_epoch) = from_epoch
+            && let Some(to_epoch) = to_epoch
+        {
+            ensure!(
+                view_mode == DistributionViewMode::Summary,
+                "--from-epoch and --to-epoch only support the summary view"
+            );
+            ensure!(
+                from_epoch <= to_epoch,
+                "--from-epoch {from_epoch} is after --to-epoch {to_epoch}"
+            );
+
+            let solana_connection = &solana_connection;
+            let value_rows =
+                try_fetch_distribution_range_rows(from_epoch..=to_epoch, |dz_epoch| async move {
+                    let (_, distribution) =
+                        try_fetch_distribution(solana_connection, dz_epoch).await?;
+                    Ok(DistributionProgress::from(&*distribution.mucked_data))
+                })
+                .await?;
+
+            return output.print(
+                value_rows,
+                TableOptions {
+                    columns_aligned_right: Some(&[0, 1, 2, 3]),
+                },
+            );
+        }
+
         let (_, config) = try_fetch_config(&solana_connection).await?;
 
         let epoch_value = match dz_epoch {
@@ -126,6 +232,7 @@ impl DistributionCommand {
                     &distribution_key,
                     &distribution,
                     &debt_accountant_key,
+                    output,
                 )
                 .await
             }
@@ -143,6 +250,7 @@ impl DistributionCommand {
                     &debt_accountant_key,
                     &distribution,
                     view_mode,
+                    output,
                 )
                 .await
             }
@@ -156,6 +264,7 @@ impl DistributionCommand {
                     &dz_connection,
                     &rewards_accountant_key.unwrap_or(config.rewards_accountant_key),
                     &distribution,
+                    output,
                 )
                 .await
             }
@@ -165,11 +274,46 @@ impl DistributionCommand {
 
 //
 
+/// Summarizes each distribution in `dz_epochs`, fetching a bounded number at
+/// once. Rows are ordered by epoch.
+async fn try_fetch_distribution_range_rows<F, Fut>(
+    dz_epochs: RangeInclusive<u64>,
+    fetch_distribution: F,
+) -> Result<Vec<DistributionRangeTableRow>>
+where
+    F: Fn(u64) -> Fut,
+    Fut: Future<Output = Result<DistributionProgress>>,
+{
+    stream::iter(dz_epochs)
+        .map(|dz_epoch| {
+            let fetch = fetch_distribution(dz_epoch);
+            async move {
+                fetch.await.with_context(|| {
+                    format!("Failed to fetch distribution for DZ epoch {dz_epoch}")
+                })
+            }
+        })
+        .buffered(MAX_CONCURRENT_DISTRIBUTION_FETCHES)
+        .map_ok(DistributionRangeTableRow::from)
+        .try_collect()
+        .await
+}
+
+fn format_sol(lamports: u64) -> String {
+    format!("{:.9} SOL", lamports as f64 / LAMPORTS_PER_SOL as f64)
+}
+
+/// Percentage of Solana validator debt collected, if any debt is owed.
+fn collected_debt_pct(total_debt: u64, collected: u64) -> Option<f64> {
+    (total_debt != 0).then(|| collected as f64 * 100.0 / total_debt as f64)
+}
+
 async fn try_print_distribution_summary_table(
     dz_connection: &DoubleZeroLedgerConnection,
     distribution_key: &Pubkey,
     distribution: &Distribution,
     debt_accountant_key: &Pubkey,
+    output: FetchOutput,
 ) -> Result<()> {
     let dz_epoch = distribution.dz_epoch.value();
 
@@ -269,8 +413,9 @@ async fn try_print_distribution_summary_table(
             });
         };
 
-        let unpaid_solana_validators_count =
-            distribution.total_solana_validators - distribution.solana_validator_payments_count;
+        let collection =
+            doublezero_solana_validator_debt::worker::CollectionSummary::from(distribution);
+        let unpaid_solana_validators_count = collection.remaining_count;
 
         let more_rows = vec![
             DistributionSummaryTableRow {
@@ -301,26 +446,14 @@ async fn try_print_distribution_summary_table(
             },
             DistributionSummaryTableRow {
                 field: "Total Solana validator payments",
-                value: format!(
-                    "{:.9} SOL",
-                    distribution.collected_solana_validator_payments as f64
-                        / LAMPORTS_PER_SOL as f64,
-                ),
-                note: format!(
-                    "{:.3}% collected",
-                    distribution.collected_solana_validator_payments as f64 * 100.0
-                        / distribution.total_solana_validator_debt as f64
-                ),
+                value: format_sol(collection.collected),
+                note: collected_debt_pct(collection.total_debt, collection.collected)
+                    .map(|pct| format!("{pct:.3}% collected"))
+                    .unwrap_or_default(),
             },
             DistributionSummaryTableRow {
                 field: "Uncollected Solana validator debt",
-                value: format!(
-                    "{:.9} SOL",
-                    (distribution.total_solana_validator_debt
-                        - distribution.collected_solana_validator_payments)
-                        as f64
-                        / LAMPORTS_PER_SOL as f64,
-                ),
+                value: format_sol(collection.remaining_debt),
                 note: if distribution.is_solana_validator_debt_write_off_enabled() {
                     "Write-off enabled".to_string()
                 } else {
@@ -425,14 +558,12 @@ async fn try_print_distribution_summary_table(
         });
     }
 
-    print_table(
+    output.print(
         value_rows,
         TableOptions {
             columns_aligned_right: Some(&[1]),
         },
-    );
-
-    Ok(())
+    )
 }
 
 async fn try_print_distribution_debt_table(
@@ -441,6 +572,7 @@ async fn try_print_distribution_debt_table(
     debt_accountant_key: &Pubkey,
     distribution: &ZeroCopyAccountOwnedData<Distribution>,
     view_mode: DistributionViewMode,
+    output: FetchOutput,
 ) -> Result<()> {
     let dz_epoch = distribution.dz_epoch.value();
 
@@ -453,7 +585,7 @@ async fn try_print_distribution_debt_table(
     .await?;
 
     if computed_debt.debts.is_empty() {
-        println!("No debts found for DZ epoch {dz_epoch}");
+        output.print_empty(&format!("No debts found for DZ epoch {dz_epoch}"));
         return Ok(());
     }
 
@@ -539,20 +671,19 @@ async fn try_print_distribution_debt_table(
         }
     }
 
-    print_table(
+    output.print(
         outputs,
         TableOptions {
             columns_aligned_right: Some(&[0, 1, 2, 4, 5, 6, 7]),
         },
-    );
-
-    Ok(())
+    )
 }
 
 async fn try_print_distribution_rewards_table(
     dz_connection: &DoubleZeroLedgerConnection,
     rewards_accountant_key: &Pubkey,
     distribution: &ZeroCopyAccountOwnedData<Distribution>,
+    output: FetchOutput,
 ) -> Result<()> {
     let dz_epoch = distribution.dz_epoch;
 
@@ -614,12 +745,148 @@ async fn try_print_distribution_rewards_table(
         });
     }
 
-    print_table(
+    output.print(
         rewards_rows,
         TableOptions {
             columns_aligned_right: Some(&[0, 1, 3, 4, 5]),
         },
-    );
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::bail;
+
+    use super::*;
+    use crate::command::revenue_distribution::fetch::first_json_row;
+
+    fn progress(
+        dz_epoch: u64,
+        total: u64,
+        collected: u64,
+        finalized: bool,
+    ) -> DistributionProgress {
+        DistributionProgress {
+            dz_epoch,
+            total_solana_validator_debt: total,
+            collected_solana_validator_payments: collected,
+            is_rewards_calculation_finalized: finalized,
+        }
+    }
 
-    Ok(())
+    #[tokio::test]
+    async fn test_fetch_distribution_range_rows() {
+        let distributions = HashMap::from([
+            (
+                10,
+                progress(10, 2 * LAMPORTS_PER_SOL, 2 * LAMPORTS_PER_SOL, true),
+            ),
+            (
+                11,
+                progress(11, 4 * LAMPORTS_PER_SOL, LAMPORTS_PER_SOL, false),
+            ),
+            (12, progress(12, 0, 0, false)),
+        ]);
+        let fetch = |dz_epoch: u64| {
+            let distribution = distributions.get(&dz_epoch).copied();
+            async move {
+                match distribution {
+                    Some(distribution) => Ok(distribution),
+                    None => bail!("Distribution not found for epoch {dz_epoch}"),
+                }
+            }
+        };
+
+        let rows = try_fetch_distribution_range_rows(10..=12, fetch)
+            .await
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                DistributionRangeTableRow {
+                    dz_epoch: 10,
+                    total_debt: "2.000000000 SOL".to_string(),
+                    collected: "2.000000000 SOL".to_string(),
+                    paid: "100.000%".to_string(),
+                    rewards_finalized: "yes",
+                },
+                DistributionRangeTableRow {
+                    dz_epoch: 11,
+                    total_debt: "4.000000000 SOL".to_string(),
+                    collected: "1.000000000 SOL".to_string(),
+                    paid: "25.000%".to_string(),
+                    rewards_finalized: "no",
+                },
+                // No debt owed, so nothing to report as paid.
+                DistributionRangeTableRow {
+                    dz_epoch: 12,
+                    total_debt: "0.000000000 SOL".to_string(),
+                    collected: "0.000000000 SOL".to_string(),
+                    paid: "-".to_string(),
+                    rewards_finalized: "no",
+                },
+            ]
+        );
+
+        // A missing distribution in the range fails the whole fetch.
+        let err = try_fetch_distribution_range_rows(11..=13, fetch)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to fetch distribution for DZ epoch 13"
+        );
+    }
+
+    #[test]
+    fn test_json_rows() {
+        let range_row = first_json_row(vec![DistributionRangeTableRow::from(progress(
+            10,
+            4 * LAMPORTS_PER_SOL,
+            LAMPORTS_PER_SOL,
+            true,
+        ))]);
+        assert_eq!(range_row["dz_epoch"], 10);
+        assert_eq!(range_row["total_debt"], "4.000000000 SOL");
+        assert_eq!(range_row["collected"], "1.000000000 SOL");
+        assert_eq!(range_row["paid"], "25.000%");
+        assert_eq!(range_row["rewards_finalized"], "yes");
+
+        let summary_row = first_json_row(vec![DistributionSummaryTableRow {
+            field: "Distribution",
+            value: "10".to_string(),
+            note: Default::default(),
+        }]);
+        assert_eq!(summary_row["field"], "Distribution");
+        assert_eq!(summary_row["value"], "10");
+
+        let node_id = Pubkey::new_unique().to_string();
+        let debt_row = first_json_row(vec![DistributionSolanaValidatorDebtTableRow {
+            dz_epoch: 10,
+            solana_epoch: "800".to_string(),
+            index: 3,
+            node_id: node_id.clone(),
+            amount: format_sol(LAMPORTS_PER_SOL),
+            deposit_balance: format_sol(0),
+            processed: "no",
+            written_off: "no",
+            note: "Not funded".to_string(),
+        }]);
+        assert_eq!(debt_row["node_id"], node_id);
+        assert_eq!(debt_row["amount"], "1.000000000 SOL");
+        assert_eq!(debt_row["processed"], "no");
+        assert_eq!(debt_row["note"], "Not funded");
+
+        let rewards_row = first_json_row(vec![DistributionRewardsTableRow {
+            dz_epoch: 10,
+            index: 0,
+            contributor: "contributor".to_string(),
+            proportion: "50.00%".to_string(),
+            reward: "100.0 2Z".to_string(),
+            distributed: "yes",
+        }]);
+        assert_eq!(rewards_row["contributor"], "contributor");
+        assert_eq!(rewards_row["reward"], "100.0 2Z");
+        assert_eq!(rewards_row["distributed"], "yes");
+    }
 }
@@ -0,0 +1,237 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::Args;
+use doublezero_solana_client_tools::{
+    account::zero_copy::ZeroCopyAccountOwnedData,
+    rpc::{SolanaConnection, SolanaConnectionOptions},
+};
+use doublezero_solana_sdk::{
+    PrecomputedDiscriminator,
+    revenue_distribution::{self, fetch::try_fetch_config, state::Distribution},
+};
+use serde::Serialize;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::{
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::pubkey::Pubkey;
+use tabled::Tabled;
+
+use crate::command::revenue_distribution::fetch::{
+    FetchOutput, TableOptions, serialize_pubkey_as_string,
+};
+
+#[derive(Debug, Args)]
+pub struct DistributionsCommand {
+    /// Only list distributions initialized at or after this time (RFC 3339,
+    /// e.g. 2025-10-01T00:00:00Z).
+    #[arg(long, value_name = "RFC3339")]
+    since: Option<DateTime<Utc>>,
+
+    #[command(flatten)]
+    connection_options: SolanaConnectionOptions,
+}
+
+#[derive(Debug, Tabled, Serialize)]
+struct DistributionsTableRow {
+    dz_epoch: u64,
+    #[serde(serialize_with = "serialize_pubkey_as_string")]
+    distribution_pda: Pubkey,
+    initialized_at: String,
+    debt_finalized: &'static str,
+    rewards_finalized: &'static str,
+}
+
+/// Distribution account fields shown in the list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DistributionListing {
+    dz_epoch: u64,
+    key: Pubkey,
+    initialized_at: DateTime<Utc>,
+    is_debt_calculation_finalized: bool,
+    is_rewards_calculation_finalized: bool,
+}
+
+impl DistributionsCommand {
+    pub async fn try_into_execute(self, output: FetchOutput) -> Result<()> {
+        let Self {
+            since,
+            connection_options,
+        } = self;
+
+        let connection = SolanaConnection::from(connection_options);
+
+        let (_, config) = try_fetch_config(&connection).await?;
+
+        // Distributions only record when calculation is allowed, which is the
+        // initialization time plus the calculation grace period.
+        let calculation_grace_period_secs = i64::from(
+            config
+                .distribution_parameters
+                .calculation_grace_period_minutes,
+        ) * 60;
+
+        let listings = connection
+            .get_program_accounts_with_config(
+                &revenue_distribution::ID,
+                distribution_program_accounts_config(),
+            )
+            .await?
+            .into_iter()
+            .map(|(distribution_key, distribution_account_info)| {
+                let distribution = ZeroCopyAccountOwnedData::<Distribution>::from_account(
+                    &distribution_account_info,
+                )
+                .with_context(|| {
+                    format!("Failed to deserialize distribution account {distribution_key}")
+                })?;
+
+                let initialized_timestamp = distribution.calculation_allowed_timestamp as i64
+                    - calculation_grace_period_secs;
+
+                Ok(DistributionListing {
+                    dz_epoch: distribution.dz_epoch.value(),
+                    key: distribution_key,
+                    initialized_at: DateTime::from_timestamp(initialized_timestamp, 0)
+                        .unwrap_or_default(),
+                    is_debt_calculation_finalized: distribution.is_debt_calculation_finalized(),
+                    is_rewards_calculation_finalized: distribution
+                        .is_rewards_calculation_finalized(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let value_rows = filter_distributions_since(listings, since)
+            .into_iter()
+            .map(|listing| DistributionsTableRow {
+                dz_epoch: listing.dz_epoch,
+                distribution_pda: listing.key,
+                initialized_at: listing.initialized_at.to_rfc3339(),
+                debt_finalized: if listing.is_debt_calculation_finalized {
+                    "yes"
+                } else {
+                    "no"
+                },
+                rewards_finalized: if listing.is_rewards_calculation_finalized {
+                    "yes"
+                } else {
+                    "no"
+                },
+            })
+            .collect::<Vec<_>>();
+
+        output.print(
+            value_rows,
+            TableOptions {
+                columns_aligned_right: Some(&[0]),
+            },
+        )
+    }
+}
+
+//
+
+fn distribution_program_accounts_config() -> RpcProgramAccountsConfig {
+    RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            0,
+            Distribution::discriminator_slice().to_vec(),
+        ))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// Keep distributions initialized at or after `since`, ordered by DZ epoch.
+fn filter_distributions_since(
+    mut listings: Vec<DistributionListing>,
+    since: Option<DateTime<Utc>>,
+) -> Vec<DistributionListing> {
+    if let Some(since) = since {
+        listings.retain(|listing| listing.initialized_at >= since);
+    }
+
+    listings.sort_by_key(|listing| listing.dz_epoch);
+    listings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::revenue_distribution::fetch::first_json_row;
+
+    fn listing(dz_epoch: u64, initialized_at: &str) -> DistributionListing {
+        DistributionListing {
+            dz_epoch,
+            key: Pubkey::new_unique(),
+            initialized_at: initialized_at.parse().unwrap(),
+            is_debt_calculation_finalized: false,
+            is_rewards_calculation_finalized: false,
+        }
+    }
+
+    #[test]
+    fn test_filter_distributions_since() {
+        let listings = vec![
+            listing(12, "2025-10-03T00:00:00Z"),
+            listing(10, "2025-10-01T00:00:00Z"),
+            listing(11, "2025-10-02T00:00:00Z"),
+        ];
+
+        let dz_epochs = |listings: Vec<DistributionListing>| {
+            listings
+                .into_iter()
+                .map(|listing| listing.dz_epoch)
+                .collect::<Vec<_>>()
+        };
+
+        // No filter lists everything, ordered by epoch.
+        assert_eq!(
+            dz_epochs(filter_distributions_since(listings.clone(), None)),
+            vec![10, 11, 12]
+        );
+
+        // The bound is inclusive.
+        assert_eq!(
+            dz_epochs(filter_distributions_since(
+                listings.clone(),
+                Some("2025-10-02T00:00:00Z".parse().unwrap())
+            )),
+            vec![11, 12]
+        );
+
+        // Offsets are compared as instants.
+        assert_eq!(
+            dz_epochs(filter_distributions_since(
+                listings.clone(),
+                Some("2025-10-02T20:00:00-03:00".parse().unwrap())
+            )),
+            vec![12]
+        );
+
+        assert!(
+            filter_distributions_since(listings, Some("2025-10-04T00:00:00Z".parse().unwrap()))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_json_rows() {
+        let distribution_pda = Pubkey::new_unique();
+        let row = first_json_row(vec![DistributionsTableRow {
+            dz_epoch: 10,
+            distribution_pda,
+            initialized_at: "2025-10-01T00:00:00+00:00".to_string(),
+            debt_finalized: "yes",
+            rewards_finalized: "no",
+        }]);
+        assert_eq!(row["dz_epoch"], 10);
+        assert_eq!(row["distribution_pda"], distribution_pda.to_string());
+        assert_eq!(row["debt_finalized"], "yes");
+        assert_eq!(row["rewards_finalized"], "no");
+    }
+}
@@ -0,0 +1,138 @@
+use anyhow::{Context, Result, ensure};
+use clap::Args;
+use doublezero_solana_client_tools::rpc::{SolanaConnection, SolanaConnectionOptions};
+use doublezero_solana_sdk::revenue_distribution::state::{Journal, find_2z_token_pda_address};
+use serde::Serialize;
+use solana_sdk::{program_pack::Pack, pubkey::Pubkey};
+
+use super::FetchOutput;
+
+#[derive(Debug, Args)]
+pub struct JournalCommand {
+    /// Print the raw account data in debug format instead of a table.
+    #[arg(long)]
+    raw: bool,
+
+    #[command(flatten)]
+    connection_options: SolanaConnectionOptions,
+}
+
+#[derive(Debug, tabled::Tabled, Serialize)]
+struct JournalTableRow {
+    field: &'static str,
+    value: String,
+    note: String,
+}
+
+impl JournalCommand {
+    pub async fn try_into_execute(self, output: FetchOutput) -> Result<()> {
+        let Self {
+            raw,
+            connection_options,
+        } = self;
+
+        ensure!(
+            !(raw && output.is_json()),
+            "--raw cannot be combined with --json"
+        );
+
+        let connection = SolanaConnection::from(connection_options);
+
+        let (journal_key, _) = Journal::find_address();
+        let journal = connection
+            .try_fetch_zero_copy_data::<Journal>(&journal_key)
+            .await
+            .with_context(|| format!("Journal not found: {journal_key}"))?;
+
+        if raw {
+            println!("{:#?}", journal.mucked_data);
+            return Ok(());
+        }
+
+        let (token_pda_key, _) = find_2z_token_pda_address(&journal_key);
+        let token_account = connection
+            .get_account(&token_pda_key)
+            .await
+            .with_context(|| format!("Journal 2Z token account not found: {token_pda_key}"))?;
+        let token_balance = spl_token_interface::state::Account::unpack(&token_account.data)
+            .map(|account| account.amount)
+            .with_context(|| format!("Account {token_pda_key} not token account"))?;
+
+        output.print(
+            journal_rows(&journal_key, &journal, &token_pda_key, token_balance),
+            super::TableOptions {
+                columns_aligned_right: Some(&[1]),
+            },
+        )
+    }
+}
+
+fn journal_rows(
+    journal_key: &Pubkey,
+    journal: &Journal,
+    token_pda_key: &Pubkey,
+    token_balance: u64,
+) -> Vec<JournalTableRow> {
+    vec![
+        JournalTableRow {
+            field: "PDA key",
+            value: journal_key.to_string(),
+            note: Default::default(),
+        },
+        JournalTableRow {
+            field: "2Z token PDA",
+            value: token_pda_key.to_string(),
+            note: Default::default(),
+        },
+        JournalTableRow {
+            field: "2Z balance",
+            value: format!("{:.8}", token_balance as f64 * 1e-8),
+            note: "Awaiting sweep to distributions".to_string(),
+        },
+        JournalTableRow {
+            field: "SOL balance",
+            value: format!("{:.9}", journal.total_sol_balance as f64 * 1e-9),
+            note: "Available for conversion".to_string(),
+        },
+        JournalTableRow {
+            field: "Next DZ epoch to sweep",
+            value: journal.next_dz_epoch_to_sweep_tokens.value().to_string(),
+            note: Default::default(),
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use doublezero_solana_sdk::revenue_distribution::types::DoubleZeroEpoch;
+
+    use super::*;
+    use crate::command::revenue_distribution::fetch::first_json_row;
+
+    #[test]
+    fn test_journal_rows() {
+        let journal_key = Pubkey::new_unique();
+        let token_pda_key = Pubkey::new_unique();
+
+        let mut journal: Journal = bytemuck::Zeroable::zeroed();
+        journal.total_sol_balance = 1_500_000_000;
+        journal.next_dz_epoch_to_sweep_tokens = DoubleZeroEpoch::new(42);
+
+        let rows = journal_rows(&journal_key, &journal, &token_pda_key, 250_000_000);
+        let value = |field: &str| {
+            rows.iter()
+                .find(|row| row.field == field)
+                .map(|row| row.value.as_str())
+                .unwrap()
+        };
+        assert_eq!(value("PDA key"), journal_key.to_string());
+        assert_eq!(value("2Z token PDA"), token_pda_key.to_string());
+        assert_eq!(value("2Z balance"), "2.50000000");
+        assert_eq!(value("SOL balance"), "1.500000000");
+        assert_eq!(value("Next DZ epoch to sweep"), "42");
+
+        let row = first_json_row(rows);
+        assert_eq!(row["field"], "PDA key");
+        assert_eq!(row["value"], journal_key.to_string());
+    }
+}
@@ -1,6 +1,8 @@
 mod config;
 mod contributor_rewards;
 mod distribution;
+mod distributions;
+mod journal;
 mod sol_conversion;
 mod validator_debts;
 mod validator_deposits;
@@ -9,6 +11,8 @@ mod validator_deposits;
 
 use anyhow::Result;
 use clap::{Args, Subcommand};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
 use tabled::{
     Table, Tabled,
     settings::{Alignment, Style, object::Columns},
@@ -16,6 +20,10 @@ use tabled::{
 
 #[derive(Debug, Args)]
 pub struct FetchCommand {
+    /// Print results as JSON instead of a table.
+    #[arg(long, global = true)]
+    json: bool,
+
     #[command(subcommand)]
     cmd: FetchSubcommand,
 }
@@ -33,6 +41,14 @@ pub enum FetchSubcommand {
     /// the distribution account for the current epoch.
     Distribution(distribution::DistributionCommand),
 
+    /// List distribution accounts with optional --since filter on their
+    /// initialization time.
+    Distributions(distributions::DistributionsCommand),
+
+    /// Show the SOL conversion journal: its 2Z token PDA, balances and the
+    /// next DZ epoch to sweep. Use --raw for the account's debug dump.
+    Journal(journal::JournalCommand),
+
     /// Show the current SOL/2Z conversion price.
     SolConversion(sol_conversion::SolConversionCommand),
 
@@ -49,26 +65,83 @@ pub enum FetchSubcommand {
 
 impl FetchCommand {
     pub async fn try_into_execute(self) -> Result<()> {
-        match self.cmd {
-            FetchSubcommand::Config(command) => command.try_into_execute().await,
-            FetchSubcommand::ContributorRewards(command) => command.try_into_execute().await,
-            FetchSubcommand::Distribution(command) => command.try_into_execute().await,
-            FetchSubcommand::SolConversion(command) => command.try_into_execute().await,
-            FetchSubcommand::ValidatorDebts(command) => command.try_into_execute().await,
-            FetchSubcommand::ValidatorDeposits(command) => command.try_into_execute().await,
-            FetchSubcommand::ValidatorFees(command) => command.try_into_execute().await,
+        let Self { json, cmd } = self;
+
+        let output = if json {
+            FetchOutput::Json
+        } else {
+            FetchOutput::Table
+        };
+
+        match cmd {
+            FetchSubcommand::Config(command) => command.try_into_execute(output).await,
+            FetchSubcommand::ContributorRewards(command) => command.try_into_execute(output).await,
+            FetchSubcommand::Distribution(command) => command.try_into_execute(output).await,
+            FetchSubcommand::Distributions(command) => command.try_into_execute(output).await,
+            FetchSubcommand::Journal(command) => command.try_into_execute(output).await,
+            FetchSubcommand::SolConversion(command) => command.try_into_execute(output).await,
+            FetchSubcommand::ValidatorDebts(command) => command.try_into_execute(output).await,
+            FetchSubcommand::ValidatorDeposits(command) => command.try_into_execute(output).await,
+            FetchSubcommand::ValidatorFees(command) => command.try_into_execute(output).await,
         }
     }
 }
 
 //
 
+/// How fetched rows are printed to stdout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FetchOutput {
+    #[default]
+    Table,
+    Json,
+}
+
+impl FetchOutput {
+    pub(super) fn is_json(self) -> bool {
+        self == Self::Json
+    }
+
+    pub(super) fn print<T: Tabled + Serialize>(
+        self,
+        value_rows: Vec<T>,
+        options: TableOptions,
+    ) -> Result<()> {
+        println!("{}", self.try_render(value_rows, options)?);
+        Ok(())
+    }
+
+    /// Prints `message` in place of an empty table. JSON output is an empty
+    /// array instead.
+    pub(super) fn print_empty(self, message: &str) {
+        match self {
+            Self::Table => println!("{message}"),
+            Self::Json => println!("[]"),
+        }
+    }
+
+    fn try_render<T: Tabled + Serialize>(
+        self,
+        value_rows: Vec<T>,
+        options: TableOptions,
+    ) -> Result<String> {
+        match self {
+            Self::Table => Ok(render_table(value_rows, options)),
+            Self::Json => serde_json::to_string_pretty(&value_rows).map_err(Into::into),
+        }
+    }
+}
+
 #[derive(Debug, Default)]
-struct TableOptions<'a> {
-    columns_aligned_right: Option<&'a [usize]>,
+pub(super) struct TableOptions<'a> {
+    pub(super) columns_aligned_right: Option<&'a [usize]>,
+}
+
+pub(super) fn print_table(value_rows: Vec<impl Tabled>, options: TableOptions) {
+    println!("{}", render_table(value_rows, options));
 }
 
-fn print_table(value_rows: Vec<impl Tabled>, options: TableOptions) {
+fn render_table(value_rows: Vec<impl Tabled>, options: TableOptions) -> String {
     let mut table = Table::new(value_rows);
     table.with(Style::markdown());
 
@@ -77,5 +150,68 @@ fn print_table(value_rows: Vec<impl Tabled>, options: TableOptions) {
             table.modify(Columns::one(*column_index), Alignment::right());
         }
     }
-    println!("{table}");
+    table.to_string()
+}
+
+/// Keys are printed in base58 in JSON, matching the table output.
+pub(super) fn serialize_pubkey_as_string<S: serde::Serializer>(
+    pubkey: &Pubkey,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(pubkey)
+}
+
+/// JSON object of the first row, for asserting which fields are emitted.
+#[cfg(test)]
+pub(super) fn first_json_row<T: Tabled + Serialize>(value_rows: Vec<T>) -> serde_json::Value {
+    let json = FetchOutput::Json
+        .try_render(value_rows, Default::default())
+        .unwrap();
+    let serde_json::Value::Array(rows) = serde_json::from_str(&json).unwrap() else {
+        panic!("expected a JSON array, got {json}");
+    };
+    rows.into_iter().next().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Tabled, Serialize)]
+    struct TestRow {
+        field: &'static str,
+        #[serde(serialize_with = "serialize_pubkey_as_string")]
+        key: Pubkey,
+    }
+
+    #[test]
+    fn test_fetch_output_render() {
+        let key = Pubkey::new_unique();
+        let value_rows = || {
+            vec![TestRow {
+                field: "PDA key",
+                key,
+            }]
+        };
+
+        let table = FetchOutput::Table
+            .try_render(value_rows(), Default::default())
+            .unwrap();
+        assert!(table.starts_with("| field"));
+        assert!(table.contains(&key.to_string()));
+
+        assert_eq!(
+            first_json_row(value_rows()),
+            serde_json::json!({
+                "field": "PDA key",
+                "key": key.to_string(),
+            })
+        );
+
+        // An empty result is still valid JSON.
+        let json = FetchOutput::Json
+            .try_render(Vec::<TestRow>::new(), Default::default())
+            .unwrap();
+        assert_eq!(json, "[]");
+    }
 }
@@ -4,16 +4,21 @@ use doublezero_solana_client_tools::rpc::{SolanaConnection, SolanaConnectionOpti
 use doublezero_solana_sdk::{
     revenue_distribution::fetch::SolConversionState, sol_conversion::oracle::DiscountParameters,
 };
+use serde::Serialize;
 
-use crate::command::revenue_distribution::try_request_oracle_conversion_price;
+use super::FetchOutput;
+use crate::command::revenue_distribution::{OracleOptions, try_request_oracle_conversion_price};
 
 #[derive(Debug, Args)]
 pub struct SolConversionCommand {
+    #[command(flatten)]
+    oracle_options: OracleOptions,
+
     #[command(flatten)]
     connection_options: SolanaConnectionOptions,
 }
 
-#[derive(Debug, tabled::Tabled)]
+#[derive(Debug, tabled::Tabled, Serialize)]
 struct SolConversionTableRow {
     field: &'static str,
     description: &'static str,
@@ -22,8 +27,11 @@ struct SolConversionTableRow {
 }
 
 impl SolConversionCommand {
-    pub async fn try_into_execute(self) -> Result<()> {
-        let Self { connection_options } = self;
+    pub async fn try_into_execute(self, output: FetchOutput) -> Result<()> {
+        let Self {
+            oracle_options,
+            connection_options,
+        } = self;
 
         let connection = SolanaConnection::from(connection_options);
 
@@ -43,7 +51,8 @@ impl SolConversionCommand {
             .checked_compute(current_slot - last_slot)
             .context("Failed to calculate discount")?;
 
-        let oracle_price_data = try_request_oracle_conversion_price().await?;
+        let oracle_endpoint = oracle_options.try_endpoint(&connection).await?;
+        let oracle_price_data = try_request_oracle_conversion_price(&oracle_endpoint).await?;
 
         let discounted_swap_rate = oracle_price_data
             .checked_discounted_swap_rate(discount)
@@ -76,8 +85,25 @@ impl SolConversionCommand {
             },
         ];
 
-        super::print_table(value_rows, Default::default());
+        output.print(value_rows, Default::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::revenue_distribution::fetch::first_json_row;
 
-        Ok(())
+    #[test]
+    fn test_json_rows() {
+        let row = first_json_row(vec![SolConversionTableRow {
+            field: "SOL per swap",
+            description: "Fixed amount",
+            value: "1.000000000".to_string(),
+            note: Default::default(),
+        }]);
+        assert_eq!(row["field"], "SOL per swap");
+        assert_eq!(row["description"], "Fixed amount");
+        assert_eq!(row["value"], "1.000000000");
     }
 }
@@ -13,8 +13,11 @@ use doublezero_solana_sdk::revenue_distribution::{
 use doublezero_solana_validator_debt::{
     rpc::try_fetch_debt_records_and_distributions, validator_debt::ComputedSolanaValidatorDebts,
 };
+use serde::Serialize;
 use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey};
 
+use super::{FetchOutput, serialize_pubkey_as_string};
+
 #[derive(Debug, Clone, PartialEq, Eq, ValueEnum)]
 pub enum ValidatorDebtsViewMode {
     Outstanding,
@@ -39,16 +42,18 @@ pub struct ValidatorDebtsCommand {
     dz_env: DoubleZeroLedgerEnvironmentOverride,
 }
 
-#[derive(Debug, tabled::Tabled)]
+#[derive(Debug, tabled::Tabled, Serialize)]
 struct ValidatorDebtsOutstandingTableRow {
+    #[serde(serialize_with = "serialize_pubkey_as_string")]
     node_id: Pubkey,
     total_amount: String,
     deposit_balance: String,
     note: String,
 }
 
-#[derive(Debug, tabled::Tabled)]
+#[derive(Debug, tabled::Tabled, Serialize)]
 struct ValidatorDebtsNodeTableRow {
+    #[serde(serialize_with = "serialize_pubkey_as_string")]
     node_id: Pubkey,
     dz_epoch: u64,
     solana_epoch: String,
@@ -58,7 +63,7 @@ struct ValidatorDebtsNodeTableRow {
 }
 
 impl ValidatorDebtsCommand {
-    pub async fn try_into_execute(self) -> Result<()> {
+    pub async fn try_into_execute(self, output: FetchOutput) -> Result<()> {
         let Self {
             node_id,
             view,
@@ -85,12 +90,18 @@ impl ValidatorDebtsCommand {
                     &debt_records,
                     &distributions,
                     node_id.as_ref(),
+                    output,
                 )
                 .await
             }
             ValidatorDebtsViewMode::Node => {
                 let node_id = node_id.context("--node-id is required for --view node")?;
-                try_print_validator_debts_node_table(&debt_records, &distributions, &node_id)
+                try_print_validator_debts_node_table(
+                    &debt_records,
+                    &distributions,
+                    &node_id,
+                    output,
+                )
             }
         }
     }
@@ -103,6 +114,7 @@ async fn try_print_validator_debts_outstanding_table(
     debt_records: &[BorshRecordAccountData<ComputedSolanaValidatorDebts>],
     distributions: &[ZeroCopyAccountOwnedData<Distribution>],
     node_id: Option<&Pubkey>,
+    output: FetchOutput,
 ) -> Result<()> {
     let node_ids = match node_id {
         Some(node_id) => HashSet::from_iter([*node_id]),
@@ -182,23 +194,23 @@ async fn try_print_validator_debts_outstanding_table(
     outputs.sort_by_key(|row| row.node_id.to_string());
 
     if outputs.is_empty() {
-        println!("No outstanding debts found");
+        output.print_empty("No outstanding debts found");
+        Ok(())
     } else {
-        super::print_table(
+        output.print(
             outputs,
             super::TableOptions {
                 columns_aligned_right: Some(&[1, 2]),
             },
-        );
+        )
     }
-
-    Ok(())
 }
 
 fn try_print_validator_debts_node_table(
     debt_records: &[BorshRecordAccountData<ComputedSolanaValidatorDebts>],
     distributions: &[ZeroCopyAccountOwnedData<Distribution>],
     node_id: &Pubkey,
+    output: FetchOutput,
 ) -> Result<()> {
     let mut outputs = Vec::with_capacity(debt_records.len());
 
@@ -252,12 +264,43 @@ fn try_print_validator_debts_node_table(
 
     outputs.sort_by_key(|row| row.dz_epoch);
 
-    super::print_table(
+    output.print(
         outputs,
         super::TableOptions {
             columns_aligned_right: Some(&[1, 2, 3, 4, 5]),
         },
-    );
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::revenue_distribution::fetch::first_json_row;
+
+    #[test]
+    fn test_json_rows() {
+        let node_id = Pubkey::new_unique();
 
-    Ok(())
+        let outstanding_row = first_json_row(vec![ValidatorDebtsOutstandingTableRow {
+            node_id,
+            total_amount: "1.000000000 SOL".to_string(),
+            deposit_balance: "0.500000000 SOL".to_string(),
+            note: Default::default(),
+        }]);
+        assert_eq!(outstanding_row["node_id"], node_id.to_string());
+        assert_eq!(outstanding_row["total_amount"], "1.000000000 SOL");
+        assert_eq!(outstanding_row["deposit_balance"], "0.500000000 SOL");
+
+        let node_row = first_json_row(vec![ValidatorDebtsNodeTableRow {
+            node_id,
+            dz_epoch: 10,
+            solana_epoch: "800".to_string(),
+            amount: "1.000000000 SOL".to_string(),
+            processed: "no",
+            written_off: "no",
+        }]);
+        assert_eq!(node_row["node_id"], node_id.to_string());
+        assert_eq!(node_row["dz_epoch"], 10);
+        assert_eq!(node_row["processed"], "no");
+    }
 }
@@ -1,13 +1,23 @@
+use std::{cmp::Reverse, collections::HashMap};
+
 use anyhow::{Result, bail};
-use clap::Args;
+use clap::{Args, ValueEnum};
 use doublezero_solana_client_tools::{
-    account::zero_copy::ZeroCopyAccountOwnedData,
-    rpc::{SolanaConnection, SolanaConnectionOptions},
+    account::{record::BorshRecordAccountData, zero_copy::ZeroCopyAccountOwnedData},
+    rpc::{DoubleZeroLedgerEnvironmentOverride, SolanaConnection, SolanaConnectionOptions},
 };
 use doublezero_solana_sdk::{
     PrecomputedDiscriminator,
-    revenue_distribution::{self, state::SolanaValidatorDeposit},
+    revenue_distribution::{
+        self,
+        state::{Distribution, SolanaValidatorDeposit},
+        try_is_processed_leaf,
+    },
+};
+use doublezero_solana_validator_debt::{
+    rpc::try_fetch_debt_records_and_distributions, validator_debt::ComputedSolanaValidatorDebts,
 };
+use serde::Serialize;
 use solana_account_decoder_client_types::UiAccountEncoding;
 use solana_client::{
     rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
@@ -15,8 +25,17 @@ use solana_client::{
 };
 use solana_sdk::pubkey::Pubkey;
 
+use super::{FetchOutput, serialize_pubkey_as_string};
 use crate::command::revenue_distribution::try_fetch_solana_validator_deposit;
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ValidatorDepositsSortOrder {
+    /// Largest balance first.
+    Balance,
+    #[default]
+    NodeId,
+}
+
 #[derive(Debug, Args)]
 pub struct ValidatorDepositsCommand {
     #[arg(long, short = 'n', value_name = "PUBKEY")]
@@ -26,28 +45,227 @@ pub struct ValidatorDepositsCommand {
     #[arg(long, short = 'b')]
     balance_only: bool,
 
+    /// Summarize total deposited SOL across all deposit accounts against the
+    /// outstanding debt of the most recent finalized epoch.
+    #[arg(long, conflicts_with_all = ["node_id", "balance_only"])]
+    balance_report: bool,
+
+    /// Show how much more SOL each validator must deposit to cover its
+    /// outstanding debt. Can be filtered with --node-id.
+    #[arg(long, conflicts_with_all = ["balance_only", "balance_report"])]
+    needs_funding: bool,
+
+    /// DZ epoch of the debt used by --needs-funding. Defaults to the most
+    /// recent finalized epoch.
+    #[arg(
+        long,
+        short = 'e',
+        visible_alias = "epoch",
+        value_name = "EPOCH",
+        requires = "needs_funding"
+    )]
+    dz_epoch: Option<u64>,
+
+    /// Order of the listed deposit accounts.
+    #[arg(
+        long,
+        value_enum,
+        default_value = "node-id",
+        conflicts_with_all = ["node_id", "balance_report", "needs_funding"]
+    )]
+    sort: ValidatorDepositsSortOrder,
+
+    /// Only list the first N deposit accounts after sorting.
+    #[arg(
+        long,
+        value_name = "N",
+        conflicts_with_all = ["node_id", "balance_report", "needs_funding"]
+    )]
+    limit: Option<usize>,
+
+    /// Only list deposit accounts holding at least this much SOL.
+    #[arg(
+        long,
+        value_name = "SOL",
+        conflicts_with_all = ["node_id", "balance_report", "needs_funding"]
+    )]
+    min_balance: Option<String>,
+
     #[command(flatten)]
     connection_options: SolanaConnectionOptions,
+
+    #[arg(hide = true, long)]
+    debt_accountant: Option<Pubkey>,
+
+    #[command(flatten)]
+    dz_env: DoubleZeroLedgerEnvironmentOverride,
 }
 
-#[derive(Debug, tabled::Tabled)]
+#[derive(Debug, tabled::Tabled, Serialize)]
 struct ValidatorDepositsTableRow {
+    #[serde(serialize_with = "serialize_pubkey_as_string")]
     deposit_pda: Pubkey,
+    #[serde(serialize_with = "serialize_pubkey_as_string")]
     node_id: Pubkey,
     balance: String,
     written_off_debt: String,
 }
 
+/// Deposit account fields shown in the list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ValidatorDepositListing {
+    deposit_key: Pubkey,
+    node_id: Pubkey,
+    balance: u64,
+    written_off_sol_debt: u64,
+}
+
+impl From<ValidatorDepositListing> for ValidatorDepositsTableRow {
+    fn from(listing: ValidatorDepositListing) -> Self {
+        Self {
+            deposit_pda: listing.deposit_key,
+            node_id: listing.node_id,
+            balance: format!("{:.9} SOL", listing.balance as f64 * 1e-9),
+            written_off_debt: if listing.written_off_sol_debt == 0 {
+                Default::default()
+            } else {
+                format!("{:.9} SOL", listing.written_off_sol_debt as f64 * 1e-9)
+            },
+        }
+    }
+}
+
+#[derive(Debug, tabled::Tabled, Serialize)]
+struct BalanceReportTableRow {
+    field: &'static str,
+    value: String,
+}
+
+#[derive(Debug, tabled::Tabled, Serialize)]
+struct NeedsFundingTableRow {
+    #[serde(serialize_with = "serialize_pubkey_as_string")]
+    node_id: Pubkey,
+    outstanding_debt: String,
+    deposit_balance: String,
+    shortfall: String,
+}
+
+/// SOL a validator must still deposit to cover its outstanding debt.
+#[derive(Debug, PartialEq, Eq)]
+struct FundingShortfall {
+    node_id: Pubkey,
+    debt: u64,
+    balance: u64,
+    shortfall: u64,
+}
+
+impl FundingShortfall {
+    /// One entry per outstanding debt, ordered by node ID. Deposits covering
+    /// their debt have no shortfall.
+    fn compute(
+        deposit_balances: &HashMap<Pubkey, u64>,
+        outstanding_debts: &[(Pubkey, u64)],
+    ) -> Vec<Self> {
+        let mut shortfalls = outstanding_debts
+            .iter()
+            .map(|&(node_id, debt)| {
+                let balance = deposit_balances.get(&node_id).copied().unwrap_or_default();
+
+                Self {
+                    node_id,
+                    debt,
+                    balance,
+                    shortfall: debt.saturating_sub(balance),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        shortfalls.sort_by_key(|shortfall| shortfall.node_id.to_string());
+        shortfalls
+    }
+}
+
+/// Deposits vs outstanding debt across all validators.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct BalanceReport {
+    deposit_count: usize,
+    total_deposited: u64,
+    debtor_count: usize,
+    total_debt: u64,
+    collectible_debt: u64,
+    uncollectible_debt: u64,
+}
+
+impl BalanceReport {
+    /// Debt is collectible up to the balance of the node's deposit account.
+    fn aggregate(
+        deposit_balances: &HashMap<Pubkey, u64>,
+        outstanding_debts: &[(Pubkey, u64)],
+    ) -> Self {
+        let mut report = Self {
+            deposit_count: deposit_balances.len(),
+            total_deposited: deposit_balances.values().sum(),
+            debtor_count: outstanding_debts.len(),
+            ..Default::default()
+        };
+
+        for (node_id, amount) in outstanding_debts {
+            let balance = deposit_balances.get(node_id).copied().unwrap_or_default();
+            let collectible = balance.min(*amount);
+
+            report.total_debt += amount;
+            report.collectible_debt += collectible;
+            report.uncollectible_debt += amount - collectible;
+        }
+
+        report
+    }
+}
+
 impl ValidatorDepositsCommand {
-    pub async fn try_into_execute(self) -> Result<()> {
+    pub async fn try_into_execute(self, output: FetchOutput) -> Result<()> {
         let Self {
             node_id,
             balance_only,
+            balance_report,
+            needs_funding,
+            dz_epoch,
+            sort,
+            limit,
+            min_balance,
             connection_options,
+            debt_accountant: debt_accountant_key,
+            dz_env,
         } = self;
 
+        let min_balance = min_balance
+            .map(crate::utils::parse_sol_amount_to_lamports)
+            .transpose()?;
+
         let connection = SolanaConnection::from(connection_options);
 
+        if needs_funding {
+            return try_print_needs_funding(
+                &connection,
+                debt_accountant_key.as_ref(),
+                dz_env,
+                dz_epoch,
+                node_id.as_ref(),
+                output,
+            )
+            .await;
+        }
+
+        if balance_report {
+            return try_print_balance_report(
+                &connection,
+                debt_accountant_key.as_ref(),
+                dz_env,
+                output,
+            )
+            .await;
+        }
+
         let (outputs, fund_warning_message) = if let Some(node_id) = node_id {
             let (deposit_key, deposit, deposit_balance) =
                 try_fetch_solana_validator_deposit(&connection, &node_id).await?;
@@ -73,9 +291,10 @@ impl ValidatorDepositsCommand {
                     None,
                 )
             } else if deposit_balance != 0 {
-                let warning_message = format!(
+                let warning_message = crate::terminal::plain(&format!(
                     "⚠️  Warning: Please use \"doublezero-solana revenue-distribution validator-deposit --node-id {node_id} -i\" to create {deposit_key}"
-                );
+                ))
+                .into_owned();
 
                 if balance_only {
                     println!("{:.9}", deposit_balance as f64 * 1e-9);
@@ -104,23 +323,13 @@ impl ValidatorDepositsCommand {
                 bail!("Cannot use --balance-only without specifying --node-id");
             }
 
-            let config = RpcProgramAccountsConfig {
-                filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
-                    0,
-                    SolanaValidatorDeposit::discriminator_slice().to_vec(),
-                ))]),
-                account_config: RpcAccountInfoConfig {
-                    encoding: Some(UiAccountEncoding::Base64),
-                    ..Default::default()
-                },
-                ..Default::default()
-            };
+            let config = deposit_program_accounts_config();
 
             let rent_sysvar = connection
                 .try_fetch_sysvar::<solana_sdk::rent::Rent>()
                 .await?;
 
-            let mut outputs = connection
+            let listings = connection
                 .get_program_accounts_with_config(&revenue_distribution::ID, config)
                 .await?
                 .into_iter()
@@ -135,39 +344,459 @@ impl ValidatorDepositsCommand {
                         )
                         .unwrap();
 
-                    ValidatorDepositsTableRow {
-                        deposit_pda: deposit_key,
+                    ValidatorDepositListing {
+                        deposit_key,
                         node_id: deposit_account.node_id,
-                        balance: format!("{:.9} SOL", balance as f64 * 1e-9),
-                        written_off_debt: if deposit_account.written_off_sol_debt == 0 {
-                            Default::default()
-                        } else {
-                            format!(
-                                "{:.9} SOL",
-                                deposit_account.written_off_sol_debt as f64 * 1e-9
-                            )
-                        },
+                        balance,
+                        written_off_sol_debt: deposit_account.written_off_sol_debt,
                     }
                 })
                 .collect::<Vec<_>>();
 
-            outputs.sort_by_key(|row| row.node_id.to_string());
+            let outputs = select_deposits(listings, sort, limit, min_balance)
+                .into_iter()
+                .map(ValidatorDepositsTableRow::from)
+                .collect::<Vec<_>>();
 
             (outputs, None)
         };
 
-        super::print_table(
+        output.print(
             outputs,
             super::TableOptions {
                 columns_aligned_right: Some(&[2, 3]),
             },
-        );
+        )?;
 
         if let Some(fund_warning_message) = fund_warning_message {
-            println!("{fund_warning_message}");
-            println!();
+            // Keep stdout parseable when printing JSON.
+            if output.is_json() {
+                eprintln!("{fund_warning_message}");
+            } else {
+                println!("{fund_warning_message}");
+                println!();
+            }
         }
 
         Ok(())
     }
 }
+
+//
+
+fn deposit_program_accounts_config() -> RpcProgramAccountsConfig {
+    RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            0,
+            SolanaValidatorDeposit::discriminator_slice().to_vec(),
+        ))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// Drop listings below `min_balance`, then sort and keep the first `limit`.
+/// Equal balances are ordered by node ID.
+fn select_deposits(
+    mut listings: Vec<ValidatorDepositListing>,
+    sort: ValidatorDepositsSortOrder,
+    limit: Option<usize>,
+    min_balance: Option<u64>,
+) -> Vec<ValidatorDepositListing> {
+    if let Some(min_balance) = min_balance {
+        listings.retain(|listing| listing.balance >= min_balance);
+    }
+
+    match sort {
+        ValidatorDepositsSortOrder::Balance => {
+            listings.sort_by_key(|listing| (Reverse(listing.balance), listing.node_id.to_string()))
+        }
+        ValidatorDepositsSortOrder::NodeId => {
+            listings.sort_by_key(|listing| listing.node_id.to_string())
+        }
+    }
+
+    if let Some(limit) = limit {
+        listings.truncate(limit);
+    }
+
+    listings
+}
+
+/// Deposit balances of all Solana validator deposit accounts by node ID.
+async fn try_fetch_deposit_balances(connection: &SolanaConnection) -> Result<HashMap<Pubkey, u64>> {
+    let rent_sysvar = connection
+        .try_fetch_sysvar::<solana_sdk::rent::Rent>()
+        .await?;
+
+    let deposit_balances = connection
+        .get_program_accounts_with_config(
+            &revenue_distribution::ID,
+            deposit_program_accounts_config(),
+        )
+        .await?
+        .into_iter()
+        .filter_map(|(_, deposit_account_info)| {
+            let balance = doublezero_solana_client_tools::account::balance(
+                &deposit_account_info,
+                &rent_sysvar,
+            );
+            let deposit_account = ZeroCopyAccountOwnedData::<SolanaValidatorDeposit>::from_account(
+                &deposit_account_info,
+            )?;
+
+            Some((deposit_account.node_id, balance))
+        })
+        .collect();
+
+    Ok(deposit_balances)
+}
+
+/// Debts neither paid nor written off, by node ID.
+fn outstanding_debts(
+    debt_record: &BorshRecordAccountData<ComputedSolanaValidatorDebts>,
+    distribution: &ZeroCopyAccountOwnedData<Distribution>,
+) -> Vec<(Pubkey, u64)> {
+    let processed_leaf_data = &distribution.remaining_data[distribution
+        .processed_solana_validator_debt_start_index
+        as usize
+        ..distribution.processed_solana_validator_debt_end_index as usize];
+    let written_off_leaf_data = distribution
+        .is_solana_validator_debt_write_off_enabled()
+        .then(|| {
+            &distribution.remaining_data[distribution
+                .processed_solana_validator_debt_write_off_start_index
+                as usize
+                ..distribution.processed_solana_validator_debt_write_off_end_index as usize]
+        });
+
+    debt_record
+        .data
+        .debts
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| {
+            !try_is_processed_leaf(processed_leaf_data, *index).unwrap()
+                && !written_off_leaf_data
+                    .is_some_and(|leaf_data| try_is_processed_leaf(leaf_data, *index).unwrap())
+        })
+        .map(|(_, debt)| (debt.node_id, debt.amount))
+        .collect()
+}
+
+async fn try_print_balance_report(
+    connection: &SolanaConnection,
+    debt_accountant_key: Option<&Pubkey>,
+    dz_env: DoubleZeroLedgerEnvironmentOverride,
+    output: FetchOutput,
+) -> Result<()> {
+    let deposit_balances = try_fetch_deposit_balances(connection).await?;
+
+    let debt_records_and_distributions =
+        try_fetch_debt_records_and_distributions(connection, dz_env.dz_env, debt_accountant_key)
+            .await?;
+
+    // Debt records are ordered by epoch, so the last one is the most recent.
+    let Some((debt_record, distribution)) = debt_records_and_distributions.last() else {
+        bail!("No finalized debt records found");
+    };
+
+    let outstanding_debts = outstanding_debts(debt_record, distribution);
+    let report = BalanceReport::aggregate(&deposit_balances, &outstanding_debts);
+
+    let format_sol = |lamports: u64| format!("{:.9} SOL", lamports as f64 * 1e-9);
+
+    output.print(
+        vec![
+            BalanceReportTableRow {
+                field: "DZ epoch",
+                value: distribution.dz_epoch.value().to_string(),
+            },
+            BalanceReportTableRow {
+                field: "Deposit accounts",
+                value: report.deposit_count.to_string(),
+            },
+            BalanceReportTableRow {
+                field: "Total deposited",
+                value: format_sol(report.total_deposited),
+            },
+            BalanceReportTableRow {
+                field: "Validators with outstanding debt",
+                value: report.debtor_count.to_string(),
+            },
+            BalanceReportTableRow {
+                field: "Total outstanding debt",
+                value: format_sol(report.total_debt),
+            },
+            BalanceReportTableRow {
+                field: "Collectible debt",
+                value: format_sol(report.collectible_debt),
+            },
+            BalanceReportTableRow {
+                field: "Uncollectible debt",
+                value: format_sol(report.uncollectible_debt),
+            },
+        ],
+        super::TableOptions {
+            columns_aligned_right: Some(&[1]),
+        },
+    )
+}
+
+async fn try_print_needs_funding(
+    connection: &SolanaConnection,
+    debt_accountant_key: Option<&Pubkey>,
+    dz_env: DoubleZeroLedgerEnvironmentOverride,
+    dz_epoch: Option<u64>,
+    node_id: Option<&Pubkey>,
+    output: FetchOutput,
+) -> Result<()> {
+    let deposit_balances = try_fetch_deposit_balances(connection).await?;
+
+    let debt_records_and_distributions =
+        try_fetch_debt_records_and_distributions(connection, dz_env.dz_env, debt_accountant_key)
+            .await?;
+
+    // Debt records are ordered by epoch, so the last one is the most recent.
+    let debt_record_and_distribution = match dz_epoch {
+        Some(dz_epoch) => debt_records_and_distributions
+            .iter()
+            .find(|(_, distribution)| distribution.dz_epoch.value() == dz_epoch),
+        None => debt_records_and_distributions.last(),
+    };
+    let Some((debt_record, distribution)) = debt_record_and_distribution else {
+        match dz_epoch {
+            Some(dz_epoch) => bail!("No finalized debt record found for DZ epoch {dz_epoch}"),
+            None => bail!("No finalized debt records found"),
+        }
+    };
+
+    let mut outstanding_debts = outstanding_debts(debt_record, distribution);
+    if let Some(node_id) = node_id {
+        outstanding_debts.retain(|(debt_node_id, _)| debt_node_id == node_id);
+    }
+
+    let shortfalls = FundingShortfall::compute(&deposit_balances, &outstanding_debts);
+
+    if shortfalls.is_empty() {
+        output.print_empty(&format!(
+            "No outstanding debts found for DZ epoch {}",
+            distribution.dz_epoch.value()
+        ));
+        return Ok(());
+    }
+
+    let format_sol = |lamports: u64| format!("{:.9} SOL", lamports as f64 * 1e-9);
+
+    output.print(
+        shortfalls
+            .into_iter()
+            .map(|shortfall| NeedsFundingTableRow {
+                node_id: shortfall.node_id,
+                outstanding_debt: format_sol(shortfall.debt),
+                deposit_balance: format_sol(shortfall.balance),
+                shortfall: format_sol(shortfall.shortfall),
+            })
+            .collect(),
+        super::TableOptions {
+            columns_aligned_right: Some(&[1, 2, 3]),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::revenue_distribution::fetch::first_json_row;
+
+    #[test]
+    fn test_balance_report_aggregation() {
+        let fully_funded = Pubkey::new_unique();
+        let partially_funded = Pubkey::new_unique();
+        let unfunded = Pubkey::new_unique();
+        let no_debt = Pubkey::new_unique();
+
+        let deposit_balances = HashMap::from([
+            (fully_funded, 5_000),
+            (partially_funded, 1_000),
+            (no_debt, 2_500),
+        ]);
+        let outstanding_debts = [
+            (fully_funded, 4_000),
+            (partially_funded, 3_000),
+            (unfunded, 700),
+        ];
+
+        let report = BalanceReport::aggregate(&deposit_balances, &outstanding_debts);
+        assert_eq!(
+            report,
+            BalanceReport {
+                deposit_count: 3,
+                total_deposited: 8_500,
+                debtor_count: 3,
+                total_debt: 7_700,
+                collectible_debt: 5_000,
+                uncollectible_debt: 2_700,
+            }
+        );
+        assert_eq!(
+            report.collectible_debt + report.uncollectible_debt,
+            report.total_debt
+        );
+    }
+
+    #[test]
+    fn test_funding_shortfalls() {
+        let over_funded = Pubkey::new_unique();
+        let exactly_funded = Pubkey::new_unique();
+        let under_funded = Pubkey::new_unique();
+        let unfunded = Pubkey::new_unique();
+        let no_debt = Pubkey::new_unique();
+
+        let deposit_balances = HashMap::from([
+            (over_funded, 5_000),
+            (exactly_funded, 2_000),
+            (under_funded, 1_000),
+            (no_debt, 2_500),
+        ]);
+        let outstanding_debts = [
+            (over_funded, 4_000),
+            (exactly_funded, 2_000),
+            (under_funded, 3_000),
+            (unfunded, 700),
+        ];
+
+        let shortfalls = FundingShortfall::compute(&deposit_balances, &outstanding_debts)
+            .into_iter()
+            .map(|shortfall| (shortfall.node_id, shortfall.shortfall))
+            .collect::<HashMap<_, _>>();
+        assert_eq!(
+            shortfalls,
+            HashMap::from([
+                (over_funded, 0),
+                (exactly_funded, 0),
+                (under_funded, 2_000),
+                (unfunded, 700),
+            ])
+        );
+
+        let shortfalls = FundingShortfall::compute(&deposit_balances, &outstanding_debts);
+        assert!(shortfalls.is_sorted_by_key(|shortfall| shortfall.node_id.to_string()));
+        assert_eq!(
+            shortfalls
+                .iter()
+                .find(|shortfall| shortfall.node_id == under_funded),
+            Some(&FundingShortfall {
+                node_id: under_funded,
+                debt: 3_000,
+                balance: 1_000,
+                shortfall: 2_000,
+            })
+        );
+    }
+
+    #[test]
+    fn test_select_deposits() {
+        let listing = |balance: u64| ValidatorDepositListing {
+            deposit_key: Pubkey::new_unique(),
+            node_id: Pubkey::new_unique(),
+            balance,
+            written_off_sol_debt: 0,
+        };
+        let listings = vec![listing(300), listing(100), listing(500), listing(200)];
+
+        let balances = |listings: Vec<ValidatorDepositListing>| {
+            listings
+                .into_iter()
+                .map(|listing| listing.balance)
+                .collect::<Vec<_>>()
+        };
+
+        // Largest balance first.
+        assert_eq!(
+            balances(select_deposits(
+                listings.clone(),
+                ValidatorDepositsSortOrder::Balance,
+                None,
+                None
+            )),
+            vec![500, 300, 200, 100]
+        );
+
+        // Ordered by node ID by default.
+        let mut node_ids = listings
+            .iter()
+            .map(|listing| listing.node_id.to_string())
+            .collect::<Vec<_>>();
+        node_ids.sort();
+        assert_eq!(
+            select_deposits(
+                listings.clone(),
+                ValidatorDepositsSortOrder::NodeId,
+                None,
+                None
+            )
+            .into_iter()
+            .map(|listing| listing.node_id.to_string())
+            .collect::<Vec<_>>(),
+            node_ids
+        );
+
+        // The limit applies after sorting.
+        assert_eq!(
+            balances(select_deposits(
+                listings.clone(),
+                ValidatorDepositsSortOrder::Balance,
+                Some(2),
+                None
+            )),
+            vec![500, 300]
+        );
+
+        // The minimum balance is inclusive and applies before the limit.
+        assert_eq!(
+            balances(select_deposits(
+                listings.clone(),
+                ValidatorDepositsSortOrder::Balance,
+                Some(3),
+                Some(300)
+            )),
+            vec![500, 300]
+        );
+
+        assert!(
+            select_deposits(
+                listings,
+                ValidatorDepositsSortOrder::NodeId,
+                None,
+                Some(1_000)
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_json_rows() {
+        let deposit_pda = Pubkey::new_unique();
+        let node_id = Pubkey::new_unique();
+        let deposit_row = first_json_row(vec![ValidatorDepositsTableRow {
+            deposit_pda,
+            node_id,
+            balance: "1.000000000 SOL".to_string(),
+            written_off_debt: Default::default(),
+        }]);
+        assert_eq!(deposit_row["deposit_pda"], deposit_pda.to_string());
+        assert_eq!(deposit_row["node_id"], node_id.to_string());
+        assert_eq!(deposit_row["balance"], "1.000000000 SOL");
+
+        let report_row = first_json_row(vec![BalanceReportTableRow {
+            field: "Total deposited",
+            value: "1.000000000 SOL".to_string(),
+        }]);
+        assert_eq!(report_row["field"], "Total deposited");
+        assert_eq!(report_row["value"], "1.000000000 SOL");
+    }
+}
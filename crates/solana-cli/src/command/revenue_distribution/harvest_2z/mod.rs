@@ -1,5 +1,7 @@
 mod jupiter;
 
+use std::time::Duration;
+
 use anyhow::{Context, Result, bail, ensure};
 use clap::Args;
 use doublezero_solana_client_tools::{
@@ -10,33 +12,95 @@ use doublezero_solana_sdk::revenue_distribution::{
     env::mainnet::DOUBLEZERO_MINT_KEY, fetch::SolConversionState,
 };
 use jupiter::{JupiterClient, quote::JupiterLegacyQuoteResponse};
+use serde::Serialize;
 use solana_client::rpc_config::{
     RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig,
 };
 use solana_sdk::{
-    compute_budget::ComputeBudgetInstruction, native_token::LAMPORTS_PER_SOL, program_pack::Pack,
-    pubkey::Pubkey,
+    compute_budget::ComputeBudgetInstruction, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey,
+    signature::Signature,
 };
 
-use crate::command::revenue_distribution::convert_2z::Convert2zContext;
+use crate::command::revenue_distribution::{
+    OracleOptions,
+    convert_2z::{Convert2zContext, OutputFormat, try_parse_simulated_balances},
+};
 
 const DEFAULT_BUY_SOL_ADDRESS_LOOKUP_TABLE_KEY: Pubkey =
     solana_sdk::pubkey!("GnwZZZVudHSqChJiAh1RULWJe2itLHSZ9HCNXrbBQKPs");
 
 const TOKEN_ACCOUNT_RENT_EXEMPTION_LAMPORTS: u64 = 2_039_280;
 
+const DEFAULT_MAX_SLIPPAGE_BPS: u16 = 500;
+
+/// Accounting receipt printed with `--output json`. SOL amounts are in
+/// lamports and 2Z amounts in the token's base units.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct Harvest2zReceipt {
+    /// Whether the transaction was only simulated
+    simulated: bool,
+    /// Transaction signature, absent when simulated
+    signature: Option<String>,
+    sol_spent: u64,
+    tokens_harvested: u64,
+    token_balance_before: u64,
+    token_balance_after: u64,
+    fill_quantity: u64,
+}
+
+impl Harvest2zReceipt {
+    fn new(
+        signature: Option<Signature>,
+        lamports_balance_before: u64,
+        lamports_balance_after: u64,
+        token_balance_before: u64,
+        token_balance_after: u64,
+        fill_quantity: u64,
+    ) -> Self {
+        Self {
+            simulated: signature.is_none(),
+            signature: signature.map(|signature| signature.to_string()),
+            sol_spent: lamports_balance_before.saturating_sub(lamports_balance_after),
+            tokens_harvested: token_balance_after.saturating_sub(token_balance_before),
+            token_balance_before,
+            token_balance_after,
+            fill_quantity,
+        }
+    }
+}
+
 #[derive(Debug, Args, Clone)]
 pub struct Harvest2zCommand {
-    /// See https://dev.jup.ag/api-reference/swap/program-id-to-label for available
+    /// Comma-separated DEXes to route through, in order of preference. Each
+    /// is tried in turn, then any DEX. See
+    /// https://dev.jup.ag/api-reference/swap/program-id-to-label for available
     /// program ID labels.
-    #[arg(long, value_name = "JUPITER_LABEL")]
-    specific_dex: Option<String>,
+    #[arg(long, value_name = "JUPITER_LABELS", value_delimiter = ',')]
+    specific_dex: Vec<String>,
 
     /// Jupiter API key for authenticated access. If not provided, falls back
     /// to the legacy lite-api.jup.ag endpoint (deprecated Jan 31 2026).
     #[arg(long, value_name = "API_KEY")]
     jupiter_api_key: Option<String>,
 
+    /// Largest deviation, in basis points, of the Jupiter quote's SOL -> 2Z
+    /// rate from the discounted oracle rate. Quotes outside this band are
+    /// rejected before the transaction is built.
+    #[arg(
+        long,
+        value_name = "BPS",
+        default_value_t = DEFAULT_MAX_SLIPPAGE_BPS,
+        value_parser = clap::value_parser!(u16).range(0..=10_000),
+    )]
+    max_slippage_bps: u16,
+
+    /// Output format. JSON prints a receipt for accounting.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+
+    #[command(flatten)]
+    oracle_options: OracleOptions,
+
     #[command(flatten)]
     solana_payer_options: SolanaPayerOptions,
 }
@@ -46,8 +110,12 @@ impl Harvest2zCommand {
         let Self {
             specific_dex,
             jupiter_api_key,
+            max_slippage_bps,
+            output,
+            oracle_options,
             solana_payer_options,
         } = self;
+        let json = output == OutputFormat::Json;
 
         let jupiter_client = JupiterClient::new(jupiter_api_key.as_deref())?;
 
@@ -56,6 +124,10 @@ impl Harvest2zCommand {
             wallet.compute_unit_price_ix.is_none(),
             "Compute unit price is not supported for harvest-2z command"
         );
+        ensure!(
+            wallet.fee_payer.is_none(),
+            "Fee payer is not supported for harvest-2z command"
+        );
 
         let wallet_key = wallet.pubkey();
         let lamports_balance_before = wallet.connection.get_balance(&wallet_key).await?;
@@ -69,6 +141,7 @@ impl Harvest2zCommand {
             None, //limit_price_str
             None, //source_token_account_key
             None, //checked_lamports
+            &oracle_options,
         )
         .await?;
         let buy_sol_ix = take_instruction(&mut convert_2z_context.instruction);
@@ -96,11 +169,13 @@ impl Harvest2zCommand {
             &jupiter_client,
             input_sol_amount,
             convert_2z_context.discount_params.max_discount,
-            specific_dex,
+            &specific_dex,
         )
         .await?;
 
         let discounted_swap_rate = convert_2z_context.limit_price;
+        try_ensure_quote_within_tolerance(&quote_response, discounted_swap_rate, max_slippage_bps)?;
+
         let min_amount_out = u128::from(discounted_swap_rate) * u128::from(input_sol_amount)
             / u128::from(LAMPORTS_PER_SOL);
         let min_amount_out =
@@ -173,57 +248,63 @@ impl Harvest2zCommand {
 
         match tx_outcome {
             TransactionOutcome::Executed(tx_sig) => {
-                println!("Harvested 2Z tokens: {tx_sig}");
-
                 let token_balance_after = convert_2z_context
                     .try_token_balance(&wallet.connection)
                     .await?;
-                println!(
-                    "Harvested {:.8} 2Z tokens with {:.9} SOL",
-                    (token_balance_after - token_balance_before) as f64 * 1e-8,
-                    (fixed_fill_quantity as f64 * 1e-9)
-                );
 
-                wallet.print_verbose_output(&[tx_sig]).await?;
+                if json {
+                    let lamports_balance_after = wallet.connection.get_balance(&wallet_key).await?;
+                    let receipt = Harvest2zReceipt::new(
+                        Some(tx_sig),
+                        lamports_balance_before,
+                        lamports_balance_after,
+                        token_balance_before,
+                        token_balance_after,
+                        fixed_fill_quantity,
+                    );
+                    println!("{}", serde_json::to_string_pretty(&receipt)?);
+                } else {
+                    println!("Harvested 2Z tokens: {tx_sig}");
+                    println!(
+                        "Harvested {:.8} 2Z tokens with {:.9} SOL",
+                        (token_balance_after - token_balance_before) as f64 * 1e-8,
+                        (fixed_fill_quantity as f64 * 1e-9)
+                    );
+
+                    wallet.print_verbose_output(&[tx_sig]).await?;
+                }
             }
             TransactionOutcome::Simulated(simulation_response) => {
-                let mut post_simulation_account_infos = simulation_response
-                    .accounts
-                    .unwrap()
-                    .into_iter()
-                    .flatten()
-                    .collect::<Vec<_>>();
-                ensure!(
-                    post_simulation_account_infos.len() == 2,
-                    "Expected 2 accounts after simulation, got {}",
-                    post_simulation_account_infos.len()
-                );
-
-                let ata_account_data = post_simulation_account_infos
-                    .pop()
-                    .unwrap()
-                    .data
-                    .decode()
-                    .context("Failed to decode ATA account info")?;
-                let token_balance_after =
-                    spl_token_interface::state::Account::unpack(&ata_account_data)
-                        .unwrap()
-                        .amount;
+                let simulated_balances = try_parse_simulated_balances(simulation_response)?;
+                let token_balance_after = simulated_balances.token_amount;
                 ensure!(
                     token_balance_after >= token_balance_before,
                     "Simulated harvesting 2Z tokens failed"
                 );
-                println!(
-                    "Simulated harvesting {:.8} 2Z tokens with {:.9} SOL",
-                    (token_balance_after - token_balance_before) as f64 * 1e-8,
-                    (fixed_fill_quantity as f64 * 1e-9)
-                );
 
-                let lamports_balance_after = post_simulation_account_infos.pop().unwrap().lamports;
+                let lamports_balance_after = simulated_balances.lamports;
                 ensure!(
                     lamports_balance_after == lamports_balance_before,
                     "SOL balance changed after simulation"
                 );
+
+                if json {
+                    let receipt = Harvest2zReceipt::new(
+                        None,
+                        lamports_balance_before,
+                        lamports_balance_after,
+                        token_balance_before,
+                        token_balance_after,
+                        fixed_fill_quantity,
+                    );
+                    println!("{}", serde_json::to_string_pretty(&receipt)?);
+                } else {
+                    println!(
+                        "Simulated harvesting {:.8} 2Z tokens with {:.9} SOL",
+                        (token_balance_after - token_balance_before) as f64 * 1e-8,
+                        (fixed_fill_quantity as f64 * 1e-9)
+                    );
+                }
             }
         }
 
@@ -235,7 +316,7 @@ async fn try_quote_sol_to_2z(
     jupiter_client: &JupiterClient,
     amount: u64,
     max_discount_rate: u64,
-    specific_dex: Option<String>,
+    preferred_dexes: &[String],
 ) -> Result<JupiterLegacyQuoteResponse> {
     let slippage_bps = u16::try_from(max_discount_rate)
         .context("Overflow when calculating slippage bps with max discount rate")?;
@@ -246,24 +327,106 @@ async fn try_quote_sol_to_2z(
         amount,
         output_mint: DOUBLEZERO_MINT_KEY.to_string(),
         input_mint: spl_token_interface::native_mint::ID.to_string(),
-        dexes: specific_dex,
         ..Default::default()
     };
 
-    for _ in 0..5 {
-        let response = quote_request.try_execute(jupiter_client).await?;
+    try_quote_with_dex_fallback(preferred_dexes, QUOTE_RETRY_DELAY, |dex| {
+        let quote_request = jupiter::quote::JupiterLegacyQuoteRequest {
+            dexes: dex.map(str::to_string),
+            ..quote_request.clone()
+        };
+        async move { quote_request.try_execute(jupiter_client).await }
+    })
+    .await
+}
+
+const QUOTE_ATTEMPTS_PER_DEX: usize = 5;
+const QUOTE_RETRY_DELAY: Duration = Duration::from_millis(400);
+
+/// Asks for a quote restricted to each preferred DEX in order, then for one
+/// through any DEX. Each gets a few attempts to produce a short enough route.
+async fn try_quote_with_dex_fallback<Q, QFut>(
+    preferred_dexes: &[String],
+    retry_delay: Duration,
+    mut quote: Q,
+) -> Result<JupiterLegacyQuoteResponse>
+where
+    Q: FnMut(Option<&str>) -> QFut,
+    QFut: Future<Output = Result<JupiterLegacyQuoteResponse>>,
+{
+    let candidates = preferred_dexes
+        .iter()
+        .map(|dex| Some(dex.as_str()))
+        .chain([None]);
+
+    for dex in candidates {
+        let dex_label = dex.unwrap_or("any DEX");
+
+        for attempt in 1..=QUOTE_ATTEMPTS_PER_DEX {
+            let response = quote(dex).await?;
+
+            // Any route plans that involve more intermediate steps will not fit in
+            // the transaction.
+            if response.route_plan.len() <= 2 {
+                eprintln!("Using quote routed through {dex_label}");
+                return Ok(response);
+            }
 
-        // Any route plans that involve more intermediate steps will not fit in
-        // the transaction.
-        if response.route_plan.len() <= 2 {
-            return Ok(response);
+            if attempt < QUOTE_ATTEMPTS_PER_DEX {
+                eprintln!("Waiting for quote response from {dex_label} to be updated...");
+                tokio::time::sleep(retry_delay).await;
+            }
         }
 
-        println!("Waiting for quote response to be updated...");
-        tokio::time::sleep(tokio::time::Duration::from_millis(400)).await;
+        eprintln!("No short enough route through {dex_label}");
     }
 
-    bail!("Failed to get valid quote response in 5 attempts");
+    bail!(
+        "Failed to get valid quote response in {QUOTE_ATTEMPTS_PER_DEX} attempts per DEX, \
+         including unrestricted routing"
+    );
+}
+
+/// SOL -> 2Z rate implied by a quote, in oracle rate precision like the limit
+/// price.
+fn try_quote_swap_rate(quote_response: &JupiterLegacyQuoteResponse) -> Result<u64> {
+    let in_amount = quote_response
+        .in_amount
+        .parse::<u64>()
+        .with_context(|| format!("Invalid quote input amount: {}", quote_response.in_amount))?;
+    let out_amount = quote_response
+        .out_amount
+        .parse::<u64>()
+        .with_context(|| format!("Invalid quote output amount: {}", quote_response.out_amount))?;
+    ensure!(in_amount != 0, "Quote input amount is zero");
+
+    let swap_rate = u128::from(out_amount) * u128::from(LAMPORTS_PER_SOL) / u128::from(in_amount);
+    u64::try_from(swap_rate).context("Overflow when calculating quote swap rate")
+}
+
+/// Rejects a quote whose implied rate is more than `max_slippage_bps` away
+/// from the discounted oracle rate in either direction. A rate far above the
+/// oracle is as suspect as one below it.
+fn try_ensure_quote_within_tolerance(
+    quote_response: &JupiterLegacyQuoteResponse,
+    discounted_swap_rate: u64,
+    max_slippage_bps: u16,
+) -> Result<()> {
+    ensure!(discounted_swap_rate != 0, "Oracle swap rate is zero");
+
+    let quote_swap_rate = try_quote_swap_rate(quote_response)?;
+    let deviation_bps = u128::from(quote_swap_rate.abs_diff(discounted_swap_rate)) * 10_000
+        / u128::from(discounted_swap_rate);
+
+    ensure!(
+        deviation_bps <= u128::from(max_slippage_bps),
+        "Jupiter quote rate {:.8} deviates {deviation_bps} bps from the oracle rate {:.8}, \
+         exceeding --max-slippage-bps {max_slippage_bps}",
+        quote_swap_rate as f64 * 1e-8,
+        discounted_swap_rate as f64 * 1e-8,
+    );
+
+    Ok(())
 }
 
 fn override_quote_response(response: &mut JupiterLegacyQuoteResponse, min_amount_out: u64) {
@@ -277,3 +440,145 @@ fn override_quote_response(response: &mut JupiterLegacyQuoteResponse, min_amount
     let last_leg = response.route_plan.last_mut().unwrap();
     last_leg.swap_info.out_amount = min_amount_out_str;
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    fn quote_with_legs(legs: usize) -> JupiterLegacyQuoteResponse {
+        JupiterLegacyQuoteResponse {
+            route_plan: vec![jupiter::JupiterRoutePlan::default(); legs],
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_quote_dex_fallback_order() {
+        let run = |preferred_dexes: Vec<String>, short_route_dex: Option<&'static str>| async move {
+            let requested = Mutex::new(Vec::new());
+            let result = try_quote_with_dex_fallback(&preferred_dexes, Duration::ZERO, |dex| {
+                requested.lock().unwrap().push(dex.map(str::to_string));
+                let legs = if dex == short_route_dex { 2 } else { 3 };
+                async move { Ok(quote_with_legs(legs)) }
+            })
+            .await;
+            (result, requested.into_inner().unwrap())
+        };
+        let dexes = vec!["Raydium".to_string(), "Orca".to_string()];
+
+        // The first DEX with a short route wins.
+        let (result, requested) = run(dexes.clone(), Some("Orca")).await;
+        assert_eq!(result.unwrap().route_plan.len(), 2);
+        assert_eq!(
+            requested
+                .iter()
+                .filter(|dex| dex.as_deref() == Some("Raydium"))
+                .count(),
+            QUOTE_ATTEMPTS_PER_DEX
+        );
+        assert_eq!(requested.last().unwrap().as_deref(), Some("Orca"));
+        assert_eq!(requested.len(), QUOTE_ATTEMPTS_PER_DEX + 1);
+
+        // Unrestricted routing is the last resort.
+        let (result, requested) = run(dexes.clone(), None).await;
+        assert!(result.is_ok());
+        assert_eq!(requested.len(), 2 * QUOTE_ATTEMPTS_PER_DEX + 1);
+        assert_eq!(requested.last().unwrap(), &None);
+
+        // Without preferred DEXes, only unrestricted routing is tried.
+        let (result, requested) = run(vec![], Some("Raydium")).await;
+        assert!(result.is_err());
+        assert_eq!(requested, vec![None; QUOTE_ATTEMPTS_PER_DEX]);
+    }
+
+    fn quote_response(in_amount: u64, out_amount: u64) -> JupiterLegacyQuoteResponse {
+        JupiterLegacyQuoteResponse {
+            in_amount: in_amount.to_string(),
+            out_amount: out_amount.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_quote_within_tolerance() {
+        // 2_500 2Z per SOL.
+        let discounted_swap_rate = 2_500 * 100_000_000;
+
+        // 0.5 SOL for 1_250 2Z matches the oracle rate exactly.
+        let quote = quote_response(LAMPORTS_PER_SOL / 2, 1_250 * 100_000_000);
+        assert_eq!(try_quote_swap_rate(&quote).unwrap(), discounted_swap_rate);
+        try_ensure_quote_within_tolerance(&quote, discounted_swap_rate, 0).unwrap();
+
+        // 1 SOL for 2_625 2Z is 5% above the oracle rate.
+        let quote = quote_response(LAMPORTS_PER_SOL, 2_625 * 100_000_000);
+        try_ensure_quote_within_tolerance(&quote, discounted_swap_rate, 500).unwrap();
+        let err = try_ensure_quote_within_tolerance(&quote, discounted_swap_rate, 499)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("deviates 500 bps"));
+        assert!(err.contains("--max-slippage-bps 499"));
+
+        // 1 SOL for 2_000 2Z is 20% below the oracle rate.
+        let quote = quote_response(LAMPORTS_PER_SOL, 2_000 * 100_000_000);
+        assert!(
+            try_ensure_quote_within_tolerance(
+                &quote,
+                discounted_swap_rate,
+                DEFAULT_MAX_SLIPPAGE_BPS
+            )
+            .is_err()
+        );
+
+        // Malformed quotes are rejected too.
+        let quote = quote_response(0, 2_500 * 100_000_000);
+        assert!(try_ensure_quote_within_tolerance(&quote, discounted_swap_rate, 500).is_err());
+    }
+
+    #[test]
+    fn test_harvest_2z_receipt_json() {
+        let tx_sig = Signature::from([7; 64]);
+        let receipt = Harvest2zReceipt::new(
+            Some(tx_sig),
+            5_000_000_000,
+            4_999_995_000,
+            100_000_000,
+            350_000_000,
+            1_000_000_000,
+        );
+        assert_eq!(
+            serde_json::to_value(&receipt).unwrap(),
+            serde_json::json!({
+                "simulated": false,
+                "signature": tx_sig.to_string(),
+                "sol_spent": 5_000,
+                "tokens_harvested": 250_000_000,
+                "token_balance_before": 100_000_000,
+                "token_balance_after": 350_000_000,
+                "fill_quantity": 1_000_000_000,
+            })
+        );
+
+        let receipt = Harvest2zReceipt::new(
+            None,
+            5_000_000_000,
+            5_000_000_000,
+            0,
+            250_000_000,
+            1_000_000_000,
+        );
+        assert_eq!(
+            serde_json::to_value(&receipt).unwrap(),
+            serde_json::json!({
+                "simulated": true,
+                "signature": null,
+                "sol_spent": 0,
+                "tokens_harvested": 250_000_000,
+                "token_balance_before": 0,
+                "token_balance_after": 250_000_000,
+                "fill_quantity": 1_000_000_000,
+            })
+        );
+    }
+}
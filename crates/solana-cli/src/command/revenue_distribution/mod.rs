@@ -1,5 +1,6 @@
 mod contributor_rewards;
 mod convert_2z;
+mod derive_deposit;
 mod fetch;
 mod harvest_2z;
 mod relay;
@@ -7,12 +8,12 @@ mod validator_deposit;
 
 //
 
-use anyhow::{Context, Result, ensure};
+use anyhow::{Context, Result, anyhow, ensure};
 use clap::{Args, Subcommand};
 use doublezero_contributor_rewards::calculator::proof::ShapleyOutputStorage;
 use doublezero_solana_client_tools::{
     account::zero_copy::ZeroCopyAccountOwnedData,
-    rpc::{DoubleZeroLedgerConnection, SolanaConnection},
+    rpc::{DoubleZeroLedgerConnection, NetworkEnvironment, SolanaConnection},
 };
 use doublezero_solana_sdk::{
     revenue_distribution::{
@@ -26,10 +27,58 @@ use doublezero_solana_validator_debt::validator_debt::{
     ComputedSolanaValidatorDebt, ComputedSolanaValidatorDebts,
 };
 use solana_sdk::{pubkey::Pubkey, rent::Rent};
+use url::Url;
 
-// TODO: Add testnet?
-const SOL_2Z_ORACLE_ENDPOINT: &str =
+const SOL_2Z_ORACLE_MAINNET_BETA_ENDPOINT: &str =
     "https://sol-2z-oracle-api-v1.mainnet-beta.doublezero.xyz/swap-rate";
+const SOL_2Z_ORACLE_TESTNET_ENDPOINT: &str =
+    "https://sol-2z-oracle-api-v1.testnet.doublezero.xyz/swap-rate";
+
+/// Oracle used when `--oracle-url` is not specified. There is no localnet
+/// oracle, so localnet uses the testnet one.
+const fn sol_2z_oracle_endpoint(network_env: NetworkEnvironment) -> &'static str {
+    match network_env {
+        NetworkEnvironment::MainnetBeta => SOL_2Z_ORACLE_MAINNET_BETA_ENDPOINT,
+        NetworkEnvironment::Testnet | NetworkEnvironment::Localnet => {
+            SOL_2Z_ORACLE_TESTNET_ENDPOINT
+        }
+    }
+}
+
+#[derive(Debug, Args, Clone, Default)]
+pub struct OracleOptions {
+    /// SOL/2Z oracle swap rate endpoint. Defaults to the oracle for the
+    /// network of the Solana connection.
+    #[arg(long, value_name = "URL", value_parser = parse_oracle_url)]
+    pub oracle_url: Option<Url>,
+}
+
+impl OracleOptions {
+    pub fn endpoint(&self, network_env: NetworkEnvironment) -> Url {
+        self.oracle_url.clone().unwrap_or_else(|| {
+            Url::parse(sol_2z_oracle_endpoint(network_env)).expect("valid oracle endpoint")
+        })
+    }
+
+    /// Only queries the network environment when there is no override.
+    pub async fn try_endpoint(&self, connection: &SolanaConnection) -> Result<Url> {
+        match &self.oracle_url {
+            Some(oracle_url) => Ok(oracle_url.clone()),
+            None => Ok(self.endpoint(connection.try_network_environment().await?)),
+        }
+    }
+}
+
+fn parse_oracle_url(oracle_url: &str) -> Result<Url> {
+    let url =
+        Url::parse(oracle_url).with_context(|| format!("Invalid oracle URL: {oracle_url}"))?;
+    ensure!(
+        matches!(url.scheme(), "http" | "https"),
+        "Oracle URL must use http or https, got {}",
+        url.scheme()
+    );
+    Ok(url)
+}
 
 #[derive(Debug, Args)]
 pub struct RevenueDistributionCommand {
@@ -60,6 +109,11 @@ pub enum RevenueDistributionSubcommand {
 
     /// Relayer instructions for the Revenue Distribution program.
     Relay(relay::RevenueDistributionRelayCommand),
+
+    /// Print the Solana validator deposit PDA and bump derived from a node ID,
+    /// with the deposit's current balance.
+    #[command(hide = true)]
+    DeriveDeposit(derive_deposit::DeriveDepositCommand),
 }
 
 impl RevenueDistributionSubcommand {
@@ -71,6 +125,7 @@ impl RevenueDistributionSubcommand {
             Self::Harvest2z(command) => command.try_into_execute().await,
             Self::ValidatorDeposit(command) => command.try_into_execute().await,
             Self::Relay(command) => command.inner.try_into_execute().await,
+            Self::DeriveDeposit(command) => command.try_into_execute().await,
         }
     }
 }
@@ -124,16 +179,40 @@ async fn try_fetch_solana_validator_deposit(
     }
 }
 
-async fn try_request_oracle_conversion_price() -> Result<OraclePriceData> {
-    reqwest::Client::new()
-        .get(SOL_2Z_ORACLE_ENDPOINT)
+async fn try_request_oracle_conversion_price(endpoint: &Url) -> Result<OraclePriceData> {
+    let body = reqwest::Client::new()
+        .get(endpoint.as_str())
         .header("User-Agent", "DoubleZero Solana CLI")
         .send()
         .await
-        .with_context(|| format!("Failed to request SOL/2Z price from {SOL_2Z_ORACLE_ENDPOINT}"))?
-        .json()
+        .with_context(|| format!("SOL/2Z oracle at {endpoint} is unreachable"))?
+        .error_for_status()
+        .with_context(|| format!("SOL/2Z oracle at {endpoint} returned an error"))?
+        .text()
         .await
-        .context("Failed to parse oracle response. Please try again")
+        .with_context(|| format!("Failed to read SOL/2Z oracle response from {endpoint}"))?;
+
+    try_parse_oracle_response(endpoint, &body)
+}
+
+fn try_parse_oracle_response(endpoint: &Url, body: &str) -> Result<OraclePriceData> {
+    const MAX_BODY_CHARS: usize = 100;
+
+    let snippet = || body.chars().take(MAX_BODY_CHARS).collect::<String>();
+
+    let value = serde_json::from_str::<serde_json::Value>(body).map_err(|_| {
+        anyhow!(
+            "SOL/2Z oracle at {endpoint} returned a non-JSON response: {}",
+            snippet()
+        )
+    })?;
+
+    serde_json::from_value(value).with_context(|| {
+        format!(
+            "SOL/2Z oracle at {endpoint} returned an unexpected response: {}",
+            snippet()
+        )
+    })
 }
 
 async fn try_fetch_shapley_record(
@@ -226,3 +305,64 @@ fn try_distribution_solana_validator_debt_iter<'a>(
             (index, debt, is_processed, is_written_off)
         }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oracle_endpoint_per_environment() {
+        let oracle_options = OracleOptions::default();
+        assert_eq!(
+            oracle_options
+                .endpoint(NetworkEnvironment::MainnetBeta)
+                .as_str(),
+            SOL_2Z_ORACLE_MAINNET_BETA_ENDPOINT
+        );
+        assert_eq!(
+            oracle_options
+                .endpoint(NetworkEnvironment::Testnet)
+                .as_str(),
+            SOL_2Z_ORACLE_TESTNET_ENDPOINT
+        );
+        assert_eq!(
+            oracle_options
+                .endpoint(NetworkEnvironment::Localnet)
+                .as_str(),
+            SOL_2Z_ORACLE_TESTNET_ENDPOINT
+        );
+
+        // An override applies to every environment.
+        let oracle_options = OracleOptions {
+            oracle_url: Some(parse_oracle_url("http://localhost:8080/swap-rate").unwrap()),
+        };
+        assert_eq!(
+            oracle_options
+                .endpoint(NetworkEnvironment::MainnetBeta)
+                .as_str(),
+            "http://localhost:8080/swap-rate"
+        );
+    }
+
+    #[test]
+    fn test_parse_oracle_url() {
+        assert!(parse_oracle_url(SOL_2Z_ORACLE_TESTNET_ENDPOINT).is_ok());
+
+        let err = parse_oracle_url("ftp://oracle.example.com/swap-rate").unwrap_err();
+        assert!(err.to_string().contains("http or https"));
+
+        assert!(parse_oracle_url("not a url").is_err());
+    }
+
+    #[test]
+    fn test_parse_oracle_response_errors() {
+        let endpoint = Url::parse(SOL_2Z_ORACLE_MAINNET_BETA_ENDPOINT).unwrap();
+
+        let err = try_parse_oracle_response(&endpoint, "<html>Bad Gateway</html>").unwrap_err();
+        assert!(err.to_string().contains("non-JSON response"));
+        assert!(err.to_string().contains("Bad Gateway"));
+
+        let err = try_parse_oracle_response(&endpoint, r#"{"error":"rate limited"}"#).unwrap_err();
+        assert!(err.to_string().contains("unexpected response"));
+    }
+}
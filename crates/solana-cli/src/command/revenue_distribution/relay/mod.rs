@@ -4,7 +4,7 @@ mod sweep_distribution_tokens;
 
 //
 
-use anyhow::Result;
+use anyhow::{Result, ensure};
 use chrono::Utc;
 use clap::{Args, Subcommand, ValueEnum};
 use doublezero_scheduled_command::Schedulable;
@@ -38,6 +38,24 @@ pub enum RevenueDistributionRelaySubcommand {
         #[arg(long, value_enum)]
         export: Option<ExportFormat>,
 
+        #[command(flatten)]
+        slack_webhook_options: worker::SlackWebhookOptions,
+
+        #[command(flatten)]
+        pay_debt_options: worker::PayDebtOptions,
+
+        #[command(flatten)]
+        solana_payer_options: SolanaPayerOptions,
+    },
+
+    /// Pay Solana validator debt for every completed DZ epoch.
+    PayAllSolanaValidatorDebt {
+        #[command(flatten)]
+        slack_report_options: worker::SlackReportOptions,
+
+        #[command(flatten)]
+        pay_debt_options: worker::PayDebtOptions,
+
         #[command(flatten)]
         solana_payer_options: SolanaPayerOptions,
     },
@@ -56,7 +74,30 @@ impl RevenueDistributionRelaySubcommand {
                 dz_epoch,
                 solana_payer_options,
                 export,
-            } => execute_pay_solana_validator_debt(dz_epoch, solana_payer_options, export).await,
+                slack_webhook_options,
+                pay_debt_options,
+            } => {
+                execute_pay_solana_validator_debt(
+                    dz_epoch,
+                    solana_payer_options,
+                    export,
+                    slack_webhook_options,
+                    pay_debt_options,
+                )
+                .await
+            }
+            Self::PayAllSolanaValidatorDebt {
+                slack_report_options,
+                pay_debt_options,
+                solana_payer_options,
+            } => {
+                execute_pay_all_solana_validator_debt(
+                    solana_payer_options,
+                    slack_report_options,
+                    pay_debt_options,
+                )
+                .await
+            }
             Self::SweepDistributionTokens(command) => command.execute().await,
             Self::FinalizeDistributionRewards(command) => command.execute().await,
             Self::DistributeRewards(command) => command.execute().await,
@@ -68,8 +109,11 @@ async fn execute_pay_solana_validator_debt(
     epoch: u64,
     solana_payer_options: SolanaPayerOptions,
     export: Option<ExportFormat>,
+    slack_webhook_options: worker::SlackWebhookOptions,
+    pay_debt_options: worker::PayDebtOptions,
 ) -> Result<()> {
     let wallet = Wallet::try_from(solana_payer_options)?;
+    ensure_no_fee_payer(&wallet)?;
 
     let dz_env = wallet.connection.try_network_environment().await?;
     let dz_connection = DoubleZeroLedgerConnection::from(dz_env);
@@ -77,8 +121,14 @@ async fn execute_pay_solana_validator_debt(
     let dry_run = wallet.dry_run;
     let (_, config) = try_fetch_config(&wallet.connection).await?;
 
-    let tx_results =
-        worker::pay_solana_validator_debt(&wallet, &dz_connection, epoch, &config).await?;
+    let tx_results = worker::pay_solana_validator_debt(
+        &wallet,
+        &dz_connection,
+        epoch,
+        &config,
+        pay_debt_options,
+    )
+    .await?;
 
     let mut filename: Option<String> = None;
 
@@ -100,8 +150,44 @@ async fn execute_pay_solana_validator_debt(
     };
 
     if let Some(ExportFormat::Slack) = export {
-        worker::post_debt_collection_to_slack(tx_results, dry_run, filename).await?;
+        worker::post_debt_collection_to_slack(
+            tx_results,
+            dry_run,
+            filename,
+            slack_webhook_options.target(),
+        )
+        .await?;
     }
 
     Ok(())
 }
+
+/// Debt payments are signed by the wallet signer alone, so a separate fee payer
+/// would be silently ignored.
+fn ensure_no_fee_payer(wallet: &Wallet) -> Result<()> {
+    ensure!(
+        wallet.fee_payer.is_none(),
+        "Fee payer is not supported for paying Solana validator debt"
+    );
+    Ok(())
+}
+
+async fn execute_pay_all_solana_validator_debt(
+    solana_payer_options: SolanaPayerOptions,
+    slack_report_options: worker::SlackReportOptions,
+    pay_debt_options: worker::PayDebtOptions,
+) -> Result<()> {
+    let wallet = Wallet::try_from(solana_payer_options)?;
+    ensure_no_fee_payer(&wallet)?;
+
+    let dz_env = wallet.connection.try_network_environment().await?;
+    let dz_connection = DoubleZeroLedgerConnection::from(dz_env);
+
+    worker::pay_all_solana_validator_debt(
+        wallet,
+        dz_connection,
+        slack_report_options,
+        pay_debt_options,
+    )
+    .await
+}
@@ -21,8 +21,9 @@ use doublezero_solana_sdk::{
 use doublezero_solana_validator_debt::rpc::try_fetch_debt_records_and_distributions;
 use solana_sdk::{compute_budget::ComputeBudgetInstruction, pubkey::Pubkey};
 
-use crate::command::{
-    revenue_distribution::convert_2z::Convert2zContext, try_prompt_proceed_confirmation,
+use crate::{
+    command::revenue_distribution::{OracleOptions, convert_2z::Convert2zContext},
+    terminal::try_prompt_proceed_confirmation,
 };
 
 #[derive(Debug, Args)]
@@ -55,6 +56,9 @@ pub struct ValidatorDepositCommand {
     #[arg(long, value_name = "PUBKEY")]
     source_2z_account: Option<Pubkey>,
 
+    #[command(flatten)]
+    oracle_options: OracleOptions,
+
     #[command(flatten)]
     solana_payer_options: SolanaPayerOptions,
 
@@ -74,6 +78,7 @@ impl ValidatorDepositCommand {
             fund_outstanding_debt: should_fund_outstanding_debt,
             convert_2z_limit_price: convert_2z_limit_price_str,
             source_2z_account: source_2z_account_key,
+            oracle_options,
             solana_payer_options,
             debt_accountant: debt_accountant_key,
             dz_env,
@@ -176,6 +181,7 @@ impl ValidatorDepositCommand {
                 Some(limit_price_str),
                 source_2z_account_key,
                 Some(fund_lamports),
+                &oracle_options,
             )
             .await?;
             let buy_sol_ix = take_instruction(&mut convert_2z_context.instruction);
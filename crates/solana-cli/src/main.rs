@@ -1,6 +1,6 @@
 use anyhow::Result;
 use clap::Parser;
-use doublezero_solana_cli::command::DoubleZeroSolanaCommand;
+use doublezero_solana_cli::{command::DoubleZeroSolanaCommand, terminal};
 use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
 
 #[derive(Debug, Parser)]
@@ -10,6 +10,14 @@ use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitEx
 struct DoubleZeroSolanaApp {
     #[command(subcommand)]
     command: DoubleZeroSolanaCommand,
+
+    /// Skip confirmation prompts. Required to confirm without a TTY.
+    #[arg(long, global = true)]
+    yes: bool,
+
+    /// Print without emoji. Implied when stdout is not a TTY.
+    #[arg(long, global = true)]
+    no_color: bool,
 }
 
 #[tokio::main]
@@ -24,8 +32,8 @@ async fn main() -> Result<()> {
         )
         .init();
 
-    DoubleZeroSolanaApp::parse()
-        .command
-        .try_into_execute()
-        .await
+    let app = DoubleZeroSolanaApp::parse();
+    terminal::configure(app.yes, app.no_color);
+
+    app.command.try_into_execute().await
 }
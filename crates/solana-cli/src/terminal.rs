@@ -0,0 +1,231 @@
+use std::{
+    borrow::Cow,
+    io::{BufRead, IsTerminal, Write},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use anyhow::{Result, bail};
+
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+static NO_COLOR: AtomicBool = AtomicBool::new(false);
+
+/// Apply the global `--yes` and `--no-color` flags.
+pub fn configure(assume_yes: bool, no_color: bool) {
+    ASSUME_YES.store(assume_yes, Ordering::Relaxed);
+    NO_COLOR.store(no_color, Ordering::Relaxed);
+}
+
+/// Whether confirmation prompts should be skipped (`--yes`).
+pub fn assume_yes() -> bool {
+    ASSUME_YES.load(Ordering::Relaxed)
+}
+
+/// Emoji are dropped when `--no-color` or `NO_COLOR` is set, or when stdout
+/// is not a TTY (e.g. captured into logs).
+pub fn is_plain_output() -> bool {
+    NO_COLOR.load(Ordering::Relaxed)
+        || std::env::var_os("NO_COLOR").is_some()
+        || !std::io::stdout().is_terminal()
+}
+
+/// Text as it should be printed to stdout.
+pub fn plain(text: &str) -> Cow<'_, str> {
+    if is_plain_output() {
+        Cow::Owned(strip_emoji(text))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Remove emoji along with the spacing that separated them from the text.
+pub fn strip_emoji(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if !is_emoji(c) {
+            stripped.push(c);
+            continue;
+        }
+
+        while chars.peek() == Some(&' ') {
+            chars.next();
+        }
+
+        // "validator 💎:" should become "validator:", not "validator :".
+        if stripped.ends_with(' ')
+            && chars
+                .peek()
+                .is_none_or(|&next| !next.is_alphanumeric() && !is_emoji(next))
+        {
+            stripped.pop();
+        }
+    }
+
+    stripped
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1F000}'..='\u{1FAFF}' // Pictographs, emoticons, transport and symbols
+            | '\u{2600}'..='\u{27BF}' // Miscellaneous symbols and dingbats
+            | '\u{FE0F}' // Emoji presentation selector
+            | '\u{200D}' // Zero width joiner
+    )
+}
+
+/// `println!` that strips emoji when output is plain.
+#[macro_export]
+macro_rules! plain_println {
+    ($($arg:tt)*) => {
+        println!("{}", $crate::terminal::plain(&format!($($arg)*)))
+    };
+}
+
+/// `print!` that strips emoji when output is plain.
+#[macro_export]
+macro_rules! plain_print {
+    ($($arg:tt)*) => {
+        print!("{}", $crate::terminal::plain(&format!($($arg)*)))
+    };
+}
+
+/// Ask the user to confirm before proceeding. With `--yes` the prompt is
+/// skipped. Without a TTY there is nobody to answer, so this errors instead
+/// of silently confirming.
+pub fn try_prompt_proceed_confirmation(
+    prompt_message: String,
+    abort_message: String,
+) -> Result<()> {
+    try_confirm(
+        &prompt_message,
+        &abort_message,
+        assume_yes(),
+        std::io::stdin().is_terminal(),
+        &mut std::io::stdin().lock(),
+        &mut std::io::stdout(),
+    )
+}
+
+fn try_confirm(
+    prompt_message: &str,
+    abort_message: &str,
+    assume_yes: bool,
+    is_tty: bool,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> Result<()> {
+    if assume_yes {
+        writeln!(output, "{prompt_message}. Proceeding (--yes).")?;
+        return Ok(());
+    }
+
+    if !is_tty {
+        bail!(
+            "{prompt_message}. Confirmation is required but no TTY is available; pass --yes to proceed"
+        );
+    }
+
+    loop {
+        writeln!(
+            output,
+            "{}",
+            plain(&format!("⚠️  {prompt_message}. Proceed? [y/N]"))
+        )?;
+        output.flush()?;
+
+        let mut line = String::new();
+        input.read_line(&mut line)?;
+
+        let first_char = line
+            .trim()
+            .chars()
+            .next()
+            .map(|c| c.to_lowercase().next().unwrap());
+
+        match first_char {
+            Some('y') => return Ok(()),
+            Some('n') | None => bail!("{abort_message}"),
+            _ => {
+                writeln!(
+                    output,
+                    "Invalid input. Please enter 'y' for yes or 'n' for no."
+                )?;
+                continue;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn confirm(assume_yes: bool, is_tty: bool, input: &str) -> (Result<()>, String) {
+        let mut output = Vec::new();
+        let result = try_confirm(
+            "You are funding 1.000000000 SOL",
+            "Aborting",
+            assume_yes,
+            is_tty,
+            &mut Cursor::new(input.as_bytes()),
+            &mut output,
+        );
+        (result, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_confirm_yes_skips_prompt_without_tty() {
+        let (result, output) = confirm(true, false, "");
+
+        assert!(result.is_ok());
+        assert!(!output.contains("Proceed?"));
+    }
+
+    #[test]
+    fn test_confirm_without_tty_requires_yes() {
+        let (result, output) = confirm(false, false, "y\n");
+
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("no TTY is available"));
+        assert!(err.contains("--yes"));
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn test_confirm_with_tty_reads_answer() {
+        let (result, output) = confirm(false, true, "maybe\nY\n");
+        assert!(result.is_ok());
+        assert!(output.contains("Invalid input"));
+
+        let (result, _) = confirm(false, true, "n\n");
+        assert_eq!(result.unwrap_err().to_string(), "Aborting");
+
+        let (result, _) = confirm(false, true, "");
+        assert_eq!(result.unwrap_err().to_string(), "Aborting");
+    }
+
+    #[test]
+    fn test_strip_emoji() {
+        assert_eq!(
+            strip_emoji("⚠️  Warning: Program is paused"),
+            "Warning: Program is paused"
+        );
+        assert_eq!(
+            strip_emoji("  Gossip: ✅ OK (1.2.3.4)"),
+            "  Gossip: OK (1.2.3.4)"
+        );
+        assert_eq!(
+            strip_emoji("Primary validator 🖥️  💎:\n  ID: abc"),
+            "Primary validator:\n  ID: abc"
+        );
+        assert_eq!(
+            strip_emoji("as a primary in DoubleZero 🖥️  💎. It is"),
+            "as a primary in DoubleZero. It is"
+        );
+        assert_eq!(strip_emoji("no emoji here"), "no emoji here");
+    }
+}
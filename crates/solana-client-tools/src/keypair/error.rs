@@ -4,12 +4,19 @@ use thiserror::Error;
 #[derive(Debug, Error)]
 pub enum KeypairLoadError {
     /// No keypair source was available
-    #[error("No keypair source available. Tried:\n{}\n\nHint: Provide keypair via:\n  - doublezero-solana --keypair /path/to/key.json\n  - cat key.json | doublezero-solana ...", format_attempted(.attempted))]
+    #[error("No keypair source available. Tried:\n{}\n\nHint: Provide keypair via:\n  - doublezero-solana --keypair /path/to/key.json\n  - SOLANA_KEYPAIR=\"$(cat key.json)\" doublezero-solana ...\n  - cat key.json | doublezero-solana ...", format_attempted(.attempted))]
     NoSourceAvailable {
         /// List of sources that were attempted
         attempted: Vec<String>,
     },
 
+    /// Every keypair source was absent, as opposed to present but unusable
+    #[error("No keypair found. Tried:\n{}\n\nHint: Run `solana-keygen new` to create a keypair at the default path, or provide one via:\n  - doublezero-solana --keypair /path/to/key.json\n  - SOLANA_KEYPAIR=\"$(cat key.json)\" doublezero-solana ...\n  - cat key.json | doublezero-solana ...", format_attempted(.attempted))]
+    KeypairNotFound {
+        /// List of sources that were attempted
+        attempted: Vec<String>,
+    },
+
     /// Failed to read keypair from stdin
     #[error("Failed to read keypair from stdin: {message}")]
     StdinReadError {
@@ -26,6 +33,10 @@ pub enum KeypairLoadError {
         message: String,
     },
 
+    /// Stdin was piped but empty
+    #[error("Stdin was empty")]
+    StdinEmpty,
+
     /// Invalid JSON format in keypair data
     #[error("Invalid keypair JSON format from {origin}: {message}")]
     InvalidJsonFormat {
@@ -35,6 +46,15 @@ pub enum KeypairLoadError {
         message: String,
     },
 
+    /// Invalid base58 encoding in keypair data
+    #[error("Invalid keypair base58 encoding from {origin}: {message}")]
+    InvalidBase58Format {
+        /// Source description
+        origin: String,
+        /// Error message
+        message: String,
+    },
+
     /// Invalid keypair bytes (not 64 bytes)
     #[error("Invalid keypair bytes from {origin}: expected 64 bytes")]
     InvalidKeypairBytes {
@@ -51,6 +71,40 @@ pub enum KeypairLoadError {
     /// Could not determine home directory
     #[error("Could not determine home directory for default keypair path")]
     HomeDirNotFound,
+
+    /// Invalid USB hardware wallet URI
+    #[error("Invalid hardware wallet URI '{uri}': {message}")]
+    InvalidHardwareWalletUri {
+        /// URI that was attempted
+        uri: String,
+        /// Error message
+        message: String,
+    },
+
+    /// A hardware wallet was given where only a keypair file can be used
+    #[error(
+        "Hardware wallet '{uri}' cannot be used here. This command requires a keypair file or stdin"
+    )]
+    HardwareWalletNotKeypair {
+        /// URI that was attempted
+        uri: String,
+    },
+
+    /// Built without hardware wallet support
+    #[error("Hardware wallet '{uri}' requires building with the `remote-wallet` feature")]
+    HardwareWalletUnsupported {
+        /// URI that was attempted
+        uri: String,
+    },
+
+    /// Failed to connect to or read from the hardware wallet
+    #[error("Failed to load hardware wallet '{uri}': {message}")]
+    HardwareWalletError {
+        /// URI that was attempted
+        uri: String,
+        /// Error message
+        message: String,
+    },
 }
 
 fn format_attempted(attempted: &[String]) -> String {
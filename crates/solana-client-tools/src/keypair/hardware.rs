@@ -0,0 +1,234 @@
+use std::path::PathBuf;
+
+use solana_sdk::signer::Signer;
+use url::Url;
+
+use crate::keypair::{error::KeypairLoadError, loader::try_load_keypair, source::KeypairSource};
+
+/// URI scheme selecting a USB hardware wallet
+const USB_SCHEME: &str = "usb";
+
+/// Supported USB hardware wallet manufacturers
+const SUPPORTED_MANUFACTURERS: [&str; 1] = ["ledger"];
+
+/// Parse a USB hardware wallet URI like `usb://ledger?key=0/0`
+///
+/// # Returns
+/// * `Ok(None)` - Not a USB hardware wallet URI
+/// * `Ok(Some(KeypairSource::UsbHardware { .. }))` - Valid hardware wallet URI
+/// * `Err(KeypairLoadError)` - USB URI with an unsupported device or key
+pub fn parse_usb_hardware_uri(uri: &str) -> Result<Option<KeypairSource>, KeypairLoadError> {
+    if !uri.starts_with(&format!("{USB_SCHEME}://")) {
+        return Ok(None);
+    }
+
+    let invalid_uri = |message: String| KeypairLoadError::InvalidHardwareWalletUri {
+        uri: uri.to_string(),
+        message,
+    };
+
+    let url = Url::parse(uri).map_err(|e| invalid_uri(e.to_string()))?;
+
+    let manufacturer = url.host_str().unwrap_or_default();
+    if !SUPPORTED_MANUFACTURERS.contains(&manufacturer) {
+        return Err(invalid_uri(format!(
+            "unsupported manufacturer '{manufacturer}', expected one of: {}",
+            SUPPORTED_MANUFACTURERS.join(", ")
+        )));
+    }
+
+    let mut derivation_path = None;
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "key" if derivation_path.is_none() => {
+                validate_derivation_path(&value).map_err(invalid_uri)?;
+                derivation_path = Some(value.into_owned());
+            }
+            "key" => return Err(invalid_uri("key given more than once".to_string())),
+            _ => return Err(invalid_uri(format!("unsupported query parameter '{key}'"))),
+        }
+    }
+
+    Ok(Some(KeypairSource::UsbHardware { derivation_path }))
+}
+
+/// Load a signer from a USB hardware wallet URI or a keypair source
+///
+/// A `usb://` URI selects a hardware wallet and never falls back to another
+/// source. Anything else follows the [`try_load_keypair`] precedence chain.
+///
+/// # Arguments
+/// * `cli_path` - Optional path or hardware wallet URI from CLI --keypair
+///   argument
+///
+/// # Returns
+/// * `Ok(Box<dyn Signer>)` - Successfully loaded signer
+/// * `Err(KeypairLoadError)` - Failed to load a signer from any source
+pub fn try_load_signer(cli_path: Option<PathBuf>) -> Result<Box<dyn Signer>, KeypairLoadError> {
+    if let Some(uri) = cli_path.as_deref().and_then(|path| path.to_str())
+        && let Some(KeypairSource::UsbHardware { derivation_path }) = parse_usb_hardware_uri(uri)?
+    {
+        return try_load_usb_hardware_signer(uri, derivation_path.as_deref());
+    }
+
+    let keypair = try_load_keypair(cli_path)?;
+    Ok(Box::new(keypair))
+}
+
+/// Account path of `key`: `ACCOUNT` or `ACCOUNT/CHANGE`, each optionally
+/// hardened with a trailing `'`
+fn validate_derivation_path(key: &str) -> Result<(), String> {
+    let indexes = key.split('/').collect::<Vec<_>>();
+    if indexes.len() > 2 {
+        return Err(format!(
+            "key '{key}' must be ACCOUNT or ACCOUNT/CHANGE, like 0 or 0/0"
+        ));
+    }
+
+    for index in indexes {
+        let index = index.strip_suffix('\'').unwrap_or(index);
+        if index.parse::<u32>().is_err() {
+            return Err(format!("key '{key}' has an invalid index '{index}'"));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "remote-wallet")]
+fn try_load_usb_hardware_signer(
+    uri: &str,
+    derivation_path: Option<&str>,
+) -> Result<Box<dyn Signer>, KeypairLoadError> {
+    use solana_derivation_path::DerivationPath;
+    use solana_remote_wallet::{
+        locator::Locator, remote_keypair::generate_remote_keypair,
+        remote_wallet::maybe_wallet_manager,
+    };
+
+    let hardware_wallet_error = |message: String| KeypairLoadError::HardwareWalletError {
+        uri: uri.to_string(),
+        message,
+    };
+
+    let locator = Locator::new_from_path(uri).map_err(|e| hardware_wallet_error(e.to_string()))?;
+    let derivation_path = derivation_path
+        .map(DerivationPath::from_key_str)
+        .transpose()
+        .map_err(|e| hardware_wallet_error(e.to_string()))?
+        .unwrap_or_default();
+
+    let wallet_manager = maybe_wallet_manager()
+        .map_err(|e| hardware_wallet_error(e.to_string()))?
+        .ok_or_else(|| hardware_wallet_error("no hardware wallet found".to_string()))?;
+
+    let remote_keypair = generate_remote_keypair(
+        locator,
+        derivation_path,
+        &wallet_manager,
+        false, // confirm_key
+        "keypair",
+    )
+    .map_err(|e| hardware_wallet_error(e.to_string()))?;
+
+    Ok(Box::new(remote_keypair))
+}
+
+#[cfg(not(feature = "remote-wallet"))]
+fn try_load_usb_hardware_signer(
+    uri: &str,
+    _derivation_path: Option<&str>,
+) -> Result<Box<dyn Signer>, KeypairLoadError> {
+    Err(KeypairLoadError::HardwareWalletUnsupported {
+        uri: uri.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use solana_sdk::signature::Keypair;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::keypair::load_keypair;
+
+    #[test]
+    fn test_parse_usb_hardware_uri() {
+        assert_eq!(
+            parse_usb_hardware_uri("usb://ledger?key=0/0").unwrap(),
+            Some(KeypairSource::UsbHardware {
+                derivation_path: Some("0/0".to_string())
+            })
+        );
+        assert_eq!(
+            parse_usb_hardware_uri("usb://ledger?key=1'/0'").unwrap(),
+            Some(KeypairSource::UsbHardware {
+                derivation_path: Some("1'/0'".to_string())
+            })
+        );
+        assert_eq!(
+            parse_usb_hardware_uri("usb://ledger").unwrap(),
+            Some(KeypairSource::UsbHardware {
+                derivation_path: None
+            })
+        );
+
+        // Keypair files are not hardware wallets.
+        assert_eq!(parse_usb_hardware_uri("/path/to/key.json").unwrap(), None);
+        assert_eq!(parse_usb_hardware_uri("ledger.json").unwrap(), None);
+
+        for uri in [
+            "usb://trezor?key=0/0",
+            "usb://ledger?key=0/0/0",
+            "usb://ledger?key=zero",
+            "usb://ledger?key=0&key=1",
+            "usb://ledger?account=0",
+        ] {
+            assert!(
+                matches!(
+                    parse_usb_hardware_uri(uri),
+                    Err(KeypairLoadError::InvalidHardwareWalletUri { .. })
+                ),
+                "{uri} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_signer_source_selection() {
+        let tmp = TempDir::new().unwrap();
+        let keypair = Keypair::new();
+        let path = tmp.path().join("test-keypair.json");
+        let json = serde_json::to_string(&keypair.to_bytes().to_vec()).unwrap();
+        std::fs::File::create(&path)
+            .unwrap()
+            .write_all(json.as_bytes())
+            .unwrap();
+
+        // Keypair files load as boxed signers.
+        let signer = try_load_signer(Some(path)).unwrap();
+        assert_eq!(signer.pubkey(), keypair.pubkey());
+
+        // A hardware wallet URI never falls back to the default path, even
+        // when a keypair exists there.
+        let default_path = tmp.path().join("test-keypair.json");
+        let result = load_keypair(Some(PathBuf::from("usb://ledger?key=0/0")), default_path);
+        assert!(matches!(
+            result,
+            Err(KeypairLoadError::HardwareWalletNotKeypair { .. })
+        ));
+
+        assert!(matches!(
+            try_load_signer(Some(PathBuf::from("usb://trezor"))),
+            Err(KeypairLoadError::InvalidHardwareWalletUri { .. })
+        ));
+
+        #[cfg(not(feature = "remote-wallet"))]
+        assert!(matches!(
+            try_load_signer(Some(PathBuf::from("usb://ledger?key=0/0"))),
+            Err(KeypairLoadError::HardwareWalletUnsupported { .. })
+        ));
+    }
+}
@@ -6,11 +6,16 @@ use std::{
 
 use solana_sdk::signature::Keypair;
 
-use crate::keypair::{error::KeypairLoadError, source::KeypairSource};
+use crate::keypair::{
+    error::KeypairLoadError, hardware::parse_usb_hardware_uri, source::KeypairSource,
+};
 
 /// Default keypair path relative to HOME
 const DEFAULT_KEYPAIR_PATH: &str = ".config/solana/id.json";
 
+/// Environment variable holding a base58 or JSON array keypair secret
+pub const KEYPAIR_ENV_VAR: &str = "SOLANA_KEYPAIR";
+
 /// Result of loading a keypair, including provenance information
 pub struct KeypairLoadResult {
     /// The loaded keypair
@@ -34,6 +39,28 @@ pub fn parse_keypair_json(json_str: &str, source_desc: &str) -> Result<Keypair,
     })
 }
 
+/// Parse keypair from a base58 string or JSON array
+fn parse_keypair_secret(secret: &str, source_desc: &str) -> Result<Keypair, KeypairLoadError> {
+    let secret = secret.trim();
+    if secret.starts_with('[') {
+        return parse_keypair_json(secret, source_desc);
+    }
+
+    let secret_key_bytes =
+        bs58::decode(secret)
+            .into_vec()
+            .map_err(|e| KeypairLoadError::InvalidBase58Format {
+                origin: source_desc.to_string(),
+                message: e.to_string(),
+            })?;
+
+    Keypair::try_from(secret_key_bytes.as_slice()).map_err(|_| {
+        KeypairLoadError::InvalidKeypairBytes {
+            origin: source_desc.to_string(),
+        }
+    })
+}
+
 /// Read keypair from a file path
 fn read_keypair_from_path(path: &PathBuf) -> Result<Keypair, KeypairLoadError> {
     let content = fs::read_to_string(path).map_err(|e| KeypairLoadError::FileReadError {
@@ -58,9 +85,7 @@ fn read_keypair_from_stdin() -> Result<Keypair, KeypairLoadError> {
         })?;
 
     if buffer.trim().is_empty() {
-        return Err(KeypairLoadError::StdinReadError {
-            message: "stdin was empty".to_string(),
-        });
+        return Err(KeypairLoadError::StdinEmpty);
     }
 
     parse_keypair_json(&buffer, "stdin")
@@ -68,8 +93,9 @@ fn read_keypair_from_stdin() -> Result<Keypair, KeypairLoadError> {
 
 /// Load keypair following the precedence chain:
 /// 1. CLI argument (--keypair)
-/// 2. Stdin (if not a TTY)
-/// 3. Default path (~/.config/solana/id.json)
+/// 2. `SOLANA_KEYPAIR` environment variable
+/// 3. Stdin (if not a TTY)
+/// 4. Default path (~/.config/solana/id.json)
 ///
 /// # Arguments
 /// * `cli_path` - Optional path from CLI --keypair argument
@@ -81,8 +107,29 @@ fn read_keypair_from_stdin() -> Result<Keypair, KeypairLoadError> {
 pub fn load_keypair(
     cli_path: Option<PathBuf>,
     default_path: PathBuf,
+) -> Result<KeypairLoadResult, KeypairLoadError> {
+    load_keypair_with_env(cli_path, default_path, |name| std::env::var(name).ok())
+}
+
+/// [`load_keypair`] reading environment variables through `env_var`
+fn load_keypair_with_env(
+    cli_path: Option<PathBuf>,
+    default_path: PathBuf,
+    env_var: impl Fn(&str) -> Option<String>,
 ) -> Result<KeypairLoadResult, KeypairLoadError> {
     let mut attempted: Vec<String> = Vec::new();
+    // Whether any source held something, even if it failed to load
+    let mut any_source_present = cli_path.is_some();
+
+    // A hardware wallet URI must never fall back to another source, which
+    // would silently sign with a different key.
+    if let Some(uri) = cli_path.as_deref().and_then(|path| path.to_str())
+        && parse_usb_hardware_uri(uri)?.is_some()
+    {
+        return Err(KeypairLoadError::HardwareWalletNotKeypair {
+            uri: uri.to_string(),
+        });
+    }
 
     // 1. Try CLI argument (highest precedence)
     if let Some(path) = cli_path {
@@ -101,7 +148,22 @@ pub fn load_keypair(
         attempted.push("CLI --keypair: not provided".to_string());
     }
 
-    // 2. Try stdin (if not a TTY)
+    // 2. Try environment variable. A set but invalid secret is an error
+    // rather than a reason to fall back to another key.
+    match env_var(KEYPAIR_ENV_VAR).filter(|secret| !secret.trim().is_empty()) {
+        Some(secret) => {
+            let keypair = parse_keypair_secret(&secret, KEYPAIR_ENV_VAR)?;
+            return Ok(KeypairLoadResult {
+                keypair,
+                source: KeypairSource::EnvVar(KEYPAIR_ENV_VAR.to_string()),
+            });
+        }
+        None => {
+            attempted.push(format!("Env {KEYPAIR_ENV_VAR}: not set"));
+        }
+    }
+
+    // 3. Try stdin (if not a TTY)
     match read_keypair_from_stdin() {
         Ok(keypair) => {
             return Ok(KeypairLoadResult {
@@ -112,12 +174,24 @@ pub fn load_keypair(
         Err(KeypairLoadError::StdinIsTty) => {
             attempted.push("Stdin: is a TTY (not piped)".to_string());
         }
+        Err(KeypairLoadError::StdinEmpty) => {
+            attempted.push("Stdin: empty".to_string());
+        }
         Err(e) => {
+            any_source_present = true;
             attempted.push(format!("Stdin: {}", e));
         }
     }
 
-    // 3. Try default path
+    // 4. Try default path
+    if !any_source_present && !default_path.exists() {
+        attempted.push(format!(
+            "Default path ({}): not found",
+            default_path.display()
+        ));
+        return Err(KeypairLoadError::KeypairNotFound { attempted });
+    }
+
     match read_keypair_from_path(&default_path) {
         Ok(keypair) => {
             return Ok(KeypairLoadResult {
@@ -135,8 +209,9 @@ pub fn load_keypair(
 
 /// Load keypair following the precedence chain:
 /// 1. CLI argument (--keypair)
-/// 2. Stdin (if not a TTY)
-/// 3. Default path (~/.config/solana/id.json)
+/// 2. `SOLANA_KEYPAIR` environment variable
+/// 3. Stdin (if not a TTY)
+/// 4. Default path (~/.config/solana/id.json)
 ///
 /// This is a convenience wrapper around [`load_keypair`] that automatically
 /// computes the default path from the HOME environment variable.
@@ -232,16 +307,102 @@ mod tests {
         assert!(matches!(result.source, KeypairSource::DefaultPath(_)));
     }
 
+    #[test]
+    fn test_parse_keypair_secret_formats() {
+        let keypair = Keypair::new();
+
+        let json = serde_json::to_string(&keypair.to_bytes().to_vec()).unwrap();
+        let parsed = parse_keypair_secret(&json, "test").unwrap();
+        assert_eq!(parsed.pubkey(), keypair.pubkey());
+
+        let base58 = keypair.to_base58_string();
+        let parsed = parse_keypair_secret(&format!("{base58}\n"), "test").unwrap();
+        assert_eq!(parsed.pubkey(), keypair.pubkey());
+
+        assert!(matches!(
+            parse_keypair_secret("not-base58-0OIl", "test"),
+            Err(KeypairLoadError::InvalidBase58Format { .. })
+        ));
+        assert!(matches!(
+            parse_keypair_secret(&bs58::encode([1; 32]).into_string(), "test"),
+            Err(KeypairLoadError::InvalidKeypairBytes { .. })
+        ));
+    }
+
+    #[test]
+    fn test_load_keypair_env_var_precedence() {
+        let tmp = TempDir::new().unwrap();
+        let (cli_path, cli_keypair) = create_test_keypair_file(&tmp);
+        let env_keypair = Keypair::new();
+        let env_secret = env_keypair.to_base58_string();
+        let env_var = |name: &str| (name == KEYPAIR_ENV_VAR).then(|| env_secret.clone());
+
+        // The CLI argument takes precedence over the environment variable.
+        let result =
+            load_keypair_with_env(Some(cli_path.clone()), cli_path.clone(), env_var).unwrap();
+        assert_eq!(result.keypair.pubkey(), cli_keypair.pubkey());
+        assert!(matches!(result.source, KeypairSource::CliArgument(_)));
+
+        // The environment variable takes precedence over the default path.
+        let result = load_keypair_with_env(None, cli_path.clone(), env_var).unwrap();
+        assert_eq!(result.keypair.pubkey(), env_keypair.pubkey());
+        assert_eq!(
+            result.source,
+            KeypairSource::EnvVar(KEYPAIR_ENV_VAR.to_string())
+        );
+
+        // An unset or empty variable falls through to the default path.
+        let result = load_keypair_with_env(None, cli_path.clone(), |_| None).unwrap();
+        assert!(matches!(result.source, KeypairSource::DefaultPath(_)));
+        let result = load_keypair_with_env(None, cli_path, |_| Some(" ".to_string())).unwrap();
+        assert!(matches!(result.source, KeypairSource::DefaultPath(_)));
+    }
+
+    #[test]
+    fn test_load_keypair_invalid_env_var() {
+        let tmp = TempDir::new().unwrap();
+        let (default_path, _) = create_test_keypair_file(&tmp);
+
+        // An invalid secret never falls back to the default path.
+        let result = load_keypair_with_env(None, default_path, |_| Some("not-base58-0OIl".into()));
+        assert!(matches!(
+            result,
+            Err(KeypairLoadError::InvalidBase58Format { .. })
+        ));
+    }
+
     #[test]
     fn test_load_keypair_no_source_available() {
         let tmp = TempDir::new().unwrap();
 
+        // A present but unusable source is reported as such.
         let nonexistent = tmp.path().join("nonexistent.json");
-        let result = load_keypair(None, nonexistent);
+        let result = load_keypair_with_env(Some(nonexistent.clone()), nonexistent, |_| None);
 
         assert!(matches!(
             result,
             Err(KeypairLoadError::NoSourceAvailable { .. })
         ));
     }
+
+    #[test]
+    fn test_load_keypair_not_found() {
+        let tmp = TempDir::new().unwrap();
+
+        let nonexistent = tmp.path().join("nonexistent.json");
+        let Err(err) = load_keypair_with_env(None, nonexistent.clone(), |_| None) else {
+            panic!("no keypair source should be available");
+        };
+        assert!(matches!(err, KeypairLoadError::KeypairNotFound { .. }));
+
+        let message = err.to_string();
+        assert!(message.contains("CLI --keypair: not provided"));
+        assert!(message.contains(&format!("Env {KEYPAIR_ENV_VAR}: not set")));
+        assert!(message.contains("Stdin:"));
+        assert!(message.contains(&format!(
+            "Default path ({}): not found",
+            nonexistent.display()
+        )));
+        assert!(message.contains("solana-keygen new"));
+    }
 }
@@ -2,8 +2,14 @@
 //!
 //! This module provides flexible keypair loading with the following precedence:
 //! 1. CLI argument (`--keypair /path/to/key.json`)
-//! 2. Stdin (if piped, not a TTY)
-//! 3. Default path (`~/.config/solana/id.json`)
+//! 2. `SOLANA_KEYPAIR` environment variable (base58 or JSON array secret)
+//! 3. Stdin (if piped, not a TTY)
+//! 4. Default path (`~/.config/solana/id.json`)
+//!
+//! Commands that accept any [`Signer`](solana_sdk::signer::Signer) can also
+//! sign with a USB hardware wallet via [`try_load_signer`], selected by passing
+//! `--keypair usb://ledger?key=<path>`. Device support requires the
+//! `remote-wallet` feature.
 //!
 //! # Example
 //!
@@ -19,9 +25,13 @@
 //! ```
 
 mod error;
+mod hardware;
 mod loader;
 mod source;
 
 pub use error::KeypairLoadError;
-pub use loader::{KeypairLoadResult, load_keypair, parse_keypair_json, try_load_keypair};
+pub use hardware::{parse_usb_hardware_uri, try_load_signer};
+pub use loader::{
+    KEYPAIR_ENV_VAR, KeypairLoadResult, load_keypair, parse_keypair_json, try_load_keypair,
+};
 pub use source::KeypairSource;
@@ -6,18 +6,30 @@ use std::{fmt, path::PathBuf};
 pub enum KeypairSource {
     /// Keypair loaded from CLI argument (highest precedence)
     CliArgument(PathBuf),
+    /// Keypair loaded from the named environment variable
+    EnvVar(String),
     /// Keypair loaded from stdin (piped input)
     Stdin,
     /// Keypair loaded from default path
     DefaultPath(PathBuf),
+    /// Signer backed by a USB hardware wallet (`usb://ledger?key=<path>`).
+    /// Without a derivation path, the device's default account is used
+    UsbHardware { derivation_path: Option<String> },
 }
 
 impl fmt::Display for KeypairSource {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::CliArgument(path) => write!(f, "CLI argument ({})", path.display()),
+            Self::EnvVar(name) => write!(f, "environment variable {name}"),
             Self::Stdin => write!(f, "stdin"),
             Self::DefaultPath(path) => write!(f, "default path ({})", path.display()),
+            Self::UsbHardware {
+                derivation_path: Some(derivation_path),
+            } => write!(f, "USB hardware wallet (key={derivation_path})"),
+            Self::UsbHardware {
+                derivation_path: None,
+            } => write!(f, "USB hardware wallet"),
         }
     }
 }
@@ -2,5 +2,6 @@ pub mod account;
 pub mod instruction;
 pub mod keypair;
 pub mod payer;
+pub mod retry;
 pub mod rpc;
 pub mod transaction;
@@ -1,7 +1,8 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Arc, time::Duration};
 
 use anyhow::{Context, Result, ensure};
 use clap::Args;
+use leaky_bucket::RateLimiter;
 use solana_client::{
     rpc_config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig, RpcTransactionConfig},
     rpc_response::RpcSimulateTransactionResult,
@@ -50,13 +51,24 @@ pub struct SolanaSignerOptions {
     #[arg(long, short = 'v', value_name = "VERBOSE", default_value = "false")]
     pub verbose: bool,
 
-    /// Filepath or URL to keypair to pay transaction fee.
+    /// Filepath or URL to keypair to pay transaction fee. The main keypair
+    /// still signs as the instruction authority. Not supported by harvest-2z
+    /// or the relay pay-solana-validator-debt commands.
     #[arg(long = "fee-payer", value_name = "KEYPAIR")]
     pub fee_payer_path: Option<String>,
 
     /// Simulate transaction only.
     #[arg(long, value_name = "DRY_RUN")]
     pub dry_run: bool,
+
+    /// Maximum number of transactions to send or simulate per second.
+    /// Unthrottled by default.
+    #[arg(
+        long,
+        value_name = "COUNT",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub max_tx_per_second: Option<u32>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -72,6 +84,8 @@ pub struct Wallet {
     pub verbose: bool,
     pub fee_payer: Option<Keypair>,
     pub dry_run: bool,
+    /// Shared throttle on transaction submission, set by --max-tx-per-second.
+    pub tx_rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl Wallet {
@@ -79,14 +93,12 @@ impl Wallet {
         self.signer.pubkey()
     }
 
-    pub async fn new_transaction_with_additional_signers_and_lookup_tables(
-        &self,
-        instructions: &[Instruction],
-        additional_signers: &[&Keypair],
-        address_lookup_table_keys: &[Pubkey],
-    ) -> Result<VersionedTransaction> {
-        let recent_blockhash = self.connection.get_latest_blockhash().await?;
-
+    /// Signers of a transaction built by this wallet. The fee payer, if any,
+    /// comes first so it pays the transaction fee.
+    pub fn transaction_signers<'a>(
+        &'a self,
+        additional_signers: &[&'a Keypair],
+    ) -> Vec<&'a Keypair> {
         let mut signers = Vec::with_capacity(2 + additional_signers.len());
 
         match self.fee_payer {
@@ -103,6 +115,18 @@ impl Wallet {
         }
 
         signers.extend_from_slice(additional_signers);
+        signers
+    }
+
+    pub async fn new_transaction_with_additional_signers_and_lookup_tables(
+        &self,
+        instructions: &[Instruction],
+        additional_signers: &[&Keypair],
+        address_lookup_table_keys: &[Pubkey],
+    ) -> Result<VersionedTransaction> {
+        let recent_blockhash = self.connection.get_latest_blockhash().await?;
+
+        let signers = self.transaction_signers(additional_signers);
 
         if address_lookup_table_keys.is_empty() {
             return try_new_transaction(instructions, &signers, &[], recent_blockhash);
@@ -224,6 +248,8 @@ impl Wallet {
         send_config: RpcSendTransactionConfig,
         simulate_config: RpcSimulateTransactionConfig,
     ) -> Result<TransactionOutcome> {
+        self.throttle_tx_submission().await;
+
         if self.dry_run {
             let simulation_response = self
                 .connection
@@ -272,6 +298,14 @@ impl Wallet {
         }
     }
 
+    /// Wait until the transaction rate limiter, if any, allows another
+    /// submission.
+    pub async fn throttle_tx_submission(&self) {
+        if let Some(tx_rate_limiter) = &self.tx_rate_limiter {
+            tx_rate_limiter.acquire_one().await;
+        }
+    }
+
     pub fn compute_units_for_bump_seed(bump: u8) -> u32 {
         1_500 * u32::from(255 - bump)
     }
@@ -312,6 +346,7 @@ impl TryFrom<SolanaPayerOptions> for Wallet {
                     verbose,
                     fee_payer_path,
                     dry_run,
+                    max_tx_per_second,
                 },
         } = opts;
 
@@ -338,10 +373,25 @@ impl TryFrom<SolanaPayerOptions> for Wallet {
             verbose,
             fee_payer,
             dry_run,
+            tx_rate_limiter: max_tx_per_second.map(|max| Arc::new(new_tx_rate_limiter(max))),
         })
     }
 }
 
+/// Rate limiter allowing `max_tx_per_second` transaction submissions per
+/// second. After an initial burst, submissions are spread evenly across each
+/// second.
+pub fn new_tx_rate_limiter(max_tx_per_second: u32) -> RateLimiter {
+    let max_tx_per_second = max_tx_per_second.max(1);
+
+    RateLimiter::builder()
+        .max(max_tx_per_second as usize)
+        .initial(max_tx_per_second as usize)
+        .refill(1)
+        .interval((Duration::from_secs(1) / max_tx_per_second).max(Duration::from_nanos(1)))
+        .build()
+}
+
 fn try_load_specified_keypair(path: &PathBuf) -> Result<Keypair> {
     let keypair_file = std::fs::read_to_string(path)
         .with_context(|| format!("Keypair not found at {}", path.display()))?;
@@ -352,3 +402,122 @@ fn try_load_specified_keypair(path: &PathBuf) -> Result<Keypair> {
 
     Ok(default_keypair)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, time::Instant};
+
+    use solana_sdk::{hash::Hash, instruction::AccountMeta};
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn try_write_keypair(path: &Path) -> Result<String> {
+        std::fs::write(
+            path,
+            serde_json::to_string(&Keypair::new().to_bytes().to_vec())?,
+        )?;
+        Ok(path.display().to_string())
+    }
+
+    fn try_new_wallet(max_tx_per_second: Option<u32>) -> Result<(TempDir, Wallet)> {
+        let dir = TempDir::new()?;
+        let keypair_path = try_write_keypair(&dir.path().join("keypair.json"))?;
+
+        let wallet = Wallet::try_from(SolanaPayerOptions {
+            connection_options: Default::default(),
+            signer_options: SolanaSignerOptions {
+                keypair_path: Some(keypair_path),
+                max_tx_per_second,
+                ..Default::default()
+            },
+        })?;
+
+        Ok((dir, wallet))
+    }
+
+    #[test]
+    fn test_fee_payer_is_first_account() {
+        let dir = TempDir::new().unwrap();
+        let keypair_path = try_write_keypair(&dir.path().join("keypair.json")).unwrap();
+        let fee_payer_path = try_write_keypair(&dir.path().join("fee-payer.json")).unwrap();
+
+        let wallet = Wallet::try_from(SolanaPayerOptions {
+            connection_options: Default::default(),
+            signer_options: SolanaSignerOptions {
+                keypair_path: Some(keypair_path.clone()),
+                fee_payer_path: Some(fee_payer_path),
+                ..Default::default()
+            },
+        })
+        .unwrap();
+        let fee_payer_key = wallet.fee_payer.as_ref().unwrap().pubkey();
+
+        // The signer authorizes the instruction while the fee payer pays fees.
+        let instruction = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![AccountMeta::new_readonly(wallet.pubkey(), true)],
+        );
+        let transaction = try_new_transaction(
+            &[instruction],
+            &wallet.transaction_signers(&[]),
+            &[],
+            Hash::default(),
+        )
+        .unwrap();
+
+        let message = &transaction.message;
+        assert_eq!(message.header().num_required_signatures, 2);
+        assert_eq!(
+            &message.static_account_keys()[..2],
+            &[fee_payer_key, wallet.pubkey()]
+        );
+        assert_eq!(
+            transaction.verify_with_results(),
+            vec![true, true],
+            "both signers sign"
+        );
+
+        // The same keypair as both signer and fee payer is rejected.
+        assert!(
+            Wallet::try_from(SolanaPayerOptions {
+                connection_options: Default::default(),
+                signer_options: SolanaSignerOptions {
+                    keypair_path: Some(keypair_path.clone()),
+                    fee_payer_path: Some(keypair_path),
+                    ..Default::default()
+                },
+            })
+            .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_tx_per_second_bounds_submission_rate() {
+        let (_dir, wallet) = try_new_wallet(Some(50)).unwrap();
+        assert!(wallet.tx_rate_limiter.is_some());
+
+        // After a burst of 50 submissions, each of the remaining 25 waits
+        // 20ms for a refill.
+        let start = Instant::now();
+        for _ in 0..75 {
+            wallet.throttle_tx_submission().await;
+        }
+        assert!(
+            start.elapsed() >= Duration::from_millis(450),
+            "75 submissions at 50/s finished in {:?}",
+            start.elapsed()
+        );
+
+        // Unthrottled by default.
+        let (_dir, wallet) = try_new_wallet(None).unwrap();
+        assert!(wallet.tx_rate_limiter.is_none());
+
+        let start = Instant::now();
+        for _ in 0..75 {
+            wallet.throttle_tx_submission().await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(450));
+    }
+}
@@ -0,0 +1,313 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use backon::{ExponentialBuilder, Retryable};
+use solana_client::{
+    client_error::{
+        ClientError, ClientErrorKind,
+        reqwest::{self, StatusCode},
+    },
+    rpc_custom_error::{
+        JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE,
+        JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED, JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+    },
+    rpc_request::{RpcError, RpcResponseErrorData},
+};
+use solana_sdk::transaction::TransactionError;
+
+/// How [with_retries] retries a failed operation. Delays grow exponentially
+/// from `min_delay` up to `max_delay`.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first. Zero is treated as one.
+    pub max_attempts: usize,
+    pub min_delay: Duration,
+    pub max_delay: Duration,
+    /// Randomize each delay so concurrent callers do not retry in lockstep.
+    pub jitter: bool,
+    /// Errors for which this returns false are returned immediately. See
+    /// [RetryableError] for a classification of Solana client errors.
+    pub is_retryable: fn(&anyhow::Error) -> bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            min_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+            jitter: true,
+            is_retryable: |_| true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn backoff(&self) -> ExponentialBuilder {
+        let backoff = ExponentialBuilder::default()
+            .with_min_delay(self.min_delay)
+            .with_max_delay(self.max_delay)
+            .with_max_times(self.max_attempts.saturating_sub(1));
+
+        if self.jitter {
+            backoff.with_jitter()
+        } else {
+            backoff
+        }
+    }
+}
+
+/// Runs `op` until it succeeds, fails with a non-retryable error or runs out
+/// of attempts. The last error is returned unchanged.
+pub async fn with_retries<F, Fut, T>(policy: RetryPolicy, op: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    op.retry(policy.backoff()).when(policy.is_retryable).await
+}
+
+/// Whether an error is worth retrying, as opposed to one that would fail the
+/// same way again.
+pub trait RetryableError {
+    fn is_retryable(&self) -> bool;
+}
+
+/// Timeouts, dropped connections, rate limiting and server errors are
+/// retryable. Other HTTP errors, like 403 and 404, are permanent.
+impl RetryableError for reqwest::Error {
+    fn is_retryable(&self) -> bool {
+        if self.is_timeout() || self.is_connect() || self.is_request() {
+            return true;
+        }
+
+        self.status().is_some_and(is_retryable_status)
+    }
+}
+
+/// A transaction may land on retry if it failed because of cluster load or an
+/// expired blockhash. Program errors, like invalid arguments or custom
+/// errors, are permanent.
+impl RetryableError for TransactionError {
+    fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            TransactionError::BlockhashNotFound
+                | TransactionError::AccountInUse
+                | TransactionError::WouldExceedMaxBlockCostLimit
+                | TransactionError::WouldExceedMaxAccountCostLimit
+                | TransactionError::ClusterMaintenance
+        )
+    }
+}
+
+impl RetryableError for ClientError {
+    fn is_retryable(&self) -> bool {
+        match self.kind() {
+            ClientErrorKind::Io(_) => true,
+            ClientErrorKind::Reqwest(err) => err.is_retryable(),
+            ClientErrorKind::TransactionError(err) => err.is_retryable(),
+            ClientErrorKind::RpcError(RpcError::RpcResponseError { code, data, .. }) => {
+                match data {
+                    RpcResponseErrorData::SendTransactionPreflightFailure(simulation) => simulation
+                        .err
+                        .as_ref()
+                        .is_some_and(|err| err.is_retryable()),
+                    _ => matches!(
+                        *code,
+                        JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY
+                            | JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE
+                            | JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED
+                    ),
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Classifies the first Solana client, HTTP, transaction or I/O error in the
+/// chain. Anything else is permanent.
+impl RetryableError for anyhow::Error {
+    fn is_retryable(&self) -> bool {
+        self.chain()
+            .find_map(|err| {
+                if let Some(err) = err.downcast_ref::<ClientError>() {
+                    Some(err.is_retryable())
+                } else if let Some(err) = err.downcast_ref::<reqwest::Error>() {
+                    Some(err.is_retryable())
+                } else if let Some(err) = err.downcast_ref::<TransactionError>() {
+                    Some(err.is_retryable())
+                } else {
+                    err.is::<std::io::Error>().then_some(true)
+                }
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Whether an RPC request failed before reaching the node or getting a
+/// response, as opposed to being rejected by it.
+pub fn is_transient_rpc_error(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<ClientError>().is_some_and(|err| {
+        matches!(
+            err.kind,
+            ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_)
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use anyhow::{anyhow, bail};
+    use solana_sdk::instruction::InstructionError;
+
+    use super::*;
+
+    fn test_policy(max_attempts: usize) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            min_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(1),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_with_retries_fail_then_succeed() {
+        let attempts = AtomicUsize::new(0);
+
+        let value = with_retries(test_policy(3), || async {
+            if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                bail!("not yet");
+            }
+            Ok(42)
+        })
+        .await
+        .unwrap();
+        assert_eq!(value, 42);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_with_retries_exhausted() {
+        let attempts = AtomicUsize::new(0);
+
+        let err = with_retries(test_policy(3), || async {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+            Err::<(), _>(anyhow!("attempt {attempt} failed"))
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(err.to_string(), "attempt 3 failed");
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        // Zero attempts still runs the operation once.
+        let attempts = AtomicUsize::new(0);
+        with_retries(test_policy(0), || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(anyhow!("failed"))
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_with_retries_non_retryable() {
+        let attempts = AtomicUsize::new(0);
+        let policy = RetryPolicy {
+            is_retryable: |err| err.to_string() != "rejected",
+            ..test_policy(3)
+        };
+
+        let err = with_retries(policy, || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(anyhow!("rejected"))
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(err.to_string(), "rejected");
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_is_transient_rpc_error() {
+        let io_error = ClientError::from(std::io::Error::other("connection reset"));
+        assert!(is_transient_rpc_error(&io_error.into()));
+
+        let rpc_error = ClientError::from(ClientErrorKind::Custom("rejected".to_string()));
+        assert!(!is_transient_rpc_error(&rpc_error.into()));
+
+        assert!(!is_transient_rpc_error(&anyhow!("not an RPC error")));
+    }
+
+    #[test]
+    fn test_retryable_status() {
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable_status(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(is_retryable_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!is_retryable_status(StatusCode::BAD_REQUEST));
+        assert!(!is_retryable_status(StatusCode::FORBIDDEN));
+        assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+    }
+
+    #[test]
+    fn test_retryable_client_errors() {
+        let rpc_response_error = |code| {
+            ClientError::from(RpcError::RpcResponseError {
+                code,
+                message: "rpc error".to_string(),
+                data: RpcResponseErrorData::Empty,
+            })
+        };
+
+        // Retryable.
+        assert!(ClientError::from(std::io::Error::other("connection reset")).is_retryable());
+        assert!(ClientError::from(TransactionError::BlockhashNotFound).is_retryable());
+        assert!(ClientError::from(TransactionError::WouldExceedMaxBlockCostLimit).is_retryable());
+        assert!(rpc_response_error(JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY).is_retryable());
+
+        // Permanent.
+        assert!(
+            !ClientError::from(TransactionError::InstructionError(
+                0,
+                InstructionError::InvalidArgument
+            ))
+            .is_retryable()
+        );
+        assert!(
+            !ClientError::from(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(1)
+            ))
+            .is_retryable()
+        );
+        assert!(!ClientError::from(TransactionError::AccountNotFound).is_retryable());
+        assert!(!rpc_response_error(-32602).is_retryable());
+        assert!(!ClientError::from(ClientErrorKind::Custom("rejected".to_string())).is_retryable());
+    }
+
+    #[test]
+    fn test_retryable_anyhow_errors() {
+        let retryable = anyhow::Error::from(ClientError::from(TransactionError::BlockhashNotFound));
+        assert!(retryable.is_retryable());
+
+        // Context does not hide the underlying error.
+        assert!(
+            retryable
+                .context("Failed to send transaction")
+                .is_retryable()
+        );
+
+        assert!(anyhow::Error::from(std::io::Error::other("timed out")).is_retryable());
+        assert!(!anyhow::Error::from(TransactionError::AlreadyProcessed).is_retryable());
+        assert!(!anyhow!("not a client error").is_retryable());
+    }
+}
@@ -0,0 +1,799 @@
+//! Fetching accounts and dumping programs from the forked cluster.
+
+use std::{fs, process::Command, thread, time::Duration};
+
+use anyhow::{Context, Result, bail, ensure};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use doublezero_solana_client_tools::rpc::SolanaConnection;
+use doublezero_solana_sdk::{
+    NetworkEnvironment, environment_2z_token_mint_key,
+    passport::state::ProgramConfig as PassportProgramConfig,
+    revenue_distribution::{
+        self,
+        state::{Distribution, Journal, ProgramConfig as RevenueDistributionProgramConfig},
+        types::DoubleZeroEpoch,
+    },
+};
+use futures::{StreamExt, TryStreamExt, future, stream};
+use solana_account_decoder_client_types::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_sdk::{account::Account, bpf_loader_upgradeable, program_pack::Pack, pubkey::Pubkey};
+use spl_token_interface::state::Mint;
+
+use crate::{
+    ForkedProgram, ProgramIds, ProgramLoader,
+    god_mode::{
+        SolConversionGodMode, format_authority_changes, try_god_mode_authority_changes,
+        try_god_mode_revenue_distribution_config, try_god_mode_sol_conversion,
+    },
+    output::{
+        WrittenAccount, try_account_file_paths, try_modify_zero_copy_account,
+        try_read_zero_copy_account, try_write_account_to_file, try_write_extra_accounts,
+        try_write_wrapped_account_to_file,
+    },
+    verify::try_verify_program_dump,
+};
+
+/// Accounts fetched per `getMultipleAccounts` request when paging through
+/// program accounts or refreshing account files.
+const PROGRAM_ACCOUNTS_PAGE_SIZE: usize = 100;
+
+/// Pages of program accounts fetched and written at the same time.
+const PROGRAM_ACCOUNTS_CONCURRENCY: usize = 8;
+
+/// Attempts made to dump each program before giving up.
+const DUMP_PROGRAM_MAX_ATTEMPTS: u32 = 4;
+
+/// Delay before the first dump retry, doubled after each failed attempt.
+const DUMP_PROGRAM_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// Lowercase fragments of `solana program dump` errors that retrying cannot
+/// fix.
+const PERMANENT_DUMP_ERRORS: [&str; 4] = [
+    "unable to find the account",
+    "accountnotfound",
+    "is not an sbf program",
+    "is not a bpf program",
+];
+
+/// Account files rewritten or left alone by --refresh.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RefreshSummary {
+    pub updated: usize,
+    pub unchanged: usize,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn try_fetch_and_write_accounts(
+    connection: &SolanaConnection,
+    accounts_dir: &str,
+    network_env: NetworkEnvironment,
+    program_ids: ProgramIds,
+    programs: &[ForkedProgram],
+    extra_account_keys: &[Pubkey],
+    upgrade_authority_key: Pubkey,
+    should_god_mode: bool,
+    should_dry_run: bool,
+    grace_period_minutes: u16,
+    next_completed_dz_epoch_override: Option<u64>,
+    sol_conversion_god_mode: SolConversionGodMode,
+) -> Result<()> {
+    // Fetch 2Z mint account.
+
+    let token_2z_mint_key = environment_2z_token_mint_key(network_env);
+
+    let mint_account = connection.get_account(&token_2z_mint_key).await?;
+    try_write_account_to_file(&token_2z_mint_key, &mint_account, accounts_dir)?;
+    tracing::info!("Wrote 2Z SPL mint account to {accounts_dir}/");
+
+    // Fetch extra accounts.
+
+    if !extra_account_keys.is_empty() {
+        let extra_accounts = connection.get_multiple_accounts(extra_account_keys).await?;
+        let extra_accounts_len =
+            try_write_extra_accounts(extra_account_keys, extra_accounts, accounts_dir)?;
+        tracing::info!(
+            "Wrote {} extra account{} to {accounts_dir}/",
+            extra_accounts_len,
+            if extra_accounts_len == 1 { "" } else { "s" },
+        );
+    }
+
+    // Fetch program accounts. Every listing and page must be served from at
+    // least the same slot, so a lagging RPC node cannot return pages that
+    // predate the account keys they were listed with.
+
+    let min_context_slot = connection.get_slot().await?;
+    let config = RpcProgramAccountsConfig {
+        filters: None,
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            min_context_slot: Some(min_context_slot),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    // Fetch all program accounts.
+
+    future::try_join_all(programs.iter().map(|&program| {
+        try_fetch_and_write_program_accounts(
+            connection,
+            program_ids.get(program),
+            program.name(),
+            accounts_dir,
+            &config,
+        )
+    }))
+    .await?;
+
+    // Dump programs.
+
+    try_dump_and_verify_programs(connection, &program_ids, programs, accounts_dir).await?;
+
+    if should_god_mode && should_dry_run {
+        let changes = try_god_mode_authority_changes(
+            accounts_dir,
+            &token_2z_mint_key,
+            upgrade_authority_key,
+            sol_conversion_god_mode,
+        )?;
+        tracing::info!(
+            "God mode dry run. No accounts were modified\n{}",
+            format_authority_changes(&changes)
+        );
+        return Ok(());
+    }
+
+    if should_god_mode {
+        tracing::info!("God mode enabled");
+
+        let forked_next_completed_dz_epoch = try_god_mode_revenue_distribution_config(
+            accounts_dir,
+            upgrade_authority_key,
+            grace_period_minutes,
+            next_completed_dz_epoch_override,
+        )?;
+        tracing::info!("Updated Revenue Distribution config authorities");
+
+        if let Some(next_completed_dz_epoch_override) = next_completed_dz_epoch_override {
+            for dz_epoch in next_completed_dz_epoch_override..forked_next_completed_dz_epoch {
+                let (distribution_key, _) =
+                    Distribution::find_address(DoubleZeroEpoch::new(dz_epoch));
+
+                // Remove the file representing this distribution key.
+                let path = format!("{accounts_dir}/{distribution_key}.json");
+                if fs::metadata(&path).is_ok() {
+                    fs::remove_file(&path)?;
+                    tracing::info!("Removed distribution account for epoch {dz_epoch}");
+                }
+            }
+        }
+
+        try_modify_zero_copy_account::<PassportProgramConfig, _>(
+            &PassportProgramConfig::find_address().0,
+            accounts_dir,
+            |config| {
+                config.admin_key = upgrade_authority_key;
+                config.sentinel_key = upgrade_authority_key;
+            },
+        )?;
+        tracing::info!("Updated Passport config authorities");
+
+        try_god_mode_sol_conversion(accounts_dir, upgrade_authority_key, sol_conversion_god_mode)?;
+        tracing::info!("Updated SOL Conversion config authorities");
+
+        // Override mint authority.
+
+        let mint_path = format!("{accounts_dir}/{token_2z_mint_key}.json");
+        let mint_json = fs::read_to_string(&mint_path)?;
+        let mut mint_wrapper = serde_json::from_str::<WrittenAccount>(&mint_json)?;
+        let mut mint_data = BASE64.decode(&mint_wrapper.account.data.0)?;
+
+        let mut mint = Mint::unpack(&mint_data)?;
+        mint.mint_authority = upgrade_authority_key.into();
+
+        Mint::pack(mint, &mut mint_data)?;
+        mint_wrapper.account.data.0 = BASE64.encode(&mint_data);
+        try_write_wrapped_account_to_file(&token_2z_mint_key, &mint_wrapper, accounts_dir)?;
+    }
+
+    if !program_ids.is_default() {
+        tracing::warn!(
+            "Program IDs were overridden. Skipping 2Z token PDA accounts, which are derived from the SDK program IDs"
+        );
+        return Ok(());
+    }
+
+    if !programs.contains(&ForkedProgram::RevenueDistribution) {
+        tracing::info!(
+            "Revenue Distribution is not forked. Skipping 2Z token PDA accounts, which are derived from its accounts"
+        );
+        return Ok(());
+    }
+
+    // Fetch various 2Z Token PDAs.
+
+    let mut token_pda_keys = Vec::new();
+
+    let (revenue_distribution_config_key, _) = RevenueDistributionProgramConfig::find_address();
+    token_pda_keys.push(
+        revenue_distribution::state::find_2z_token_pda_address(&revenue_distribution_config_key).0,
+    );
+
+    let (swap_authority_key, _) = revenue_distribution::state::find_swap_authority_address();
+    token_pda_keys
+        .push(revenue_distribution::state::find_2z_token_pda_address(&swap_authority_key).0);
+
+    let (journal_key, _) = Journal::find_address();
+    token_pda_keys.push(revenue_distribution::state::find_2z_token_pda_address(&journal_key).0);
+
+    // For existing distributions, fetch the 2Z token PDAs. Read the
+    // Revenue Distribution config account file to deserialize the data
+    // and read the next completed DZ epoch.
+    let (_, revenue_distribution_config, _) = try_read_zero_copy_account::<
+        RevenueDistributionProgramConfig,
+    >(&revenue_distribution_config_key, accounts_dir)?;
+
+    let forked_next_completed_dz_epoch =
+        revenue_distribution_config.next_completed_dz_epoch.value();
+    let next_completed_dz_epoch = next_completed_dz_epoch_override
+        .unwrap_or(forked_next_completed_dz_epoch)
+        .min(forked_next_completed_dz_epoch);
+    for epoch in 0..next_completed_dz_epoch {
+        let (distribution_key, _) = Distribution::find_address(DoubleZeroEpoch::new(epoch));
+        token_pda_keys
+            .push(revenue_distribution::state::find_2z_token_pda_address(&distribution_key).0);
+    }
+
+    // Fetch all 2Z token PDA accounts, chunking 100 accounts at a time.
+    for token_pda_keys_chunk in token_pda_keys.chunks(100) {
+        let token_accounts = connection
+            .get_multiple_accounts(token_pda_keys_chunk)
+            .await?;
+        for (key, token_account) in token_pda_keys_chunk.iter().zip(token_accounts) {
+            let account = token_account
+                .as_ref()
+                .with_context(|| format!("Account does not exist: {}", key))?;
+            try_write_account_to_file(key, account, accounts_dir)?;
+        }
+    }
+
+    let token_pda_keys_len = token_pda_keys.len();
+    tracing::info!(
+        "Wrote {} 2Z token PDA account{} to {accounts_dir}/",
+        token_pda_keys_len,
+        if token_pda_keys_len == 1 { "" } else { "s" }
+    );
+
+    Ok(())
+}
+
+/// Fetch and write every account owned by the program matching `config`'s
+/// filters.
+///
+/// Only the account keys are listed with `getProgramAccounts`, using an empty
+/// data slice so the response stays small. The accounts themselves are then
+/// fetched in pages with `getMultipleAccounts` and written concurrently. Pages
+/// are fetched with `config`'s account config, so its `min_context_slot`
+/// applies to every page.
+async fn try_fetch_and_write_program_accounts(
+    connection: &SolanaConnection,
+    program_id: &Pubkey,
+    program_name: &str,
+    accounts_dir: &str,
+    config: &RpcProgramAccountsConfig,
+) -> Result<usize> {
+    let mut keys_config = config.clone();
+    keys_config.account_config.data_slice = Some(UiDataSliceConfig {
+        offset: 0,
+        length: 0,
+    });
+
+    let account_keys = connection
+        .get_program_accounts_with_config(program_id, keys_config)
+        .await?
+        .into_iter()
+        .map(|(key, _)| key)
+        .collect::<Vec<_>>();
+
+    let accounts_len = try_write_account_pages(&account_keys, accounts_dir, |keys| {
+        let page_config = config.account_config.clone();
+        async move {
+            connection
+                .get_multiple_accounts_with_config(keys, page_config)
+                .await
+                .map(|response| response.value)
+                .map_err(anyhow::Error::from)
+        }
+    })
+    .await?;
+
+    tracing::info!(
+        "Wrote {} {program_name} account{} to {accounts_dir}/",
+        accounts_len,
+        if accounts_len == 1 { "" } else { "s" },
+    );
+
+    Ok(accounts_len)
+}
+
+/// Fetch `account_keys` in pages of `PROGRAM_ACCOUNTS_PAGE_SIZE`, with at most
+/// `PROGRAM_ACCOUNTS_CONCURRENCY` pages in flight, and write each account as
+/// its page arrives. Accounts closed since they were listed are skipped.
+async fn try_write_account_pages<'a, F, Fut>(
+    account_keys: &'a [Pubkey],
+    accounts_dir: &str,
+    fetch_page: F,
+) -> Result<usize>
+where
+    F: Fn(&'a [Pubkey]) -> Fut,
+    Fut: Future<Output = Result<Vec<Option<Account>>>>,
+{
+    stream::iter(account_keys.chunks(PROGRAM_ACCOUNTS_PAGE_SIZE))
+        .map(|keys| try_write_account_page(keys, fetch_page(keys), accounts_dir))
+        .buffer_unordered(PROGRAM_ACCOUNTS_CONCURRENCY)
+        .try_fold(
+            0,
+            |total, accounts_len| async move { Ok(total + accounts_len) },
+        )
+        .await
+}
+
+async fn try_write_account_page(
+    keys: &[Pubkey],
+    page: impl Future<Output = Result<Vec<Option<Account>>>>,
+    accounts_dir: &str,
+) -> Result<usize> {
+    let accounts = page.await?;
+    ensure!(
+        keys.len() == accounts.len(),
+        "Expected {} accounts, fetched {}",
+        keys.len(),
+        accounts.len()
+    );
+
+    let mut accounts_len = 0;
+    for (key, account) in keys.iter().zip(accounts) {
+        match account {
+            Some(account) => {
+                try_write_account_to_file(key, &account, accounts_dir)?;
+                accounts_len += 1;
+            }
+            None => tracing::warn!("Account {key} was closed while forking, skipping"),
+        }
+    }
+
+    Ok(accounts_len)
+}
+
+/// Re-fetch every account file in `accounts_dir` and rewrite only the
+/// accounts whose on-chain state differs from the local copy. Accounts closed
+/// since they were forked keep their local copy.
+pub async fn try_refresh_accounts<F, Fut>(
+    accounts_dir: &str,
+    fetch_page: F,
+) -> Result<RefreshSummary>
+where
+    F: Fn(Vec<Pubkey>) -> Fut,
+    Fut: Future<Output = Result<Vec<Option<Account>>>>,
+{
+    let mut local_accounts = Vec::new();
+
+    for path in try_account_file_paths(accounts_dir)? {
+        let json = fs::read_to_string(&path)?;
+        let wrapper = serde_json::from_str::<WrittenAccount>(&json)
+            .with_context(|| format!("Failed to parse account file {}", path.display()))?;
+        let key = wrapper
+            .pubkey
+            .parse::<Pubkey>()
+            .with_context(|| format!("Invalid pubkey in account file {}", path.display()))?;
+        local_accounts.push((key, wrapper));
+    }
+
+    let mut summary = RefreshSummary::default();
+
+    for local_accounts_page in local_accounts.chunks(PROGRAM_ACCOUNTS_PAGE_SIZE) {
+        let keys = local_accounts_page
+            .iter()
+            .map(|(key, _)| *key)
+            .collect::<Vec<_>>();
+        let accounts = fetch_page(keys).await?;
+        ensure!(
+            local_accounts_page.len() == accounts.len(),
+            "Expected {} accounts, fetched {}",
+            local_accounts_page.len(),
+            accounts.len()
+        );
+
+        for ((key, local_wrapper), account) in local_accounts_page.iter().zip(accounts) {
+            let Some(account) = account else {
+                tracing::warn!("Account {key} no longer exists, keeping the local copy");
+                summary.unchanged += 1;
+                continue;
+            };
+
+            let wrapper = WrittenAccount::new(key, &account);
+            if wrapper == *local_wrapper {
+                summary.unchanged += 1;
+            } else {
+                try_write_wrapped_account_to_file(key, &wrapper, accounts_dir)?;
+                summary.updated += 1;
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Dump each selected program into `accounts_dir` and verify it against the
+/// executable bytes on chain.
+pub async fn try_dump_and_verify_programs(
+    connection: &SolanaConnection,
+    program_ids: &ProgramIds,
+    programs: &[ForkedProgram],
+    accounts_dir: &str,
+) -> Result<()> {
+    for &program in programs {
+        let program_id = program_ids.get(program);
+        let dump_path = program.dump_path(accounts_dir);
+
+        try_dump_program(connection, program_id, program.name(), &dump_path)?;
+        try_verify_program_dump(
+            |key| async move {
+                connection
+                    .get_account(&key)
+                    .await
+                    .map_err(anyhow::Error::from)
+            },
+            program_id,
+            program.name(),
+            &dump_path,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn try_fetch_program_loader(
+    connection: &SolanaConnection,
+    program_id: &Pubkey,
+    program_name: &str,
+) -> Result<ProgramLoader> {
+    let program_account = connection
+        .get_account(program_id)
+        .await
+        .with_context(|| format!("{program_name} program {program_id} does not exist"))?;
+    let loader = ProgramLoader::try_from_program_account(program_id, &program_account)?;
+
+    // A closed upgradeable program keeps its program account, but its program
+    // data is gone and cannot be dumped.
+    if loader == ProgramLoader::Upgradeable {
+        let program_data_key = bpf_loader_upgradeable::get_program_data_address(program_id);
+        connection
+            .get_account(&program_data_key)
+            .await
+            .with_context(|| {
+                format!(
+                    "{program_name} program {program_id} has been closed (no program data at {program_data_key})"
+                )
+            })?;
+    }
+
+    tracing::info!("{program_name} program {program_id} is owned by {loader}");
+    Ok(loader)
+}
+
+fn try_dump_program(
+    connection: &SolanaConnection,
+    program_id: &Pubkey,
+    program_name: &str,
+    output_path: &str,
+) -> Result<()> {
+    tracing::info!("Dumping {} program to {}...", program_name, output_path);
+
+    let url = connection.url();
+    try_run_dump_command(
+        || {
+            let mut command = Command::new("solana");
+            command
+                .arg("program")
+                .arg("dump")
+                .arg("--url")
+                .arg(&url)
+                .arg(program_id.to_string())
+                .arg(output_path);
+            command
+        },
+        DUMP_PROGRAM_MAX_ATTEMPTS,
+        DUMP_PROGRAM_RETRY_DELAY,
+    )
+    .with_context(|| format!("Failed to dump {program_name} program {program_id}"))?;
+
+    tracing::info!("{} program dumped successfully", program_name);
+    Ok(())
+}
+
+/// Run a dump command until it succeeds, backing off between attempts.
+/// Errors that cannot be fixed by retrying, like a missing program, fail
+/// immediately. Returns the number of attempts made.
+fn try_run_dump_command(
+    mut make_command: impl FnMut() -> Command,
+    max_attempts: u32,
+    retry_delay: Duration,
+) -> Result<u32> {
+    let mut attempt = 1;
+    let mut retry_delay = retry_delay;
+
+    loop {
+        let output = make_command().output()?;
+        if output.status.success() {
+            return Ok(attempt);
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = stderr.trim();
+        let lowercase_stderr = stderr.to_lowercase();
+        if PERMANENT_DUMP_ERRORS
+            .iter()
+            .any(|error| lowercase_stderr.contains(error))
+        {
+            bail!("solana program dump failed: {stderr}");
+        }
+
+        ensure!(
+            attempt < max_attempts,
+            "solana program dump exited with status {} after {} attempts: {}",
+            output.status,
+            attempt,
+            stderr
+        );
+
+        tracing::warn!(
+            "solana program dump failed (attempt {attempt}/{max_attempts}), retrying in {retry_delay:?}: {stderr}"
+        );
+        thread::sleep(retry_delay);
+
+        attempt += 1;
+        retry_delay *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use doublezero_solana_sdk::{
+        passport::ID as PASSPORT_PROGRAM_ID,
+        revenue_distribution::ID as REVENUE_DISTRIBUTION_PROGRAM_ID,
+    };
+
+    use super::*;
+    use crate::verify::try_verify_accounts;
+
+    #[tokio::test]
+    async fn test_refresh_rewrites_only_changed_accounts() {
+        let accounts_dir = tempfile::TempDir::new().unwrap();
+        let accounts_dir_str = accounts_dir.path().to_str().unwrap();
+
+        let unchanged_key = Pubkey::new_unique();
+        let unchanged_account = Account {
+            lamports: 1_000,
+            data: vec![1; 8],
+            owner: PASSPORT_PROGRAM_ID,
+            executable: false,
+            rent_epoch: u64::MAX,
+        };
+        let changed_key = Pubkey::new_unique();
+        let changed_account = Account {
+            lamports: 2_000,
+            data: vec![2; 8],
+            owner: REVENUE_DISTRIBUTION_PROGRAM_ID,
+            executable: false,
+            rent_epoch: u64::MAX,
+        };
+        try_write_account_to_file(&unchanged_key, &unchanged_account, accounts_dir_str).unwrap();
+        try_write_account_to_file(&changed_key, &changed_account, accounts_dir_str).unwrap();
+
+        // Program dumps are not account files and are never refreshed.
+        let dump_path = ForkedProgram::Passport.dump_path(accounts_dir_str);
+        fs::write(&dump_path, b"program").unwrap();
+
+        let unchanged_path = accounts_dir.path().join(format!("{unchanged_key}.json"));
+        let unchanged_json = fs::read_to_string(&unchanged_path).unwrap();
+        let unchanged_modified = fs::metadata(&unchanged_path).unwrap().modified().unwrap();
+
+        let refreshed_account = Account {
+            lamports: 3_000,
+            data: vec![3; 8],
+            ..changed_account
+        };
+        let fetch_page = |keys: Vec<Pubkey>| {
+            let accounts = keys
+                .iter()
+                .map(|key| {
+                    if *key == changed_key {
+                        Some(refreshed_account.clone())
+                    } else {
+                        Some(unchanged_account.clone())
+                    }
+                })
+                .collect::<Vec<_>>();
+            async move { Ok::<_, anyhow::Error>(accounts) }
+        };
+
+        assert_eq!(
+            try_refresh_accounts(accounts_dir_str, &fetch_page)
+                .await
+                .unwrap(),
+            RefreshSummary {
+                updated: 1,
+                unchanged: 1,
+            }
+        );
+
+        let written = serde_json::from_str::<WrittenAccount>(
+            &fs::read_to_string(accounts_dir.path().join(format!("{changed_key}.json"))).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            written,
+            WrittenAccount::new(&changed_key, &refreshed_account)
+        );
+
+        // The unchanged account file was not rewritten.
+        assert_eq!(fs::read_to_string(&unchanged_path).unwrap(), unchanged_json);
+        assert_eq!(
+            fs::metadata(&unchanged_path).unwrap().modified().unwrap(),
+            unchanged_modified
+        );
+        assert_eq!(fs::read(&dump_path).unwrap(), b"program");
+
+        // Refreshing again finds nothing to update.
+        assert_eq!(
+            try_refresh_accounts(accounts_dir_str, &fetch_page)
+                .await
+                .unwrap(),
+            RefreshSummary {
+                updated: 0,
+                unchanged: 2,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_program_account_pages_are_all_written() {
+        let accounts_dir = tempfile::TempDir::new().unwrap();
+        let accounts_dir_str = accounts_dir.path().to_str().unwrap();
+
+        let keys = (0..PROGRAM_ACCOUNTS_PAGE_SIZE * 3 + 7)
+            .map(|_| Pubkey::new_unique())
+            .collect::<Vec<_>>();
+        let closed_key = keys[PROGRAM_ACCOUNTS_PAGE_SIZE + 1];
+        let pages_len = keys.chunks(PROGRAM_ACCOUNTS_PAGE_SIZE).count();
+
+        let fetched_pages = std::sync::Mutex::new(Vec::new());
+        let accounts_len = try_write_account_pages(&keys, accounts_dir_str, |page_keys| {
+            let mut fetched_pages = fetched_pages.lock().unwrap();
+            let page_index = fetched_pages.len();
+            fetched_pages.push(page_keys.len());
+
+            async move {
+                // Later pages finish first.
+                for _ in page_index..pages_len {
+                    tokio::task::yield_now().await;
+                }
+
+                Ok::<_, anyhow::Error>(
+                    page_keys
+                        .iter()
+                        .map(|key| {
+                            (*key != closed_key).then(|| Account {
+                                lamports: 1_000,
+                                data: key.to_bytes().to_vec(),
+                                owner: REVENUE_DISTRIBUTION_PROGRAM_ID,
+                                executable: false,
+                                rent_epoch: u64::MAX,
+                            })
+                        })
+                        .collect(),
+                )
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            fetched_pages.into_inner().unwrap(),
+            [
+                PROGRAM_ACCOUNTS_PAGE_SIZE,
+                PROGRAM_ACCOUNTS_PAGE_SIZE,
+                PROGRAM_ACCOUNTS_PAGE_SIZE,
+                7
+            ]
+        );
+        assert_eq!(accounts_len, keys.len() - 1);
+        assert_eq!(
+            try_verify_accounts(accounts_dir_str).unwrap(),
+            keys.len() - 1
+        );
+
+        for key in keys.iter().filter(|key| **key != closed_key) {
+            let written = serde_json::from_str::<WrittenAccount>(
+                &fs::read_to_string(accounts_dir.path().join(format!("{key}.json"))).unwrap(),
+            )
+            .unwrap();
+            assert_eq!(
+                BASE64.decode(&written.account.data.0).unwrap(),
+                key.to_bytes()
+            );
+        }
+        assert!(
+            !accounts_dir
+                .path()
+                .join(format!("{closed_key}.json"))
+                .exists()
+        );
+    }
+
+    /// Shell command that fails with `stderr` until it has run `succeed_on`
+    /// times, counting runs in `counter_path`.
+    fn fake_dump_command(counter_path: &Path, succeed_on: u32, stderr: &str) -> Command {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(format!(
+            "n=$(($(cat {counter} 2>/dev/null || echo 0) + 1)); echo $n > {counter}; \
+             [ $n -ge {succeed_on} ] || {{ echo '{stderr}' >&2; exit 1; }}",
+            counter = counter_path.display(),
+        ));
+        command
+    }
+
+    fn fake_dump_runs(counter_path: &Path) -> u32 {
+        fs::read_to_string(counter_path)
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_dump_program_retries_transient_failures() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+
+        // Succeeds on the third attempt.
+        let counter_path = temp_dir.path().join("transient");
+        let attempts = try_run_dump_command(
+            || fake_dump_command(&counter_path, 3, "Error: connection refused"),
+            4,
+            Duration::ZERO,
+        )
+        .unwrap();
+        assert_eq!(attempts, 3);
+        assert_eq!(fake_dump_runs(&counter_path), 3);
+
+        // Gives up once attempts are exhausted.
+        let counter_path = temp_dir.path().join("exhausted");
+        let err = try_run_dump_command(
+            || fake_dump_command(&counter_path, 10, "Error: connection refused"),
+            4,
+            Duration::ZERO,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("after 4 attempts"));
+        assert_eq!(fake_dump_runs(&counter_path), 4);
+
+        // A missing program is not retried.
+        let counter_path = temp_dir.path().join("not-found");
+        let err = try_run_dump_command(
+            || fake_dump_command(&counter_path, 10, "Error: Unable to find the account"),
+            4,
+            Duration::ZERO,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("Unable to find the account"));
+        assert_eq!(fake_dump_runs(&counter_path), 1);
+    }
+}
@@ -0,0 +1,471 @@
+//! God mode overrides applied to forked config accounts.
+
+use std::fs;
+
+use anyhow::Result;
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use doublezero_solana_sdk::{
+    passport::state::ProgramConfig as PassportProgramConfig,
+    revenue_distribution::{
+        state::ProgramConfig as RevenueDistributionProgramConfig, types::DoubleZeroEpoch,
+    },
+    sol_conversion::state::{
+        ConfigurationRegistry as SolConversionConfigurationRegistry,
+        ProgramState as SolConversionProgramState,
+    },
+};
+use solana_sdk::{program_pack::Pack, pubkey::Pubkey};
+use spl_token_interface::state::Mint;
+
+use crate::output::{
+    WrittenAccount, try_modify_borsh_account, try_modify_zero_copy_account, try_read_borsh_account,
+    try_read_zero_copy_account,
+};
+
+/// An authority god mode would overwrite.
+#[derive(Debug, PartialEq, Eq)]
+pub struct AuthorityChange {
+    account: &'static str,
+    field: &'static str,
+    before: String,
+    after: String,
+}
+
+impl AuthorityChange {
+    fn new(account: &'static str, field: &'static str, before: String, after: &Pubkey) -> Self {
+        Self {
+            account,
+            field,
+            before,
+            after: after.to_string(),
+        }
+    }
+}
+
+/// Read the current authorities from each config account and the 2Z mint,
+/// pairing them with the upgrade authority god mode would write.
+pub fn try_god_mode_authority_changes(
+    accounts_dir: &str,
+    token_2z_mint_key: &Pubkey,
+    upgrade_authority_key: Pubkey,
+    sol_conversion_god_mode: SolConversionGodMode,
+) -> Result<Vec<AuthorityChange>> {
+    let (_, revenue_distribution_config, _) =
+        try_read_zero_copy_account::<RevenueDistributionProgramConfig>(
+            &RevenueDistributionProgramConfig::find_address().0,
+            accounts_dir,
+        )?;
+    let (_, passport_config, _) = try_read_zero_copy_account::<PassportProgramConfig>(
+        &PassportProgramConfig::find_address().0,
+        accounts_dir,
+    )?;
+    let (_, sol_conversion_state) = try_read_borsh_account::<SolConversionProgramState>(
+        &SolConversionProgramState::find_address().0,
+        accounts_dir,
+    )?;
+
+    let mint_json = fs::read_to_string(format!("{accounts_dir}/{token_2z_mint_key}.json"))?;
+    let mint_wrapper = serde_json::from_str::<WrittenAccount>(&mint_json)?;
+    let mint = Mint::unpack(&BASE64.decode(&mint_wrapper.account.data.0)?)?;
+
+    let mut changes = config_authority_changes(
+        &revenue_distribution_config,
+        &passport_config,
+        upgrade_authority_key,
+    );
+    changes.extend([
+        AuthorityChange::new(
+            "SOL Conversion",
+            "admin",
+            sol_conversion_state.admin_key.to_string(),
+            &upgrade_authority_key,
+        ),
+        AuthorityChange::new(
+            "SOL Conversion",
+            "deny list authority",
+            sol_conversion_state.deny_list_authority.to_string(),
+            &upgrade_authority_key,
+        ),
+    ]);
+
+    if sol_conversion_god_mode.should_set_fills_consumer {
+        let (_, sol_conversion_registry) =
+            try_read_borsh_account::<SolConversionConfigurationRegistry>(
+                &SolConversionConfigurationRegistry::find_address().0,
+                accounts_dir,
+            )?;
+        changes.push(AuthorityChange::new(
+            "SOL Conversion",
+            "fills consumer",
+            sol_conversion_registry.fill_consumer_key.to_string(),
+            &upgrade_authority_key,
+        ));
+    }
+
+    changes.push(AuthorityChange::new(
+        "2Z mint",
+        "mint authority",
+        Option::<Pubkey>::from(mint.mint_authority)
+            .map_or_else(|| "none".to_string(), |key| key.to_string()),
+        &upgrade_authority_key,
+    ));
+
+    Ok(changes)
+}
+
+fn config_authority_changes(
+    revenue_distribution_config: &RevenueDistributionProgramConfig,
+    passport_config: &PassportProgramConfig,
+    upgrade_authority_key: Pubkey,
+) -> Vec<AuthorityChange> {
+    [
+        (
+            "Revenue Distribution",
+            "admin",
+            revenue_distribution_config.admin_key,
+        ),
+        (
+            "Revenue Distribution",
+            "debt accountant",
+            revenue_distribution_config.debt_accountant_key,
+        ),
+        (
+            "Revenue Distribution",
+            "rewards accountant",
+            revenue_distribution_config.rewards_accountant_key,
+        ),
+        (
+            "Revenue Distribution",
+            "contributor manager",
+            revenue_distribution_config.contributor_manager_key,
+        ),
+        ("Passport", "admin", passport_config.admin_key),
+        ("Passport", "sentinel", passport_config.sentinel_key),
+    ]
+    .into_iter()
+    .map(|(account, field, before)| {
+        AuthorityChange::new(account, field, before.to_string(), &upgrade_authority_key)
+    })
+    .collect()
+}
+
+pub fn format_authority_changes(changes: &[AuthorityChange]) -> String {
+    let mut out = String::new();
+    let mut current_account = None;
+
+    for change in changes {
+        if current_account != Some(change.account) {
+            out.push_str(&format!("{}:\n", change.account));
+            current_account = Some(change.account);
+        }
+
+        if change.before == change.after {
+            out.push_str(&format!(
+                "  {}: {} (unchanged)\n",
+                change.field, change.before
+            ));
+        } else {
+            out.push_str(&format!(
+                "  {}: {} -> {}\n",
+                change.field, change.before, change.after
+            ));
+        }
+    }
+
+    out
+}
+
+/// Optional SOL Conversion overrides applied by god mode on top of handing
+/// its authorities to the upgrade authority.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SolConversionGodMode {
+    pub should_unpause: bool,
+    pub should_set_fills_consumer: bool,
+}
+
+/// Hand SOL Conversion authorities to the upgrade authority, then unpause the
+/// system and take over the fills consumer if requested.
+pub fn try_god_mode_sol_conversion(
+    accounts_dir: &str,
+    upgrade_authority_key: Pubkey,
+    sol_conversion_god_mode: SolConversionGodMode,
+) -> Result<()> {
+    try_modify_borsh_account::<SolConversionProgramState>(
+        &SolConversionProgramState::find_address().0,
+        accounts_dir,
+        |config| {
+            config.admin_key = upgrade_authority_key;
+            config.last_trade_slot = 0;
+            config.deny_list_authority = upgrade_authority_key;
+
+            if sol_conversion_god_mode.should_unpause {
+                config.is_paused = false;
+                tracing::info!("Unpaused SOL Conversion system");
+            }
+        },
+    )?;
+
+    if sol_conversion_god_mode.should_set_fills_consumer {
+        try_modify_borsh_account::<SolConversionConfigurationRegistry>(
+            &SolConversionConfigurationRegistry::find_address().0,
+            accounts_dir,
+            |registry| {
+                registry.fill_consumer_key = upgrade_authority_key;
+            },
+        )?;
+        tracing::info!("Updated SOL Conversion fills consumer");
+    }
+
+    Ok(())
+}
+
+/// Hand Revenue Distribution config authorities to the upgrade authority and
+/// shorten grace periods. Returns the forked next completed DZ epoch.
+pub fn try_god_mode_revenue_distribution_config(
+    accounts_dir: &str,
+    upgrade_authority_key: Pubkey,
+    grace_period_minutes: u16,
+    next_completed_dz_epoch_override: Option<u64>,
+) -> Result<u64> {
+    try_modify_zero_copy_account::<RevenueDistributionProgramConfig, _>(
+        &RevenueDistributionProgramConfig::find_address().0,
+        accounts_dir,
+        |config| {
+            let forked_next_completed_dz_epoch = config.next_completed_dz_epoch.value();
+
+            config.admin_key = upgrade_authority_key;
+            config.debt_accountant_key = upgrade_authority_key;
+            config.rewards_accountant_key = upgrade_authority_key;
+            config.contributor_manager_key = upgrade_authority_key;
+            config.last_initialized_distribution_timestamp = Default::default();
+
+            let distribution_params = &mut config.distribution_parameters;
+            distribution_params.calculation_grace_period_minutes = grace_period_minutes.into();
+            distribution_params.initialization_grace_period_minutes = grace_period_minutes.into();
+
+            if let Some(dz_epoch) = next_completed_dz_epoch_override {
+                if dz_epoch > forked_next_completed_dz_epoch {
+                    tracing::warn!(
+                        "DZ epoch {dz_epoch} override is greater than forked DZ epoch {forked_next_completed_dz_epoch}. Ignoring --next-completed-dz-epoch-override"
+                    );
+                } else {
+                    tracing::info!("Overriding next completed DZ epoch to {dz_epoch}");
+                    config.next_completed_dz_epoch = DoubleZeroEpoch::new(dz_epoch);
+                }
+            }
+
+            forked_next_completed_dz_epoch
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use doublezero_solana_sdk::{
+        PrecomputedDiscriminator, revenue_distribution::ID as REVENUE_DISTRIBUTION_PROGRAM_ID,
+        sol_conversion::ID as SOL_CONVERSION_PROGRAM_ID,
+    };
+    use solana_sdk::account::Account;
+
+    use super::*;
+    use crate::output::try_write_account_to_file;
+
+    #[test]
+    fn test_god_mode_writes_grace_period_override() {
+        let accounts_dir = tempfile::TempDir::new().unwrap();
+        let accounts_dir_str = accounts_dir.path().to_str().unwrap();
+
+        let config_key = RevenueDistributionProgramConfig::find_address().0;
+        let config: RevenueDistributionProgramConfig = bytemuck::Zeroable::zeroed();
+
+        let mut data = RevenueDistributionProgramConfig::discriminator_slice().to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&config));
+        let account = Account {
+            lamports: 1_000,
+            data,
+            owner: REVENUE_DISTRIBUTION_PROGRAM_ID,
+            executable: false,
+            rent_epoch: u64::MAX,
+        };
+        try_write_account_to_file(&config_key, &account, accounts_dir_str).unwrap();
+
+        let upgrade_authority_key = Pubkey::new_unique();
+        for grace_period_minutes in [0, 15] {
+            try_god_mode_revenue_distribution_config(
+                accounts_dir_str,
+                upgrade_authority_key,
+                grace_period_minutes,
+                None,
+            )
+            .unwrap();
+
+            let (_, config, _) = try_read_zero_copy_account::<RevenueDistributionProgramConfig>(
+                &config_key,
+                accounts_dir_str,
+            )
+            .unwrap();
+            let distribution_params = &config.distribution_parameters;
+            assert_eq!(
+                u64::from(distribution_params.calculation_grace_period_minutes),
+                u64::from(grace_period_minutes)
+            );
+            assert_eq!(
+                u64::from(distribution_params.initialization_grace_period_minutes),
+                u64::from(grace_period_minutes)
+            );
+            assert_eq!(config.admin_key, upgrade_authority_key);
+        }
+    }
+
+    #[test]
+    fn test_god_mode_sol_conversion_overrides() {
+        let accounts_dir = tempfile::TempDir::new().unwrap();
+        let accounts_dir_str = accounts_dir.path().to_str().unwrap();
+
+        let write_borsh_account = |key: &Pubkey, discriminator: &[u8], data: Vec<u8>| {
+            let account = Account {
+                lamports: 1_000,
+                data: [discriminator, data.as_slice()].concat(),
+                owner: SOL_CONVERSION_PROGRAM_ID,
+                executable: false,
+                rent_epoch: u64::MAX,
+            };
+            try_write_account_to_file(key, &account, accounts_dir_str).unwrap();
+        };
+
+        let state_key = SolConversionProgramState::find_address().0;
+        let state = SolConversionProgramState {
+            admin_key: Pubkey::new_unique(),
+            fills_registry_key: Pubkey::new_unique(),
+            is_paused: true,
+            configuration_registry_bump: 255,
+            program_state_bump: 254,
+            deny_list_registry_bump: 253,
+            withdraw_authority_bump: 252,
+            last_trade_slot: 42,
+            deny_list_authority: Pubkey::new_unique(),
+        };
+        write_borsh_account(
+            &state_key,
+            SolConversionProgramState::discriminator_slice(),
+            borsh::to_vec(&state).unwrap(),
+        );
+
+        let registry_key = SolConversionConfigurationRegistry::find_address().0;
+        let registry = SolConversionConfigurationRegistry {
+            oracle_key: Pubkey::new_unique(),
+            fixed_fill_quantity: 1_000,
+            price_maximum_age_seconds: 30,
+            fill_consumer_key: Pubkey::new_unique(),
+            coefficient: 1,
+            max_discount_rate: 2,
+            min_discount_rate: 3,
+        };
+        write_borsh_account(
+            &registry_key,
+            SolConversionConfigurationRegistry::discriminator_slice(),
+            borsh::to_vec(&registry).unwrap(),
+        );
+
+        let upgrade_authority_key = Pubkey::new_unique();
+
+        // Without the flags, only authorities change.
+        try_god_mode_sol_conversion(
+            accounts_dir_str,
+            upgrade_authority_key,
+            SolConversionGodMode::default(),
+        )
+        .unwrap();
+
+        let (_, modified_state) =
+            try_read_borsh_account::<SolConversionProgramState>(&state_key, accounts_dir_str)
+                .unwrap();
+        assert!(modified_state.is_paused);
+        assert_eq!(modified_state.admin_key, upgrade_authority_key);
+        let (_, modified_registry) = try_read_borsh_account::<SolConversionConfigurationRegistry>(
+            &registry_key,
+            accounts_dir_str,
+        )
+        .unwrap();
+        assert_eq!(*modified_registry, registry);
+
+        try_god_mode_sol_conversion(
+            accounts_dir_str,
+            upgrade_authority_key,
+            SolConversionGodMode {
+                should_unpause: true,
+                should_set_fills_consumer: true,
+            },
+        )
+        .unwrap();
+
+        let (_, modified_state) =
+            try_read_borsh_account::<SolConversionProgramState>(&state_key, accounts_dir_str)
+                .unwrap();
+        assert_eq!(
+            *modified_state,
+            SolConversionProgramState {
+                admin_key: upgrade_authority_key,
+                is_paused: false,
+                last_trade_slot: 0,
+                deny_list_authority: upgrade_authority_key,
+                ..state
+            }
+        );
+
+        let (_, modified_registry) = try_read_borsh_account::<SolConversionConfigurationRegistry>(
+            &registry_key,
+            accounts_dir_str,
+        )
+        .unwrap();
+        assert_eq!(
+            *modified_registry,
+            SolConversionConfigurationRegistry {
+                fill_consumer_key: upgrade_authority_key,
+                ..registry
+            }
+        );
+    }
+
+    #[test]
+    fn test_god_mode_authority_diff() {
+        let upgrade_authority_key = Pubkey::new_unique();
+        let current_admin_key = Pubkey::new_unique();
+
+        let mut revenue_distribution_config: RevenueDistributionProgramConfig =
+            bytemuck::Zeroable::zeroed();
+        revenue_distribution_config.admin_key = current_admin_key;
+        revenue_distribution_config.rewards_accountant_key = upgrade_authority_key;
+
+        let mut passport_config: PassportProgramConfig = bytemuck::Zeroable::zeroed();
+        passport_config.sentinel_key = current_admin_key;
+
+        let changes = config_authority_changes(
+            &revenue_distribution_config,
+            &passport_config,
+            upgrade_authority_key,
+        );
+        assert_eq!(changes.len(), 6);
+        assert!(
+            changes
+                .iter()
+                .all(|change| change.after == upgrade_authority_key.to_string())
+        );
+
+        let zeroed_key = Pubkey::default();
+        let expected = [
+            "Revenue Distribution:".to_string(),
+            format!("  admin: {current_admin_key} -> {upgrade_authority_key}"),
+            format!("  debt accountant: {zeroed_key} -> {upgrade_authority_key}"),
+            format!("  rewards accountant: {upgrade_authority_key} (unchanged)"),
+            format!("  contributor manager: {zeroed_key} -> {upgrade_authority_key}"),
+            "Passport:".to_string(),
+            format!("  admin: {zeroed_key} -> {upgrade_authority_key}"),
+            format!("  sentinel: {current_admin_key} -> {upgrade_authority_key}"),
+        ];
+        assert_eq!(
+            format_authority_changes(&changes),
+            expected.join("\n") + "\n"
+        );
+    }
+}
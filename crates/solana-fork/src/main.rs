@@ -1,50 +1,269 @@
-use std::{fs, process::Command};
+mod fetch;
+mod god_mode;
+mod output;
+mod verify;
 
-use anyhow::{Context, Result, ensure};
-use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
-use clap::Parser;
+use std::{fmt, fs, path::PathBuf, process::Command};
+
+use anyhow::{Result, bail, ensure};
+use clap::{Parser, ValueEnum};
 use doublezero_solana_client_tools::{
     payer::try_load_keypair,
     rpc::{SolanaConnection, SolanaConnectionOptions},
 };
 use doublezero_solana_sdk::{
-    NetworkEnvironment, PrecomputedDiscriminator, environment_2z_token_mint_key,
-    passport::{ID as PASSPORT_PROGRAM_ID, state::ProgramConfig as PassportProgramConfig},
-    revenue_distribution::{
-        self, ID as REVENUE_DISTRIBUTION_PROGRAM_ID,
-        state::{Distribution, Journal, ProgramConfig as RevenueDistributionProgramConfig},
-        types::DoubleZeroEpoch,
-    },
-    sol_conversion::{
-        ID as SOL_CONVERSION_PROGRAM_ID, state::ProgramState as SolConversionProgramState,
-    },
-    zero_copy,
+    passport::ID as PASSPORT_PROGRAM_ID,
+    revenue_distribution::ID as REVENUE_DISTRIBUTION_PROGRAM_ID,
+    sol_conversion::ID as SOL_CONVERSION_PROGRAM_ID,
+};
+use solana_sdk::{
+    account::Account, bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable, loader_v4,
+    pubkey::Pubkey, signer::Signer,
 };
-use serde::{Deserialize, Serialize};
-use solana_account_decoder_client_types::UiAccountEncoding;
-use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
-use solana_sdk::{account::Account, program_pack::Pack, pubkey::Pubkey, signer::Signer};
-use spl_token_interface::state::Mint;
 use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
 
-const ACCOUNTS_PATH: &str = "forked-accounts";
-const TMP_ACCOUNTS_PATH: &str = "forked-accounts.tmp";
-
-#[derive(Deserialize, Serialize)]
-struct WrittenAccountInfo {
-    lamports: u64,
-    data: (String, String),
-    owner: String,
-    executable: bool,
-    #[serde(rename = "rentEpoch")]
-    rent_epoch: u64,
-    space: usize,
+use crate::{
+    fetch::{
+        RefreshSummary, try_dump_and_verify_programs, try_fetch_and_write_accounts,
+        try_fetch_program_loader, try_refresh_accounts,
+    },
+    god_mode::SolConversionGodMode,
+    output::try_write_combined_accounts,
+    verify::try_verify_accounts,
+};
+
+/// Directory forked accounts are written to when --accounts-dir is not
+/// passed.
+const DEFAULT_ACCOUNTS_DIR: &str = "forked-accounts";
+
+/// Grace period god mode applies when --grace-period-minutes is not passed.
+const DEFAULT_GOD_MODE_GRACE_PERIOD_MINUTES: u16 = 1;
+
+/// Upper bound for --grace-period-minutes (one day).
+const MAX_GOD_MODE_GRACE_PERIOD_MINUTES: i64 = 24 * 60;
+
+/// Value of --programs, selecting which programs to fork.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ProgramSelection {
+    All,
+    RevenueDistribution,
+    Passport,
+    SolConversion,
 }
 
-#[derive(Deserialize, Serialize)]
-struct WrittenAccount {
-    pubkey: String,
-    account: WrittenAccountInfo,
+/// A forked DoubleZero program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ForkedProgram {
+    RevenueDistribution,
+    Passport,
+    SolConversion,
+}
+
+impl ForkedProgram {
+    const ALL: [Self; 3] = [
+        Self::RevenueDistribution,
+        Self::Passport,
+        Self::SolConversion,
+    ];
+
+    /// Expand a --programs selection into the programs to fork, in the order
+    /// of [ForkedProgram::ALL].
+    fn selected(selection: &[ProgramSelection]) -> Vec<Self> {
+        let mut programs = selection
+            .iter()
+            .flat_map(|selection| match selection {
+                ProgramSelection::All => Self::ALL.to_vec(),
+                ProgramSelection::RevenueDistribution => vec![Self::RevenueDistribution],
+                ProgramSelection::Passport => vec![Self::Passport],
+                ProgramSelection::SolConversion => vec![Self::SolConversion],
+            })
+            .collect::<Vec<_>>();
+        programs.sort();
+        programs.dedup();
+        programs
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::RevenueDistribution => "Revenue Distribution",
+            Self::Passport => "Passport",
+            Self::SolConversion => "SOL Conversion",
+        }
+    }
+
+    /// Path of the program dump in `accounts_dir`.
+    fn dump_path(self, accounts_dir: &str) -> String {
+        let file_name = match self {
+            Self::RevenueDistribution => "revenue_distribution.so",
+            Self::Passport => "passport.so",
+            Self::SolConversion => "sol_conversion.so",
+        };
+        format!("{accounts_dir}/{file_name}")
+    }
+}
+
+/// Program IDs to fork, defaulting to the IDs from the SDK.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ProgramIds {
+    revenue_distribution: Pubkey,
+    passport: Pubkey,
+    sol_conversion: Pubkey,
+}
+
+impl Default for ProgramIds {
+    fn default() -> Self {
+        Self {
+            revenue_distribution: REVENUE_DISTRIBUTION_PROGRAM_ID,
+            passport: PASSPORT_PROGRAM_ID,
+            sol_conversion: SOL_CONVERSION_PROGRAM_ID,
+        }
+    }
+}
+
+impl ProgramIds {
+    fn with_overrides(
+        revenue_distribution: Option<Pubkey>,
+        passport: Option<Pubkey>,
+        sol_conversion: Option<Pubkey>,
+    ) -> Self {
+        let default = Self::default();
+
+        Self {
+            revenue_distribution: revenue_distribution.unwrap_or(default.revenue_distribution),
+            passport: passport.unwrap_or(default.passport),
+            sol_conversion: sol_conversion.unwrap_or(default.sol_conversion),
+        }
+    }
+
+    /// Config and token PDAs are derived from the SDK program IDs, so they
+    /// only line up with the forked programs when no ID is overridden.
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn get(&self, program: ForkedProgram) -> &Pubkey {
+        match program {
+            ForkedProgram::RevenueDistribution => &self.revenue_distribution,
+            ForkedProgram::Passport => &self.passport,
+            ForkedProgram::SolConversion => &self.sol_conversion,
+        }
+    }
+}
+
+/// Loader owning a forked program, which decides how the validator loads it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProgramLoader {
+    Upgradeable,
+    NonUpgradeable,
+    Deprecated,
+}
+
+impl fmt::Display for ProgramLoader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Upgradeable => write!(f, "BPF Loader Upgradeable"),
+            Self::NonUpgradeable => write!(f, "BPF Loader (non-upgradeable)"),
+            Self::Deprecated => write!(f, "BPF Loader Deprecated (non-upgradeable)"),
+        }
+    }
+}
+
+impl ProgramLoader {
+    fn try_from_program_account(program_id: &Pubkey, account: &Account) -> Result<Self> {
+        ensure!(
+            account.executable,
+            "Account {program_id} is not an executable program"
+        );
+
+        if account.owner == bpf_loader_upgradeable::id() {
+            Ok(Self::Upgradeable)
+        } else if account.owner == bpf_loader::id() {
+            Ok(Self::NonUpgradeable)
+        } else if account.owner == bpf_loader_deprecated::id() {
+            Ok(Self::Deprecated)
+        } else if account.owner == loader_v4::id() {
+            bail!("Program {program_id} is owned by loader v4, which is not supported")
+        } else {
+            bail!(
+                "Program {program_id} is owned by {}, which is not a known loader",
+                account.owner
+            )
+        }
+    }
+
+    fn validator_args(
+        self,
+        program_id: &Pubkey,
+        program_path: String,
+        upgrade_authority_key: &Pubkey,
+    ) -> Vec<String> {
+        match self {
+            Self::Upgradeable => vec![
+                "--upgradeable-program".to_string(),
+                program_id.to_string(),
+                program_path,
+                upgrade_authority_key.to_string(),
+            ],
+            Self::NonUpgradeable | Self::Deprecated => {
+                vec![
+                    "--bpf-program".to_string(),
+                    program_id.to_string(),
+                    program_path,
+                ]
+            }
+        }
+    }
+}
+
+/// Loaders of the forked programs, in the same layout as [ProgramIds].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ProgramLoaders {
+    revenue_distribution: ProgramLoader,
+    passport: ProgramLoader,
+    sol_conversion: ProgramLoader,
+}
+
+impl Default for ProgramLoaders {
+    fn default() -> Self {
+        Self {
+            revenue_distribution: ProgramLoader::Upgradeable,
+            passport: ProgramLoader::Upgradeable,
+            sol_conversion: ProgramLoader::Upgradeable,
+        }
+    }
+}
+
+impl ProgramLoaders {
+    /// Fetch the loaders of the selected programs. Programs that are not
+    /// forked keep the default loader, which is never used.
+    async fn try_fetch(
+        connection: &SolanaConnection,
+        program_ids: &ProgramIds,
+        programs: &[ForkedProgram],
+    ) -> Result<Self> {
+        let mut loaders = Self::default();
+
+        for &program in programs {
+            let loader =
+                try_fetch_program_loader(connection, program_ids.get(program), program.name())
+                    .await?;
+            match program {
+                ForkedProgram::RevenueDistribution => loaders.revenue_distribution = loader,
+                ForkedProgram::Passport => loaders.passport = loader,
+                ForkedProgram::SolConversion => loaders.sol_conversion = loader,
+            }
+        }
+
+        Ok(loaders)
+    }
+
+    fn get(&self, program: ForkedProgram) -> ProgramLoader {
+        match program {
+            ForkedProgram::RevenueDistribution => self.revenue_distribution,
+            ForkedProgram::Passport => self.passport,
+            ForkedProgram::SolConversion => self.sol_conversion,
+        }
+    }
 }
 
 #[derive(Parser, Debug)]
@@ -61,11 +280,29 @@ struct Args {
     #[arg(long)]
     reset: bool,
 
+    /// Re-fetch every account in the accounts directory and rewrite only the
+    /// ones whose on-chain state differs from the local copy. Program dumps
+    /// are left untouched unless --refresh-programs is also passed. Accounts
+    /// modified by god mode are restored to their on-chain state.
+    #[arg(long, conflicts_with = "reset")]
+    refresh: bool,
+
+    /// Dump the selected programs again when refreshing. This option can
+    /// only be used in combination with --refresh.
+    #[arg(long, requires = "refresh")]
+    refresh_programs: bool,
+
     /// Hidden god-mode command, which will overwrite admin and other
     /// authorities with the upgrade authority.
     #[arg(long, hide = true)]
     god_mode: bool,
 
+    /// Hidden like --god-mode. Print the authorities god mode would overwrite
+    /// without writing them, then exit. This option can only be used in
+    /// combination with --god-mode and --reset.
+    #[arg(long, hide = true)]
+    dry_run: bool,
+
     /// Override the next completed DZ epoch to the specified epoch. This option
     /// can only be used in combination with --god-mode and can only be less
     /// than the forked next completed DZ epoch found in the Revenue
@@ -73,6 +310,74 @@ struct Args {
     #[arg(long, value_name = "EPOCH")]
     next_completed_dz_epoch_override: Option<u64>,
 
+    /// Calculation and initialization grace period in minutes applied by god
+    /// mode (0 disables waiting, max 1440). This option can only be used in
+    /// combination with --god-mode.
+    #[arg(
+        long,
+        value_name = "MINUTES",
+        value_parser = clap::value_parser!(u16).range(0..=MAX_GOD_MODE_GRACE_PERIOD_MINUTES)
+    )]
+    grace_period_minutes: Option<u16>,
+
+    /// Unpause the SOL Conversion system. This option can only be used in
+    /// combination with --god-mode.
+    #[arg(long)]
+    unpause: bool,
+
+    /// Set the SOL Conversion fills consumer to the upgrade authority. This
+    /// option can only be used in combination with --god-mode.
+    #[arg(long)]
+    set_fills_consumer: bool,
+
+    /// Also write every forked account to a single JSON array at this path,
+    /// for loading into custom test harnesses. Per-account files are still
+    /// written for solana-test-validator.
+    #[arg(long, value_name = "PATH")]
+    combined_output: Option<PathBuf>,
+
+    /// Re-read every account file before launching the validator and fail if
+    /// any cannot be parsed or its data does not match its declared size.
+    #[arg(long)]
+    verify_accounts: bool,
+
+    /// Revenue Distribution program ID to fork (defaults to the SDK program
+    /// ID).
+    #[arg(long, value_name = "PUBKEY")]
+    revenue_distribution_id: Option<Pubkey>,
+
+    /// Passport program ID to fork (defaults to the SDK program ID).
+    #[arg(long, value_name = "PUBKEY")]
+    passport_id: Option<Pubkey>,
+
+    /// SOL Conversion program ID to fork (defaults to the SDK program ID).
+    #[arg(long, value_name = "PUBKEY")]
+    sol_conversion_id: Option<Pubkey>,
+
+    /// Comma-separated programs to fork. Only their accounts and program
+    /// dumps are fetched and loaded into the validator. God mode requires
+    /// all programs.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "NAME",
+        value_delimiter = ',',
+        default_value = "all"
+    )]
+    programs: Vec<ProgramSelection>,
+
+    /// Additional account to fetch and write, such as a payer or accountant
+    /// wallet. Can be passed multiple times. This option is only used in
+    /// combination with --reset.
+    #[arg(long = "extra-account", value_name = "PUBKEY")]
+    extra_account_keys: Vec<Pubkey>,
+
+    /// Directory to write forked accounts and program dumps to. Accounts are
+    /// fetched into a sibling `.tmp` directory first. Use a separate
+    /// directory per fork when running several forks in parallel.
+    #[arg(long, value_name = "PATH", default_value = DEFAULT_ACCOUNTS_DIR)]
+    accounts_dir: String,
+
     #[command(flatten)]
     solana_connection_options: SolanaConnectionOptions,
 }
@@ -92,8 +397,22 @@ async fn main() -> Result<()> {
     let Args {
         upgrade_authority: upgrade_authority_key,
         reset: should_reset,
+        refresh: should_refresh,
+        refresh_programs: should_refresh_programs,
         god_mode: should_god_mode,
+        dry_run: should_dry_run,
         next_completed_dz_epoch_override,
+        grace_period_minutes,
+        unpause: should_unpause,
+        set_fills_consumer: should_set_fills_consumer,
+        combined_output,
+        verify_accounts: should_verify_accounts,
+        revenue_distribution_id,
+        passport_id,
+        sol_conversion_id,
+        programs,
+        extra_account_keys,
+        accounts_dir,
         solana_connection_options,
     } = Args::parse();
 
@@ -101,10 +420,53 @@ async fn main() -> Result<()> {
         next_completed_dz_epoch_override.is_none() || should_god_mode,
         "--next-completed-dz-epoch-override can only be used in combination with --god-mode"
     );
+    ensure!(
+        !should_dry_run || (should_god_mode && should_reset),
+        "--dry-run can only be used in combination with --god-mode and --reset"
+    );
+    ensure!(
+        extra_account_keys.is_empty() || should_reset,
+        "--extra-account can only be used in combination with --reset"
+    );
+    ensure!(
+        grace_period_minutes.is_none() || should_god_mode,
+        "--grace-period-minutes can only be used in combination with --god-mode"
+    );
+    ensure!(
+        !should_unpause || should_god_mode,
+        "--unpause can only be used in combination with --god-mode"
+    );
+    ensure!(
+        !should_set_fills_consumer || should_god_mode,
+        "--set-fills-consumer can only be used in combination with --god-mode"
+    );
+
+    let grace_period_minutes =
+        grace_period_minutes.unwrap_or(DEFAULT_GOD_MODE_GRACE_PERIOD_MINUTES);
+    if should_god_mode && grace_period_minutes == 0 {
+        tracing::warn!(
+            "Grace periods are disabled. Distributions can be calculated and initialized without any wait"
+        );
+    }
+
+    let program_ids =
+        ProgramIds::with_overrides(revenue_distribution_id, passport_id, sol_conversion_id);
+    ensure!(
+        !should_god_mode || program_ids.is_default(),
+        "--god-mode cannot be used with overridden program IDs"
+    );
+
+    let programs = ForkedProgram::selected(&programs);
+    ensure!(
+        !should_god_mode || programs == ForkedProgram::ALL,
+        "--god-mode cannot be used with --programs selecting a subset of programs"
+    );
 
     let connection = SolanaConnection::from(solana_connection_options);
     let network_env = connection.try_network_environment().await?;
 
+    let program_loaders = ProgramLoaders::try_fetch(&connection, &program_ids, &programs).await?;
+
     // Get upgrade authority from argument or default keypair.
     let upgrade_authority_key = match upgrade_authority_key {
         Some(key) => key,
@@ -121,42 +483,102 @@ async fn main() -> Result<()> {
         );
     }
 
+    let accounts_dir = accounts_dir.trim_end_matches('/');
+    ensure!(!accounts_dir.is_empty(), "--accounts-dir cannot be empty");
+    let tmp_accounts_dir = tmp_accounts_dir(accounts_dir);
+
     if should_reset {
         // Clean up any leftover temporary directory from previous failed runs.
-        if fs::metadata(TMP_ACCOUNTS_PATH).is_ok() {
-            fs::remove_dir_all(TMP_ACCOUNTS_PATH)?;
+        if fs::metadata(&tmp_accounts_dir).is_ok() {
+            fs::remove_dir_all(&tmp_accounts_dir)?;
         }
 
-        // Remove existing accounts directory if it exists.
-        if fs::metadata(ACCOUNTS_PATH).is_ok() {
-            fs::remove_dir_all(ACCOUNTS_PATH)?;
+        // Remove existing accounts directory if it exists. A dry run leaves
+        // it untouched.
+        if !should_dry_run && fs::metadata(accounts_dir).is_ok() {
+            fs::remove_dir_all(accounts_dir)?;
         }
 
-        fs::create_dir_all(TMP_ACCOUNTS_PATH)?;
+        fs::create_dir_all(&tmp_accounts_dir)?;
 
         match try_fetch_and_write_accounts(
             &connection,
+            &tmp_accounts_dir,
             network_env,
+            program_ids,
+            &programs,
+            &extra_account_keys,
             upgrade_authority_key,
             should_god_mode,
+            should_dry_run,
+            grace_period_minutes,
             next_completed_dz_epoch_override,
+            SolConversionGodMode {
+                should_unpause,
+                should_set_fills_consumer,
+            },
         )
         .await
         {
+            Ok(_) if should_dry_run => {
+                fs::remove_dir_all(&tmp_accounts_dir)?;
+                return Ok(());
+            }
             Ok(_) => {
                 // Rename temporary directory to final location.
-                fs::rename(TMP_ACCOUNTS_PATH, ACCOUNTS_PATH)?;
+                fs::rename(&tmp_accounts_dir, accounts_dir)?;
             }
             Err(e) => {
-                fs::remove_dir_all(TMP_ACCOUNTS_PATH)?;
+                fs::remove_dir_all(&tmp_accounts_dir)?;
                 return Err(e);
             }
         }
     } else {
-        // Ensure ACCOUNTS_PATH exists when not resetting.
+        // Ensure the accounts directory exists when not resetting.
         ensure!(
-            fs::metadata(ACCOUNTS_PATH).is_ok(),
-            "Directory {ACCOUNTS_PATH} does not exist. Run with --reset to fetch accounts from the network"
+            fs::metadata(accounts_dir).is_ok(),
+            "Directory {accounts_dir} does not exist. Run with --reset to fetch accounts from the network"
+        );
+    }
+
+    if should_refresh {
+        let connection = &connection;
+        let RefreshSummary { updated, unchanged } =
+            try_refresh_accounts(accounts_dir, |keys| async move {
+                connection
+                    .get_multiple_accounts(&keys)
+                    .await
+                    .map_err(anyhow::Error::from)
+            })
+            .await?;
+        tracing::info!(
+            "Refreshed {accounts_dir}/: {updated} account{} updated, {unchanged} unchanged",
+            if updated == 1 { "" } else { "s" },
+        );
+
+        if should_refresh_programs {
+            try_dump_and_verify_programs(&connection, &program_ids, &programs, accounts_dir)
+                .await?;
+        }
+    }
+
+    if should_verify_accounts {
+        let accounts_len = try_verify_accounts(accounts_dir)?;
+        tracing::info!(
+            "Verified {} account{} in {}",
+            accounts_len,
+            if accounts_len == 1 { "" } else { "s" },
+            accounts_dir
+        );
+    }
+
+    if let Some(combined_output) = combined_output {
+        let accounts_len = try_write_combined_accounts(accounts_dir, &combined_output)?;
+        tracing::info!(
+            "Wrote {} account{} to {}",
+            accounts_len,
+            if accounts_len == 1 { "" } else { "s" },
+            combined_output.display()
         );
     }
 
@@ -170,30 +592,18 @@ async fn main() -> Result<()> {
         "solana-test-validator not found. Please install Solana CLI tools"
     );
 
-    let mut command = Command::new("solana-test-validator");
-    command
-        .arg("--url")
-        .arg(connection.url())
-        .arg("--account-dir")
-        .arg(ACCOUNTS_PATH)
-        .arg("--upgradeable-program")
-        .arg(REVENUE_DISTRIBUTION_PROGRAM_ID.to_string())
-        .arg(format!("{ACCOUNTS_PATH}/revenue_distribution.so"))
-        .arg(upgrade_authority_key.to_string())
-        .arg("--upgradeable-program")
-        .arg(PASSPORT_PROGRAM_ID.to_string())
-        .arg(format!("{ACCOUNTS_PATH}/passport.so"))
-        .arg(upgrade_authority_key.to_string())
-        .arg("--upgradeable-program")
-        .arg(SOL_CONVERSION_PROGRAM_ID.to_string())
-        .arg(format!("{ACCOUNTS_PATH}/sol_conversion.so"))
-        .arg(upgrade_authority_key.to_string());
-
-    if should_reset {
-        command.arg("--reset");
-    }
-
-    let status = command.status()?;
+    let status = validator_command(
+        &connection.url(),
+        accounts_dir,
+        &program_ids,
+        &program_loaders,
+        &programs,
+        &upgrade_authority_key,
+        // The validator only loads account files when creating its ledger, so
+        // refreshed accounts need a fresh ledger too.
+        should_reset || should_refresh,
+    )
+    .status()?;
 
     ensure!(
         status.success(),
@@ -203,411 +613,310 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-//
-
-async fn try_fetch_and_write_accounts(
-    connection: &SolanaConnection,
-    network_env: NetworkEnvironment,
-    upgrade_authority_key: Pubkey,
-    should_god_mode: bool,
-    next_completed_dz_epoch_override: Option<u64>,
-) -> Result<()> {
-    // Fetch 2Z mint account.
-
-    let token_2z_mint_key = environment_2z_token_mint_key(network_env);
-
-    let mint_account = connection.get_account(&token_2z_mint_key).await?;
-    try_write_account_to_file(&token_2z_mint_key, &mint_account, TMP_ACCOUNTS_PATH)?;
-    tracing::info!("Wrote 2Z SPL mint account to {TMP_ACCOUNTS_PATH}/");
-
-    // Fetch program accounts.
-
-    let config = RpcProgramAccountsConfig {
-        filters: None,
-        account_config: RpcAccountInfoConfig {
-            encoding: Some(UiAccountEncoding::Base64),
-            ..Default::default()
-        },
-        ..Default::default()
-    };
-
-    // Fetch all program accounts.
-
-    try_fetch_and_write_program_accounts(
-        connection,
-        &REVENUE_DISTRIBUTION_PROGRAM_ID,
-        "Revenue Distribution",
-        TMP_ACCOUNTS_PATH,
-        &config,
-    )
-    .await?;
-
-    try_fetch_and_write_program_accounts(
-        connection,
-        &PASSPORT_PROGRAM_ID,
-        "Passport",
-        TMP_ACCOUNTS_PATH,
-        &config,
-    )
-    .await?;
-
-    try_fetch_and_write_program_accounts(
-        connection,
-        &SOL_CONVERSION_PROGRAM_ID,
-        "SOL Conversion",
-        TMP_ACCOUNTS_PATH,
-        &config,
-    )
-    .await?;
-
-    // Dump programs.
-
-    try_dump_program(
-        connection,
-        &REVENUE_DISTRIBUTION_PROGRAM_ID,
-        "Revenue Distribution",
-        &format!("{TMP_ACCOUNTS_PATH}/revenue_distribution.so"),
-    )?;
-
-    try_dump_program(
-        connection,
-        &PASSPORT_PROGRAM_ID,
-        "Passport",
-        &format!("{TMP_ACCOUNTS_PATH}/passport.so"),
-    )?;
-
-    try_dump_program(
-        connection,
-        &SOL_CONVERSION_PROGRAM_ID,
-        "SOL Conversion",
-        &format!("{TMP_ACCOUNTS_PATH}/sol_conversion.so"),
-    )?;
-
-    if should_god_mode {
-        tracing::info!("God mode enabled");
-
-        let forked_next_completed_dz_epoch = try_modify_zero_copy_account::<
-            RevenueDistributionProgramConfig,
-            _,
-        >(
-            &RevenueDistributionProgramConfig::find_address().0,
-            TMP_ACCOUNTS_PATH,
-            |config| {
-                let forked_next_completed_dz_epoch = config.next_completed_dz_epoch.value();
-
-                config.admin_key = upgrade_authority_key;
-                config.debt_accountant_key = upgrade_authority_key;
-                config.rewards_accountant_key = upgrade_authority_key;
-                config.contributor_manager_key = upgrade_authority_key;
-                config.last_initialized_distribution_timestamp = Default::default();
-
-                let distribution_params = &mut config.distribution_parameters;
-                distribution_params.calculation_grace_period_minutes = 1;
-                distribution_params.initialization_grace_period_minutes = 1;
-
-                if let Some(dz_epoch) = next_completed_dz_epoch_override {
-                    if dz_epoch > forked_next_completed_dz_epoch {
-                        tracing::warn!(
-                            "DZ epoch {dz_epoch} override is greater than forked DZ epoch {forked_next_completed_dz_epoch}. Ignoring --next-completed-dz-epoch-override"
-                        );
-                    } else {
-                        tracing::info!("Overriding next completed DZ epoch to {dz_epoch}");
-                        config.next_completed_dz_epoch = DoubleZeroEpoch::new(dz_epoch);
-                    }
-                }
-
-                forked_next_completed_dz_epoch
-            },
-        )?;
-        tracing::info!("Updated Revenue Distribution config authorities");
-
-        if let Some(next_completed_dz_epoch_override) = next_completed_dz_epoch_override {
-            for dz_epoch in next_completed_dz_epoch_override..forked_next_completed_dz_epoch {
-                let (distribution_key, _) =
-                    Distribution::find_address(DoubleZeroEpoch::new(dz_epoch));
-
-                // Remove the file representing this distribution key.
-                let path = format!("{TMP_ACCOUNTS_PATH}/{distribution_key}.json");
-                if fs::metadata(&path).is_ok() {
-                    fs::remove_file(&path)?;
-                    tracing::info!("Removed distribution account for epoch {dz_epoch}");
-                }
-            }
-        }
-
-        try_modify_zero_copy_account::<PassportProgramConfig, _>(
-            &PassportProgramConfig::find_address().0,
-            TMP_ACCOUNTS_PATH,
-            |config| {
-                config.admin_key = upgrade_authority_key;
-                config.sentinel_key = upgrade_authority_key;
-            },
-        )?;
-        tracing::info!("Updated Passport config authorities");
-
-        try_modify_borsh_account::<SolConversionProgramState>(
-            &SolConversionProgramState::find_address().0,
-            TMP_ACCOUNTS_PATH,
-            |config| {
-                config.admin_key = upgrade_authority_key;
-                config.last_trade_slot = 0;
-                config.deny_list_authority = upgrade_authority_key;
-            },
-        )?;
-        tracing::info!("Updated SOL Conversion config authorities");
-
-        // Override mint authority.
-
-        let mint_path = format!("{TMP_ACCOUNTS_PATH}/{token_2z_mint_key}.json");
-        let mint_json = fs::read_to_string(&mint_path)?;
-        let mut mint_wrapper = serde_json::from_str::<WrittenAccount>(&mint_json)?;
-        let mut mint_data = BASE64.decode(&mint_wrapper.account.data.0)?;
-
-        let mut mint = Mint::unpack(&mint_data)?;
-        mint.mint_authority = upgrade_authority_key.into();
-
-        Mint::pack(mint, &mut mint_data)?;
-        mint_wrapper.account.data.0 = BASE64.encode(&mint_data);
-        try_write_wrapped_account_to_file(&token_2z_mint_key, &mint_wrapper, TMP_ACCOUNTS_PATH)?;
-    }
-
-    // Fetch various 2Z Token PDAs.
-
-    let mut token_pda_keys = Vec::new();
-
-    let (revenue_distribution_config_key, _) = RevenueDistributionProgramConfig::find_address();
-    token_pda_keys.push(
-        revenue_distribution::state::find_2z_token_pda_address(&revenue_distribution_config_key).0,
-    );
+fn validator_command(
+    url: &str,
+    accounts_dir: &str,
+    program_ids: &ProgramIds,
+    program_loaders: &ProgramLoaders,
+    programs: &[ForkedProgram],
+    upgrade_authority_key: &Pubkey,
+    should_reset: bool,
+) -> Command {
+    let mut command = Command::new("solana-test-validator");
+    command
+        .arg("--url")
+        .arg(url)
+        .arg("--account-dir")
+        .arg(accounts_dir);
 
-    let (swap_authority_key, _) = revenue_distribution::state::find_swap_authority_address();
-    token_pda_keys
-        .push(revenue_distribution::state::find_2z_token_pda_address(&swap_authority_key).0);
-
-    let (journal_key, _) = Journal::find_address();
-    token_pda_keys.push(revenue_distribution::state::find_2z_token_pda_address(&journal_key).0);
-
-    // For existing distributions, fetch the 2Z token PDAs. Read the
-    // Revenue Distribution config account file to deserialize the data
-    // and read the next completed DZ epoch.
-    let (_, revenue_distribution_config, _) =
-        try_read_zero_copy_account::<RevenueDistributionProgramConfig>(
-            &revenue_distribution_config_key,
-            TMP_ACCOUNTS_PATH,
-        )?;
-
-    let forked_next_completed_dz_epoch =
-        revenue_distribution_config.next_completed_dz_epoch.value();
-    let next_completed_dz_epoch = next_completed_dz_epoch_override
-        .unwrap_or(forked_next_completed_dz_epoch)
-        .min(forked_next_completed_dz_epoch);
-    for epoch in 0..next_completed_dz_epoch {
-        let (distribution_key, _) = Distribution::find_address(DoubleZeroEpoch::new(epoch));
-        token_pda_keys
-            .push(revenue_distribution::state::find_2z_token_pda_address(&distribution_key).0);
+    for &program in programs {
+        command.args(program_loaders.get(program).validator_args(
+            program_ids.get(program),
+            program.dump_path(accounts_dir),
+            upgrade_authority_key,
+        ));
     }
 
-    // Fetch all 2Z token PDA accounts, chunking 100 accounts at a time.
-    for token_pda_keys_chunk in token_pda_keys.chunks(100) {
-        let token_accounts = connection
-            .get_multiple_accounts(token_pda_keys_chunk)
-            .await?;
-        for (key, token_account) in token_pda_keys_chunk.iter().zip(token_accounts) {
-            let account = token_account
-                .as_ref()
-                .with_context(|| format!("Account does not exist: {}", key))?;
-            try_write_account_to_file(key, account, TMP_ACCOUNTS_PATH)?;
-        }
+    if should_reset {
+        command.arg("--reset");
     }
 
-    let token_pda_keys_len = token_pda_keys.len();
-    tracing::info!(
-        "Wrote {} 2Z token PDA account{} to {TMP_ACCOUNTS_PATH}/",
-        token_pda_keys_len,
-        if token_pda_keys_len == 1 { "" } else { "s" }
-    );
-
-    Ok(())
-}
-
-fn try_read_zero_copy_account<T>(
-    account_key: &Pubkey,
-    accounts_dir: &str,
-) -> Result<(WrittenAccount, Box<T>, Vec<u8>)>
-where
-    T: PrecomputedDiscriminator + bytemuck::Pod,
-{
-    let path = format!("{accounts_dir}/{account_key}.json");
-    let json = fs::read_to_string(&path)?;
-    let wrapper = serde_json::from_str::<WrittenAccount>(&json)?;
-    let data = BASE64.decode(&wrapper.account.data.0)?;
-
-    let (mucked_data, remaining_data) = zero_copy::checked_from_bytes_with_discriminator(&data)
-        .map(|data| (Box::new(*data.0), data.1))
-        .unwrap();
-
-    Ok((wrapper, mucked_data, remaining_data.to_vec()))
-}
-
-fn try_modify_zero_copy_account<T, U>(
-    account_key: &Pubkey,
-    accounts_dir: &str,
-    modify_fn: impl FnOnce(&mut T) -> U,
-) -> Result<U>
-where
-    T: PrecomputedDiscriminator + bytemuck::Pod,
-{
-    let (wrapper, mut mucked_data, remaining_data) =
-        try_read_zero_copy_account::<T>(account_key, accounts_dir)?;
-
-    let out = modify_fn(&mut mucked_data);
-
-    let mut modified_data = Vec::with_capacity(zero_copy::data_end::<T>() + remaining_data.len());
-    modified_data.extend_from_slice(T::discriminator_slice());
-    modified_data.extend_from_slice(bytemuck::bytes_of(&*mucked_data));
-    modified_data.extend_from_slice(&remaining_data);
-
-    let modified_account = Account {
-        lamports: wrapper.account.lamports,
-        data: modified_data,
-        owner: wrapper.account.owner.parse()?,
-        executable: wrapper.account.executable,
-        rent_epoch: wrapper.account.rent_epoch,
-    };
-
-    try_write_account_to_file(account_key, &modified_account, accounts_dir)?;
-
-    Ok(out)
+    command
 }
 
-fn try_read_borsh_account<T>(
-    account_key: &Pubkey,
-    accounts_dir: &str,
-) -> Result<(WrittenAccount, Box<T>)>
-where
-    T: PrecomputedDiscriminator + borsh::BorshDeserialize,
-{
-    let path = format!("{accounts_dir}/{account_key}.json");
-    let json = fs::read_to_string(&path)?;
-    let wrapper = serde_json::from_str::<WrittenAccount>(&json)?;
-    let data = BASE64.decode(&wrapper.account.data.0)?;
-
-    ensure!(
-        data.len() > 8 && &data[..8] == T::discriminator_slice(),
-        "Invalid discriminator for account: {account_key}",
-    );
-
-    let borshed_data = T::deserialize(&mut &data[8..]).map(Box::new)?;
-
-    Ok((wrapper, borshed_data))
+/// Directory accounts are fetched into before replacing `accounts_dir`.
+fn tmp_accounts_dir(accounts_dir: &str) -> String {
+    format!("{accounts_dir}.tmp")
 }
 
-fn try_modify_borsh_account<T>(
-    account_key: &Pubkey,
-    accounts_dir: &str,
-    modify_fn: impl FnOnce(&mut Box<T>),
-) -> Result<()>
-where
-    T: PrecomputedDiscriminator + borsh::BorshDeserialize + borsh::BorshSerialize,
-{
-    let (wrapper, mut borshed_data) = try_read_borsh_account::<T>(account_key, accounts_dir)?;
-
-    modify_fn(&mut borshed_data);
-
-    let serialized_data = borsh::to_vec(&borshed_data)?;
-    let mut modified_data = Vec::with_capacity(8 + serialized_data.len());
-    modified_data.extend_from_slice(T::discriminator_slice());
-    modified_data.extend_from_slice(&serialized_data);
-
-    let modified_account = Account {
-        lamports: wrapper.account.lamports,
-        data: modified_data,
-        owner: wrapper.account.owner.parse()?,
-        executable: wrapper.account.executable,
-        rent_epoch: wrapper.account.rent_epoch,
-    };
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+
+    use super::*;
+    use crate::output::{WrittenAccount, try_write_account_to_file};
+
+    #[test]
+    fn test_validator_command_uses_program_id_overrides() {
+        let upgrade_authority_key = Pubkey::new_unique();
+        let passport_id = Pubkey::new_unique();
+        let program_ids = ProgramIds::with_overrides(None, Some(passport_id), None);
+        assert!(!program_ids.is_default());
+
+        let command = validator_command(
+            "http://localhost:8899",
+            DEFAULT_ACCOUNTS_DIR,
+            &program_ids,
+            &ProgramLoaders::default(),
+            &ForkedProgram::ALL,
+            &upgrade_authority_key,
+            true,
+        );
+        let args = command
+            .get_args()
+            .map(|arg| arg.to_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+
+        let upgradeable_programs = args
+            .iter()
+            .enumerate()
+            .filter(|(_, arg)| *arg == "--upgradeable-program")
+            .map(|(i, _)| (args[i + 1].as_str(), args[i + 2].as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            upgradeable_programs,
+            [
+                (
+                    REVENUE_DISTRIBUTION_PROGRAM_ID.to_string().as_str(),
+                    "forked-accounts/revenue_distribution.so"
+                ),
+                (
+                    passport_id.to_string().as_str(),
+                    "forked-accounts/passport.so"
+                ),
+                (
+                    SOL_CONVERSION_PROGRAM_ID.to_string().as_str(),
+                    "forked-accounts/sol_conversion.so"
+                ),
+            ]
+        );
+        assert!(!args.contains(&PASSPORT_PROGRAM_ID.to_string()));
+        assert_eq!(args.last().unwrap(), "--reset");
+    }
 
-    try_write_account_to_file(account_key, &modified_account, accounts_dir)
-}
+    #[test]
+    fn test_validator_command_includes_only_selected_programs() {
+        let upgrade_authority_key = Pubkey::new_unique();
+        let programs = ForkedProgram::selected(&[
+            ProgramSelection::SolConversion,
+            ProgramSelection::RevenueDistribution,
+            ProgramSelection::SolConversion,
+        ]);
+        assert_eq!(
+            programs,
+            [
+                ForkedProgram::RevenueDistribution,
+                ForkedProgram::SolConversion
+            ]
+        );
+        assert_eq!(
+            ForkedProgram::selected(&[ProgramSelection::Passport, ProgramSelection::All]),
+            ForkedProgram::ALL
+        );
 
-fn try_write_account_to_file(
-    account_key: &Pubkey,
-    account: &Account,
-    accounts_dir: &str,
-) -> Result<()> {
-    let wrapper = WrittenAccount {
-        pubkey: account_key.to_string(),
-        account: WrittenAccountInfo {
-            lamports: account.lamports,
-            data: (BASE64.encode(&account.data), "base64".to_string()),
-            owner: account.owner.to_string(),
-            executable: account.executable,
-            rent_epoch: account.rent_epoch,
-            space: account.data.len(),
-        },
-    };
+        let command = validator_command(
+            "http://localhost:8899",
+            DEFAULT_ACCOUNTS_DIR,
+            &ProgramIds::default(),
+            &ProgramLoaders::default(),
+            &programs,
+            &upgrade_authority_key,
+            false,
+        );
+        let args = command
+            .get_args()
+            .map(|arg| arg.to_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+
+        let upgradeable_programs = args
+            .iter()
+            .enumerate()
+            .filter(|(_, arg)| *arg == "--upgradeable-program")
+            .map(|(i, _)| (args[i + 1].as_str(), args[i + 2].as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            upgradeable_programs,
+            [
+                (
+                    REVENUE_DISTRIBUTION_PROGRAM_ID.to_string().as_str(),
+                    "forked-accounts/revenue_distribution.so"
+                ),
+                (
+                    SOL_CONVERSION_PROGRAM_ID.to_string().as_str(),
+                    "forked-accounts/sol_conversion.so"
+                ),
+            ]
+        );
+        assert!(!args.contains(&PASSPORT_PROGRAM_ID.to_string()));
+        assert!(!args.contains(&"--bpf-program".to_string()));
+    }
 
-    try_write_wrapped_account_to_file(account_key, &wrapper, accounts_dir)
-}
+    #[test]
+    fn test_program_loader_detection() {
+        let program_id = Pubkey::new_unique();
+        let program_account = |owner: Pubkey, executable: bool| Account {
+            owner,
+            executable,
+            ..Default::default()
+        };
+
+        for (owner, expected) in [
+            (bpf_loader_upgradeable::id(), ProgramLoader::Upgradeable),
+            (bpf_loader::id(), ProgramLoader::NonUpgradeable),
+            (bpf_loader_deprecated::id(), ProgramLoader::Deprecated),
+        ] {
+            let loader =
+                ProgramLoader::try_from_program_account(&program_id, &program_account(owner, true))
+                    .unwrap();
+            assert_eq!(loader, expected);
+        }
 
-fn try_write_wrapped_account_to_file(
-    account_key: &Pubkey,
-    wrapper: &WrittenAccount,
-    accounts_dir: &str,
-) -> Result<()> {
-    let json = serde_json::to_string_pretty(&wrapper)?;
-    let file_path = format!("{accounts_dir}/{account_key}.json");
-    fs::write(&file_path, json).map_err(Into::into)
-}
+        // Not executable, unsupported loader and unknown owner are rejected.
+        for (owner, executable) in [
+            (bpf_loader_upgradeable::id(), false),
+            (loader_v4::id(), true),
+            (Pubkey::new_unique(), true),
+        ] {
+            assert!(
+                ProgramLoader::try_from_program_account(
+                    &program_id,
+                    &program_account(owner, executable)
+                )
+                .is_err()
+            );
+        }
+    }
 
-async fn try_fetch_and_write_program_accounts(
-    connection: &SolanaConnection,
-    program_id: &Pubkey,
-    program_name: &str,
-    accounts_dir: &str,
-    config: &RpcProgramAccountsConfig,
-) -> Result<usize> {
-    let accounts = connection
-        .get_program_accounts_with_config(program_id, config.clone())
-        .await?;
-
-    for (key, account) in &accounts {
-        try_write_account_to_file(key, account, accounts_dir)?;
+    #[test]
+    fn test_validator_command_uses_bpf_program_for_non_upgradeable() {
+        let upgrade_authority_key = Pubkey::new_unique();
+        let program_loaders = ProgramLoaders {
+            passport: ProgramLoader::NonUpgradeable,
+            ..Default::default()
+        };
+
+        let command = validator_command(
+            "http://localhost:8899",
+            DEFAULT_ACCOUNTS_DIR,
+            &ProgramIds::default(),
+            &program_loaders,
+            &ForkedProgram::ALL,
+            &upgrade_authority_key,
+            false,
+        );
+        let args = command
+            .get_args()
+            .map(|arg| arg.to_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+
+        let bpf_program = args.iter().position(|arg| arg == "--bpf-program").unwrap();
+        assert_eq!(args[bpf_program + 1], PASSPORT_PROGRAM_ID.to_string());
+        assert_eq!(args[bpf_program + 2], "forked-accounts/passport.so");
+        assert_eq!(
+            args.iter()
+                .filter(|arg| *arg == "--upgradeable-program")
+                .count(),
+            2
+        );
     }
 
-    let accounts_len = accounts.len();
-    tracing::info!(
-        "Wrote {} {program_name} account{} to {accounts_dir}/",
-        accounts_len,
-        if accounts_len == 1 { "" } else { "s" },
-    );
+    #[test]
+    fn test_custom_accounts_dir_is_honored() {
+        let root = tempfile::TempDir::new().unwrap();
+        let accounts_dir = root.path().join("testnet-fork");
+        let accounts_dir_str = accounts_dir.to_str().unwrap();
+        let tmp_accounts_dir = tmp_accounts_dir(accounts_dir_str);
+        assert_eq!(tmp_accounts_dir, format!("{accounts_dir_str}.tmp"));
+
+        // Accounts are written to the tmp directory, then moved into place.
+        let key = Pubkey::new_unique();
+        let account = Account {
+            lamports: 1_000,
+            data: vec![3; 16],
+            owner: PASSPORT_PROGRAM_ID,
+            executable: false,
+            rent_epoch: u64::MAX,
+        };
+        fs::create_dir_all(&tmp_accounts_dir).unwrap();
+        try_write_account_to_file(&key, &account, &tmp_accounts_dir).unwrap();
+        fs::rename(&tmp_accounts_dir, &accounts_dir).unwrap();
+
+        assert!(
+            !Path::new(DEFAULT_ACCOUNTS_DIR)
+                .join(format!("{key}.json"))
+                .exists()
+        );
+        assert_eq!(try_verify_accounts(accounts_dir_str).unwrap(), 1);
 
-    Ok(accounts_len)
-}
+        let written = serde_json::from_str::<WrittenAccount>(
+            &fs::read_to_string(accounts_dir.join(format!("{key}.json"))).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(written.pubkey, key.to_string());
+        assert_eq!(
+            BASE64.decode(&written.account.data.0).unwrap(),
+            account.data
+        );
 
-fn try_dump_program(
-    connection: &SolanaConnection,
-    program_id: &Pubkey,
-    program_name: &str,
-    output_path: &str,
-) -> Result<()> {
-    tracing::info!("Dumping {} program to {}...", program_name, output_path);
-
-    let dump_status = Command::new("solana")
-        .arg("program")
-        .arg("dump")
-        .arg("--url")
-        .arg(connection.url())
-        .arg(program_id.to_string())
-        .arg(output_path)
-        .status()?;
+        let command = validator_command(
+            "http://localhost:8899",
+            accounts_dir_str,
+            &ProgramIds::default(),
+            &ProgramLoaders::default(),
+            &ForkedProgram::ALL,
+            &Pubkey::new_unique(),
+            false,
+        );
+        let args = command
+            .get_args()
+            .map(|arg| arg.to_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+
+        let account_dir = args.iter().position(|arg| arg == "--account-dir").unwrap();
+        assert_eq!(args[account_dir + 1], accounts_dir_str);
+        assert!(args.contains(&format!("{accounts_dir_str}/passport.so")));
+        assert!(!args.iter().any(|arg| arg.starts_with(DEFAULT_ACCOUNTS_DIR)));
+    }
 
-    ensure!(
-        dump_status.success(),
-        "solana program dump exited with status: {}",
-        dump_status
-    );
+    #[test]
+    fn test_program_ids_default_to_sdk() {
+        let program_ids = ProgramIds::with_overrides(None, None, None);
+        assert!(program_ids.is_default());
+        assert_eq!(
+            program_ids.revenue_distribution,
+            REVENUE_DISTRIBUTION_PROGRAM_ID
+        );
+        assert_eq!(program_ids.passport, PASSPORT_PROGRAM_ID);
+        assert_eq!(program_ids.sol_conversion, SOL_CONVERSION_PROGRAM_ID);
+    }
 
-    tracing::info!("{} program dumped successfully", program_name);
-    Ok(())
+    #[test]
+    fn test_grace_period_minutes_is_bounded() {
+        let parse = |minutes: &str| {
+            Args::try_parse_from([
+                "doublezero-solana-fork",
+                "--god-mode",
+                "--grace-period-minutes",
+                minutes,
+            ])
+            .map(|args| args.grace_period_minutes)
+        };
+
+        assert_eq!(parse("0").unwrap(), Some(0));
+        assert_eq!(parse("1440").unwrap(), Some(1440));
+        assert!(parse("1441").is_err());
+        assert!(parse("-1").is_err());
+    }
 }
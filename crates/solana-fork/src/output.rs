@@ -0,0 +1,319 @@
+//! Reading and writing forked account files.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, ensure};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use doublezero_solana_sdk::{PrecomputedDiscriminator, zero_copy};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct WrittenAccountInfo {
+    pub lamports: u64,
+    pub data: (String, String),
+    pub owner: String,
+    pub executable: bool,
+    #[serde(rename = "rentEpoch")]
+    pub rent_epoch: u64,
+    pub space: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct WrittenAccount {
+    pub pubkey: String,
+    pub account: WrittenAccountInfo,
+}
+
+impl WrittenAccount {
+    pub fn new(account_key: &Pubkey, account: &Account) -> Self {
+        Self {
+            pubkey: account_key.to_string(),
+            account: WrittenAccountInfo {
+                lamports: account.lamports,
+                data: (BASE64.encode(&account.data), "base64".to_string()),
+                owner: account.owner.to_string(),
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+                space: account.data.len(),
+            },
+        }
+    }
+}
+
+pub fn try_read_zero_copy_account<T>(
+    account_key: &Pubkey,
+    accounts_dir: &str,
+) -> Result<(WrittenAccount, Box<T>, Vec<u8>)>
+where
+    T: PrecomputedDiscriminator + bytemuck::Pod,
+{
+    let path = format!("{accounts_dir}/{account_key}.json");
+    let json = fs::read_to_string(&path)?;
+    let wrapper = serde_json::from_str::<WrittenAccount>(&json)?;
+    let data = BASE64.decode(&wrapper.account.data.0)?;
+
+    let (mucked_data, remaining_data) = zero_copy::checked_from_bytes_with_discriminator(&data)
+        .map(|data| (Box::new(*data.0), data.1))
+        .unwrap();
+
+    Ok((wrapper, mucked_data, remaining_data.to_vec()))
+}
+
+pub fn try_modify_zero_copy_account<T, U>(
+    account_key: &Pubkey,
+    accounts_dir: &str,
+    modify_fn: impl FnOnce(&mut T) -> U,
+) -> Result<U>
+where
+    T: PrecomputedDiscriminator + bytemuck::Pod,
+{
+    let (wrapper, mut mucked_data, remaining_data) =
+        try_read_zero_copy_account::<T>(account_key, accounts_dir)?;
+
+    let out = modify_fn(&mut mucked_data);
+
+    let mut modified_data = Vec::with_capacity(zero_copy::data_end::<T>() + remaining_data.len());
+    modified_data.extend_from_slice(T::discriminator_slice());
+    modified_data.extend_from_slice(bytemuck::bytes_of(&*mucked_data));
+    modified_data.extend_from_slice(&remaining_data);
+
+    let modified_account = Account {
+        lamports: wrapper.account.lamports,
+        data: modified_data,
+        owner: wrapper.account.owner.parse()?,
+        executable: wrapper.account.executable,
+        rent_epoch: wrapper.account.rent_epoch,
+    };
+
+    try_write_account_to_file(account_key, &modified_account, accounts_dir)?;
+
+    Ok(out)
+}
+
+pub fn try_read_borsh_account<T>(
+    account_key: &Pubkey,
+    accounts_dir: &str,
+) -> Result<(WrittenAccount, Box<T>)>
+where
+    T: PrecomputedDiscriminator + borsh::BorshDeserialize,
+{
+    let path = format!("{accounts_dir}/{account_key}.json");
+    let json = fs::read_to_string(&path)?;
+    let wrapper = serde_json::from_str::<WrittenAccount>(&json)?;
+    let data = BASE64.decode(&wrapper.account.data.0)?;
+
+    ensure!(
+        data.len() > 8 && &data[..8] == T::discriminator_slice(),
+        "Invalid discriminator for account: {account_key}",
+    );
+
+    let borshed_data = T::deserialize(&mut &data[8..]).map(Box::new)?;
+
+    Ok((wrapper, borshed_data))
+}
+
+pub fn try_modify_borsh_account<T>(
+    account_key: &Pubkey,
+    accounts_dir: &str,
+    modify_fn: impl FnOnce(&mut Box<T>),
+) -> Result<()>
+where
+    T: PrecomputedDiscriminator + borsh::BorshDeserialize + borsh::BorshSerialize,
+{
+    let (wrapper, mut borshed_data) = try_read_borsh_account::<T>(account_key, accounts_dir)?;
+
+    modify_fn(&mut borshed_data);
+
+    let serialized_data = borsh::to_vec(&borshed_data)?;
+    let mut modified_data = Vec::with_capacity(8 + serialized_data.len());
+    modified_data.extend_from_slice(T::discriminator_slice());
+    modified_data.extend_from_slice(&serialized_data);
+
+    let modified_account = Account {
+        lamports: wrapper.account.lamports,
+        data: modified_data,
+        owner: wrapper.account.owner.parse()?,
+        executable: wrapper.account.executable,
+        rent_epoch: wrapper.account.rent_epoch,
+    };
+
+    try_write_account_to_file(account_key, &modified_account, accounts_dir)
+}
+
+pub fn try_write_account_to_file(
+    account_key: &Pubkey,
+    account: &Account,
+    accounts_dir: &str,
+) -> Result<()> {
+    let wrapper = WrittenAccount::new(account_key, account);
+    try_write_wrapped_account_to_file(account_key, &wrapper, accounts_dir)
+}
+
+/// Write the fetched extra accounts, failing if any of them does not exist.
+pub fn try_write_extra_accounts(
+    account_keys: &[Pubkey],
+    accounts: Vec<Option<Account>>,
+    accounts_dir: &str,
+) -> Result<usize> {
+    ensure!(
+        account_keys.len() == accounts.len(),
+        "Expected {} extra accounts, fetched {}",
+        account_keys.len(),
+        accounts.len()
+    );
+
+    for (key, account) in account_keys.iter().zip(accounts) {
+        let account = account.with_context(|| format!("Extra account {key} does not exist"))?;
+        try_write_account_to_file(key, &account, accounts_dir)?;
+    }
+
+    Ok(account_keys.len())
+}
+
+pub fn try_write_wrapped_account_to_file(
+    account_key: &Pubkey,
+    wrapper: &WrittenAccount,
+    accounts_dir: &str,
+) -> Result<()> {
+    let json = serde_json::to_string_pretty(&wrapper)?;
+    let file_path = format!("{accounts_dir}/{account_key}.json");
+    fs::write(&file_path, json).map_err(Into::into)
+}
+
+/// Collect every account file in `accounts_dir` into one JSON array, sorted
+/// by pubkey.
+pub fn try_write_combined_accounts(accounts_dir: &str, output_path: &Path) -> Result<usize> {
+    let mut accounts = Vec::new();
+
+    for path in try_account_file_paths(accounts_dir)? {
+        let json = fs::read_to_string(&path)?;
+        let wrapper = serde_json::from_str::<WrittenAccount>(&json)
+            .with_context(|| format!("Failed to parse account file {}", path.display()))?;
+        accounts.push(wrapper);
+    }
+
+    accounts.sort_by(|a, b| a.pubkey.cmp(&b.pubkey));
+
+    let json = serde_json::to_string_pretty(&accounts)?;
+    fs::write(output_path, json)?;
+
+    Ok(accounts.len())
+}
+
+/// Account JSON files in `accounts_dir`, skipping program dumps.
+pub fn try_account_file_paths(accounts_dir: &str) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+
+    for entry in fs::read_dir(accounts_dir)? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            paths.push(path);
+        }
+    }
+
+    paths.sort();
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use doublezero_solana_sdk::revenue_distribution::ID as REVENUE_DISTRIBUTION_PROGRAM_ID;
+
+    use super::*;
+    use crate::verify::try_verify_accounts;
+
+    #[test]
+    fn test_combined_output_contains_all_accounts() {
+        let accounts_dir = tempfile::TempDir::new().unwrap();
+        let accounts_dir_str = accounts_dir.path().to_str().unwrap();
+
+        let mut keys = (0..3).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+        for (i, key) in keys.iter().enumerate() {
+            let account = Account {
+                lamports: 1_000 + i as u64,
+                data: vec![i as u8; 8],
+                owner: REVENUE_DISTRIBUTION_PROGRAM_ID,
+                executable: false,
+                rent_epoch: u64::MAX,
+            };
+            try_write_account_to_file(key, &account, accounts_dir_str).unwrap();
+        }
+
+        // Program dumps live in the same directory and must be skipped.
+        fs::write(accounts_dir.path().join("passport.so"), [0; 4]).unwrap();
+
+        let output_path = accounts_dir.path().join("combined.json");
+        let accounts_len = try_write_combined_accounts(accounts_dir_str, &output_path).unwrap();
+        assert_eq!(accounts_len, keys.len());
+
+        let combined =
+            serde_json::from_str::<Vec<WrittenAccount>>(&fs::read_to_string(&output_path).unwrap())
+                .unwrap();
+
+        keys.sort_by_key(|key| key.to_string());
+        assert_eq!(
+            combined
+                .iter()
+                .map(|account| account.pubkey.clone())
+                .collect::<Vec<_>>(),
+            keys.iter().map(ToString::to_string).collect::<Vec<_>>()
+        );
+
+        // Each combined entry matches its per-account file.
+        for account in &combined {
+            let per_file =
+                fs::read_to_string(accounts_dir.path().join(format!("{}.json", account.pubkey)))
+                    .unwrap();
+            assert_eq!(
+                serde_json::to_value(account).unwrap(),
+                serde_json::from_str::<serde_json::Value>(&per_file).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_extra_accounts_are_written() {
+        let accounts_dir = tempfile::TempDir::new().unwrap();
+        let accounts_dir_str = accounts_dir.path().to_str().unwrap();
+
+        let keys = (0..2).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+        let accounts = keys
+            .iter()
+            .enumerate()
+            .map(|(i, _)| {
+                Some(Account {
+                    lamports: 1_000_000_000 * (i as u64 + 1),
+                    owner: solana_sdk::system_program::id(),
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        let accounts_len = try_write_extra_accounts(&keys, accounts, accounts_dir_str).unwrap();
+        assert_eq!(accounts_len, keys.len());
+        assert_eq!(try_verify_accounts(accounts_dir_str).unwrap(), keys.len());
+
+        for (i, key) in keys.iter().enumerate() {
+            let written = serde_json::from_str::<WrittenAccount>(
+                &fs::read_to_string(accounts_dir.path().join(format!("{key}.json"))).unwrap(),
+            )
+            .unwrap();
+            assert_eq!(written.pubkey, key.to_string());
+            assert_eq!(written.account.lamports, 1_000_000_000 * (i as u64 + 1));
+        }
+
+        // A missing extra account is an error.
+        let missing_key = Pubkey::new_unique();
+        let err = try_write_extra_accounts(&[missing_key], vec![None], accounts_dir_str)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(&missing_key.to_string()));
+    }
+}
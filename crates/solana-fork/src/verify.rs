@@ -0,0 +1,295 @@
+//! Integrity checks for forked account files and program dumps.
+
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result, ensure};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use solana_sdk::{
+    account::Account,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    hash::hash,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    ProgramLoader,
+    output::{WrittenAccount, try_account_file_paths},
+};
+
+/// Re-read every account file in `accounts_dir`, returning the number of
+/// valid accounts or an error listing every file that failed verification.
+pub fn try_verify_accounts(accounts_dir: &str) -> Result<usize> {
+    let mut accounts_len = 0;
+    let mut failures = Vec::new();
+
+    for path in try_account_file_paths(accounts_dir)? {
+        match try_verify_account_file(&path) {
+            Ok(()) => accounts_len += 1,
+            Err(e) => failures.push(format!("{}: {e:#}", path.display())),
+        }
+    }
+
+    ensure!(
+        failures.is_empty(),
+        "{} invalid account file(s) in {accounts_dir}. Run with --reset to fetch accounts again:\n  - {}",
+        failures.len(),
+        failures.join("\n  - ")
+    );
+
+    Ok(accounts_len)
+}
+
+fn try_verify_account_file(path: &Path) -> Result<()> {
+    let json = fs::read_to_string(path)?;
+    let wrapper =
+        serde_json::from_str::<WrittenAccount>(&json).context("Failed to parse account JSON")?;
+
+    ensure!(
+        path.file_stem()
+            .is_some_and(|file_stem| file_stem == wrapper.pubkey.as_str()),
+        "Pubkey {} does not match file name",
+        wrapper.pubkey
+    );
+
+    let (encoded_data, encoding) = &wrapper.account.data;
+    ensure!(
+        encoding == "base64",
+        "Unsupported data encoding: {encoding}"
+    );
+
+    let data = BASE64
+        .decode(encoded_data)
+        .context("Failed to decode base64 account data")?;
+    ensure!(
+        data.len() == wrapper.account.space,
+        "Data length {} does not match space {}",
+        data.len(),
+        wrapper.account.space
+    );
+
+    Ok(())
+}
+
+/// Compare a dumped program against the executable bytes on chain, which live
+/// in the program data account for upgradeable programs and in the program
+/// account otherwise. Catches dumps that were cut short.
+pub async fn try_verify_program_dump<F, Fut>(
+    get_account: F,
+    program_id: &Pubkey,
+    program_name: &str,
+    dump_path: &str,
+) -> Result<()>
+where
+    F: Fn(Pubkey) -> Fut,
+    Fut: Future<Output = Result<Account>>,
+{
+    let program_account = get_account(*program_id).await?;
+    let (executable_key, executable_account, offset) =
+        match ProgramLoader::try_from_program_account(program_id, &program_account)? {
+            ProgramLoader::Upgradeable => {
+                let program_data_key = bpf_loader_upgradeable::get_program_data_address(program_id);
+                (
+                    program_data_key,
+                    get_account(program_data_key).await?,
+                    UpgradeableLoaderState::size_of_programdata_metadata(),
+                )
+            }
+            ProgramLoader::NonUpgradeable | ProgramLoader::Deprecated => {
+                (*program_id, program_account, 0)
+            }
+        };
+
+    let expected = executable_account.data.get(offset..).with_context(|| {
+        format!("Program data {executable_key} is smaller than its {offset}-byte header")
+    })?;
+    let dumped = fs::read(dump_path)?;
+
+    ensure!(
+        dumped.len() == expected.len(),
+        "Dumped {program_name} program {dump_path} is {} bytes, but {executable_key} holds {} bytes. The dump may have been cut short. Run with --reset again",
+        dumped.len(),
+        expected.len()
+    );
+
+    let dumped_hash = hash(&dumped);
+    let expected_hash = hash(expected);
+    ensure!(
+        dumped_hash == expected_hash,
+        "Dumped {program_name} program {dump_path} has hash {dumped_hash}, but {executable_key} has hash {expected_hash}. Run with --reset again"
+    );
+
+    tracing::info!("Verified {program_name} program dump ({expected_hash})");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use doublezero_solana_sdk::revenue_distribution::ID as REVENUE_DISTRIBUTION_PROGRAM_ID;
+    use solana_sdk::bpf_loader;
+
+    use super::*;
+    use crate::output::{
+        WrittenAccountInfo, try_write_account_to_file, try_write_wrapped_account_to_file,
+    };
+
+    #[test]
+    fn test_verify_accounts_detects_truncated_file() {
+        let accounts_dir = tempfile::TempDir::new().unwrap();
+        let accounts_dir_str = accounts_dir.path().to_str().unwrap();
+
+        let keys = (0..2).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+        for key in &keys {
+            let account = Account {
+                lamports: 1_000,
+                data: vec![7; 64],
+                owner: REVENUE_DISTRIBUTION_PROGRAM_ID,
+                executable: false,
+                rent_epoch: u64::MAX,
+            };
+            try_write_account_to_file(key, &account, accounts_dir_str).unwrap();
+        }
+
+        assert_eq!(try_verify_accounts(accounts_dir_str).unwrap(), keys.len());
+
+        // Simulate a write that was cut short (e.g. disk full).
+        let truncated_path = accounts_dir.path().join(format!("{}.json", keys[1]));
+        let json = fs::read(&truncated_path).unwrap();
+        fs::write(&truncated_path, &json[..json.len() / 2]).unwrap();
+
+        let err = try_verify_accounts(accounts_dir_str)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("1 invalid account file(s)"));
+        assert!(err.contains(&keys[1].to_string()));
+        assert!(!err.contains(&keys[0].to_string()));
+    }
+
+    #[test]
+    fn test_verify_accounts_detects_space_mismatch() {
+        let accounts_dir = tempfile::TempDir::new().unwrap();
+        let accounts_dir_str = accounts_dir.path().to_str().unwrap();
+
+        let key = Pubkey::new_unique();
+        let wrapper = WrittenAccount {
+            pubkey: key.to_string(),
+            account: WrittenAccountInfo {
+                lamports: 1_000,
+                data: (BASE64.encode([1, 2, 3]), "base64".to_string()),
+                owner: REVENUE_DISTRIBUTION_PROGRAM_ID.to_string(),
+                executable: false,
+                rent_epoch: u64::MAX,
+                space: 4,
+            },
+        };
+        try_write_wrapped_account_to_file(&key, &wrapper, accounts_dir_str).unwrap();
+
+        let err = try_verify_accounts(accounts_dir_str)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Data length 3 does not match space 4"));
+    }
+
+    #[tokio::test]
+    async fn test_verify_program_dump_against_program_data() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dump_path = temp_dir.path().join("program.so");
+        let dump_path_str = dump_path.to_str().unwrap();
+
+        let program_id = Pubkey::new_unique();
+        let program_data_key = bpf_loader_upgradeable::get_program_data_address(&program_id);
+        let executable = (0..1_024).map(|i| i as u8).collect::<Vec<_>>();
+
+        let header_len = UpgradeableLoaderState::size_of_programdata_metadata();
+        let mut program_data = vec![0; header_len];
+        program_data.extend_from_slice(&executable);
+
+        let accounts = std::collections::HashMap::from([
+            (
+                program_id,
+                Account {
+                    lamports: 1,
+                    data: vec![0; 36],
+                    owner: bpf_loader_upgradeable::id(),
+                    executable: true,
+                    rent_epoch: u64::MAX,
+                },
+            ),
+            (
+                program_data_key,
+                Account {
+                    lamports: 1,
+                    data: program_data,
+                    owner: bpf_loader_upgradeable::id(),
+                    executable: false,
+                    rent_epoch: u64::MAX,
+                },
+            ),
+        ]);
+        let get_account = |key: Pubkey| {
+            let account = accounts.get(&key).cloned();
+            async move { account.with_context(|| format!("Account {key} not found")) }
+        };
+
+        fs::write(&dump_path, &executable).unwrap();
+        try_verify_program_dump(get_account, &program_id, "Test", dump_path_str)
+            .await
+            .unwrap();
+
+        // Truncated dump.
+        fs::write(&dump_path, &executable[..512]).unwrap();
+        let err = try_verify_program_dump(get_account, &program_id, "Test", dump_path_str)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("is 512 bytes"));
+        assert!(err.contains(&format!("{program_data_key} holds 1024 bytes")));
+
+        // Corrupted dump of the right size.
+        let mut corrupted = executable.clone();
+        corrupted[100] ^= 0xff;
+        fs::write(&dump_path, &corrupted).unwrap();
+        let err = try_verify_program_dump(get_account, &program_id, "Test", dump_path_str)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(&format!(
+            "{program_data_key} has hash {}",
+            hash(&executable)
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_verify_program_dump_for_non_upgradeable_program() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dump_path = temp_dir.path().join("program.so");
+        let dump_path_str = dump_path.to_str().unwrap();
+
+        let program_id = Pubkey::new_unique();
+        let program_account = Account {
+            lamports: 1,
+            data: vec![7; 256],
+            owner: bpf_loader::id(),
+            executable: true,
+            rent_epoch: u64::MAX,
+        };
+        let get_account = |key: Pubkey| {
+            let account = program_account.clone();
+            async move {
+                assert_eq!(key, program_id, "only the program account is fetched");
+                Ok::<_, anyhow::Error>(account)
+            }
+        };
+
+        fs::write(&dump_path, [7; 256]).unwrap();
+        try_verify_program_dump(get_account, &program_id, "Test", dump_path_str)
+            .await
+            .unwrap();
+
+        fs::write(&dump_path, [7; 255]).unwrap();
+        assert!(
+            try_verify_program_dump(get_account, &program_id, "Test", dump_path_str)
+                .await
+                .is_err()
+        );
+    }
+}
@@ -1,20 +1,28 @@
-use anyhow::Result;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
 use chrono::Utc;
 use clap::{Args, ValueEnum};
 use doublezero_solana_client_tools::{
     payer::{SolanaPayerOptions, try_load_keypair},
     rpc::{DoubleZeroLedgerConnectionOptions, SolanaConnection, SolanaConnectionOptions},
 };
-use doublezero_solana_sdk::revenue_distribution::state::ProgramConfig;
+use doublezero_solana_sdk::revenue_distribution::state::{Distribution, ProgramConfig};
 use leaky_bucket::RateLimiter;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::commitment_config::CommitmentConfig;
-use tabled::{Table, settings::Style};
+use tabled::{Table, Tabled, settings::Style};
 
 use crate::{
     rpc::{JoinedSolanaEpochs, SolanaValidatorDebtConnectionOptions},
+    s3_fetcher,
     solana_debt_calculator::SolanaDebtCalculator,
     transaction::Transaction,
+    validator_debt::ComputedSolanaValidatorDebts,
+    worker::{SlackWebhookOptions, ValidatorSummary},
 };
 
 #[derive(Debug, Clone, ValueEnum)]
@@ -28,6 +36,24 @@ pub struct CalculateValidatorDebtCommand {
     #[arg(long)]
     force: bool,
 
+    /// Always write a freshly computed debt record to the DoubleZero Ledger,
+    /// overwriting an existing record even if it matches. Use after a change
+    /// to the debt calculation.
+    #[arg(long, conflicts_with = "estimate_only")]
+    reprocess: bool,
+
+    /// Fail instead of warning when the DZ epoch only overlaps an already
+    /// charged Solana epoch, when `--force` would overwrite a DoubleZero
+    /// Ledger record that does not match the computed debt, or when the
+    /// validators fetched from S3 do not reconcile with the computed debts.
+    #[arg(long, conflicts_with = "estimate_only")]
+    strict: bool,
+
+    /// Fail if the distribution became calculable more than this many
+    /// seconds ago, which usually means the pipeline has stalled.
+    #[arg(long, value_name = "SECONDS")]
+    max_distribution_age: Option<u64>,
+
     #[command(flatten)]
     solana_payer_options: SolanaPayerOptions,
 
@@ -41,18 +67,75 @@ pub struct CalculateValidatorDebtCommand {
     /// export results: csv, slack
     #[arg(long, value_enum)]
     export: Option<ExportFormat>,
+
+    #[command(flatten)]
+    slack_webhook_options: SlackWebhookOptions,
+
+    /// Write one row per validator (pubkey, total debt) to a CSV file.
+    /// Defaults to a timestamped filename when no path is given.
+    #[arg(long, value_name = "PATH", num_args = 0..=1)]
+    export_csv: Option<Option<PathBuf>>,
+
+    /// Write the computed debts (blockhash, Solana epoch range and every
+    /// validator debt) to a JSON file. The merkle root posted to the
+    /// DoubleZero Ledger can be recomputed from this file alone.
+    #[arg(long, value_name = "PATH")]
+    dump_computed_debts: Option<PathBuf>,
+
+    /// Validators must appear in more than this many hourly S3 snapshots to
+    /// be charged. The default of 12 is the 12-hour connection rule.
+    #[arg(long, default_value_t = s3_fetcher::DEFAULT_MIN_HOURLY_APPEARANCES)]
+    min_appearances: usize,
+
+    /// S3 dataset network. Defaults to the network of the Solana connection.
+    #[arg(long, value_enum)]
+    network: Option<s3_fetcher::Network>,
+
+    /// Only estimate debt for the last completed DZ epoch. Nothing is posted
+    /// to the DoubleZero Ledger or Solana, and no keypair is required.
+    #[arg(long, conflicts_with = "post_to_ledger_only")]
+    estimate_only: bool,
+
+    /// Print the Solana validator fee parameters of the distribution before
+    /// calculating.
+    #[arg(long)]
+    show_fees: bool,
+
+    /// Print the Solana validator fee parameters of the distribution and exit
+    /// without calculating.
+    #[arg(long, conflicts_with = "show_fees")]
+    show_fees_only: bool,
 }
 
 impl CalculateValidatorDebtCommand {
     pub async fn try_into_execute(self) -> Result<()> {
         let Self {
             force,
+            reprocess,
+            strict,
+            max_distribution_age,
             solana_payer_options,
             dz_ledger_connection_options,
             post_to_ledger_only,
             export,
+            slack_webhook_options,
+            export_csv,
+            dump_computed_debts,
+            min_appearances,
+            network,
+            estimate_only,
+            show_fees,
+            show_fees_only,
         } = self;
 
+        let network = match network {
+            Some(network) => network,
+            None => SolanaConnection::from(solana_payer_options.connection_options.clone())
+                .try_network_environment()
+                .await?
+                .try_into()?,
+        };
+
         let connection_options = SolanaValidatorDebtConnectionOptions {
             solana_url_or_moniker: solana_payer_options
                 .connection_options
@@ -62,47 +145,82 @@ impl CalculateValidatorDebtCommand {
         };
         let solana_debt_calculator: SolanaDebtCalculator =
             SolanaDebtCalculator::try_from(connection_options)?;
-        let signer = try_load_keypair(None).expect("failed to load keypair");
-        let transaction = Transaction::new(
-            signer.into(),
-            solana_payer_options.signer_options.dry_run,
-            force,
-        );
-        let dry_run = transaction.dry_run;
-        let write_summary = crate::worker::calculate_distribution(
-            &solana_debt_calculator,
-            transaction,
-            post_to_ledger_only,
-        )
-        .await?;
+
+        if show_fees || show_fees_only {
+            let (dz_epoch, distribution) =
+                crate::worker::fetch_last_completed_distribution(&solana_debt_calculator).await?;
+            tracing::info!(
+                "Solana validator fee parameters for DoubleZero epoch {dz_epoch}:\n{}",
+                Table::new(fee_parameter_rows(&distribution))
+                    .with(Style::psql().remove_horizontals())
+            );
+
+            if show_fees_only {
+                return Ok(());
+            }
+        }
+
+        let (write_summary, dry_run) = if estimate_only {
+            let write_summary = crate::worker::estimate_distribution(
+                &solana_debt_calculator,
+                min_appearances,
+                network,
+            )
+            .await?;
+            (write_summary, true)
+        } else {
+            if reprocess {
+                tracing::warn!(
+                    "--reprocess is set: the DoubleZero Ledger debt record will be overwritten \
+                     with freshly computed debt even if it matches"
+                );
+            }
+
+            let signer = try_load_keypair(None).expect("failed to load keypair");
+            let transaction = Transaction::new(
+                signer.into(),
+                solana_payer_options.signer_options.dry_run,
+                force,
+            )
+            .with_strict(strict);
+            let dry_run = transaction.dry_run;
+            let write_summary = crate::worker::calculate_distribution(
+                &solana_debt_calculator,
+                transaction,
+                post_to_ledger_only,
+                reprocess,
+                max_distribution_age,
+                min_appearances,
+                network,
+            )
+            .await?;
+            (write_summary, dry_run)
+        };
+
+        if let Some(path) = dump_computed_debts {
+            write_computed_debts_json(&path, &write_summary.computed_debts)?;
+            tracing::info!("Wrote computed validator debts to {}", path.display());
+        }
 
         let mut filename: Option<String> = None;
 
         if let Some(ExportFormat::Csv) = export {
-            let now = Utc::now();
-            let timestamp_milliseconds: i64 = now.timestamp_millis();
-            let string_filename = if dry_run {
-                format!(
-                    "DRY_RUN_dz_epoch_{}_calculate_distribution_{timestamp_milliseconds}.csv",
-                    write_summary.dz_epoch
-                )
-            } else {
-                format!(
-                    "dz_epoch_{}_calculate_distribution_{timestamp_milliseconds}.csv",
-                    write_summary.dz_epoch
-                )
-            };
-            let mut writer = csv::Writer::from_path(string_filename.clone())?;
-            filename = Some(string_filename);
-            for w in write_summary.validator_summaries.iter() {
-                writer.serialize(w)?;
-            }
-            writer.flush()?;
+            let path = default_csv_path(write_summary.dz_epoch, dry_run);
+            write_validator_summaries_csv(&path, &write_summary.validator_summaries)?;
+            filename = Some(path.display().to_string());
+        };
+
+        if let Some(path) = export_csv {
+            let path = path.unwrap_or_else(|| default_csv_path(write_summary.dz_epoch, dry_run));
+            write_validator_summaries_csv(&path, &write_summary.validator_summaries)?;
+            tracing::info!("Wrote validator debt CSV to {}", path.display());
+            filename = Some(path.display().to_string());
         };
 
         if let Some(ExportFormat::Slack) = export {
             slack_notifier::validator_debt::post_distribution_to_slack(
                 filename,
+                slack_webhook_options.target(),
                 write_summary.solana_epoch,
                 write_summary.dz_epoch,
                 dry_run,
@@ -124,6 +242,67 @@ impl CalculateValidatorDebtCommand {
     }
 }
 
+#[derive(Debug, Tabled)]
+struct FeeParameterRow {
+    parameter: &'static str,
+    value: String,
+}
+
+/// One row per Solana validator fee parameter, including those set to zero.
+fn fee_parameter_rows(distribution: &Distribution) -> Vec<FeeParameterRow> {
+    let fee_parameters = &distribution.solana_validator_fee_parameters;
+    let pct = |value: u16| format!("{:.2}%", value as f64 / 100.0);
+
+    vec![
+        FeeParameterRow {
+            parameter: "Base block rewards",
+            value: pct(u16::from(fee_parameters.base_block_rewards_pct)),
+        },
+        FeeParameterRow {
+            parameter: "Priority block rewards",
+            value: pct(u16::from(fee_parameters.priority_block_rewards_pct)),
+        },
+        FeeParameterRow {
+            parameter: "Inflation rewards",
+            value: pct(u16::from(fee_parameters.inflation_rewards_pct)),
+        },
+        FeeParameterRow {
+            parameter: "Jito tips",
+            value: pct(u16::from(fee_parameters.jito_tips_pct)),
+        },
+        FeeParameterRow {
+            parameter: "Fixed SOL",
+            value: format!("{:.9} SOL", fee_parameters.fixed_sol_amount as f64 * 1e-9),
+        },
+    ]
+}
+
+/// Timestamped CSV filename, prefixed with `DRY_RUN_` for dry runs.
+fn default_csv_path(dz_epoch: u64, dry_run: bool) -> PathBuf {
+    let timestamp_milliseconds: i64 = Utc::now().timestamp_millis();
+    let filename = if dry_run {
+        format!("DRY_RUN_dz_epoch_{dz_epoch}_calculate_distribution_{timestamp_milliseconds}.csv")
+    } else {
+        format!("dz_epoch_{dz_epoch}_calculate_distribution_{timestamp_milliseconds}.csv")
+    };
+    PathBuf::from(filename)
+}
+
+fn write_validator_summaries_csv(path: &Path, summaries: &[ValidatorSummary]) -> Result<()> {
+    let mut writer = csv::Writer::from_path(path)?;
+    for summary in summaries {
+        writer.serialize(summary)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_computed_debts_json(path: &Path, debts: &ComputedSolanaValidatorDebts) -> Result<()> {
+    let json = serde_json::to_string_pretty(debts)?;
+    fs::write(path, json)
+        .with_context(|| format!("Failed to write computed debts to {}", path.display()))
+}
+
 #[derive(Debug, Args, Clone)]
 pub struct FindSolanaEpochCommand {
     /// Target DoubleZero Ledger epoch.
@@ -221,3 +400,126 @@ async fn latest_distribution_epoch(
         .value()
         .saturating_sub(1))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validator_summaries_csv_round_trip() {
+        let summaries = vec![
+            ValidatorSummary {
+                validator_pubkey: "6WgdYhhGE53WrZ7ywJA15hBVkw7CRbQ8yDBBTwmBtAHN".to_string(),
+                total_debt: 1_343_542_456,
+            },
+            ValidatorSummary {
+                validator_pubkey: "7WgdYhhGE53WrZ7ywJA15hBVkw7CRbQ8yDBBTwmBtAHN".to_string(),
+                total_debt: 0,
+            },
+        ];
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("summaries.csv");
+        write_validator_summaries_csv(&path, &summaries).unwrap();
+
+        let mut reader = csv::Reader::from_path(&path).unwrap();
+        assert_eq!(
+            reader.headers().unwrap().iter().collect::<Vec<_>>(),
+            vec!["validator_pubkey", "total_debt"]
+        );
+        let read_back: Vec<ValidatorSummary> =
+            reader.deserialize().collect::<Result<_, _>>().unwrap();
+        assert_eq!(read_back, summaries);
+    }
+
+    #[test]
+    fn test_computed_debts_json_round_trip() {
+        use solana_sdk::{hash::Hash, pubkey::Pubkey};
+
+        use crate::validator_debt::ComputedSolanaValidatorDebt;
+
+        let debts = ComputedSolanaValidatorDebts {
+            blockhash: Hash::new_unique(),
+            first_solana_epoch: 822,
+            last_solana_epoch: 823,
+            debts: vec![
+                ComputedSolanaValidatorDebt {
+                    node_id: Pubkey::new_unique(),
+                    amount: 1_343_542_456,
+                },
+                ComputedSolanaValidatorDebt {
+                    node_id: Pubkey::new_unique(),
+                    amount: 234_234_324,
+                },
+            ],
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("debts.json");
+        write_computed_debts_json(&path, &debts).unwrap();
+
+        // Keys are base58 strings, not byte arrays.
+        let json = fs::read_to_string(&path).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["blockhash"], debts.blockhash.to_string());
+        assert_eq!(
+            value["debts"][0]["node_id"],
+            debts.debts[0].node_id.to_string()
+        );
+
+        let loaded: ComputedSolanaValidatorDebts = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, debts);
+        assert_eq!(loaded.merkle_root(), debts.merkle_root());
+
+        // Dumping the loaded debts again is byte-for-byte identical.
+        let path_again = dir.path().join("debts_again.json");
+        write_computed_debts_json(&path_again, &loaded).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            fs::read_to_string(&path_again).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_fee_parameter_rows() {
+        let mut distribution: Distribution = bytemuck::Zeroable::zeroed();
+        distribution
+            .solana_validator_fee_parameters
+            .fixed_sol_amount = 1_500_000_000;
+
+        let rows = fee_parameter_rows(&distribution);
+        assert_eq!(
+            rows.iter()
+                .map(|row| (row.parameter, row.value.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Base block rewards", "0.00%"),
+                ("Priority block rewards", "0.00%"),
+                ("Inflation rewards", "0.00%"),
+                ("Jito tips", "0.00%"),
+                ("Fixed SOL", "1.500000000 SOL"),
+            ]
+        );
+
+        let table = Table::new(rows).to_string();
+        assert!(table.contains("parameter"));
+        assert!(table.contains("1.500000000 SOL"));
+    }
+
+    #[test]
+    fn test_default_csv_path_marks_dry_run() {
+        let dry_run_path = default_csv_path(42, true);
+        let path = default_csv_path(42, false);
+
+        assert!(
+            dry_run_path
+                .to_string_lossy()
+                .starts_with("DRY_RUN_dz_epoch_42_calculate_distribution_")
+        );
+        assert!(
+            path.to_string_lossy()
+                .starts_with("dz_epoch_42_calculate_distribution_")
+        );
+        assert_eq!(path.extension().unwrap(), "csv");
+    }
+}
@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use anyhow::Result;
 use clap::Args;
-use solana_client::nonblocking::rpc_client::RpcClient;
+use doublezero_solana_client_tools::rpc::SolanaConnection;
 use solana_sdk::commitment_config::CommitmentConfig;
 use url::Url;
 
@@ -22,6 +22,10 @@ pub struct ExportValidatorsCommand {
     /// [mainnet-beta, testnet, localhost].
     #[arg(long = "url", short = 'u')]
     solana_url_or_moniker: Option<String>,
+
+    /// S3 dataset network. Defaults to the network of the Solana connection.
+    #[arg(long, value_enum)]
+    network: Option<s3_fetcher::Network>,
 }
 
 impl ExportValidatorsCommand {
@@ -30,6 +34,7 @@ impl ExportValidatorsCommand {
             epoch,
             output,
             solana_url_or_moniker,
+            network,
         } = self;
 
         tracing::info!("Exporting validators for Solana epoch {}", epoch);
@@ -37,15 +42,24 @@ impl ExportValidatorsCommand {
         // Create RPC client
         let solana_url_or_moniker = solana_url_or_moniker.as_deref().unwrap_or("m");
         let solana_url = Url::parse(normalize_to_url_if_moniker(solana_url_or_moniker))?;
-        let rpc_client =
-            RpcClient::new_with_commitment(solana_url.into(), CommitmentConfig::confirmed());
+        let solana_connection =
+            SolanaConnection::new_with_commitment(solana_url.into(), CommitmentConfig::confirmed());
+
+        let network = match network {
+            Some(network) => network,
+            None => solana_connection
+                .try_network_environment()
+                .await?
+                .try_into()?,
+        };
 
         // Fetch validators from S3
-        tracing::info!("Fetching validator pubkeys from S3...");
+        tracing::info!("Fetching validator pubkeys from S3 ({network:?})...");
         let validator_keys = s3_fetcher::fetch_validator_pubkeys(
             epoch,
-            &rpc_client,
-            s3_fetcher::Network::MainnetBeta,
+            &solana_connection,
+            network,
+            s3_fetcher::DEFAULT_MIN_HOURLY_APPEARANCES,
         )
         .await?;
 
@@ -21,6 +21,12 @@ pub struct InitializeDistributionCommand {
 
     #[arg(hide = true, long)]
     record_debt_accountant: Option<Pubkey>,
+
+    /// Stop looking for debt to write off after this many consecutive older
+    /// epochs with all debt processed. Zero, the default, scans back to
+    /// genesis.
+    #[arg(hide = true, long, default_value_t = worker::DEFAULT_WRITE_OFF_SETTLED_EPOCHS)]
+    write_off_settled_epochs: usize,
 }
 
 impl InitializeDistributionCommand {
@@ -30,6 +36,7 @@ impl InitializeDistributionCommand {
             dz_env,
             bypass_dz_epoch_check,
             record_debt_accountant: record_accountant_key,
+            write_off_settled_epochs,
         } = self;
 
         let wallet = Wallet::try_from(solana_payer_options)?;
@@ -39,6 +46,7 @@ impl InitializeDistributionCommand {
             dz_env.dz_env,
             bypass_dz_epoch_check,
             record_accountant_key,
+            write_off_settled_epochs,
         )
         .await
     }
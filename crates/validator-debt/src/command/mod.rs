@@ -1,7 +1,9 @@
 mod calculate;
 mod export_validators;
 mod initialize;
+mod summarize_collection;
 mod verify;
+mod write_off;
 
 //
 
@@ -27,9 +29,15 @@ pub enum ValidatorDebtCommand {
 
     VerifyValidatorDebt(verify::VerifyValidatorDebtCommand),
 
+    /// Verify the merkle proof of every validator debt for an epoch.
+    VerifyAllValidatorDebt(verify::VerifyAllValidatorDebtCommand),
+
     /// Export validator pubkeys for a given Solana epoch.
     ExportValidators(export_validators::ExportValidatorsCommand),
 
+    /// Summarize Solana validator debt collection for a DZ epoch.
+    SummarizeCollection(summarize_collection::SummarizeCollectionCommand),
+
     /// Finalize Epoch Distribution.
     FinalizeDistribution {
         #[command(flatten)]
@@ -40,6 +48,11 @@ pub enum ValidatorDebtCommand {
         dry_run: bool,
         #[arg(long, value_name = "FORCE")]
         force: bool,
+        /// Print the finalization result as JSON.
+        #[arg(long)]
+        json: bool,
+        #[command(flatten)]
+        slack_webhook_options: worker::SlackWebhookOptions,
     },
 
     // Initialize a new distribution on Solana.
@@ -48,6 +61,11 @@ pub enum ValidatorDebtCommand {
     // scheduler handles initialization.
     #[command(hide = true)]
     InitializeDistribution(initialize::InitializeDistributionCommand),
+
+    // Write off debt covered by an existing rewards distribution without
+    // initializing a new one.
+    #[command(hide = true)]
+    WriteOffDebt(write_off::WriteOffDebtCommand),
 }
 
 impl ValidatorDebtCommand {
@@ -56,19 +74,34 @@ impl ValidatorDebtCommand {
             ValidatorDebtCommand::InitializeDistribution(command) => {
                 command.try_into_execute().await
             }
+            ValidatorDebtCommand::WriteOffDebt(command) => command.try_into_execute().await,
             ValidatorDebtCommand::CalculateValidatorDebt(command) => {
                 command.try_into_execute().await
             }
             ValidatorDebtCommand::FindSolanaEpoch(command) => command.try_into_execute().await,
             ValidatorDebtCommand::VerifyValidatorDebt(command) => command.try_into_execute().await,
+            ValidatorDebtCommand::VerifyAllValidatorDebt(command) => {
+                command.try_into_execute().await
+            }
             ValidatorDebtCommand::ExportValidators(command) => command.try_into_execute().await,
+            ValidatorDebtCommand::SummarizeCollection(command) => command.try_into_execute().await,
             ValidatorDebtCommand::FinalizeDistribution {
                 solana_connection_options,
                 epoch,
                 dry_run,
                 force,
+                json,
+                slack_webhook_options,
             } => {
-                execute_finalize_transaction(solana_connection_options, epoch, dry_run, force).await
+                execute_finalize_transaction(
+                    solana_connection_options,
+                    epoch,
+                    dry_run,
+                    force,
+                    json,
+                    slack_webhook_options,
+                )
+                .await
             }
         }
     }
@@ -79,12 +112,25 @@ async fn execute_finalize_transaction(
     epoch: u64,
     dry_run: bool,
     force: bool,
+    json: bool,
+    slack_webhook_options: worker::SlackWebhookOptions,
 ) -> Result<()> {
     let solana_debt_calculator: SolanaDebtCalculator =
         SolanaDebtCalculator::try_from(solana_connection_options)?;
     let signer = try_load_keypair(None)?;
     let transaction = Transaction::new(signer.into(), dry_run, force);
-    worker::finalize_distribution(&solana_debt_calculator, transaction, epoch).await?;
+    let finalize_summary = worker::finalize_distribution(
+        &solana_debt_calculator,
+        transaction,
+        epoch,
+        slack_webhook_options.target(),
+    )
+    .await?;
+
+    if json {
+        println!("{}", serde_json::to_string(&finalize_summary)?);
+    }
+
     Ok(())
 }
 
@@ -0,0 +1,61 @@
+use anyhow::Result;
+use clap::Args;
+use doublezero_solana_client_tools::rpc::SolanaConnection;
+use solana_sdk::{commitment_config::CommitmentConfig, native_token::LAMPORTS_PER_SOL};
+use url::Url;
+
+use crate::{rpc::normalize_to_url_if_moniker, worker};
+
+#[derive(Debug, Args, Clone)]
+pub struct SummarizeCollectionCommand {
+    /// DZ epoch of the distribution to summarize
+    #[arg(long, short = 'e')]
+    epoch: u64,
+
+    /// URL for Solana's JSON RPC or moniker (or their first letter):
+    /// [mainnet-beta, testnet, localhost].
+    #[arg(long = "url", short = 'u')]
+    solana_url_or_moniker: Option<String>,
+
+    /// Print the summary as JSON.
+    #[arg(long)]
+    json: bool,
+}
+
+impl SummarizeCollectionCommand {
+    pub async fn try_into_execute(self) -> Result<()> {
+        let Self {
+            epoch,
+            solana_url_or_moniker,
+            json,
+        } = self;
+
+        let solana_url_or_moniker = solana_url_or_moniker.as_deref().unwrap_or("m");
+        let solana_url = Url::parse(normalize_to_url_if_moniker(solana_url_or_moniker))?;
+        let solana_connection =
+            SolanaConnection::new_with_commitment(solana_url.into(), CommitmentConfig::confirmed());
+
+        let summary = worker::summarize_collection(&solana_connection, epoch).await?;
+
+        if json {
+            println!("{}", serde_json::to_string(&summary)?);
+            return Ok(());
+        }
+
+        let format_sol =
+            |lamports: u64| format!("{:.9} SOL", lamports as f64 / LAMPORTS_PER_SOL as f64);
+
+        println!("DZ epoch:        {}", summary.dz_epoch);
+        println!("Total debt:      {}", format_sol(summary.total_debt));
+        println!("Collected:       {}", format_sol(summary.collected));
+        println!("Remaining debt:  {}", format_sol(summary.remaining_debt));
+        println!("Uncollectible:   {}", format_sol(summary.uncollectible));
+        println!(
+            "Validators paid: {} / {}",
+            summary.processed_count,
+            summary.processed_count + summary.remaining_count
+        );
+
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
-use anyhow::Result;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
 use clap::Args;
 use doublezero_solana_client_tools::{
     payer::{SolanaPayerOptions, try_load_keypair},
@@ -6,7 +8,8 @@ use doublezero_solana_client_tools::{
 };
 use doublezero_solana_sdk::revenue_distribution::state::ProgramConfig;
 use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use tabled::{Table, settings::Style};
 
 use crate::{
     rpc::SolanaValidatorDebtConnectionOptions, solana_debt_calculator::SolanaDebtCalculator,
@@ -77,6 +80,113 @@ impl VerifyValidatorDebtCommand {
     }
 }
 
+#[derive(Debug, Args, Clone)]
+pub struct VerifyAllValidatorDebtCommand {
+    #[arg(long)]
+    epoch: Option<u64>,
+
+    /// Instead of verifying every proof, export this validator's debt leaf,
+    /// amount, merkle proof, leaf index and on-chain root to JSON.
+    #[arg(long, value_name = "NODE_ID", requires = "output")]
+    dump_proof: Option<Pubkey>,
+
+    /// Path to write the exported proof to.
+    #[arg(long, value_name = "PATH", requires = "dump_proof")]
+    output: Option<PathBuf>,
+
+    #[command(flatten)]
+    solana_payer_options: SolanaPayerOptions,
+
+    #[command(flatten)]
+    dz_ledger_connection_options: DoubleZeroLedgerConnectionOptions,
+}
+
+impl VerifyAllValidatorDebtCommand {
+    pub async fn try_into_execute(self) -> Result<()> {
+        let Self {
+            epoch,
+            dump_proof,
+            output,
+            solana_payer_options,
+            dz_ledger_connection_options,
+        } = self;
+
+        let epoch = match epoch {
+            Some(epoch) => epoch,
+            None => {
+                latest_distribution_epoch(
+                    &solana_payer_options.connection_options,
+                    &dz_ledger_connection_options,
+                )
+                .await?
+            }
+        };
+
+        let connection_options = SolanaValidatorDebtConnectionOptions {
+            solana_url_or_moniker: solana_payer_options
+                .connection_options
+                .solana_url_or_moniker
+                .clone(),
+            dz_ledger_url: dz_ledger_connection_options.dz_ledger_url.clone(),
+        };
+
+        let solana_debt_calculator: SolanaDebtCalculator =
+            SolanaDebtCalculator::try_from(connection_options)?;
+        let signer = try_load_keypair(None).expect("failed to load keypair");
+        let transaction = Transaction::new(signer.into(), true, false);
+
+        if let Some(node_id) = dump_proof
+            && let Some(output) = output
+        {
+            let export = crate::worker::export_debt_proof(
+                &solana_debt_calculator,
+                transaction,
+                epoch,
+                &node_id,
+            )
+            .await?;
+
+            std::fs::write(&output, serde_json::to_string_pretty(&export)?)
+                .with_context(|| format!("Failed to write proof to {}", output.display()))?;
+
+            if !export.verified {
+                bail!(
+                    "Debt proof for {node_id} does not match the on-chain root for DZ epoch {epoch}"
+                );
+            }
+
+            println!(
+                "Debt proof for {node_id} in DZ epoch {epoch} written to {}",
+                output.display()
+            );
+
+            return Ok(());
+        }
+
+        let verifications =
+            crate::worker::verify_all_validator_debt(&solana_debt_calculator, transaction, epoch)
+                .await?;
+
+        let failed_count = verifications.iter().filter(|v| !v.verified).count();
+        let total_count = verifications.len();
+
+        println!(
+            "{}",
+            Table::new(verifications).with(Style::psql().remove_horizontals())
+        );
+
+        if failed_count > 0 {
+            bail!(
+                "{failed_count} of {total_count} debt proofs failed verification for DZ epoch {epoch}"
+            );
+        }
+
+        println!("All {total_count} debt proofs verified for DZ epoch {epoch}");
+
+        Ok(())
+    }
+}
+
 // TODO: Does the dz ledger connection need to be an argument? Also, this is a
 // duplicate of the function in calculate.rs.
 async fn latest_distribution_epoch(
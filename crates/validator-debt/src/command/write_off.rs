@@ -0,0 +1,56 @@
+use anyhow::Result;
+use clap::Args;
+use doublezero_solana_client_tools::{
+    payer::{SolanaPayerOptions, Wallet},
+    rpc::DoubleZeroLedgerEnvironmentOverride,
+};
+use doublezero_solana_sdk::revenue_distribution::types::DoubleZeroEpoch;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::worker;
+
+#[derive(Debug, Args, Clone)]
+pub struct WriteOffDebtCommand {
+    /// DZ epoch of the rewards distribution that absorbs the written off
+    /// debt.
+    #[arg(long)]
+    epoch: u64,
+
+    #[command(flatten)]
+    solana_payer_options: SolanaPayerOptions,
+
+    #[command(flatten)]
+    dz_env: DoubleZeroLedgerEnvironmentOverride,
+
+    #[arg(hide = true, long)]
+    record_debt_accountant: Option<Pubkey>,
+
+    /// Stop looking for debt to write off after this many consecutive older
+    /// epochs with all debt processed. Zero, the default, scans back to
+    /// genesis.
+    #[arg(hide = true, long, default_value_t = worker::DEFAULT_WRITE_OFF_SETTLED_EPOCHS)]
+    write_off_settled_epochs: usize,
+}
+
+impl WriteOffDebtCommand {
+    pub async fn try_into_execute(self) -> Result<()> {
+        let Self {
+            epoch,
+            solana_payer_options,
+            dz_env,
+            record_debt_accountant: record_accountant_key,
+            write_off_settled_epochs,
+        } = self;
+
+        let wallet = Wallet::try_from(solana_payer_options)?;
+
+        worker::write_off_debt(
+            &wallet,
+            dz_env.dz_env,
+            record_accountant_key,
+            DoubleZeroEpoch::new(epoch),
+            write_off_settled_epochs,
+        )
+        .await
+    }
+}
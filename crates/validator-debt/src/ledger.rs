@@ -152,16 +152,57 @@ pub async fn ensure_same_network_environment(
     is_mainnet: bool,
 ) -> Result<()> {
     let genesis_hash = dz_ledger_rpc.get_genesis_hash().await?;
+    check_same_network_environment(&genesis_hash, is_mainnet)
+}
 
+fn check_same_network_environment(dz_ledger_genesis_hash: &Hash, is_mainnet: bool) -> Result<()> {
     // This check is safe to do because there are only two possible DoubleZero
     // Ledger networks: mainnet and testnet.
-    if (is_mainnet
-        && genesis_hash.to_bytes() != DOUBLEZERO_LEDGER_MAINNET_BETA_GENESIS_HASH.to_bytes())
-        || (!is_mainnet
-            && genesis_hash.to_bytes() == DOUBLEZERO_LEDGER_MAINNET_BETA_GENESIS_HASH.to_bytes())
-    {
-        bail!("DoubleZero Ledger environment is not the same as the Solana environment");
+    let is_dz_ledger_mainnet =
+        dz_ledger_genesis_hash.to_bytes() == DOUBLEZERO_LEDGER_MAINNET_BETA_GENESIS_HASH.to_bytes();
+
+    if is_dz_ledger_mainnet != is_mainnet {
+        let network_name = |is_mainnet| {
+            if is_mainnet {
+                "mainnet-beta"
+            } else {
+                "testnet"
+            }
+        };
+        bail!(
+            "DoubleZero Ledger environment ({}) is not the same as the Solana environment ({})",
+            network_name(is_dz_ledger_mainnet),
+            network_name(is_mainnet)
+        );
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_same_network_environment() {
+        let mainnet_genesis_hash =
+            Hash::new_from_array(DOUBLEZERO_LEDGER_MAINNET_BETA_GENESIS_HASH.to_bytes());
+        let testnet_genesis_hash = Hash::new_unique();
+
+        assert!(check_same_network_environment(&mainnet_genesis_hash, true).is_ok());
+        assert!(check_same_network_environment(&testnet_genesis_hash, false).is_ok());
+
+        assert_eq!(
+            check_same_network_environment(&testnet_genesis_hash, true)
+                .unwrap_err()
+                .to_string(),
+            "DoubleZero Ledger environment (testnet) is not the same as the Solana environment (mainnet-beta)"
+        );
+        assert_eq!(
+            check_same_network_environment(&mainnet_genesis_hash, false)
+                .unwrap_err()
+                .to_string(),
+            "DoubleZero Ledger environment (mainnet-beta) is not the same as the Solana environment (testnet)"
+        );
+    }
+}
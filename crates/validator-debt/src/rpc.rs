@@ -1,4 +1,4 @@
-use anyhow::{Error, Result, bail, ensure};
+use anyhow::{Result, bail, ensure};
 use clap::Args;
 use doublezero_solana_client_tools::{
     account::{record::BorshRecordAccountData, zero_copy::ZeroCopyAccountOwnedData},
@@ -23,6 +23,7 @@ use solana_client::{
 };
 use solana_sdk::commitment_config::CommitmentConfig;
 use solana_transaction_status_client_types::{TransactionDetails, UiTransactionEncoding};
+use thiserror::Error;
 use url::Url;
 
 use crate::{
@@ -41,24 +42,48 @@ pub struct SolanaValidatorDebtConnectionOptions {
     pub solana_url_or_moniker: Option<String>,
 }
 
+/// Why [SolanaValidatorDebtConnectionOptions] could not be turned into a
+/// [SolanaDebtCalculator].
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SolanaValidatorDebtConnectionError {
+    /// Neither a valid URL nor a known moniker
+    #[error(
+        "Invalid Solana URL or moniker '{value}': {message}. Expected an http(s) URL or one of mainnet-beta, testnet, localhost (or their first letter)"
+    )]
+    InvalidSolanaUrlOrMoniker { value: String, message: String },
+
+    /// No DoubleZero Ledger URL was given
+    #[error("DoubleZero Ledger URL is required")]
+    MissingLedgerUrl,
+
+    /// DoubleZero Ledger URL could not be parsed
+    #[error("Invalid DoubleZero Ledger URL '{url}': {message}")]
+    InvalidLedgerUrl { url: String, message: String },
+
+    /// DoubleZero Ledger URL parses but cannot point at a JSON RPC server
+    #[error("DoubleZero Ledger URL '{url}' is unreachable: {message}")]
+    UnreachableLedgerUrl { url: String, message: String },
+}
+
 impl TryFrom<SolanaValidatorDebtConnectionOptions> for SolanaDebtCalculator {
-    type Error = Error;
+    type Error = SolanaValidatorDebtConnectionError;
 
-    fn try_from(opts: SolanaValidatorDebtConnectionOptions) -> Result<SolanaDebtCalculator> {
+    fn try_from(
+        opts: SolanaValidatorDebtConnectionOptions,
+    ) -> Result<SolanaDebtCalculator, Self::Error> {
         let SolanaValidatorDebtConnectionOptions {
             solana_url_or_moniker,
             dz_ledger_url,
         } = opts;
 
-        let ledger_rpc_client = Url::parse(&dz_ledger_url).map(|url| {
-            DoubleZeroLedgerConnection::new_with_commitment(
-                url.into(),
-                CommitmentConfig::confirmed(),
-            )
-        })?;
+        let ledger_url = try_parse_ledger_url(&dz_ledger_url)?;
+        let ledger_rpc_client = DoubleZeroLedgerConnection::new_with_commitment(
+            ledger_url.into(),
+            CommitmentConfig::confirmed(),
+        );
 
         let solana_url_or_moniker = solana_url_or_moniker.as_deref().unwrap_or("m");
-        let solana_url = Url::parse(normalize_to_url_if_moniker(solana_url_or_moniker))?;
+        let solana_url = try_parse_solana_url_or_moniker(solana_url_or_moniker)?;
 
         let solana_rpc_client =
             RpcClient::new_with_commitment(solana_url.into(), CommitmentConfig::confirmed());
@@ -87,6 +112,55 @@ impl TryFrom<SolanaValidatorDebtConnectionOptions> for SolanaDebtCalculator {
     }
 }
 
+fn try_parse_ledger_url(url: &str) -> Result<Url, SolanaValidatorDebtConnectionError> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Err(SolanaValidatorDebtConnectionError::MissingLedgerUrl);
+    }
+
+    let parsed_url =
+        Url::parse(url).map_err(|e| SolanaValidatorDebtConnectionError::InvalidLedgerUrl {
+            url: url.to_string(),
+            message: e.to_string(),
+        })?;
+
+    ensure_rpc_url(&parsed_url).map_err(|message| {
+        SolanaValidatorDebtConnectionError::UnreachableLedgerUrl {
+            url: url.to_string(),
+            message,
+        }
+    })?;
+
+    Ok(parsed_url)
+}
+
+fn try_parse_solana_url_or_moniker(
+    url_or_moniker: &str,
+) -> Result<Url, SolanaValidatorDebtConnectionError> {
+    let invalid = |message: String| SolanaValidatorDebtConnectionError::InvalidSolanaUrlOrMoniker {
+        value: url_or_moniker.to_string(),
+        message,
+    };
+
+    let url = Url::parse(normalize_to_url_if_moniker(url_or_moniker))
+        .map_err(|e| invalid(e.to_string()))?;
+    ensure_rpc_url(&url).map_err(invalid)?;
+
+    Ok(url)
+}
+
+/// JSON RPC is only served over HTTP(S).
+fn ensure_rpc_url(url: &Url) -> Result<(), String> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!("unsupported scheme '{}'", url.scheme()));
+    }
+    if url.host_str().is_none_or(str::is_empty) {
+        return Err("missing host".to_string());
+    }
+
+    Ok(())
+}
+
 // Forked from solana-clap-utils.
 pub fn normalize_to_url_if_moniker(url_or_moniker: &str) -> &str {
     match url_or_moniker {
@@ -368,3 +442,62 @@ pub async fn try_fetch_debt_records_and_distributions(
 
     Ok(debt_records.into_iter().zip(distributions).collect())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn try_calculator(
+        solana_url_or_moniker: Option<&str>,
+        dz_ledger_url: &str,
+    ) -> Result<SolanaDebtCalculator, SolanaValidatorDebtConnectionError> {
+        SolanaDebtCalculator::try_from(SolanaValidatorDebtConnectionOptions {
+            dz_ledger_url: dz_ledger_url.to_string(),
+            solana_url_or_moniker: solana_url_or_moniker.map(ToString::to_string),
+        })
+    }
+
+    #[test]
+    fn test_connection_options() {
+        const LEDGER_URL: &str = "https://ledger.example.com";
+
+        assert!(try_calculator(None, LEDGER_URL).is_ok());
+        assert!(try_calculator(Some("t"), LEDGER_URL).is_ok());
+        assert!(try_calculator(Some("http://127.0.0.1:8899"), LEDGER_URL).is_ok());
+
+        // Typo in a moniker.
+        let err = try_calculator(Some("mainnet"), LEDGER_URL).err().unwrap();
+        assert!(matches!(
+            err,
+            SolanaValidatorDebtConnectionError::InvalidSolanaUrlOrMoniker { ref value, .. }
+                if value == "mainnet"
+        ));
+        assert!(err.to_string().contains("mainnet-beta, testnet, localhost"));
+
+        let err = try_calculator(Some("ws://127.0.0.1:8900"), LEDGER_URL)
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err,
+            SolanaValidatorDebtConnectionError::InvalidSolanaUrlOrMoniker { .. }
+        ));
+
+        assert_eq!(
+            try_calculator(None, " ").err().unwrap(),
+            SolanaValidatorDebtConnectionError::MissingLedgerUrl
+        );
+
+        assert!(matches!(
+            try_calculator(None, "ledger.example.com").err().unwrap(),
+            SolanaValidatorDebtConnectionError::InvalidLedgerUrl { .. }
+        ));
+
+        assert_eq!(
+            try_calculator(None, "file:///tmp/ledger").err().unwrap(),
+            SolanaValidatorDebtConnectionError::UnreachableLedgerUrl {
+                url: "file:///tmp/ledger".to_string(),
+                message: "unsupported scheme 'file'".to_string(),
+            }
+        );
+    }
+}
@@ -3,7 +3,8 @@
 //! This module fetches validator public keys from the S3 metrics bucket by:
 //! 1. Downloading hourly Parquet snapshots for a given Solana epoch
 //! 2. Merging gossip, validators, users, and devices datasets
-//! 3. Applying the 12-hour connection rule (validators must appear in >12 hourly snapshots)
+//! 3. Applying the 12-hour connection rule (validators must appear in more than
+//!    `min_hourly_appearances` hourly snapshots, >12 by default)
 //! 4. Returning the list of qualifying validator public keys
 //!
 //! This replicates the canonical Python script approach for identifying validators
@@ -18,37 +19,54 @@
 //! Optional:
 //! - `VALIDATOR_DEBT_S3_BUCKET`: S3 bucket name (default: "malbeclabs-data-metrics-dev")
 //! - `VALIDATOR_DEBT_AWS_REGION`: AWS region (default: "us-east-1")
-//! - `VALIDATOR_DEBT_S3_MAX_CONSECUTIVE_FAILURES`: Max consecutive failures before stopping (default: 12)
+//! - `VALIDATOR_DEBT_S3_MAX_CONSECUTIVE_FAILURES`: Max consecutive failed hours before the fetch is aborted (default: 12)
+//! - `VALIDATOR_DEBT_S3_MAX_RETRIES`: Retries per S3 object before its hour is dropped (default: 3)
 //! - `VALIDATOR_DEBT_S3_ENDPOINT`: Custom S3 endpoint for S3-compatible services (optional)
+//! - `VALIDATOR_DEBT_S3_CACHE_DIR`: Directory for caching downloaded Parquet files across runs.
+//!   Entries are keyed by bucket and object key and revalidated against the object's ETag.
 
 use std::{
     collections::{HashMap, HashSet},
     env,
     fs::File as StdFile,
+    future::Future,
+    path::{Path, PathBuf},
     sync::Arc,
+    time::Duration as StdDuration,
 };
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, anyhow, bail};
 use arrow::{
     array::{Array, AsArray, BooleanArray, RecordBatch, StringArray},
     datatypes::DataType,
 };
+use async_trait::async_trait;
 use aws_config::BehaviorVersion;
 use aws_sdk_s3::{
     Client as S3Client,
     config::{Credentials, Region},
 };
+use backon::{ExponentialBuilder, Retryable};
 use chrono::{DateTime, Duration, Timelike, Utc};
+use clap::ValueEnum;
+use doublezero_solana_client_tools::rpc::NetworkEnvironment;
 use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 use serde::Serialize;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use tempfile::NamedTempFile;
-use tokio::{fs::File, io::AsyncWriteExt, sync::Semaphore, task::JoinSet};
+use tokio::{fs, fs::File, io::AsyncWriteExt, sync::Semaphore, task::JoinSet};
 use tracing::{debug, info, warn};
 
 /// Maximum number of concurrent S3 downloads
 const MAX_CONCURRENT_DOWNLOADS: usize = 10;
 
+/// Default number of retries per S3 object
+const DEFAULT_MAX_RETRIES: usize = 3;
+
+/// Default threshold for the 12-hour connection rule. Validators must appear
+/// in more than this many hourly snapshots
+pub const DEFAULT_MIN_HOURLY_APPEARANCES: usize = 12;
+
 /// Vote account key -> Number of hours recorded
 type VoteAccountHours = HashMap<String, usize>;
 
@@ -77,7 +95,7 @@ impl ValidatorKey {
 }
 
 /// Network type for dataset selection
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 pub enum Network {
     MainnetBeta,
     Testnet,
@@ -92,12 +110,74 @@ impl Network {
     }
 }
 
+impl TryFrom<NetworkEnvironment> for Network {
+    type Error = anyhow::Error;
+
+    fn try_from(network_env: NetworkEnvironment) -> Result<Self> {
+        match network_env {
+            NetworkEnvironment::MainnetBeta => Ok(Network::MainnetBeta),
+            NetworkEnvironment::Testnet => Ok(Network::Testnet),
+            NetworkEnvironment::Localnet => Err(anyhow!(
+                "No S3 validator datasets exist for localnet, specify the network explicitly"
+            )),
+        }
+    }
+}
+
+/// Object body and ETag returned by a GET request
+struct S3Object {
+    body: Vec<u8>,
+    etag: Option<String>,
+}
+
+/// Minimal object store interface used to fetch Parquet snapshots
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+trait S3ObjectStore: Send + Sync {
+    /// Returns the current ETag of an object without downloading it
+    async fn fetch_etag(&self, bucket: &str, key: &str) -> Result<Option<String>>;
+    /// Downloads an object
+    async fn fetch_object(&self, bucket: &str, key: &str) -> Result<S3Object>;
+}
+
+#[async_trait]
+impl S3ObjectStore for S3Client {
+    async fn fetch_etag(&self, bucket: &str, key: &str) -> Result<Option<String>> {
+        let response = self
+            .head_object()
+            .bucket(bucket)
+            .key(key)
+            .send()
+            .await
+            .context(format!("Failed to fetch S3 object metadata: {}", key))?;
+
+        Ok(response.e_tag().map(str::to_string))
+    }
+
+    async fn fetch_object(&self, bucket: &str, key: &str) -> Result<S3Object> {
+        let response = self
+            .get_object()
+            .bucket(bucket)
+            .key(key)
+            .send()
+            .await
+            .context(format!("Failed to download S3 object: {}", key))?;
+
+        let etag = response.e_tag().map(str::to_string);
+        let body = response.body.collect().await?.into_bytes().to_vec();
+
+        Ok(S3Object { body, etag })
+    }
+}
+
 /// S3 configuration
 #[derive(Clone)]
 struct S3Config {
-    client: S3Client,
+    client: Arc<dyn S3ObjectStore>,
     bucket: String,
     max_consecutive_failures: usize,
+    max_retries: usize,
+    cache_dir: Option<PathBuf>,
 }
 
 impl S3Config {
@@ -110,6 +190,11 @@ impl S3Config {
             .and_then(|v| v.parse().ok())
             .unwrap_or(12);
 
+        let max_retries = env::var("VALIDATOR_DEBT_S3_MAX_RETRIES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_MAX_RETRIES);
+
         // Load AWS credentials from environment variables
         let access_key_id = env::var("VALIDATOR_DEBT_AWS_ACCESS_KEY_ID")
             .context("VALIDATOR_DEBT_AWS_ACCESS_KEY_ID environment variable not set")?;
@@ -144,15 +229,21 @@ impl S3Config {
         let config = config_builder.build();
         let client = S3Client::from_conf(config);
 
+        let cache_dir = env::var("VALIDATOR_DEBT_S3_CACHE_DIR")
+            .ok()
+            .map(PathBuf::from);
+
         info!(
-            "S3 client initialized: bucket={}, region={}, max_consecutive_failures={}",
-            bucket, region, max_consecutive_failures
+            "S3 client initialized: bucket={}, region={}, max_consecutive_failures={}, max_retries={}, cache_dir={:?}",
+            bucket, region, max_consecutive_failures, max_retries, cache_dir
         );
 
         Ok(Self {
-            client,
+            client: Arc::new(client),
             bucket,
             max_consecutive_failures,
+            max_retries,
+            cache_dir,
         })
     }
 }
@@ -163,16 +254,18 @@ impl S3Config {
 /// 1. Converts epoch to timestamp range
 /// 2. Downloads hourly Parquet files from S3
 /// 3. Merges datasets and applies filters
-/// 4. Applies 12-hour connection rule
+/// 4. Applies the connection rule: a vote account qualifies only if it appears
+///    in more than `min_hourly_appearances` hourly snapshots
 /// 5. Returns validator keys
 pub async fn fetch_validator_pubkeys(
     solana_epoch: u64,
     rpc_client: &RpcClient,
     network: Network,
+    min_hourly_appearances: usize,
 ) -> Result<Vec<ValidatorKey>> {
     info!(
-        "Fetching validator pubkeys for Solana epoch {} ({:?})",
-        solana_epoch, network
+        "Fetching validator pubkeys for Solana epoch {} ({:?}), min hourly appearances: {}",
+        solana_epoch, network, min_hourly_appearances
     );
 
     let s3_config = S3Config::new().await?;
@@ -192,23 +285,13 @@ pub async fn fetch_validator_pubkeys(
         solana_epoch
     );
 
-    // Fetch and process hourly data in parallel
-    let sem = Arc::new(Semaphore::new(MAX_CONCURRENT_DOWNLOADS));
-    let mut tasks = JoinSet::new();
-
-    // Spawn tasks for all hourly snapshots
-    for timestamp in hourly_timestamps {
-        let s3_config_clone = s3_config.clone();
-        let sem_clone = sem.clone();
-
-        tasks.spawn(async move {
-            // Acquire permit to limit concurrent downloads
-            let _permit = sem_clone.acquire().await.unwrap();
-
-            let result = process_hourly_data(&s3_config_clone, timestamp, network).await;
-
-            (timestamp, result)
-        });
+    let hourly_validators = fetch_hourly_validators(&s3_config, hourly_timestamps, network).await?;
+    if !hourly_validators.dropped.is_empty() {
+        warn!(
+            "Epoch {} is missing {} hourly snapshots; they count as hours without an appearance",
+            solana_epoch,
+            hourly_validators.dropped.len()
+        );
     }
 
     let leader_scheduling_epoch = solana_epoch.saturating_sub(2);
@@ -225,161 +308,239 @@ pub async fn fetch_validator_pubkeys(
     info!(
         "Processing {} hourly snapshots for epoch {}",
         hourly_timestamps.len(),
-        solana_epoch
+        leader_scheduling_epoch
     );
 
-    let mut two_epochs_ago_tasks = JoinSet::new();
-
-    // Spawn tasks for all hourly snapshots
-    for timestamp in hourly_timestamps {
-        let s3_config_clone = s3_config.clone();
-        let sem_clone = sem.clone();
-
-        two_epochs_ago_tasks.spawn(async move {
-            // Acquire permit to limit concurrent downloads
-            let _ = sem_clone.acquire().await.unwrap();
-
-            let result = download_and_parse_parquet(
-                &s3_config_clone,
-                &format!("snapshot-solana-{}-validators", network.prefix()),
-                timestamp,
-            )
-            .await;
-
-            (timestamp, result)
-        });
-    }
+    let validators_prefix = format!("snapshot-solana-{}-validators", network.prefix());
+    let two_epochs_ago_batches = collect_hourly_results(
+        "leader scheduling validators",
+        hourly_timestamps,
+        s3_config.max_consecutive_failures,
+        |timestamp| {
+            let s3_config = s3_config.clone();
+            let prefix = validators_prefix.clone();
+            async move { download_and_parse_parquet(&s3_config, &prefix, timestamp).await }
+        },
+    )
+    .await?;
 
     // This is a bloody hack to get the identities of validators that had active
     // stake two epochs ago. We should clean this up later.
     let mut two_epochs_ago_vote_key_identities = HashMap::new();
 
-    // Collect results as they complete
+    for (_, batches) in two_epochs_ago_batches.succeeded {
+        let vote_key_identities = build_lut(&batches, "identity_pubkey")?
+            .into_iter()
+            .map(|(k, mut v)| (v.remove("vote_account_pubkey").unwrap(), k))
+            .collect::<Vec<_>>();
+
+        for (vote_key, identity) in vote_key_identities {
+            two_epochs_ago_vote_key_identities
+                .entry(vote_key)
+                .or_insert(HashSet::new())
+                .insert(identity);
+        }
+    }
+
     // Count hours by vote_account_pubkey (not identity_pubkey) to prevent rotation
     let mut vote_account_hours = VoteAccountHours::new();
     // Track all identity_pubkeys associated with each vote_account_pubkey
     let mut vote_account_identities = VoteAccountIdentities::new();
 
-    let mut processed_count = 0;
-    let mut failed_count = 0;
-    let total_hours = two_epochs_ago_tasks.len();
-
-    while let Some(task_result) = two_epochs_ago_tasks.join_next().await {
-        match task_result {
-            Ok((timestamp, Ok(batches))) => {
-                processed_count += 1;
-
-                let vote_key_identities = build_lut(&batches, "identity_pubkey")?
-                    .into_iter()
-                    .map(|(k, mut v)| (v.remove("vote_account_pubkey").unwrap(), k))
-                    .collect::<Vec<_>>();
-
-                for (vote_key, identity) in vote_key_identities {
-                    two_epochs_ago_vote_key_identities
-                        .entry(vote_key)
-                        .or_insert(HashSet::new())
-                        .insert(identity);
-                }
-
-                info!(
-                    "Processed vote key identities for two epochs ago hour {} [{}/{}]",
-                    timestamp.format("%Y-%m-%d %H:00"),
-                    processed_count + failed_count,
-                    total_hours,
-                );
-            }
-            Ok((timestamp, Err(e))) => {
-                failed_count += 1;
-                warn!(
-                    "Failed to process hour {} [{}/{}]: {}",
-                    timestamp.format("%Y-%m-%d %H:00"),
-                    processed_count + failed_count,
-                    total_hours,
-                    e
-                );
-            }
-            Err(e) => {
-                failed_count += 1;
-                warn!("Task join error: {}", e);
-            }
+    for (_, validators) in hourly_validators.succeeded {
+        // Count appearances by vote_account_pubkey and track all identities
+        for validator in validators {
+            *vote_account_hours
+                .entry(validator.vote_account_pubkey.clone())
+                .or_insert(0) += 1;
+
+            let relevant_identities = two_epochs_ago_vote_key_identities
+                .get(&validator.vote_account_pubkey)
+                .cloned()
+                .unwrap_or_default();
+
+            vote_account_identities
+                .entry(validator.vote_account_pubkey)
+                .or_default()
+                .extend(relevant_identities);
         }
     }
 
-    let mut processed_count = 0;
-    let mut failed_count = 0;
-    let total_hours = tasks.len();
+    let (qualified_validators, qualified_vote_accounts, filtered_vote_accounts) =
+        apply_hourly_appearance_rule(
+            vote_account_hours,
+            vote_account_identities,
+            min_hourly_appearances,
+        );
+
+    info!(
+        "Applied hourly appearance rule (more than {}): {} vote accounts qualified, {} filtered out, {} identity pubkeys returned",
+        min_hourly_appearances,
+        qualified_vote_accounts,
+        filtered_vote_accounts,
+        qualified_validators.len()
+    );
+
+    Ok(qualified_validators)
+}
+
+/// Outcome of fetching one dataset for a range of hours
+struct HourlyResults<T> {
+    /// Successfully processed hours, sorted by timestamp
+    succeeded: Vec<(DateTime<Utc>, T)>,
+    /// Hours that failed after all retries, sorted by timestamp
+    dropped: Vec<DateTime<Utc>>,
+}
+
+/// Downloads and merges the hourly datasets, returning the validators found in each hour
+async fn fetch_hourly_validators(
+    s3_config: &S3Config,
+    hourly_timestamps: Vec<DateTime<Utc>>,
+    network: Network,
+) -> Result<HourlyResults<Vec<ValidatorKey>>> {
+    collect_hourly_results(
+        "validators",
+        hourly_timestamps,
+        s3_config.max_consecutive_failures,
+        |timestamp| {
+            let s3_config = s3_config.clone();
+            async move { process_hourly_data(&s3_config, timestamp, network).await }
+        },
+    )
+    .await
+}
+
+/// Runs `fetch_hour` for every hour with bounded concurrency
+///
+/// Hours that fail are dropped and logged rather than aborting the whole fetch,
+/// unless `max_consecutive_failures` or more consecutive hours are missing.
+async fn collect_hourly_results<T, F, Fut>(
+    dataset: &str,
+    hourly_timestamps: Vec<DateTime<Utc>>,
+    max_consecutive_failures: usize,
+    fetch_hour: F,
+) -> Result<HourlyResults<T>>
+where
+    T: Send + 'static,
+    F: Fn(DateTime<Utc>) -> Fut,
+    Fut: Future<Output = Result<T>> + Send + 'static,
+{
+    let sem = Arc::new(Semaphore::new(MAX_CONCURRENT_DOWNLOADS));
+    let mut tasks = JoinSet::new();
+    let total_hours = hourly_timestamps.len();
+
+    // Spawn tasks for all hourly snapshots
+    for timestamp in hourly_timestamps.iter().copied() {
+        let sem_clone = sem.clone();
+        let fetch = fetch_hour(timestamp);
 
+        tasks.spawn(async move {
+            // Acquire permit to limit concurrent downloads
+            let _permit = sem_clone.acquire().await.unwrap();
+
+            (timestamp, fetch.await)
+        });
+    }
+
+    // Collect results as they complete
+    let mut succeeded = Vec::new();
     while let Some(task_result) = tasks.join_next().await {
         match task_result {
-            Ok((timestamp, Ok(validators))) => {
-                processed_count += 1;
-                let count = validators.len();
-
-                // Count appearances by vote_account_pubkey and track all identities
-                for validator in validators {
-                    *vote_account_hours
-                        .entry(validator.vote_account_pubkey.clone())
-                        .or_insert(0) += 1;
-
-                    let relevant_identities = two_epochs_ago_vote_key_identities
-                        .get(&validator.vote_account_pubkey)
-                        .cloned()
-                        .unwrap_or_default();
-
-                    vote_account_identities
-                        .entry(validator.vote_account_pubkey)
-                        .or_default()
-                        .extend(relevant_identities);
-                }
-
+            Ok((timestamp, Ok(result))) => {
+                succeeded.push((timestamp, result));
                 info!(
-                    "Hour {} [{}/{}]: Found {} validators (total unique vote accounts: {})",
+                    "Processed {} for hour {} [{}/{}]",
+                    dataset,
                     timestamp.format("%Y-%m-%d %H:00"),
-                    processed_count,
+                    succeeded.len(),
                     total_hours,
-                    count,
-                    vote_account_hours.len()
                 );
             }
             Ok((timestamp, Err(e))) => {
-                failed_count += 1;
                 warn!(
-                    "Failed to process hour {} [{}/{}]: {}",
+                    "Failed to process {} for hour {}: {:#}",
+                    dataset,
                     timestamp.format("%Y-%m-%d %H:00"),
-                    processed_count + failed_count,
-                    total_hours,
                     e
                 );
             }
             Err(e) => {
-                failed_count += 1;
                 warn!("Task join error: {}", e);
             }
         }
     }
+    succeeded.sort_by_key(|(timestamp, _)| *timestamp);
+
+    let mut dropped = hourly_timestamps;
+    dropped.retain(|timestamp| {
+        succeeded
+            .binary_search_by_key(timestamp, |(succeeded_timestamp, _)| *succeeded_timestamp)
+            .is_err()
+    });
+    dropped.sort();
 
-    if failed_count > 0 {
+    if !dropped.is_empty() {
         warn!(
-            "Completed with {} successful and {} failed hours",
-            processed_count, failed_count
+            "Dropped {} of {} hours for {}: {}",
+            dropped.len(),
+            total_hours,
+            dataset,
+            dropped
+                .iter()
+                .map(|timestamp| timestamp.format("%Y-%m-%d %H:00").to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
         );
 
-        // Check if we exceeded the failure threshold
-        if failed_count >= s3_config.max_consecutive_failures {
-            warn!(
-                "Failed hour count ({}) exceeded threshold ({})",
-                failed_count, s3_config.max_consecutive_failures
+        let longest_gap = longest_consecutive_run(&dropped);
+        if longest_gap >= max_consecutive_failures {
+            bail!(
+                "{} consecutive hours missing for {} (threshold: {})",
+                longest_gap,
+                dataset,
+                max_consecutive_failures
             );
         }
     }
 
-    // Apply 12-hour connection rule by vote_account_pubkey
-    // When a vote_account qualifies, return ALL associated identity_pubkeys
+    Ok(HourlyResults { succeeded, dropped })
+}
+
+/// Length of the longest run of sorted timestamps that are exactly one hour apart
+fn longest_consecutive_run(timestamps: &[DateTime<Utc>]) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    let mut previous: Option<DateTime<Utc>> = None;
+
+    for timestamp in timestamps {
+        current = match previous {
+            Some(previous) if *timestamp - previous == Duration::hours(1) => current + 1,
+            _ => 1,
+        };
+        longest = longest.max(current);
+        previous = Some(*timestamp);
+    }
+
+    longest
+}
+
+/// Applies the connection rule by vote_account_pubkey. A vote account qualifies
+/// when it appears in more than `min_hourly_appearances` hours; when it
+/// qualifies, ALL associated identity_pubkeys are returned.
+///
+/// Returns the qualifying validator keys, the number of qualifying vote accounts
+/// and the number of vote accounts filtered out by the rule.
+fn apply_hourly_appearance_rule(
+    vote_account_hours: VoteAccountHours,
+    mut vote_account_identities: VoteAccountIdentities,
+    min_hourly_appearances: usize,
+) -> (Vec<ValidatorKey>, usize, usize) {
     let mut qualified_validators = Vec::new();
     let mut qualified_vote_accounts = 0;
+    let mut filtered_vote_accounts = 0;
 
     for (vote_account, hours) in vote_account_hours {
-        if hours > 12 {
+        if hours > min_hourly_appearances {
             qualified_vote_accounts += 1;
             // Get all identity_pubkeys for this qualifying vote_account
             if let Some(identities) = vote_account_identities.remove(&vote_account) {
@@ -392,18 +553,18 @@ pub async fn fetch_validator_pubkeys(
                     ));
                 }
             }
+        } else {
+            filtered_vote_accounts += 1;
         }
     }
 
     qualified_validators.sort_by(|a, b| a.vote_account_pubkey.cmp(&b.vote_account_pubkey));
 
-    info!(
-        "Applied 12-hour rule: {} vote accounts qualified, {} identity pubkeys returned",
+    (
+        qualified_validators,
         qualified_vote_accounts,
-        qualified_validators.len()
-    );
-
-    Ok(qualified_validators)
+        filtered_vote_accounts,
+    )
 }
 
 /// Converts Solana epoch number to start and end timestamps
@@ -513,38 +674,142 @@ async fn process_hourly_data(
     extract_validator_identities(merged)
 }
 
-/// Downloads a Parquet file from S3 and parses it with Arrow
+/// Downloads a Parquet file from S3 (or reuses a cached copy) and parses it with Arrow
 async fn download_and_parse_parquet(
     s3_config: &S3Config,
     prefix: &str,
     timestamp: DateTime<Utc>,
 ) -> Result<Vec<RecordBatch>> {
     let key = build_s3_key(prefix, timestamp);
+
+    let local_file = (|| fetch_local_parquet(s3_config, &key))
+        .retry(
+            &ExponentialBuilder::default()
+                .with_max_times(s3_config.max_retries)
+                .with_min_delay(StdDuration::from_millis(100))
+                .with_max_delay(StdDuration::from_secs(5))
+                .with_jitter(),
+        )
+        .notify(|err: &anyhow::Error, dur: StdDuration| {
+            debug!("Fetching {} failed, retrying in {:?}: {:#}", key, dur, err);
+        })
+        .await?;
+
+    parse_parquet(local_file.path(), &key)
+}
+
+/// Fetches a local copy of an object, through the cache when one is configured
+async fn fetch_local_parquet(s3_config: &S3Config, key: &str) -> Result<LocalParquet> {
+    match &s3_config.cache_dir {
+        Some(cache_dir) => Ok(LocalParquet::Cached(
+            fetch_cached(s3_config, cache_dir, key).await?,
+        )),
+        None => Ok(LocalParquet::Temp(
+            download_to_temp_file(s3_config, key).await?,
+        )),
+    }
+}
+
+/// Local copy of a Parquet object
+enum LocalParquet {
+    /// Persistent copy inside the cache directory
+    Cached(PathBuf),
+    /// Temporary copy removed when dropped
+    Temp(NamedTempFile),
+}
+
+impl LocalParquet {
+    fn path(&self) -> &Path {
+        match self {
+            LocalParquet::Cached(path) => path,
+            LocalParquet::Temp(file) => file.path(),
+        }
+    }
+}
+
+/// Downloads an object into a temporary file
+async fn download_to_temp_file(s3_config: &S3Config, key: &str) -> Result<NamedTempFile> {
     debug!("Downloading s3://{}/{}", s3_config.bucket, key);
 
-    // Download to temporary file
+    let object = s3_config
+        .client
+        .fetch_object(&s3_config.bucket, key)
+        .await?;
+
     let temp_file = NamedTempFile::new().context("Failed to create temporary file")?;
-    let temp_path = temp_file.path().to_path_buf();
+    write_file(temp_file.path(), &object.body).await?;
+
+    Ok(temp_file)
+}
+
+/// Returns the path of a cached copy of `key`, downloading it first when the
+/// cache is cold or the cached ETag no longer matches the object in S3
+async fn fetch_cached(s3_config: &S3Config, cache_dir: &Path, key: &str) -> Result<PathBuf> {
+    let (data_path, etag_path) = cache_paths(cache_dir, &s3_config.bucket, key);
+
+    let current_etag = s3_config.client.fetch_etag(&s3_config.bucket, key).await?;
+    let cached_etag = fs::read_to_string(&etag_path).await.ok();
+
+    if let (Some(current), Some(cached)) = (&current_etag, &cached_etag)
+        && current == cached
+        && fs::try_exists(&data_path).await.unwrap_or(false)
+    {
+        debug!("Using cached s3://{}/{}", s3_config.bucket, key);
+        return Ok(data_path);
+    }
+
+    if cached_etag.is_some() {
+        debug!(
+            "Invalidating cached s3://{}/{} (etag changed)",
+            s3_config.bucket, key
+        );
+        let _ = fs::remove_file(&etag_path).await;
+        let _ = fs::remove_file(&data_path).await;
+    }
 
-    let response = s3_config
+    debug!("Downloading s3://{}/{}", s3_config.bucket, key);
+    let object = s3_config
         .client
-        .get_object()
-        .bucket(&s3_config.bucket)
-        .key(&key)
-        .send()
-        .await
-        .context(format!("Failed to download S3 object: {}", key))?;
+        .fetch_object(&s3_config.bucket, key)
+        .await?;
 
-    // Write to temp file
-    let mut file = File::create(&temp_path).await?;
-    let body = response.body.collect().await?;
-    file.write_all(&body.into_bytes()).await?;
+    if let Some(parent) = data_path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .context(format!("Failed to create cache directory: {:?}", parent))?;
+    }
+
+    // Write the data first so a crash never leaves an ETag pointing at a partial file
+    write_file(&data_path, &object.body).await?;
+    if let Some(etag) = object.etag.or(current_etag) {
+        write_file(&etag_path, etag.as_bytes()).await?;
+    }
+
+    Ok(data_path)
+}
+
+/// Cache file locations for an object: `{cache_dir}/{bucket}/{key}` and its `.etag` sidecar
+fn cache_paths(cache_dir: &Path, bucket: &str, key: &str) -> (PathBuf, PathBuf) {
+    let data_path = cache_dir.join(bucket).join(key);
+    let mut etag_path = data_path.clone().into_os_string();
+    etag_path.push(".etag");
+
+    (data_path, PathBuf::from(etag_path))
+}
+
+async fn write_file(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut file = File::create(path)
+        .await
+        .context(format!("Failed to create file: {:?}", path))?;
+    file.write_all(contents).await?;
     file.flush().await?;
-    // Close file before reading
-    drop(file);
 
-    // Parse Parquet with Arrow
-    let file = StdFile::open(&temp_path)?;
+    Ok(())
+}
+
+/// Parses a local Parquet file into record batches
+fn parse_parquet(path: &Path, key: &str) -> Result<Vec<RecordBatch>> {
+    let file = StdFile::open(path)?;
     let builder = ParquetRecordBatchReaderBuilder::try_new(file)
         .context(format!("Failed to create Parquet reader for: {}", key))?;
 
@@ -776,3 +1041,318 @@ fn extract_validator_identities(batches: Vec<RecordBatch>) -> Result<Vec<Validat
 
     Ok(validators)
 }
+
+#[cfg(test)]
+mod tests {
+    use arrow::datatypes::{Field, Schema};
+    use parquet::arrow::ArrowWriter;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn parquet_bytes() -> Vec<u8> {
+        parquet_bytes_with(&[("identity_pubkey", vec!["identity_a", "identity_b"])])
+    }
+
+    fn parquet_bytes_with(columns: &[(&str, Vec<&str>)]) -> Vec<u8> {
+        let schema = Arc::new(Schema::new(
+            columns
+                .iter()
+                .map(|(name, _)| Field::new(*name, DataType::Utf8, false))
+                .collect::<Vec<_>>(),
+        ));
+        let arrays = columns
+            .iter()
+            .map(|(_, values)| Arc::new(StringArray::from(values.clone())) as Arc<dyn Array>)
+            .collect();
+        let batch = RecordBatch::try_new(schema.clone(), arrays).unwrap();
+
+        let mut bytes = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut bytes, schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        bytes
+    }
+
+    fn cached_config(client: MockS3ObjectStore, cache_dir: &TempDir) -> S3Config {
+        S3Config {
+            client: Arc::new(client),
+            bucket: "test-bucket".to_string(),
+            max_consecutive_failures: 12,
+            max_retries: 0,
+            cache_dir: Some(cache_dir.path().to_path_buf()),
+        }
+    }
+
+    /// Serves one connected validator per hour, failing every object in `failing_hours`
+    fn hourly_client(failing_hours: &'static [&'static str]) -> MockS3ObjectStore {
+        let mut client = MockS3ObjectStore::new();
+        client.expect_fetch_object().returning(move |_, key| {
+            if failing_hours
+                .iter()
+                .any(|hour| key.contains(&format!("hour={hour}/")))
+            {
+                anyhow::bail!("object not found: {key}");
+            }
+
+            let body = if key.contains("-gossip/") {
+                parquet_bytes_with(&[
+                    ("identity_pubkey", vec!["identity_a"]),
+                    ("ip_address", vec!["10.0.0.1"]),
+                ])
+            } else if key.contains("-validators/") {
+                parquet_bytes_with(&[
+                    ("identity_pubkey", vec!["identity_a"]),
+                    ("vote_account_pubkey", vec!["vote_a"]),
+                ])
+            } else if key.contains("-device-users/") {
+                parquet_bytes_with(&[
+                    ("client_ip", vec!["10.0.0.1"]),
+                    ("device_pubkey", vec!["device_a"]),
+                ])
+            } else {
+                parquet_bytes_with(&[("pubkey", vec!["device_a"])])
+            };
+
+            Ok(S3Object { body, etag: None })
+        });
+        client
+    }
+
+    fn hourly_config(client: MockS3ObjectStore, max_consecutive_failures: usize) -> S3Config {
+        S3Config {
+            client: Arc::new(client),
+            bucket: "test-bucket".to_string(),
+            max_consecutive_failures,
+            max_retries: 1,
+            cache_dir: None,
+        }
+    }
+
+    fn six_hours() -> Vec<DateTime<Utc>> {
+        let start = DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        (0..6).map(|hour| start + Duration::hours(hour)).collect()
+    }
+
+    fn identities(identities: &[&str]) -> HashSet<String> {
+        identities
+            .iter()
+            .map(|identity| identity.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_hourly_appearance_rule_cutoff_is_exclusive() {
+        let vote_account_hours = VoteAccountHours::from([
+            ("below".to_string(), 11),
+            ("twelve".to_string(), 12),
+            ("at".to_string(), 13),
+        ]);
+        let vote_account_identities = VoteAccountIdentities::from([
+            ("below".to_string(), identities(&["below_identity"])),
+            ("twelve".to_string(), identities(&["twelve_identity"])),
+            (
+                "at".to_string(),
+                identities(&["at_identity", "at_rotated_identity"]),
+            ),
+        ]);
+
+        let (validators, qualified, filtered) = apply_hourly_appearance_rule(
+            vote_account_hours,
+            vote_account_identities,
+            DEFAULT_MIN_HOURLY_APPEARANCES,
+        );
+
+        // Appearing in exactly `min_hourly_appearances` hours is not enough.
+        // The default keeps the rule of more than 12 hours.
+        assert_eq!(qualified, 1);
+        assert_eq!(filtered, 2);
+        assert_eq!(validators.len(), 2);
+        assert!(validators.iter().all(|validator| {
+            validator.vote_account_pubkey == "at" && validator.identity_count == 2
+        }));
+    }
+
+    #[test]
+    fn test_hourly_appearance_rule_custom_threshold() {
+        let vote_account_hours =
+            VoteAccountHours::from([("short".to_string(), 3), ("shorter".to_string(), 2)]);
+        let vote_account_identities = VoteAccountIdentities::from([
+            ("short".to_string(), identities(&["short_identity"])),
+            ("shorter".to_string(), identities(&["shorter_identity"])),
+        ]);
+
+        // --min-appearances 2 rejects exactly 2 appearances.
+        let (validators, qualified, filtered) =
+            apply_hourly_appearance_rule(vote_account_hours, vote_account_identities, 2);
+
+        assert_eq!(qualified, 1);
+        assert_eq!(filtered, 1);
+        assert_eq!(
+            validators,
+            vec![ValidatorKey::new(
+                "short_identity".to_string(),
+                "short".to_string(),
+                1
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_warm_cache_skips_download() {
+        let cache_dir = TempDir::new().unwrap();
+        let body = parquet_bytes();
+
+        let mut client = MockS3ObjectStore::new();
+        client
+            .expect_fetch_etag()
+            .times(2)
+            .returning(|_, _| Ok(Some("\"etag-1\"".to_string())));
+        client
+            .expect_fetch_object()
+            .times(1)
+            .returning(move |_, _| {
+                Ok(S3Object {
+                    body: body.clone(),
+                    etag: Some("\"etag-1\"".to_string()),
+                })
+            });
+        let s3_config = cached_config(client, &cache_dir);
+        let timestamp = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+
+        let cold = download_and_parse_parquet(&s3_config, "snapshot-test", timestamp)
+            .await
+            .unwrap();
+        let warm = download_and_parse_parquet(&s3_config, "snapshot-test", timestamp)
+            .await
+            .unwrap();
+
+        assert_eq!(cold, warm);
+        assert_eq!(warm[0].num_rows(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_changed_etag_invalidates_cache() {
+        let cache_dir = TempDir::new().unwrap();
+        let body = parquet_bytes();
+
+        let mut etags = vec!["\"etag-2\"", "\"etag-1\""];
+        let mut client = MockS3ObjectStore::new();
+        client
+            .expect_fetch_etag()
+            .times(2)
+            .returning(move |_, _| Ok(etags.pop().map(str::to_string)));
+        client
+            .expect_fetch_object()
+            .times(2)
+            .returning(move |_, _| {
+                Ok(S3Object {
+                    body: body.clone(),
+                    etag: None,
+                })
+            });
+        let s3_config = cached_config(client, &cache_dir);
+        let timestamp = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+
+        for _ in 0..2 {
+            download_and_parse_parquet(&s3_config, "snapshot-test", timestamp)
+                .await
+                .unwrap();
+        }
+
+        let key = build_s3_key("snapshot-test", timestamp);
+        let (_, etag_path) = cache_paths(cache_dir.path(), "test-bucket", &key);
+        assert_eq!(std::fs::read_to_string(etag_path).unwrap(), "\"etag-2\"");
+    }
+
+    #[tokio::test]
+    async fn test_failed_hours_are_dropped() {
+        let s3_config = hourly_config(hourly_client(&["02", "03"]), 3);
+
+        let results = fetch_hourly_validators(&s3_config, six_hours(), Network::Testnet)
+            .await
+            .unwrap();
+
+        let dropped_hours = results
+            .dropped
+            .iter()
+            .map(|timestamp| timestamp.hour())
+            .collect::<Vec<_>>();
+        assert_eq!(dropped_hours, vec![2, 3]);
+
+        let succeeded_hours = results
+            .succeeded
+            .iter()
+            .map(|(timestamp, _)| timestamp.hour())
+            .collect::<Vec<_>>();
+        assert_eq!(succeeded_hours, vec![0, 1, 4, 5]);
+
+        for (_, validators) in &results.succeeded {
+            assert_eq!(
+                validators,
+                &vec![ValidatorKey::new(
+                    "identity_a".to_string(),
+                    "vote_a".to_string(),
+                    0
+                )]
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_too_many_consecutive_failed_hours_is_fatal() {
+        let s3_config = hourly_config(hourly_client(&["02", "03"]), 2);
+
+        let err = fetch_hourly_validators(&s3_config, six_hours(), Network::Testnet)
+            .await
+            .err()
+            .unwrap();
+
+        assert!(
+            err.to_string().contains("2 consecutive hours missing"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn test_longest_consecutive_run() {
+        let hours = six_hours();
+
+        assert_eq!(longest_consecutive_run(&[]), 0);
+        assert_eq!(longest_consecutive_run(&[hours[1]]), 1);
+        assert_eq!(
+            longest_consecutive_run(&[hours[0], hours[2], hours[3], hours[5]]),
+            2
+        );
+    }
+
+    #[test]
+    fn test_network_dataset_selection() {
+        let timestamp = DateTime::parse_from_rfc3339("2025-01-01T05:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let mainnet = Network::try_from(NetworkEnvironment::MainnetBeta).unwrap();
+        let testnet = Network::try_from(NetworkEnvironment::Testnet).unwrap();
+        assert_eq!(mainnet, Network::MainnetBeta);
+        assert_eq!(testnet, Network::Testnet);
+        assert!(Network::try_from(NetworkEnvironment::Localnet).is_err());
+
+        assert_eq!(
+            build_s3_key(
+                &format!("snapshot-solana-{}-gossip", mainnet.prefix()),
+                timestamp
+            ),
+            "datasets/snapshot-solana-mainnet-beta-gossip/date=2025-01-01/hour=05/part-00000.parquet"
+        );
+        assert_eq!(
+            build_s3_key(
+                &format!("snapshot-solana-{}-gossip", testnet.prefix()),
+                timestamp
+            ),
+            "datasets/snapshot-solana-testnet-gossip/date=2025-01-01/hour=05/part-00000.parquet"
+        );
+    }
+}
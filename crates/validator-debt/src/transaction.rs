@@ -1,14 +1,16 @@
-use std::{fs::File, sync::Arc};
+use std::{cmp::Reverse, collections::HashMap, fs::File, sync::Arc};
 
 use anyhow::{Result, anyhow};
 use doublezero_sdk::record::pubkey;
 use doublezero_solana_client_tools::{
-    account::zero_copy::ZeroCopyAccountOwnedData, rpc::DoubleZeroLedgerConnection,
+    account::zero_copy::ZeroCopyAccountOwnedData,
+    retry::{RetryPolicy, is_transient_rpc_error, with_retries},
+    rpc::DoubleZeroLedgerConnection,
 };
 use doublezero_solana_sdk::{
     merkle::MerkleProof,
     revenue_distribution::{
-        ID,
+        ID, compute_unit,
         instruction::{
             DistributionMerkleRootKind, RevenueDistributionInstructionData,
             account::{
@@ -23,6 +25,7 @@ use doublezero_solana_sdk::{
     try_build_instruction, zero_copy,
 };
 use futures::stream::{self, StreamExt, TryStreamExt};
+use leaky_bucket::RateLimiter;
 use serde::Serialize;
 use solana_client::{
     client_error::{ClientError, ClientErrorKind},
@@ -31,7 +34,9 @@ use solana_client::{
     rpc_request::{RpcError, RpcResponseErrorData},
 };
 use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
     hash::Hash,
+    instruction::Instruction,
     message::{VersionedMessage, v0::Message},
     pubkey::Pubkey,
     signature::Keypair,
@@ -52,6 +57,12 @@ pub struct Transaction {
     pub signer: Arc<Keypair>,
     pub dry_run: bool,
     pub force: bool,
+    /// Fail instead of warning on unexpected conditions. See `--strict` on
+    /// the calculate command.
+    pub strict: bool,
+    /// Appended to each debt payment so operators can raise priority fees
+    /// during congestion.
+    pub compute_unit_price_ix: Option<Instruction>,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -67,6 +78,25 @@ pub struct DebtCollectionResults {
     pub total_validators: usize,
 }
 
+/// Reported each time a validator's debt payment completes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DebtCollectionProgress {
+    pub dz_epoch: u64,
+    pub processed_count: usize,
+    pub total_count: usize,
+}
+
+impl DebtCollectionProgress {
+    /// Fraction of validators processed. An empty run counts as complete.
+    pub fn fraction(&self) -> f64 {
+        if self.total_count == 0 {
+            1.0
+        } else {
+            self.processed_count as f64 / self.total_count as f64
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct DebtCollectionResult {
     pub validator_id: String,
@@ -81,6 +111,22 @@ impl Transaction {
             signer,
             dry_run,
             force,
+            strict: false,
+            compute_unit_price_ix: None,
+        }
+    }
+
+    pub fn with_strict(self, strict: bool) -> Transaction {
+        Transaction { strict, ..self }
+    }
+
+    pub fn with_compute_unit_price_ix(
+        self,
+        compute_unit_price_ix: Option<Instruction>,
+    ) -> Transaction {
+        Transaction {
+            compute_unit_price_ix,
+            ..self
         }
     }
 
@@ -101,7 +147,7 @@ impl Transaction {
             &debts,
         ) {
             Ok(instruction) => {
-                let recent_blockhash = solana_rpc_client.get_latest_blockhash().await?;
+                let recent_blockhash = try_get_latest_blockhash(solana_rpc_client).await?;
                 let message = Message::try_compile(
                     &self.signer.pubkey(),
                     &[instruction],
@@ -167,7 +213,7 @@ impl Transaction {
             &RevenueDistributionInstructionData::FinalizeDistributionDebt,
         ) {
             Ok(instruction) => {
-                let recent_blockhash = solana_rpc_client.get_latest_blockhash().await?;
+                let recent_blockhash = try_get_latest_blockhash(solana_rpc_client).await?;
                 let message = Message::try_compile(
                     &self.signer.pubkey(),
                     &[instruction],
@@ -205,7 +251,7 @@ impl Transaction {
             },
         )?;
 
-        let recent_blockhash = solana_rpc_client.get_latest_blockhash().await?;
+        let recent_blockhash = try_get_latest_blockhash(solana_rpc_client).await?;
         let message =
             Message::try_compile(&self.signer.pubkey(), &[instruction], &[], recent_blockhash)
                 .unwrap();
@@ -238,9 +284,12 @@ impl Transaction {
             let simulation_response = solana_rpc_client.simulate_transaction(transaction).await?;
             Ok(Some(simulation_response.value.logs.unwrap().join("\n ")))
         } else {
-            let tx_sig = solana_rpc_client
-                .send_and_confirm_transaction(transaction)
-                .await?;
+            let tx_sig = with_retries(rpc_retry_policy(), || async {
+                Ok(solana_rpc_client
+                    .send_and_confirm_transaction(transaction)
+                    .await?)
+            })
+            .await?;
             Ok(Some(tx_sig.to_string()))
         }
     }
@@ -275,12 +324,16 @@ impl Transaction {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn pay_solana_validator_debt(
         &self,
         solana_rpc_client: &RpcClient,
         debt: ComputedSolanaValidatorDebts,
         dz_epoch: u64,
         distribution: &ZeroCopyAccountOwnedData<Distribution>,
+        tx_rate_limiter: Option<&RateLimiter>,
+        deposit_balances: Option<&HashMap<Pubkey, u64>>,
+        progress: Option<&(dyn Fn(DebtCollectionProgress) + Send + Sync)>,
     ) -> Result<DebtCollectionResults> {
         let mut overrides = Vec::new();
         // TODO: This is a temporary fix to exclude a couple of validators
@@ -297,7 +350,7 @@ impl Transaction {
                     }),
             );
         }
-        let debts_to_process: Vec<ComputedSolanaValidatorDebt> = debt.debts.iter().filter(|debt| {
+        let mut debts_to_process: Vec<ComputedSolanaValidatorDebt> = debt.debts.iter().filter(|debt| {
           let node_id_str = debt.node_id.to_string();
           let excluded = overrides.iter().any(|(key, epoch)| key == &node_id_str && *epoch == dz_epoch);
       if excluded {
@@ -310,12 +363,17 @@ impl Transaction {
 
         }).cloned().collect();
 
+        if let Some(deposit_balances) = deposit_balances {
+            order_collectible_first(&mut debts_to_process, deposit_balances);
+        }
+
         let start_index = distribution.processed_solana_validator_debt_start_index as usize;
         let end_index = distribution.processed_solana_validator_debt_end_index as usize;
         let processed_leaf_data = &distribution.remaining_data[start_index..end_index];
 
         let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_CONNECTIONS));
         let debt_clone = Arc::new(debt);
+        let total_count = debts_to_process.len();
 
         let debt_collection_results: Vec<Result<DebtCollectionResult>> =
             stream::iter(debts_to_process)
@@ -347,12 +405,24 @@ impl Transaction {
                                 &debt,
                                 proof,
                                 dz_epoch,
+                                tx_rate_limiter,
                             )
                             .await
                         }
                     }
                 })
                 .buffer_unordered(20)
+                .enumerate()
+                .map(|(index, result)| {
+                    if let Some(progress) = progress {
+                        progress(DebtCollectionProgress {
+                            dz_epoch,
+                            processed_count: index + 1,
+                            total_count,
+                        });
+                    }
+                    result
+                })
                 .collect()
                 .await;
 
@@ -416,30 +486,16 @@ impl Transaction {
         debt: &ComputedSolanaValidatorDebt,
         proof: MerkleProof,
         dz_epoch: u64,
+        tx_rate_limiter: Option<&RateLimiter>,
     ) -> Result<DebtCollectionResult> {
-        let instruction = try_build_instruction(
-            &ID,
-            PaySolanaValidatorDebtAccounts::new(DoubleZeroEpoch::new(dz_epoch), &debt.node_id),
-            &RevenueDistributionInstructionData::PaySolanaValidatorDebt {
-                amount: debt.amount,
-                proof,
-            },
-        )
-        .unwrap();
-
-        let recent_blockhash = solana_rpc_client.get_latest_blockhash().await?;
-
-        let message = Message::try_compile(
-            &transaction.signer.pubkey(),
-            &[instruction],
-            &[],
-            recent_blockhash,
-        )
-        .unwrap();
+        let recent_blockhash = try_get_latest_blockhash(solana_rpc_client).await?;
 
         let versioned_transaction =
-            VersionedTransaction::try_new(VersionedMessage::V0(message), &[&transaction.signer])
-                .unwrap();
+            transaction.build_debt_payment_transaction(debt, proof, dz_epoch, recent_blockhash);
+
+        if let Some(tx_rate_limiter) = tx_rate_limiter {
+            tx_rate_limiter.acquire_one().await;
+        }
 
         let result = Self::send_or_simulate_transaction(
             transaction,
@@ -493,6 +549,41 @@ impl Transaction {
         }
     }
 
+    fn build_debt_payment_transaction(
+        &self,
+        debt: &ComputedSolanaValidatorDebt,
+        proof: MerkleProof,
+        dz_epoch: u64,
+        recent_blockhash: Hash,
+    ) -> VersionedTransaction {
+        let compute_unit_limit = 5_000 + compute_unit::pay_solana_validator_debt(&proof);
+
+        let instruction = try_build_instruction(
+            &ID,
+            PaySolanaValidatorDebtAccounts::new(DoubleZeroEpoch::new(dz_epoch), &debt.node_id),
+            &RevenueDistributionInstructionData::PaySolanaValidatorDebt {
+                amount: debt.amount,
+                proof,
+            },
+        )
+        .unwrap();
+
+        let mut instructions = vec![
+            instruction,
+            ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit),
+        ];
+
+        if let Some(ref compute_unit_price_ix) = self.compute_unit_price_ix {
+            instructions.push(compute_unit_price_ix.clone());
+        }
+
+        let message =
+            Message::try_compile(&self.signer.pubkey(), &instructions, &[], recent_blockhash)
+                .unwrap();
+
+        VersionedTransaction::try_new(VersionedMessage::V0(message), &[&self.signer]).unwrap()
+    }
+
     // TODO: Get rid of this because only one thing calls it.
     pub async fn read_distribution(
         &self,
@@ -512,6 +603,22 @@ impl Transaction {
     }
 }
 
+/// Only retry requests that failed to reach the RPC node. Anything it
+/// rejected, like a failed preflight, would fail the same way again.
+fn rpc_retry_policy() -> RetryPolicy {
+    RetryPolicy {
+        is_retryable: is_transient_rpc_error,
+        ..Default::default()
+    }
+}
+
+pub(crate) async fn try_get_latest_blockhash(rpc_client: &RpcClient) -> Result<Hash> {
+    with_retries(rpc_retry_policy(), || async {
+        Ok(rpc_client.get_latest_blockhash().await?)
+    })
+    .await
+}
+
 fn parse_program_logs(
     amount: u64,
     node_id: Pubkey,
@@ -537,3 +644,166 @@ fn parse_program_logs(
         success,
     }
 }
+
+/// Order debts so those fully covered by their deposits are attempted first,
+/// largest first, followed by underfunded debts in record order.
+fn order_collectible_first(
+    debts: &mut [ComputedSolanaValidatorDebt],
+    deposit_balances: &HashMap<Pubkey, u64>,
+) {
+    debts.sort_by_key(|debt| {
+        if debt.is_collectible(deposit_balances) {
+            (false, Reverse(debt.amount))
+        } else {
+            (true, Reverse(0))
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::compute_budget;
+
+    use super::*;
+
+    #[test]
+    fn test_debt_payment_compute_unit_price() {
+        let debt = ComputedSolanaValidatorDebt {
+            node_id: Pubkey::new_unique(),
+            amount: 1_000,
+        };
+        let debts = ComputedSolanaValidatorDebts {
+            debts: vec![debt],
+            ..Default::default()
+        };
+        let proof = || debts.find_debt_proof(&debt.node_id).unwrap().1;
+
+        let compute_budget_instructions = |transaction: &Transaction| {
+            let tx = transaction.build_debt_payment_transaction(&debt, proof(), 1, Hash::default());
+            let account_keys = tx.message.static_account_keys();
+            tx.message
+                .instructions()
+                .iter()
+                .filter(|ix| account_keys[ix.program_id_index as usize] == compute_budget::ID)
+                .map(|ix| ix.data.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let compute_unit_limit_ix = ComputeBudgetInstruction::set_compute_unit_limit(
+            5_000 + compute_unit::pay_solana_validator_debt(&proof()),
+        );
+
+        let transaction = Transaction::new(Arc::new(Keypair::new()), false, false);
+        assert_eq!(
+            compute_budget_instructions(&transaction),
+            vec![compute_unit_limit_ix.data.clone()]
+        );
+
+        let compute_unit_price_ix = ComputeBudgetInstruction::set_compute_unit_price(5_000);
+        let transaction =
+            transaction.with_compute_unit_price_ix(Some(compute_unit_price_ix.clone()));
+        assert_eq!(
+            compute_budget_instructions(&transaction),
+            vec![compute_unit_limit_ix.data, compute_unit_price_ix.data]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_debt_collection_progress() {
+        const VALIDATOR_COUNT: usize = 10;
+
+        let debts = ComputedSolanaValidatorDebts {
+            debts: (0..VALIDATOR_COUNT)
+                .map(|_| ComputedSolanaValidatorDebt {
+                    node_id: Pubkey::new_unique(),
+                    amount: 1_000,
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        // Every leaf is already processed, so no payments are sent.
+        let mut mucked_data: Box<Distribution> = Box::new(bytemuck::Zeroable::zeroed());
+        mucked_data.processed_solana_validator_debt_end_index = 2;
+        let distribution = ZeroCopyAccountOwnedData {
+            mucked_data,
+            remaining_data: vec![u8::MAX; 2],
+        };
+
+        let reports = std::sync::Mutex::new(Vec::new());
+        let progress = |progress: DebtCollectionProgress| reports.lock().unwrap().push(progress);
+
+        let transaction = Transaction::new(Arc::new(Keypair::new()), true, false);
+        let results = transaction
+            .pay_solana_validator_debt(
+                &RpcClient::new("http://127.0.0.1:1".to_string()),
+                debts,
+                1,
+                &distribution,
+                None,
+                None,
+                Some(&progress),
+            )
+            .await
+            .unwrap();
+        assert_eq!(results.already_paid_count, VALIDATOR_COUNT);
+
+        let reports = reports.into_inner().unwrap();
+        assert_eq!(reports.len(), VALIDATOR_COUNT);
+        assert_eq!(
+            reports
+                .iter()
+                .map(|progress| progress.processed_count)
+                .collect::<Vec<_>>(),
+            (1..=VALIDATOR_COUNT).collect::<Vec<_>>()
+        );
+        assert!(
+            reports
+                .iter()
+                .all(|progress| progress.total_count == VALIDATOR_COUNT && progress.dz_epoch == 1)
+        );
+        assert_eq!(reports.last().unwrap().fraction(), 1.0);
+    }
+
+    #[test]
+    fn test_order_collectible_first() {
+        let debt = |amount| ComputedSolanaValidatorDebt {
+            node_id: Pubkey::new_unique(),
+            amount,
+        };
+
+        let underfunded = debt(500);
+        let small_collectible = debt(100);
+        let missing_deposit = debt(50);
+        let large_collectible = debt(300);
+        let zero_debt = debt(0);
+
+        let deposit_balances = HashMap::from([
+            (underfunded.node_id, 499),
+            (small_collectible.node_id, 100),
+            (large_collectible.node_id, 1_000),
+        ]);
+
+        let mut debts = vec![
+            underfunded,
+            small_collectible,
+            missing_deposit,
+            large_collectible,
+            zero_debt,
+        ];
+        order_collectible_first(&mut debts, &deposit_balances);
+
+        // Collectible debts come first, largest first. Underfunded debts,
+        // including those without a deposit, keep their record order.
+        assert_eq!(
+            debts.iter().map(|debt| debt.node_id).collect::<Vec<_>>(),
+            vec![
+                large_collectible.node_id,
+                small_collectible.node_id,
+                zero_debt.node_id,
+                underfunded.node_id,
+                missing_deposit.node_id,
+            ]
+        );
+    }
+}
@@ -1,12 +1,18 @@
+use std::collections::HashMap;
+
 use borsh::{BorshDeserialize, BorshSerialize};
 use doublezero_solana_sdk::{
     merkle::{MerkleProof, merkle_root_from_indexed_byte_ref_leaves},
     sha2,
 };
+use serde::{Deserialize, Serialize};
 use solana_sdk::{hash::Hash, pubkey::Pubkey};
 
-#[derive(Debug, Default, BorshDeserialize, BorshSerialize, Clone, PartialEq, Eq)]
+#[derive(
+    Debug, Default, BorshDeserialize, BorshSerialize, Clone, PartialEq, Eq, Serialize, Deserialize,
+)]
 pub struct ComputedSolanaValidatorDebts {
+    #[serde(with = "base58")]
     pub blockhash: Hash,
     pub first_solana_epoch: u64,
     pub last_solana_epoch: u64,
@@ -43,6 +49,20 @@ impl ComputedSolanaValidatorDebts {
         )
     }
 
+    pub fn total_debt(&self) -> u64 {
+        self.debts.iter().map(|debt| debt.amount).sum()
+    }
+
+    /// Sum of debts whose deposit balance covers them in full. Payments are
+    /// all-or-nothing, so an underfunded debt contributes nothing.
+    pub fn total_collectible(&self, deposit_balances: &HashMap<Pubkey, u64>) -> u64 {
+        self.debts
+            .iter()
+            .filter(|debt| debt.is_collectible(deposit_balances))
+            .map(|debt| debt.amount)
+            .sum()
+    }
+
     fn to_byte_leaves(&self) -> Vec<Vec<u8>> {
         self.debts
             .iter()
@@ -51,8 +71,20 @@ impl ComputedSolanaValidatorDebts {
     }
 }
 
-#[derive(Debug, BorshDeserialize, BorshSerialize, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(
+    Debug,
+    BorshDeserialize,
+    BorshSerialize,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
 pub struct ComputedSolanaValidatorDebt {
+    #[serde(with = "base58")]
     pub node_id: Pubkey,
     pub amount: u64,
 }
@@ -68,6 +100,42 @@ impl ComputedSolanaValidatorDebt {
 
         proof.root_from_leaf(&leaf, Some(Self::LEAF_PREFIX))
     }
+
+    /// Whether the node's deposit balance covers this debt in full. Nodes
+    /// missing from `deposit_balances` have no balance.
+    pub fn is_collectible(&self, deposit_balances: &HashMap<Pubkey, u64>) -> bool {
+        deposit_balances
+            .get(&self.node_id)
+            .copied()
+            .unwrap_or_default()
+            >= self.amount
+    }
+}
+
+/// Serializes hashes and pubkeys as base58 strings, so JSON dumps are
+/// readable and match what explorers and the CLI print.
+mod base58 {
+    use std::{fmt::Display, str::FromStr};
+
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub fn serialize<T: Display, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
 }
 
 #[cfg(test)]
@@ -136,4 +204,41 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_total_debt_and_collectible() {
+        let debt = |amount| ComputedSolanaValidatorDebt {
+            node_id: Pubkey::new_unique(),
+            amount,
+        };
+        let debts = ComputedSolanaValidatorDebts {
+            debts: vec![debt(100), debt(200), debt(300), debt(400)],
+            ..Default::default()
+        };
+
+        assert_eq!(ComputedSolanaValidatorDebts::default().total_debt(), 0);
+        assert_eq!(debts.total_debt(), 1_000);
+
+        // No deposits, nothing is collectible.
+        assert_eq!(debts.total_collectible(&HashMap::new()), 0);
+
+        // Exactly covered and over-funded debts count in full. A partially
+        // covered debt contributes nothing, as does a node without a deposit.
+        let deposit_balances = HashMap::from([
+            (debts.debts[0].node_id, 100),
+            (debts.debts[1].node_id, 250),
+            (debts.debts[2].node_id, 299),
+        ]);
+        assert_eq!(debts.total_collectible(&deposit_balances), 300);
+
+        let deposit_balances = debts
+            .debts
+            .iter()
+            .map(|debt| (debt.node_id, debt.amount))
+            .collect();
+        assert_eq!(
+            debts.total_collectible(&deposit_balances),
+            debts.total_debt()
+        );
+    }
 }
@@ -4,7 +4,7 @@ use anyhow::{Context, Result, ensure};
 use doublezero_solana_client_tools::{
     account::zero_copy::ZeroCopyAccountOwnedData,
     payer::{TransactionOutcome, Wallet},
-    rpc::{DoubleZeroLedgerConnection, NetworkEnvironment},
+    rpc::{DoubleZeroLedgerConnection, NetworkEnvironment, SolanaConnection},
 };
 use doublezero_solana_sdk::{
     environment_2z_token_mint_key,
@@ -27,11 +27,17 @@ use doublezero_solana_sdk::{
 };
 use solana_sdk::{compute_budget::ComputeBudgetInstruction, pubkey::Pubkey, signer::Signer};
 
+/// Default number of consecutive fully processed epochs after which debt
+/// write-offs stop looking at older epochs. Zero scans back to genesis, so
+/// stopping early is opt-in.
+pub const DEFAULT_WRITE_OFF_SETTLED_EPOCHS: usize = 0;
+
 pub async fn try_initialize_distribution(
     wallet: &Wallet,
     dz_env_override: Option<NetworkEnvironment>,
     bypass_dz_epoch_check: bool,
     record_accountant_key: Option<Pubkey>,
+    write_off_settled_epochs: usize,
 ) -> Result<()> {
     let network_env = wallet.connection.try_network_environment().await?;
 
@@ -48,26 +54,8 @@ pub async fn try_initialize_distribution(
         return Ok(());
     }
 
-    let record_accountant_key = match record_accountant_key {
-        Some(accountant_key) => {
-            // Disallow if the accountant key is not used with localnet.
-            ensure!(
-                network_env.is_localnet(),
-                "Cannot specify accountant key with non-localnet network"
-            );
-
-            accountant_key
-        }
-        None => {
-            let expected_accountant_key = config.debt_accountant_key;
-            ensure!(
-                wallet.signer.pubkey() == expected_accountant_key,
-                "Signer does not match expected debt accountant"
-            );
-
-            expected_accountant_key
-        }
-    };
+    let record_accountant_key =
+        try_debt_accountant_key(wallet, network_env, &config, record_accountant_key)?;
 
     let next_dz_epoch = config.next_completed_dz_epoch;
 
@@ -80,17 +68,16 @@ pub async fn try_initialize_distribution(
             "Cannot bypass DZ epoch check with non-localnet network"
         );
     } else {
-        let expected_completed_dz_epoch = dz_connection
-            .get_epoch_info()
-            .await?
-            .epoch
-            .saturating_sub(1);
+        let expected_completed_dz_epoch = try_fetch_last_completed_dz_epoch(&dz_connection).await?;
 
         // Ensure that the epoch from the DoubleZero Ledger network equals
         // the next one known by the Revenue Distribution program.
         if next_dz_epoch.value() != expected_completed_dz_epoch {
+            let epochs_behind = epochs_behind(next_dz_epoch.value(), expected_completed_dz_epoch);
+            metrics::gauge!("doublezero_validator_debt_epochs_behind").set(epochs_behind as f64);
+
             tracing::warn!(
-                "Last completed DZ epoch {expected_completed_dz_epoch} != program's epoch {next_dz_epoch}"
+                "Last completed DZ epoch {expected_completed_dz_epoch} != program's epoch {next_dz_epoch} ({epochs_behind} epochs behind)"
             );
             return Ok(());
         }
@@ -122,6 +109,7 @@ pub async fn try_initialize_distribution(
             &dz_connection,
             &record_accountant_key,
             &rewards_distribution,
+            write_off_settled_epochs,
         )
         .await?;
     } else {
@@ -221,12 +209,132 @@ pub async fn try_initialize_distribution(
 
 //
 
-// TODO: This method may need a rate limiter for account fetches.
+/// Number of DZ epochs the Revenue Distribution program's next completed DZ
+/// epoch lags the DoubleZero Ledger's last completed epoch. Also set as the
+/// `doublezero_validator_debt_epochs_behind` gauge.
+pub async fn try_fetch_epochs_behind(
+    solana_connection: &SolanaConnection,
+    dz_connection: &DoubleZeroLedgerConnection,
+) -> Result<u64> {
+    let config = solana_connection
+        .try_fetch_zero_copy_data::<ProgramConfig>(&ProgramConfig::find_address().0)
+        .await?;
+    let last_completed_dz_epoch = try_fetch_last_completed_dz_epoch(dz_connection).await?;
+
+    let epochs_behind = epochs_behind(
+        config.next_completed_dz_epoch.value(),
+        last_completed_dz_epoch,
+    );
+    metrics::gauge!("doublezero_validator_debt_epochs_behind").set(epochs_behind as f64);
+
+    Ok(epochs_behind)
+}
+
+async fn try_fetch_last_completed_dz_epoch(
+    dz_connection: &DoubleZeroLedgerConnection,
+) -> Result<u64> {
+    let epoch_info = dz_connection.get_epoch_info().await?;
+    Ok(epoch_info.epoch.saturating_sub(1))
+}
+
+/// Write off debt that the rewards distribution for the given DZ epoch can
+/// cover, without initializing a new distribution.
+pub async fn write_off_debt(
+    wallet: &Wallet,
+    dz_env_override: Option<NetworkEnvironment>,
+    record_accountant_key: Option<Pubkey>,
+    dz_epoch: DoubleZeroEpoch,
+    write_off_settled_epochs: usize,
+) -> Result<()> {
+    let network_env = wallet.connection.try_network_environment().await?;
+
+    let dz_env = dz_env_override.unwrap_or(network_env);
+    let dz_connection = DoubleZeroLedgerConnection::from(dz_env);
+
+    let config = wallet
+        .connection
+        .try_fetch_zero_copy_data::<ProgramConfig>(&ProgramConfig::find_address().0)
+        .await?;
+
+    if super::is_config_paused(&config) {
+        return Ok(());
+    }
+
+    ensure_debt_write_off_feature_activated(&config)?;
+
+    let record_accountant_key =
+        try_debt_accountant_key(wallet, network_env, &config, record_accountant_key)?;
+
+    let rewards_distribution = wallet
+        .connection
+        .try_fetch_zero_copy_data::<Distribution>(&Distribution::find_address(dz_epoch).0)
+        .await
+        .with_context(|| format!("Distribution not found for epoch {dz_epoch}"))?;
+
+    tracing::info!("Processing debt write-offs affecting epoch {dz_epoch}");
+
+    try_write_off_distribution_debt(
+        wallet,
+        &dz_connection,
+        &record_accountant_key,
+        &rewards_distribution,
+        write_off_settled_epochs,
+    )
+    .await
+}
+
+fn ensure_debt_write_off_feature_activated(config: &ProgramConfig) -> Result<()> {
+    ensure!(
+        config.is_debt_write_off_feature_activated(),
+        "Debt write-off feature is not activated (activation epoch {})",
+        config.debt_write_off_feature_activation_epoch.value()
+    );
+
+    Ok(())
+}
+
+/// Only localnet may record debt under an accountant other than the one in
+/// the program config, which must be the signer.
+fn try_debt_accountant_key(
+    wallet: &Wallet,
+    network_env: NetworkEnvironment,
+    config: &ProgramConfig,
+    record_accountant_key: Option<Pubkey>,
+) -> Result<Pubkey> {
+    match record_accountant_key {
+        Some(accountant_key) => {
+            // Disallow if the accountant key is not used with localnet.
+            ensure!(
+                network_env.is_localnet(),
+                "Cannot specify accountant key with non-localnet network"
+            );
+
+            Ok(accountant_key)
+        }
+        None => {
+            let expected_accountant_key = config.debt_accountant_key;
+            ensure!(
+                wallet.signer.pubkey() == expected_accountant_key,
+                "Signer does not match expected debt accountant"
+            );
+
+            Ok(expected_accountant_key)
+        }
+    }
+}
+
+/// The program is never ahead of the ledger, so a program epoch past the last
+/// completed one counts as in sync.
+fn epochs_behind(next_completed_dz_epoch: u64, last_completed_dz_epoch: u64) -> u64 {
+    last_completed_dz_epoch.saturating_sub(next_completed_dz_epoch)
+}
+
 async fn try_write_off_distribution_debt(
     wallet: &Wallet,
     dz_ledger_connection: &DoubleZeroLedgerConnection,
     record_accountant_key: &Pubkey,
     rewards_distribution: &ZeroCopyAccountOwnedData<Distribution>,
+    write_off_settled_epochs: usize,
 ) -> Result<()> {
     let wallet_key = wallet.pubkey();
     let rewards_dz_epoch = rewards_distribution.dz_epoch;
@@ -252,11 +360,14 @@ async fn try_write_off_distribution_debt(
     // bail out.
     let mut must_terminate_debt_write_offs = false;
 
-    // Traverse backwards through epochs to write off debt.
-    //
-    // TODO: We should be able to terminate this loop early if we find that
-    // all processed debt is already accounted for. But for now, we will just
-    // iterate through all epochs.
+    let mut settled_epochs = SettledEpochs::new(
+        write_off_settled_epochs,
+        rewards_distribution.uncollectible_sol_debt,
+    );
+
+    // Traverse backwards through epochs to write off debt. Stop early once
+    // enough consecutive epochs have all debt processed without changing the
+    // uncollectible debt, since older epochs are then assumed settled too.
     for dz_epoch in (GENESIS_DZ_EPOCH_MAINNET_BETA..=rewards_dz_epoch.value())
         .rev()
         .map(DoubleZeroEpoch::new)
@@ -279,6 +390,16 @@ async fn try_write_off_distribution_debt(
                 .await?
         };
 
+        if settled_epochs.record(
+            distribution.is_all_solana_validator_debt_processed(),
+            rewards_distribution.uncollectible_sol_debt,
+        ) {
+            tracing::info!(
+                "Stopping debt write-offs at epoch {dz_epoch} after {write_off_settled_epochs} settled epochs"
+            );
+            break;
+        }
+
         if distribution.is_all_solana_validator_debt_processed() {
             continue;
         }
@@ -299,6 +420,23 @@ async fn try_write_off_distribution_debt(
             .try_fetch_sysvar::<solana_sdk::rent::Rent>()
             .await?;
 
+        // Fetch the deposit accounts of every unpaid node not seen in a more
+        // recent epoch up front instead of one request per node.
+        let mut deposit_accounts = super::try_fetch_deposit_accounts(
+            &wallet.connection,
+            computed_debt
+                .debts
+                .iter()
+                .enumerate()
+                .filter(|(leaf_index, _)| {
+                    !revenue_distribution::try_is_processed_leaf(processed_leaf_data, *leaf_index)
+                        .unwrap()
+                })
+                .map(|(_, debt)| debt.node_id)
+                .filter(|node_id| !deposit_balances.contains_key(node_id)),
+        )
+        .await?;
+
         let mut instructions_and_compute_units = Vec::new();
         let mut pay_count = 0;
         let mut write_off_count = 0;
@@ -314,16 +452,12 @@ async fn try_write_off_distribution_debt(
                 .unwrap_or_default();
 
             let node_id = debt.node_id;
-            let (deposit_key, deposit_bump) = SolanaValidatorDeposit::find_address(&node_id);
+            let (_, deposit_bump) = SolanaValidatorDeposit::find_address(&node_id);
 
             if let std::collections::hash_map::Entry::Vacant(entry) =
                 deposit_balances.entry(node_id)
             {
-                let deposit_account_info = wallet
-                    .connection
-                    .get_account(&deposit_key)
-                    .await
-                    .unwrap_or_default();
+                let deposit_account_info = deposit_accounts.remove(&node_id).unwrap_or_default();
 
                 if deposit_account_info.data.is_empty() {
                     let instruction = try_build_instruction(
@@ -431,7 +565,7 @@ async fn try_write_off_distribution_debt(
         let instruction_batches =
         doublezero_solana_client_tools::transaction::try_batch_instructions_with_common_signers(
             instructions_and_compute_units,
-            &[wallet],
+            &wallet.transaction_signers(&[]),
             &[],
             true, // allow_compute_price_instruction
         )?;
@@ -455,7 +589,104 @@ async fn try_write_off_distribution_debt(
     Ok(())
 }
 
+/// Counts consecutive epochs whose debt is all processed while the
+/// uncollectible debt stays the same. Zero `max_epochs` never stops early.
+struct SettledEpochs {
+    max_epochs: usize,
+    count: usize,
+    last_uncollectible_sol_debt: u64,
+}
+
+impl SettledEpochs {
+    fn new(max_epochs: usize, uncollectible_sol_debt: u64) -> Self {
+        Self {
+            max_epochs,
+            count: 0,
+            last_uncollectible_sol_debt: uncollectible_sol_debt,
+        }
+    }
+
+    /// Records the next epoch and returns whether the scan should stop.
+    fn record(&mut self, is_all_debt_processed: bool, uncollectible_sol_debt: u64) -> bool {
+        if is_all_debt_processed && uncollectible_sol_debt == self.last_uncollectible_sol_debt {
+            self.count += 1;
+        } else {
+            self.count = 0;
+        }
+        self.last_uncollectible_sol_debt = uncollectible_sol_debt;
+
+        self.max_epochs != 0 && self.count >= self.max_epochs
+    }
+}
+
 #[inline(always)]
 fn has_zero_distribution_debt(rewards_distribution: &Distribution) -> bool {
     rewards_distribution.solana_validator_debt_merkle_root == Default::default()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Epochs scanned before stopping, walking `history` from newest to oldest
+    /// like the write-off loop. Each entry is whether the epoch's debt is all
+    /// processed and the uncollectible debt when it is visited.
+    fn scanned_epochs(max_epochs: usize, history: &[(bool, u64)]) -> usize {
+        let mut settled_epochs = SettledEpochs::new(max_epochs, 0);
+        history
+            .iter()
+            .take_while(|(is_all_debt_processed, uncollectible_sol_debt)| {
+                !settled_epochs.record(*is_all_debt_processed, *uncollectible_sol_debt)
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_settled_epochs_stop_early() {
+        // A fully processed history stops after the configured count.
+        let fully_processed = vec![(true, 0); 100];
+        assert_eq!(scanned_epochs(3, &fully_processed), 2);
+
+        // Zero, the default, disables early termination.
+        assert_eq!(
+            scanned_epochs(DEFAULT_WRITE_OFF_SETTLED_EPOCHS, &fully_processed),
+            100
+        );
+
+        // Unprocessed debt or a write-off restarts the count.
+        let history = [
+            (true, 0),
+            (true, 0),
+            (false, 0),
+            (true, 0),
+            (true, 5),
+            (true, 5),
+            (true, 5),
+            (true, 5),
+        ];
+        assert_eq!(scanned_epochs(3, &history), 7);
+    }
+
+    #[test]
+    fn test_write_off_feature_gate() {
+        let config: ProgramConfig = bytemuck::Zeroable::zeroed();
+
+        let err = ensure_debt_write_off_feature_activated(&config).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Debt write-off feature is not activated (activation epoch 0)"
+        );
+    }
+
+    #[test]
+    fn test_epochs_behind() {
+        // In sync: the program's next epoch is the ledger's last completed.
+        assert_eq!(epochs_behind(42, 42), 0);
+
+        assert_eq!(epochs_behind(40, 42), 2);
+        assert_eq!(epochs_behind(0, 42), 42);
+
+        // A program epoch past the ledger's does not underflow.
+        assert_eq!(epochs_behind(43, 42), 0);
+    }
+}
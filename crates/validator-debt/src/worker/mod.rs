@@ -4,36 +4,38 @@ mod slack_report;
 
 //
 
-use std::{collections::HashMap, str::FromStr, sync::Arc};
+use std::{collections::HashMap, ops::RangeInclusive, str::FromStr, sync::Arc};
 
-use anyhow::{Result, bail, ensure};
+use anyhow::{Context, Result, bail, ensure};
+use base64::Engine;
 use doublezero_solana_client_tools::{
     payer::{TransactionOutcome, Wallet},
     rpc::{DoubleZeroLedgerConnection, SolanaConnection},
 };
 use doublezero_solana_sdk::{
+    merkle::MerkleProof,
     revenue_distribution::{
         GENESIS_DZ_EPOCH_MAINNET_BETA, ID,
         fetch::{try_fetch_config, try_fetch_distribution},
         instruction::{
             RevenueDistributionInstructionData, account::InitializeSolanaValidatorDepositAccounts,
         },
-        state::{ProgramConfig, SolanaValidatorDeposit},
+        state::{Distribution, ProgramConfig, SolanaValidatorDeposit},
         types::SolanaValidatorDebt,
     },
-    try_build_instruction,
+    sha2, try_build_instruction,
 };
 use futures::{StreamExt, TryStreamExt, stream};
 pub use initialize_distribution::*;
 use leaky_bucket::RateLimiter;
 pub(super) use pause_gate::is_config_paused;
 use reqwest::Client;
-use serde::Serialize;
-use slack_notifier;
+use serde::{Deserialize, Serialize};
+use slack_notifier::{self, validator_debt::SlackTarget};
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{
-    clock::Clock, compute_budget::ComputeBudgetInstruction, pubkey::Pubkey, signer::Signer,
-    sysvar::clock,
+    account::Account, clock::Clock, compute_budget::ComputeBudgetInstruction, pubkey::Pubkey,
+    signer::Signer, sysvar::clock,
 };
 use tabled::Tabled;
 
@@ -42,7 +44,9 @@ use crate::{
     rpc::JoinedSolanaEpochs,
     s3_fetcher,
     solana_debt_calculator::ValidatorRewards,
-    transaction::{DebtCollectionResults, Transaction},
+    transaction::{
+        DebtCollectionProgress, DebtCollectionResults, Transaction, try_get_latest_blockhash,
+    },
     validator_debt::{ComputedSolanaValidatorDebt, ComputedSolanaValidatorDebts},
 };
 
@@ -55,14 +59,88 @@ pub struct WriteSummary {
     pub total_validators: u64,
     pub validator_summaries: Vec<ValidatorSummary>,
     pub transaction_id: Option<String>,
+    #[serde(skip)]
+    pub computed_debts: ComputedSolanaValidatorDebts,
 }
 
-#[derive(Debug, Default, Serialize, Tabled)]
+#[derive(Debug, Default, Serialize)]
+pub struct FinalizeSummary {
+    pub dz_epoch: u64,
+    pub dry_run: bool,
+    pub transaction_signature: Option<String>,
+    pub posted_to_slack: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, Tabled)]
 pub struct ValidatorSummary {
     pub validator_pubkey: String,
     pub total_debt: u64,
 }
 
+#[derive(Debug, Default, Serialize, Tabled)]
+pub struct DebtVerification {
+    pub node_id: String,
+    pub amount: u64,
+    pub verified: bool,
+    pub error: String,
+}
+
+/// A single validator's debt leaf and merkle proof, for checking against the
+/// on-chain root without access to the full debt record.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct DebtProofExport {
+    pub dz_epoch: u64,
+    pub node_id: String,
+    pub amount: u64,
+    pub leaf_index: usize,
+    /// Prefix hashed with the leaf.
+    pub leaf_prefix: String,
+    /// Base64 of the Borsh-serialized leaf.
+    pub leaf: String,
+    /// Base64 of the Borsh-serialized merkle proof.
+    pub proof: String,
+    /// Solana validator debt merkle root posted to the distribution.
+    pub merkle_root: String,
+    /// Whether the proof reproduces `merkle_root` from the leaf.
+    pub verified: bool,
+}
+
+/// Solana validator debt collection progress of one distribution. Amounts are
+/// in lamports.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CollectionSummary {
+    pub dz_epoch: u64,
+    pub total_debt: u64,
+    pub collected: u64,
+    /// Debt not collected yet, including any written off.
+    pub remaining_debt: u64,
+    /// Debt written off as uncollectible.
+    pub uncollectible: u64,
+    /// Validators whose debt has been paid.
+    pub processed_count: u64,
+    /// Validators whose debt has not been paid.
+    pub remaining_count: u64,
+}
+
+impl From<&Distribution> for CollectionSummary {
+    fn from(distribution: &Distribution) -> Self {
+        let total_validators = u64::from(distribution.total_solana_validators);
+        let processed_count = u64::from(distribution.solana_validator_payments_count);
+
+        Self {
+            dz_epoch: distribution.dz_epoch.value(),
+            total_debt: distribution.total_solana_validator_debt,
+            collected: distribution.collected_solana_validator_payments,
+            remaining_debt: distribution
+                .total_solana_validator_debt
+                .saturating_sub(distribution.collected_solana_validator_payments),
+            uncollectible: distribution.uncollectible_sol_debt,
+            processed_count,
+            remaining_count: total_validators.saturating_sub(processed_count),
+        }
+    }
+}
+
 /// Helper to fetch ProgramConfig using an RpcClient.
 async fn fetch_config_from_rpc(rpc_client: &RpcClient) -> anyhow::Result<Box<ProgramConfig>> {
     let connection =
@@ -75,10 +153,15 @@ pub async fn finalize_distribution(
     solana_debt_calculator: &impl ValidatorRewards,
     transaction: Transaction,
     dz_epoch: u64,
-) -> Result<()> {
+    slack_target: SlackTarget<'_>,
+) -> Result<FinalizeSummary> {
     let config = fetch_config_from_rpc(solana_debt_calculator.solana_rpc_client()).await?;
     if is_config_paused(&config) {
-        return Ok(());
+        return Ok(FinalizeSummary {
+            dz_epoch,
+            dry_run: transaction.dry_run,
+            ..Default::default()
+        });
     }
 
     let transaction_to_submit = transaction
@@ -89,23 +172,50 @@ pub async fn finalize_distribution(
         )
         .await?;
 
-    let transaction_signature = transaction
+    let transaction_output = transaction
         .send_or_simulate_transaction(
             solana_debt_calculator.solana_rpc_client(),
             &transaction_to_submit,
         )
         .await?;
 
-    if let Some(finalized_sig) = transaction_signature {
+    let mut finalize_summary = FinalizeSummary {
+        dz_epoch,
+        dry_run: transaction.dry_run,
+        transaction_signature: finalized_signature(&transaction, transaction_output.as_deref()),
+        posted_to_slack: false,
+    };
+
+    if let Some(finalized_sig) = transaction_output {
         tracing::info!("finalized distribution tx: {finalized_sig:?}");
-        slack_notifier::validator_debt::post_finalized_distribution_to_slack(
+        // A Slack outage should not hide that the distribution was finalized.
+        match slack_notifier::validator_debt::post_finalized_distribution_to_slack(
             finalized_sig,
             dz_epoch,
             transaction.dry_run,
+            slack_target,
         )
-        .await?;
+        .await
+        {
+            Ok(()) => finalize_summary.posted_to_slack = true,
+            Err(err) => tracing::error!("Failed to post finalized distribution to Slack: {err:#}"),
+        }
+    }
+
+    Ok(finalize_summary)
+}
+
+/// The output of a sent transaction is its signature. A dry run only yields
+/// simulation logs, so there is no signature to report.
+fn finalized_signature(
+    transaction: &Transaction,
+    transaction_output: Option<&str>,
+) -> Option<String> {
+    if transaction.dry_run {
+        None
+    } else {
+        transaction_output.map(String::from)
     }
-    Ok(())
 }
 
 pub async fn verify_validator_debt(
@@ -142,10 +252,156 @@ pub async fn verify_validator_debt(
     Ok(())
 }
 
+/// Verifies the merkle proof of every debt in the DZ epoch's debt record
+/// against the on-chain root, reporting a result per validator rather than
+/// stopping at the first failure.
+pub async fn verify_all_validator_debt(
+    solana_debt_calculator: &impl ValidatorRewards,
+    transaction: Transaction,
+    dz_epoch: u64,
+) -> Result<Vec<DebtVerification>> {
+    let (_, computed_debt) = ledger::try_fetch_debt_record(
+        solana_debt_calculator.ledger_rpc_client(),
+        &transaction.signer.pubkey(),
+        dz_epoch,
+        solana_debt_calculator.ledger_commitment_config(),
+    )
+    .await?;
+
+    let verifications = verify_debt_leaves(&computed_debt, &computed_debt.debts, |proof, leaf| {
+        transaction.verify_merkle_root(
+            solana_debt_calculator.solana_rpc_client(),
+            dz_epoch,
+            proof,
+            leaf,
+        )
+    })
+    .await;
+
+    Ok(verifications)
+}
+
+/// Checks each leaf with the proof built from `computed_debt` for its node.
+/// Leaves whose node has no proof in the record fail without calling `verify`.
+async fn verify_debt_leaves<F, Fut>(
+    computed_debt: &ComputedSolanaValidatorDebts,
+    leaves: &[ComputedSolanaValidatorDebt],
+    verify: F,
+) -> Vec<DebtVerification>
+where
+    F: Fn(MerkleProof, SolanaValidatorDebt) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    stream::iter(leaves)
+        .map(|debt| {
+            let proof = computed_debt.find_debt_proof(&debt.node_id);
+            let verify = &verify;
+            async move {
+                let result = match proof {
+                    Some((_, proof)) => {
+                        let leaf = SolanaValidatorDebt {
+                            node_id: debt.node_id,
+                            amount: debt.amount,
+                        };
+                        verify(proof, leaf).await
+                    }
+                    None => Err(anyhow::anyhow!(
+                        "No debt proof found for node {}",
+                        debt.node_id
+                    )),
+                };
+
+                if let Err(err) = &result {
+                    tracing::warn!("Debt proof for {} failed: {err:#}", debt.node_id);
+                }
+
+                DebtVerification {
+                    node_id: debt.node_id.to_string(),
+                    amount: debt.amount,
+                    verified: result.is_ok(),
+                    error: result
+                        .err()
+                        .map(|err| format!("{err:#}"))
+                        .unwrap_or_default(),
+                }
+            }
+        })
+        .buffered(20)
+        .collect()
+        .await
+}
+
+/// Exports the debt proof of one validator in the DZ epoch's debt record along
+/// with the merkle root posted to its distribution.
+pub async fn export_debt_proof(
+    solana_debt_calculator: &impl ValidatorRewards,
+    transaction: Transaction,
+    dz_epoch: u64,
+    node_id: &Pubkey,
+) -> Result<DebtProofExport> {
+    let (_, computed_debt) = ledger::try_fetch_debt_record(
+        solana_debt_calculator.ledger_rpc_client(),
+        &transaction.signer.pubkey(),
+        dz_epoch,
+        solana_debt_calculator.ledger_commitment_config(),
+    )
+    .await?;
+
+    let solana_rpc_client = solana_debt_calculator.solana_rpc_client();
+    let connection = SolanaConnection::new_with_commitment(
+        solana_rpc_client.url(),
+        solana_rpc_client.commitment(),
+    );
+    let (_, distribution) = try_fetch_distribution(&connection, dz_epoch).await?;
+
+    build_debt_proof_export(
+        &computed_debt,
+        dz_epoch,
+        node_id,
+        distribution.solana_validator_debt_merkle_root,
+    )
+}
+
+fn build_debt_proof_export(
+    computed_debt: &ComputedSolanaValidatorDebts,
+    dz_epoch: u64,
+    node_id: &Pubkey,
+    merkle_root: sha2::Hash,
+) -> Result<DebtProofExport> {
+    let leaf_index = computed_debt
+        .debts
+        .iter()
+        .position(|debt| &debt.node_id == node_id)
+        .with_context(|| format!("No debt found for node {node_id} in DZ epoch {dz_epoch}"))?;
+    let (debt, proof) = computed_debt
+        .find_debt_proof(node_id)
+        .with_context(|| format!("No debt proof found for node {node_id}"))?;
+
+    let encoding = base64::engine::general_purpose::STANDARD;
+    let leaf = encoding.encode(borsh::to_vec(debt)?);
+    let encoded_proof = encoding.encode(borsh::to_vec(&proof)?);
+
+    Ok(DebtProofExport {
+        dz_epoch,
+        node_id: node_id.to_string(),
+        amount: debt.amount,
+        leaf_index,
+        leaf_prefix: String::from_utf8_lossy(ComputedSolanaValidatorDebt::LEAF_PREFIX).into_owned(),
+        leaf,
+        proof: encoded_proof,
+        merkle_root: merkle_root.to_string(),
+        verified: debt.merkle_root(proof) == merkle_root,
+    })
+}
+
 pub async fn calculate_distribution(
     solana_debt_calculator: &impl ValidatorRewards,
     transaction: Transaction,
     post_to_ledger_only: bool,
+    reprocess: bool,
+    max_distribution_age_secs: Option<u64>,
+    min_hourly_appearances: usize,
+    network: s3_fetcher::Network,
 ) -> Result<WriteSummary> {
     let config = fetch_config_from_rpc(solana_debt_calculator.solana_rpc_client()).await?;
     let dz_epoch = config.last_completed_epoch().unwrap_or_default().value();
@@ -195,6 +451,248 @@ pub async fn calculate_distribution(
         );
     };
 
+    check_distribution_age(
+        dz_epoch,
+        distribution.calculation_allowed_timestamp as i64,
+        solana_timestamp,
+        max_distribution_age_secs,
+    )?;
+
+    let solana_epoch = match joined_solana_epochs(solana_debt_calculator, dz_epoch).await? {
+        JoinedSolanaEpochs::Range(solana_epoch_range) => {
+            select_solana_epoch(&solana_epoch_range, dz_epoch)
+        }
+        JoinedSolanaEpochs::Duplicate(overlapping_solana_epoch) => {
+            let outcome = handle_overlapping_epoch(
+                transaction.force,
+                transaction.strict,
+                dz_epoch,
+                overlapping_solana_epoch,
+                || async {
+                    let recent_blockhash =
+                        try_get_latest_blockhash(solana_debt_calculator.ledger_rpc_client())
+                            .await?;
+
+                    ledger::create_record_on_ledger(
+                        solana_debt_calculator.ledger_rpc_client(),
+                        recent_blockhash,
+                        &transaction.signer,
+                        &ComputedSolanaValidatorDebts::default(),
+                        solana_debt_calculator.ledger_commitment_config(),
+                        &[
+                            ComputedSolanaValidatorDebts::RECORD_SEED_PREFIX,
+                            &dz_epoch.to_le_bytes(),
+                        ],
+                    )
+                    .await
+                },
+                || async {
+                    transaction
+                        .finalize_distribution(
+                            solana_debt_calculator.solana_rpc_client(),
+                            solana_debt_calculator.ledger_rpc_client(),
+                            dz_epoch,
+                        )
+                        .await
+                        .map(|_| ())
+                },
+            )
+            .await?;
+
+            match outcome {
+                OverlappingEpochOutcome::ZeroedAndFinalized => {
+                    bail!("No debt to pay for dz epoch {dz_epoch}")
+                }
+                OverlappingEpochOutcome::RequiresForce => {
+                    bail!("To finalize the debt for an empty DZ epoch use `--force`")
+                }
+            }
+        }
+    };
+
+    let validator_pubkeys = fetch_s3_validator_pubkeys(
+        solana_debt_calculator,
+        solana_epoch,
+        min_hourly_appearances,
+        network,
+    )
+    .await?;
+
+    let computed_solana_validator_debt_vec = compute_validator_debts(
+        solana_debt_calculator,
+        &distribution,
+        &validator_pubkeys,
+        solana_epoch,
+        transaction.strict,
+    )
+    .await?;
+
+    let recent_blockhash =
+        try_get_latest_blockhash(solana_debt_calculator.ledger_rpc_client()).await?;
+
+    let computed_solana_validator_debts = ComputedSolanaValidatorDebts {
+        blockhash: recent_blockhash,
+        first_solana_epoch: solana_epoch,
+        last_solana_epoch: solana_epoch,
+        debts: computed_solana_validator_debt_vec,
+    };
+
+    if transaction.dry_run {
+        // TODO: Should this be an error?
+        tracing::warn!("Posting to ledger is not supported with `--dry-run`");
+    } else {
+        create_or_validate_ledger_record(
+            solana_debt_calculator,
+            &transaction,
+            computed_solana_validator_debts.clone(),
+            dz_epoch,
+            recent_blockhash,
+            reprocess,
+        )
+        .await?;
+    }
+
+    if post_to_ledger_only {
+        bail!("Debt posted only to DoubleZero Ledger and process exited")
+    }
+
+    let submitted_tx = write_transaction(
+        solana_debt_calculator.solana_rpc_client(),
+        &computed_solana_validator_debts,
+        &transaction,
+        dz_epoch,
+    )
+    .await?;
+
+    Ok(build_write_summary(
+        dz_epoch,
+        solana_epoch,
+        transaction.dry_run,
+        &computed_solana_validator_debts,
+        submitted_tx,
+    ))
+}
+
+/// Fetches the distribution of the last completed DZ epoch, which is the one
+/// `calculate_distribution` computes debt for.
+pub async fn fetch_last_completed_distribution(
+    solana_debt_calculator: &impl ValidatorRewards,
+) -> Result<(u64, Distribution)> {
+    let solana_rpc_client = solana_debt_calculator.solana_rpc_client();
+    let connection = SolanaConnection::new_with_commitment(
+        solana_rpc_client.url(),
+        solana_rpc_client.commitment(),
+    );
+
+    let (_, config) = try_fetch_config(&connection).await?;
+    let dz_epoch = config.last_completed_epoch().unwrap_or_default().value();
+    let (_, distribution) = try_fetch_distribution(&connection, dz_epoch).await?;
+
+    Ok((dz_epoch, distribution))
+}
+
+/// Reads the distribution of `dz_epoch` and summarizes its debt collection.
+pub async fn summarize_collection(
+    connection: &SolanaConnection,
+    dz_epoch: u64,
+) -> Result<CollectionSummary> {
+    let (_, distribution) = try_fetch_distribution(connection, dz_epoch).await?;
+    Ok(CollectionSummary::from(&*distribution.mucked_data))
+}
+
+/// Runs the same reward-to-debt computation as `calculate_distribution` for
+/// the last completed DZ epoch, without creating ledger records or submitting
+/// transactions. Only read-only RPC calls are made.
+pub async fn estimate_distribution(
+    solana_debt_calculator: &impl ValidatorRewards,
+    min_hourly_appearances: usize,
+    network: s3_fetcher::Network,
+) -> Result<WriteSummary> {
+    let solana_rpc_client = solana_debt_calculator.solana_rpc_client();
+    let connection = SolanaConnection::new_with_commitment(
+        solana_rpc_client.url(),
+        solana_rpc_client.commitment(),
+    );
+
+    let (_, config) = try_fetch_config(&connection).await?;
+    let dz_epoch = config.last_completed_epoch().unwrap_or_default().value();
+    if is_config_paused(&config) {
+        return Ok(WriteSummary {
+            dz_epoch,
+            dry_run: true,
+            ..Default::default()
+        });
+    }
+
+    let (_, distribution) = try_fetch_distribution(&connection, dz_epoch).await?;
+
+    let solana_epoch = match joined_solana_epochs(solana_debt_calculator, dz_epoch).await? {
+        JoinedSolanaEpochs::Range(solana_epoch_range) => {
+            select_solana_epoch(&solana_epoch_range, dz_epoch)
+        }
+        JoinedSolanaEpochs::Duplicate(overlapping_solana_epoch) => bail!(
+            "DZ epoch {dz_epoch} overlaps already accounted Solana epoch {overlapping_solana_epoch}; its debt would be zeroed out"
+        ),
+    };
+
+    let validator_pubkeys = fetch_s3_validator_pubkeys(
+        solana_debt_calculator,
+        solana_epoch,
+        min_hourly_appearances,
+        network,
+    )
+    .await?;
+
+    estimate_debts(
+        solana_debt_calculator,
+        &distribution,
+        &validator_pubkeys,
+        dz_epoch,
+        solana_epoch,
+    )
+    .await
+}
+
+/// Computes debts for the given validators and summarizes them as a dry run.
+/// Never touches the Solana or DZ Ledger RPC clients directly.
+async fn estimate_debts(
+    solana_debt_calculator: &impl ValidatorRewards,
+    distribution: &Distribution,
+    validator_pubkeys: &[String],
+    dz_epoch: u64,
+    solana_epoch: u64,
+) -> Result<WriteSummary> {
+    let computed_solana_validator_debt_vec = compute_validator_debts(
+        solana_debt_calculator,
+        distribution,
+        validator_pubkeys,
+        solana_epoch,
+        false, // strict
+    )
+    .await?;
+
+    let computed_solana_validator_debts = ComputedSolanaValidatorDebts {
+        first_solana_epoch: solana_epoch,
+        last_solana_epoch: solana_epoch,
+        debts: computed_solana_validator_debt_vec,
+        ..Default::default()
+    };
+
+    Ok(build_write_summary(
+        dz_epoch,
+        solana_epoch,
+        true,
+        &computed_solana_validator_debts,
+        None,
+    ))
+}
+
+/// Finds the Solana epochs joined to a DZ epoch. `Duplicate` means the DZ
+/// epoch only overlaps a Solana epoch that was already accounted for.
+async fn joined_solana_epochs(
+    solana_debt_calculator: &impl ValidatorRewards,
+    dz_epoch: u64,
+) -> Result<JoinedSolanaEpochs> {
     let rate_limiter = RateLimiter::builder()
         .max(10)
         .initial(10)
@@ -202,19 +700,17 @@ pub async fn calculate_distribution(
         .interval(std::time::Duration::from_secs(1))
         .build();
 
-    let mut epochs: Vec<u64> = Vec::new();
-
-    match JoinedSolanaEpochs::try_new(
+    let joined_solana_epochs = JoinedSolanaEpochs::try_new(
         solana_debt_calculator.solana_rpc_client(),
         solana_debt_calculator.ledger_rpc_client(),
         dz_epoch,
         &rate_limiter,
     )
-    .await?
-    {
+    .await?;
+
+    match &joined_solana_epochs {
         JoinedSolanaEpochs::Range(solana_epoch_range) => {
-            solana_epoch_range.into_iter().for_each(|solana_epoch| {
-                epochs.push(solana_epoch);
+            solana_epoch_range.clone().for_each(|solana_epoch| {
                 tracing::info!("Joined Solana epoch: {solana_epoch}");
             });
         }
@@ -225,56 +721,99 @@ pub async fn calculate_distribution(
         }
     };
 
-    let recent_blockhash = solana_debt_calculator
-        .ledger_rpc_client()
-        .get_latest_blockhash()
-        .await?;
+    Ok(joined_solana_epochs)
+}
 
-    // this means the previous dz epoch traversed more than one solana epoch
-    // if the current dz_epoch_record's solana epoch is also in the previous record's epoch
-    //  then we've already calculated the debt for that epoch and will send a zeroed-out record
-    //  and transaction for the current dz epoch
-    if epochs.is_empty() {
-        // zero out the debt
-        let computed_solana_validator_debts = ComputedSolanaValidatorDebts::default();
+/// What happened to a DZ epoch whose only Solana epoch was already charged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlappingEpochOutcome {
+    /// A zeroed debt record was written and the distribution finalized
+    ZeroedAndFinalized,
+    /// A zeroed debt record was written, but finalizing needs `--force`
+    RequiresForce,
+}
 
-        ledger::create_record_on_ledger(
-            solana_debt_calculator.ledger_rpc_client(),
-            recent_blockhash,
-            &transaction.signer,
-            &computed_solana_validator_debts,
-            solana_debt_calculator.ledger_commitment_config(),
-            &[
-                ComputedSolanaValidatorDebts::RECORD_SEED_PREFIX,
-                &dz_epoch.to_le_bytes(),
-            ],
-        )
-        .await?;
+/// Handles a DZ epoch that only overlaps an already charged Solana epoch.
+/// A zeroed debt record is always written, since the debt for that Solana
+/// epoch was collected with the previous DZ epoch. The distribution is only
+/// finalized when `force` is set. With `strict`, nothing is written.
+async fn handle_overlapping_epoch<Z, ZFut, F, FFut>(
+    force: bool,
+    strict: bool,
+    dz_epoch: u64,
+    overlapping_solana_epoch: u64,
+    zero_out_debt: Z,
+    finalize: F,
+) -> Result<OverlappingEpochOutcome>
+where
+    Z: FnOnce() -> ZFut,
+    ZFut: Future<Output = Result<()>>,
+    F: FnOnce() -> FFut,
+    FFut: Future<Output = Result<()>>,
+{
+    ensure_not_strict(
+        strict,
+        format_args!(
+            "DZ epoch {dz_epoch} only overlaps already charged Solana epoch {overlapping_solana_epoch}"
+        ),
+    )?;
 
-        // TODO: Do we want force as an option?
-        if transaction.force {
-            tracing::warn!(
-                "No non-overlapping solana epoch found. Zeroing out debt for DZ epoch {dz_epoch}"
-            );
-            transaction
-                .finalize_distribution(
-                    solana_debt_calculator.solana_rpc_client(),
-                    solana_debt_calculator.ledger_rpc_client(),
-                    dz_epoch,
-                )
-                .await?;
-            bail!("No debt to pay for dz epoch {dz_epoch}")
-        } else {
-            bail!("To finalize the debt for an empty DZ epoch use `--force`");
-        };
-    };
+    tracing::warn!(
+        dz_epoch,
+        overlapping_solana_epoch,
+        force,
+        "DZ epoch only overlaps an already charged Solana epoch, zeroing out its debt"
+    );
 
-    let solana_epoch_from_first_dz_epoch_block = epochs.first().unwrap().to_owned();
-    let solana_epoch_from_last_dz_epoch_block = epochs.last().unwrap().to_owned();
+    zero_out_debt().await?;
 
-    let solana_epoch = if solana_epoch_from_first_dz_epoch_block
-        == solana_epoch_from_last_dz_epoch_block
-    {
+    if !force {
+        return Ok(OverlappingEpochOutcome::RequiresForce);
+    }
+
+    finalize().await?;
+
+    Ok(OverlappingEpochOutcome::ZeroedAndFinalized)
+}
+
+/// Fails with `warning` when `strict` is set. Otherwise the caller logs the
+/// warning and carries on.
+fn ensure_not_strict(strict: bool, warning: impl std::fmt::Display) -> Result<()> {
+    ensure!(!strict, "{warning} (--strict)");
+    Ok(())
+}
+
+/// Guards against calculating for a distribution whose calculation window
+/// opened more than `max_age_secs` ago, which points to a stalled pipeline
+fn check_distribution_age(
+    dz_epoch: u64,
+    calculation_allowed_timestamp: i64,
+    solana_timestamp: i64,
+    max_age_secs: Option<u64>,
+) -> Result<()> {
+    let age_secs = solana_timestamp
+        .saturating_sub(calculation_allowed_timestamp)
+        .max(0) as u64;
+
+    if let Some(max_age_secs) = max_age_secs {
+        ensure!(
+            age_secs <= max_age_secs,
+            "distribution for dz epoch {dz_epoch} became calculable {age_secs}s ago, \
+             exceeding the maximum age of {max_age_secs}s; the pipeline may be stalled"
+        );
+    }
+
+    tracing::info!(dz_epoch, age_secs, "distribution age");
+
+    Ok(())
+}
+
+/// Picks the Solana epoch to charge for from the epochs joined to a DZ epoch
+fn select_solana_epoch(solana_epoch_range: &RangeInclusive<u64>, dz_epoch: u64) -> u64 {
+    let solana_epoch_from_first_dz_epoch_block = *solana_epoch_range.start();
+    let solana_epoch_from_last_dz_epoch_block = *solana_epoch_range.end();
+
+    if solana_epoch_from_first_dz_epoch_block == solana_epoch_from_last_dz_epoch_block {
         tracing::info!(
             "DZ epoch {dz_epoch} contains only {solana_epoch_from_first_dz_epoch_block} only"
         );
@@ -284,19 +823,28 @@ pub async fn calculate_distribution(
             "DZ epoch {dz_epoch} overlaps {solana_epoch_from_last_dz_epoch_block} and {solana_epoch_from_first_dz_epoch_block}"
         );
         solana_epoch_from_last_dz_epoch_block
-    };
+    }
+}
 
+/// Fetches the sorted identity pubkeys of validators eligible for fees
+async fn fetch_s3_validator_pubkeys(
+    solana_debt_calculator: &impl ValidatorRewards,
+    solana_epoch: u64,
+    min_hourly_appearances: usize,
+    network: s3_fetcher::Network,
+) -> Result<Vec<String>> {
     // Fetch validator pubkeys from S3 using the canonical approach
-    tracing::info!("Fetching validator pubkeys from S3 for epoch {solana_epoch}");
+    tracing::info!("Fetching validator pubkeys from S3 for epoch {solana_epoch} ({network:?})");
     let s3_validator_keys = s3_fetcher::fetch_validator_pubkeys(
         solana_epoch,
         solana_debt_calculator.solana_rpc_client(),
-        s3_fetcher::Network::MainnetBeta,
+        network,
+        min_hourly_appearances,
     )
     .await?;
 
     tracing::info!(
-        "Found {} validators from S3 (after 12-hour rule)",
+        "Found {} validators from S3 (more than {min_hourly_appearances} hourly appearances)",
         s3_validator_keys.len()
     );
 
@@ -308,10 +856,21 @@ pub async fn calculate_distribution(
 
     validator_pubkeys.sort();
 
+    Ok(validator_pubkeys)
+}
+
+/// Applies the distribution's fee parameters to each validator's rewards.
+/// Validators that owe nothing are left out.
+async fn compute_validator_debts(
+    solana_debt_calculator: &impl ValidatorRewards,
+    distribution: &Distribution,
+    validator_pubkeys: &[String],
+    solana_epoch: u64,
+    strict: bool,
+) -> Result<Vec<ComputedSolanaValidatorDebt>> {
     // Use S3-fetched validators and calculate rewards
     let validator_rewards =
-        rewards::get_total_rewards(solana_debt_calculator, &validator_pubkeys, solana_epoch)
-            .await?;
+        rewards::get_total_rewards(solana_debt_calculator, validator_pubkeys, solana_epoch).await?;
 
     // gather rewards into debts for all validators
     tracing::info!("Computing solana validator debt");
@@ -347,75 +906,195 @@ pub async fn calculate_distribution(
         .filter(|vd| vd.amount != 0)
         .collect::<Vec<_>>();
 
-    let recent_blockhash = solana_debt_calculator
-        .ledger_rpc_client()
-        .get_latest_blockhash()
-        .await?;
+    DebtReconciliation {
+        s3_validators: validator_pubkeys.len(),
+        nonzero_debts: computed_solana_validator_debt_vec.len(),
+        zero_debts: validator_rewards.rewards.len() - computed_solana_validator_debt_vec.len(),
+    }
+    .report(solana_epoch, strict)?;
 
-    let computed_solana_validator_debts = ComputedSolanaValidatorDebts {
-        blockhash: recent_blockhash,
-        first_solana_epoch: solana_epoch,
-        last_solana_epoch: solana_epoch,
-        debts: computed_solana_validator_debt_vec.clone(),
-    };
+    Ok(computed_solana_validator_debt_vec)
+}
 
-    if transaction.dry_run {
-        // TODO: Should this be an error?
-        tracing::warn!("Posting to ledger is not supported with `--dry-run`");
-    } else {
-        create_or_validate_ledger_record(
-            solana_debt_calculator,
-            &transaction,
-            computed_solana_validator_debts.clone(),
-            dz_epoch,
-            recent_blockhash,
-        )
-        .await?;
+/// How the validators fetched from S3 were accounted for in the debt vector.
+/// Every S3 validator should end up with either a nonzero or a zero debt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DebtReconciliation {
+    s3_validators: usize,
+    nonzero_debts: usize,
+    zero_debts: usize,
+}
+
+impl DebtReconciliation {
+    /// More debts than S3 validators means rewards were computed for
+    /// validators that were never fetched.
+    fn is_reconciled(&self) -> bool {
+        self.s3_validators >= self.nonzero_debts + self.zero_debts
     }
 
-    if post_to_ledger_only {
-        bail!("Debt posted only to DoubleZero Ledger and process exited")
+    /// S3 validators that were dropped before a debt was computed for them.
+    fn dropped_validators(&self) -> usize {
+        self.s3_validators
+            .saturating_sub(self.nonzero_debts + self.zero_debts)
     }
 
-    let submitted_tx = write_transaction(
-        solana_debt_calculator.solana_rpc_client(),
-        &computed_solana_validator_debts,
-        &transaction,
+    fn report(&self, solana_epoch: u64, strict: bool) -> Result<()> {
+        let Self {
+            s3_validators,
+            nonzero_debts,
+            zero_debts,
+        } = *self;
+
+        tracing::info!(
+            solana_epoch,
+            s3_validators,
+            nonzero_debts,
+            zero_debts,
+            "validator debt reconciliation"
+        );
+        metrics::gauge!("doublezero_validator_debt_s3_validators", "solana_epoch" => solana_epoch.to_string())
+            .set(s3_validators as f64);
+        metrics::gauge!("doublezero_validator_debt_nonzero_debts", "solana_epoch" => solana_epoch.to_string())
+            .set(nonzero_debts as f64);
+        metrics::gauge!("doublezero_validator_debt_zero_debts", "solana_epoch" => solana_epoch.to_string())
+            .set(zero_debts as f64);
+
+        let warning = if !self.is_reconciled() {
+            format!(
+                "Computed {} debts for Solana epoch {solana_epoch} but only {s3_validators} \
+                 validators were fetched from S3",
+                nonzero_debts + zero_debts
+            )
+        } else if self.dropped_validators() > 0 {
+            format!(
+                "{} of {s3_validators} validators fetched from S3 for Solana epoch \
+                 {solana_epoch} have no computed debt",
+                self.dropped_validators()
+            )
+        } else {
+            return Ok(());
+        };
+
+        ensure_not_strict(strict, &warning)?;
+        tracing::warn!("{warning}");
+
+        Ok(())
+    }
+}
+
+fn build_write_summary(
+    dz_epoch: u64,
+    solana_epoch: u64,
+    dry_run: bool,
+    computed_solana_validator_debts: &ComputedSolanaValidatorDebts,
+    transaction_id: Option<String>,
+) -> WriteSummary {
+    let validator_summaries: Vec<ValidatorSummary> = computed_solana_validator_debts
+        .debts
+        .iter()
+        .map(|vr| ValidatorSummary {
+            validator_pubkey: vr.node_id.to_string(),
+            total_debt: vr.amount,
+        })
+        .collect();
+
+    WriteSummary {
         dz_epoch,
-    )
-    .await?;
+        solana_epoch,
+        total_debt: computed_solana_validator_debts.total_debt(),
+        dry_run,
+        total_validators: computed_solana_validator_debts.debts.len() as u64,
+        transaction_id,
+        validator_summaries,
+        computed_debts: computed_solana_validator_debts.clone(),
+    }
+}
+
+/// Overrides the `VALIDATOR_SLACK_WEBHOOK` and `SLACK_MAX_ATTEMPTS` env vars
+/// for Slack reports.
+#[derive(Debug, Clone, Default, PartialEq, Eq, clap::Args)]
+pub struct SlackWebhookOptions {
+    /// Slack webhook URL to post to instead of `VALIDATOR_SLACK_WEBHOOK`.
+    #[arg(long, value_name = "URL", value_parser = parse_slack_webhook)]
+    pub slack_webhook: Option<String>,
+
+    /// Attempts to post each Slack message, retrying rate limits and server
+    /// errors, instead of `SLACK_MAX_ATTEMPTS` (default 3).
+    #[arg(long, value_name = "N", value_parser = parse_max_attempts)]
+    pub max_attempts: Option<usize>,
+}
+
+impl SlackWebhookOptions {
+    pub fn target(&self) -> SlackTarget<'_> {
+        SlackTarget {
+            webhook_override: self.slack_webhook.as_deref(),
+            max_attempts: self.max_attempts,
+        }
+    }
+}
+
+fn parse_slack_webhook(webhook: &str) -> Result<String> {
+    slack_notifier::validator_debt::validate_webhook_url(webhook)
+}
+
+fn parse_max_attempts(max_attempts: &str) -> Result<usize> {
+    let max_attempts = max_attempts.parse()?;
+    ensure!(max_attempts > 0, "Max attempts must be at least 1");
+    Ok(max_attempts)
+}
+
+/// Controls which Slack reports `pay_all_solana_validator_debt` posts.
+#[derive(Debug, Clone, PartialEq, Eq, clap::Args)]
+pub struct SlackReportOptions {
+    /// Post the total debt collection summary to Slack.
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+    pub slack_summary: bool,
+
+    /// Post the per-epoch debt collection table to Slack.
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+    pub slack_details: bool,
 
-    let debt_map: HashMap<String, u64> = computed_solana_validator_debts
-        .debts
-        .iter()
-        .map(|debt| (debt.node_id.to_string(), debt.amount))
-        .collect();
+    /// Format of the total debt collection summary.
+    #[arg(long, value_enum, default_value_t = SlackSummaryFormat::Blocks)]
+    pub slack_summary_format: SlackSummaryFormat,
 
-    let validator_summaries: Vec<ValidatorSummary> = computed_solana_validator_debt_vec
-        .clone()
-        .into_iter()
-        .map(|vr| ValidatorSummary {
-            validator_pubkey: vr.node_id.to_string().clone(),
-            total_debt: vr.amount,
-        })
-        .collect();
+    #[command(flatten)]
+    pub webhook: SlackWebhookOptions,
+}
 
-    let write_summary = WriteSummary {
-        dz_epoch,
-        solana_epoch,
-        total_debt: debt_map.iter().map(|dm| dm.1).sum(),
-        dry_run: transaction.dry_run,
-        total_validators: computed_solana_validator_debts.debts.len() as u64,
-        transaction_id: submitted_tx,
-        validator_summaries,
-    };
+impl Default for SlackReportOptions {
+    fn default() -> Self {
+        Self {
+            slack_summary: true,
+            slack_details: true,
+            slack_summary_format: SlackSummaryFormat::Blocks,
+            webhook: SlackWebhookOptions::default(),
+        }
+    }
+}
+
+/// Options shared by the commands paying Solana validator debt.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::Args)]
+pub struct PayDebtOptions {
+    /// Attempt payments whose deposits fully cover their debt before
+    /// underfunded ones, so a limited fee budget collects the most SOL.
+    #[arg(long)]
+    pub prioritize_collectible: bool,
+}
 
-    Ok(write_summary)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SlackSummaryFormat {
+    /// Color-coded Block Kit fields.
+    Blocks,
+    /// Plain table.
+    Table,
 }
 
 pub async fn pay_all_solana_validator_debt(
     wallet: Wallet,
     dz_ledger: DoubleZeroLedgerConnection,
+    slack_report_options: SlackReportOptions,
+    pay_debt_options: PayDebtOptions,
 ) -> Result<()> {
     let (_, config) = try_fetch_config(&wallet.connection).await?;
 
@@ -434,8 +1113,14 @@ pub async fn pay_all_solana_validator_debt(
             let config_ref = &config;
 
             async move {
-                let result =
-                    pay_solana_validator_debt(wallet_ref, ledger_ref, dz_epoch, config_ref).await?;
+                let result = pay_solana_validator_debt(
+                    wallet_ref,
+                    ledger_ref,
+                    dz_epoch,
+                    config_ref,
+                    pay_debt_options,
+                )
+                .await?;
                 tracing::info!("Finished debt collection for epoch {dz_epoch}");
                 Ok::<_, anyhow::Error>(result)
             }
@@ -445,9 +1130,45 @@ pub async fn pay_all_solana_validator_debt(
         .await?;
 
     let client = reqwest::Client::new();
+    let slack_target = slack_report_options.webhook.target();
+
+    post_debt_collection_reports(
+        &slack_report_options,
+        || {
+            post_debt_collection_summary_to_slack(
+                &tasks,
+                slack_report_options.slack_summary_format,
+                slack_target,
+                &client,
+            )
+        },
+        || post_debt_collections_to_slack(&tasks, false, slack_target, &client),
+    )
+    .await
+}
 
-    post_debt_collection_summary_to_slack(&tasks, &client).await?;
-    post_debt_collections_to_slack(&tasks, false, &client).await?;
+async fn post_debt_collection_reports<S, SFut, D, DFut>(
+    slack_report_options: &SlackReportOptions,
+    post_summary: S,
+    post_details: D,
+) -> Result<()>
+where
+    S: FnOnce() -> SFut,
+    SFut: Future<Output = Result<()>>,
+    D: FnOnce() -> DFut,
+    DFut: Future<Output = Result<()>>,
+{
+    // Debt has already been paid, so a Slack outage only loses the reports.
+    if slack_report_options.slack_summary
+        && let Err(err) = post_summary().await
+    {
+        tracing::error!("Failed to post debt collection summary to Slack: {err:#}");
+    }
+    if slack_report_options.slack_details
+        && let Err(err) = post_details().await
+    {
+        tracing::error!("Failed to post debt collection details to Slack: {err:#}");
+    }
 
     Ok(())
 }
@@ -457,7 +1178,17 @@ pub async fn pay_solana_validator_debt(
     dz_ledger: &DoubleZeroLedgerConnection,
     dz_epoch_value: u64,
     config: &ProgramConfig,
+    pay_debt_options: PayDebtOptions,
 ) -> Result<DebtCollectionResults> {
+    // Never pay debt computed on one environment's ledger with another
+    // environment's deposits.
+    let is_mainnet = wallet
+        .connection
+        .try_network_environment()
+        .await?
+        .is_mainnet_beta();
+    ledger::ensure_same_network_environment(dz_ledger, is_mainnet).await?;
+
     let (_, computed_debt) = ledger::try_fetch_debt_record(
         dz_ledger,
         &config.debt_accountant_key,
@@ -470,8 +1201,15 @@ pub async fn pay_solana_validator_debt(
 
     try_initialize_missing_deposit_accounts(wallet, &computed_debt).await?;
 
+    let deposit_balances = if pay_debt_options.prioritize_collectible {
+        Some(try_fetch_deposit_balances(&wallet.connection, &computed_debt).await?)
+    } else {
+        None
+    };
+
     let arc_signer = Arc::new(wallet.signer.insecure_clone());
-    let transaction = Transaction::new(arc_signer, wallet.dry_run, false);
+    let transaction = Transaction::new(arc_signer, wallet.dry_run, false)
+        .with_compute_unit_price_ix(wallet.compute_unit_price_ix.clone());
 
     transaction
         .pay_solana_validator_debt(
@@ -479,10 +1217,35 @@ pub async fn pay_solana_validator_debt(
             computed_debt,
             dz_epoch_value,
             &distribution,
+            wallet.tx_rate_limiter.as_deref(),
+            deposit_balances.as_ref(),
+            Some(&report_debt_collection_progress),
         )
         .await
 }
 
+/// Log collection progress every this many validators.
+const DEBT_COLLECTION_PROGRESS_LOG_INTERVAL: usize = 100;
+
+fn report_debt_collection_progress(progress: DebtCollectionProgress) {
+    let DebtCollectionProgress {
+        dz_epoch,
+        processed_count,
+        total_count,
+    } = progress;
+
+    metrics::gauge!("doublezero_validator_debt_collection_progress", "dz_epoch" => dz_epoch.to_string())
+        .set(progress.fraction());
+
+    if processed_count.is_multiple_of(DEBT_COLLECTION_PROGRESS_LOG_INTERVAL)
+        || processed_count == total_count
+    {
+        tracing::info!(
+            "Processed {processed_count} of {total_count} validator debts for epoch {dz_epoch}"
+        );
+    }
+}
+
 async fn write_transaction(
     solana_rpc_client: &RpcClient,
     computed_solana_validator_debts: &ComputedSolanaValidatorDebts,
@@ -493,11 +1256,7 @@ async fn write_transaction(
 
     // Create the data for the solana transaction
     let total_validators: u32 = computed_solana_validator_debts.debts.len() as u32;
-    let total_debt: u64 = computed_solana_validator_debts
-        .debts
-        .iter()
-        .map(|debt| debt.amount)
-        .sum();
+    let total_debt = computed_solana_validator_debts.total_debt();
 
     tracing::info!("Writing total debt {total_debt} to solana for {total_validators} validators");
 
@@ -529,48 +1288,39 @@ async fn write_transaction(
 
 pub async fn post_debt_collection_summary_to_slack(
     debt_collection_results: &[DebtCollectionResults],
+    format: SlackSummaryFormat,
+    slack_target: SlackTarget<'_>,
     client: &Client,
 ) -> Result<()> {
-    let total_paid: u64 = debt_collection_results.iter().map(|tp| tp.total_paid).sum();
-    let total_debt: u64 = debt_collection_results.iter().map(|td| td.total_debt).sum();
-    let insufficient_funds_count: usize = debt_collection_results
-        .iter()
-        .map(|ifc| ifc.insufficient_funds_count)
-        .sum();
-
-    let header = "Total Debt Collection";
-    let table_header = vec![
-        "Total Paid".to_string(),
-        "Total Debt".to_string(),
-        "Total Outstanding".to_string(),
-        "Total Percentage Paid".to_string(),
-        "Total Insufficient Funds Count".to_string(),
-    ];
+    let summary = slack_notifier::validator_debt::DebtCollectionSummary {
+        total_paid: debt_collection_results.iter().map(|tp| tp.total_paid).sum(),
+        total_debt: debt_collection_results.iter().map(|td| td.total_debt).sum(),
+        insufficient_funds_count: debt_collection_results
+            .iter()
+            .map(|ifc| ifc.insufficient_funds_count)
+            .sum(),
+    };
 
     // TODO: figure out why a mysterious empty total debt collection is posted only on remote env
     // this is a dumb bandaid to fix the quirk
-    if total_debt == 0 {
+    if summary.total_debt == 0 {
         return Ok(());
     };
 
-    let total_outstanding = total_debt.saturating_sub(total_paid);
-    let percentage_paid = total_paid as f64 / total_debt as f64;
-
-    let table_values = vec![
-        format!("{:.9} SOL", total_paid as f64 * 1e-9),
-        format!("{:.9} SOL", total_debt as f64 * 1e-9),
-        format!("{:.9} SOL", total_outstanding as f64 * 1e-9),
-        format!("{:.2}%", percentage_paid * 100.0),
-        insufficient_funds_count.to_string(),
-    ];
-    slack_notifier::validator_debt::post_to_slack(None, client, header, table_header, table_values)
-        .await?;
-    Ok(())
+    slack_notifier::validator_debt::post_debt_collection_summary_to_slack(
+        client,
+        slack_target,
+        "Total Debt Collection",
+        &summary,
+        format == SlackSummaryFormat::Blocks,
+    )
+    .await
 }
 
 pub async fn post_debt_collections_to_slack(
     debt_collection_results: &[DebtCollectionResults],
     dry_run: bool,
+    slack_target: SlackTarget<'_>,
     client: &Client,
 ) -> Result<()> {
     let header = if dry_run {
@@ -620,6 +1370,7 @@ pub async fn post_debt_collections_to_slack(
     if !table_values.is_empty() {
         slack_notifier::validator_debt::post_debt_collections_to_slack(
             client,
+            slack_target,
             header,
             table_header,
             table_values,
@@ -633,6 +1384,7 @@ pub async fn post_debt_collection_to_slack(
     debt_collection_results: DebtCollectionResults,
     dry_run: bool,
     filepath: Option<String>,
+    slack_target: SlackTarget<'_>,
 ) -> Result<()> {
     let client = reqwest::Client::new();
     let header = if dry_run {
@@ -693,6 +1445,7 @@ pub async fn post_debt_collection_to_slack(
 
     slack_notifier::validator_debt::post_to_slack(
         filepath,
+        slack_target,
         &client,
         header,
         table_header,
@@ -709,17 +1462,61 @@ async fn create_or_validate_ledger_record(
     new_computed_debt: ComputedSolanaValidatorDebts,
     dz_epoch: u64,
     recent_blockhash: solana_sdk::hash::Hash,
+    reprocess: bool,
 ) -> Result<ComputedSolanaValidatorDebts> {
-    let record_result = ledger::try_fetch_debt_record(
+    let existing_computed_debt = ledger::try_fetch_debt_record(
         solana_debt_calculator.ledger_rpc_client(),
         &transaction.signer.pubkey(),
         dz_epoch,
         solana_debt_calculator.ledger_commitment_config(),
     )
-    .await;
+    .await
+    .ok()
+    .map(|(_, existing_computed_debt)| existing_computed_debt);
+
+    reconcile_ledger_record(
+        existing_computed_debt,
+        &new_computed_debt,
+        transaction.force,
+        transaction.strict,
+        reprocess,
+        || async {
+            ledger::create_record_on_ledger(
+                solana_debt_calculator.ledger_rpc_client(),
+                recent_blockhash,
+                &transaction.signer,
+                &new_computed_debt,
+                solana_debt_calculator.ledger_commitment_config(),
+                &[
+                    ComputedSolanaValidatorDebts::RECORD_SEED_PREFIX,
+                    &dz_epoch.to_le_bytes(),
+                ],
+            )
+            .await
+        },
+    )
+    .await
+}
 
-    match record_result {
-        Ok((_, existing_computed_debt)) => {
+/// Decides whether the computed debt is written to the DZ Ledger record.
+/// A missing record is created. An existing record is overwritten when
+/// `reprocess` is set, even if it matches, or when `force` is set. Otherwise
+/// it must match the computed debt and is reused. With `strict`, `force` no
+/// longer overwrites a record that does not match.
+async fn reconcile_ledger_record<W, WFut>(
+    existing_computed_debt: Option<ComputedSolanaValidatorDebts>,
+    new_computed_debt: &ComputedSolanaValidatorDebts,
+    force: bool,
+    strict: bool,
+    reprocess: bool,
+    write_record: W,
+) -> Result<ComputedSolanaValidatorDebts>
+where
+    W: FnOnce() -> WFut,
+    WFut: Future<Output = Result<()>>,
+{
+    match existing_computed_debt {
+        Some(existing_computed_debt) => {
             if existing_computed_debt.blockhash == new_computed_debt.blockhash {
                 bail!(
                     "retrieved record blockhash {} is equal to created record blockhash {}",
@@ -728,19 +1525,27 @@ async fn create_or_validate_ledger_record(
                 );
             }
 
-            if transaction.force {
-                ledger::create_record_on_ledger(
-                    solana_debt_calculator.ledger_rpc_client(),
-                    recent_blockhash,
-                    &transaction.signer,
-                    &new_computed_debt,
-                    solana_debt_calculator.ledger_commitment_config(),
-                    &[
-                        ComputedSolanaValidatorDebts::RECORD_SEED_PREFIX,
-                        &dz_epoch.to_le_bytes(),
-                    ],
-                )
-                .await?;
+            if reprocess {
+                tracing::warn!(
+                    existing_blockhash = %existing_computed_debt.blockhash,
+                    new_blockhash = %new_computed_debt.blockhash,
+                    matches_existing = existing_computed_debt.debts == new_computed_debt.debts,
+                    "REPROCESSING: overwriting the DZ Ledger debt record with freshly computed debt. \
+                     Merkle roots and proofs derived from the previous record are no longer valid"
+                );
+                write_record().await?;
+                return Ok(new_computed_debt.clone());
+            }
+
+            if force {
+                if existing_computed_debt.debts != new_computed_debt.debts {
+                    ensure_not_strict(
+                        strict,
+                        "DZ Ledger record does not match the new computed solana validator debt",
+                    )?;
+                }
+
+                write_record().await?;
                 tracing::warn!(
                     "DZ Ledger record does not match the new computed solana validator debt and has been overwritten"
                 );
@@ -756,21 +1561,10 @@ async fn create_or_validate_ledger_record(
             );
             Ok(existing_computed_debt)
         }
-        Err(_err) => {
+        None => {
             // create record
             tracing::info!("Creating a new record on DZ ledger");
-            ledger::create_record_on_ledger(
-                solana_debt_calculator.ledger_rpc_client(),
-                recent_blockhash,
-                &transaction.signer,
-                &new_computed_debt,
-                solana_debt_calculator.ledger_commitment_config(),
-                &[
-                    ComputedSolanaValidatorDebts::RECORD_SEED_PREFIX,
-                    &dz_epoch.to_le_bytes(),
-                ],
-            )
-            .await?;
+            write_record().await?;
             bail!("new record created; shutting down until the next check")
         }
     }
@@ -856,3 +1650,898 @@ async fn try_initialize_missing_deposit_accounts(
 
     Ok(())
 }
+
+/// Deposit balance of each node in `computed_debt`. Deposits that do not
+/// exist have a zero balance.
+async fn try_fetch_deposit_balances(
+    connection: &SolanaConnection,
+    computed_debt: &ComputedSolanaValidatorDebts,
+) -> Result<HashMap<Pubkey, u64>> {
+    let rent_sysvar = connection
+        .try_fetch_sysvar::<solana_sdk::rent::Rent>()
+        .await?;
+
+    let deposit_balances = try_fetch_deposit_accounts(
+        connection,
+        computed_debt.debts.iter().map(|debt| debt.node_id),
+    )
+    .await?
+    .into_iter()
+    .map(|(node_id, deposit_account)| {
+        let deposit_balance =
+            doublezero_solana_client_tools::account::balance(&deposit_account, &rent_sysvar);
+        (node_id, deposit_balance)
+    })
+    .collect();
+
+    Ok(deposit_balances)
+}
+
+/// Fetches the deposit accounts for the given nodes in chunks. Accounts that
+/// do not exist yet come back with empty data.
+async fn try_fetch_deposit_accounts(
+    connection: &SolanaConnection,
+    node_ids: impl Iterator<Item = Pubkey>,
+) -> Result<HashMap<Pubkey, Account>> {
+    let mut node_ids = node_ids.collect::<Vec<_>>();
+    node_ids.sort_unstable();
+    node_ids.dedup();
+
+    let deposit_keys = node_ids
+        .iter()
+        .map(|node_id| SolanaValidatorDeposit::find_address(node_id).0)
+        .collect::<Vec<_>>();
+
+    let deposit_accounts = connection
+        .try_fetch_multiple_accounts(&deposit_keys)
+        .await?;
+    ensure!(
+        deposit_accounts.len() == node_ids.len(),
+        "Expected {} deposit accounts, got {}",
+        node_ids.len(),
+        deposit_accounts.len()
+    );
+
+    Ok(node_ids.into_iter().zip(deposit_accounts).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use doublezero_solana_sdk::revenue_distribution::types::DoubleZeroEpoch;
+    use solana_client::rpc_response::{
+        RpcInflationReward, RpcVoteAccountInfo, RpcVoteAccountStatus,
+    };
+    use solana_sdk::{
+        epoch_info::EpochInfo, hash::Hash, reward_type::RewardType::Fee, signature::Keypair,
+        transaction::VersionedTransaction,
+    };
+    use solana_transaction_status_client_types::UiConfirmedBlock;
+
+    use super::*;
+    use crate::{
+        jito::{JitoReward, JitoRewards},
+        solana_debt_calculator::MockValidatorRewards,
+    };
+
+    fn rpc_account_response(owner: &Pubkey, data: &[u8]) -> serde_json::Value {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "result": {
+                "context": { "slot": 1 },
+                "value": {
+                    "data": [base64::prelude::BASE64_STANDARD.encode(data), "base64"],
+                    "executable": false,
+                    "lamports": 1_000_000_000,
+                    "owner": owner.to_string(),
+                    "rentEpoch": 0,
+                    "space": data.len()
+                }
+            },
+            "id": 1
+        })
+    }
+
+    fn sent_transaction(body: &serde_json::Value) -> VersionedTransaction {
+        let tx_data = base64::prelude::BASE64_STANDARD
+            .decode(body["params"][0].as_str().unwrap())
+            .unwrap();
+        bincode::deserialize(&tx_data).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_fetch_deposit_accounts_in_chunks() {
+        use wiremock::{Mock, MockServer, Request, ResponseTemplate, matchers};
+
+        let mock_server = MockServer::start().await;
+
+        // None of the deposit accounts exist. One request per chunk of 100.
+        Mock::given(matchers::method("POST"))
+            .and(matchers::body_partial_json(serde_json::json!({
+                "method": "getMultipleAccounts"
+            })))
+            .respond_with(|request: &Request| {
+                let body = request.body_json::<serde_json::Value>().unwrap();
+                let key_count = body["params"][0].as_array().unwrap().len();
+
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "result": {
+                        "context": { "slot": 1 },
+                        "value": vec![serde_json::Value::Null; key_count]
+                    },
+                    "id": body["id"]
+                }))
+            })
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+
+        let connection = SolanaConnection::new(mock_server.uri());
+        let node_ids = (0..250).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+
+        // Duplicates do not add requests.
+        let deposit_accounts = try_fetch_deposit_accounts(
+            &connection,
+            node_ids.iter().chain(&node_ids[..50]).copied(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(deposit_accounts.len(), 250);
+        assert!(
+            deposit_accounts
+                .values()
+                .all(|account| account.data.is_empty())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_finalize_distribution_returns_sent_signature() {
+        use doublezero_solana_sdk::PrecomputedDiscriminator;
+        use wiremock::{Mock, MockServer, Request, ResponseTemplate, matchers};
+
+        let mock_server = MockServer::start().await;
+        let rpc_method =
+            |method: &str| matchers::body_partial_json(serde_json::json!({ "method": method }));
+
+        // The program config is not paused.
+        let (config_key, _) = ProgramConfig::find_address();
+        let config: ProgramConfig = bytemuck::Zeroable::zeroed();
+        let mut config_data = ProgramConfig::discriminator_slice().to_vec();
+        config_data.extend_from_slice(bytemuck::bytes_of(&config));
+        Mock::given(matchers::method("POST"))
+            .and(matchers::body_partial_json(serde_json::json!({
+                "method": "getAccountInfo",
+                "params": [config_key.to_string()]
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(rpc_account_response(&ID, &config_data)),
+            )
+            .with_priority(1)
+            .mount(&mock_server)
+            .await;
+
+        // Any other account is the debt record, which has no debts to
+        // verify.
+        let mut record_data = bytemuck::bytes_of(
+            &<doublezero_record::state::RecordData as bytemuck::Zeroable>::zeroed(),
+        )
+        .to_vec();
+        record_data.extend(borsh::to_vec(&ComputedSolanaValidatorDebts::default()).unwrap());
+        Mock::given(matchers::method("POST"))
+            .and(rpc_method("getAccountInfo"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(rpc_account_response(&Pubkey::default(), &record_data)),
+            )
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(matchers::method("POST"))
+            .and(rpc_method("getVersion"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "result": { "solana-core": "2.2.0", "feature-set": 0 },
+                "id": 1
+            })))
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(matchers::method("POST"))
+            .and(rpc_method("getLatestBlockhash"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "result": {
+                    "context": { "slot": 1 },
+                    "value": {
+                        "blockhash": Hash::new_unique().to_string(),
+                        "lastValidBlockHeight": 100
+                    }
+                },
+                "id": 1
+            })))
+            .mount(&mock_server)
+            .await;
+
+        // Echo the signature of the transaction that was sent.
+        Mock::given(matchers::method("POST"))
+            .and(rpc_method("sendTransaction"))
+            .respond_with(|request: &Request| {
+                let body = request.body_json::<serde_json::Value>().unwrap();
+
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "result": sent_transaction(&body).signatures[0].to_string(),
+                    "id": body["id"]
+                }))
+            })
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(matchers::method("POST"))
+            .and(rpc_method("getSignatureStatuses"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "result": {
+                    "context": { "slot": 1 },
+                    "value": [{
+                        "slot": 1,
+                        "confirmations": null,
+                        "err": null,
+                        "status": { "Ok": null },
+                        "confirmationStatus": "finalized"
+                    }]
+                },
+                "id": 1
+            })))
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(matchers::method("POST"))
+            .and(matchers::path("/slack"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut mock_solana_debt_calculator = MockValidatorRewards::new();
+        mock_solana_debt_calculator
+            .expect_solana_rpc_client()
+            .return_const(RpcClient::new(mock_server.uri()));
+        mock_solana_debt_calculator
+            .expect_ledger_rpc_client()
+            .return_const(DoubleZeroLedgerConnection::new(mock_server.uri()));
+
+        let transaction = Transaction::new(Arc::new(Keypair::new()), false, false);
+        let slack_webhook = format!("{}/slack", mock_server.uri());
+
+        let finalize_summary = finalize_distribution(
+            &mock_solana_debt_calculator,
+            transaction,
+            42,
+            SlackTarget {
+                webhook_override: Some(&slack_webhook),
+                max_attempts: Some(1),
+            },
+        )
+        .await
+        .unwrap();
+
+        // The summary reports the signature of the transaction that was
+        // actually sent.
+        let sent_signature = mock_server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter_map(|request| request.body_json::<serde_json::Value>().ok())
+            .find(|body| body["method"] == "sendTransaction")
+            .map(|body| sent_transaction(&body).signatures[0])
+            .unwrap();
+        assert_eq!(finalize_summary.dz_epoch, 42);
+        assert!(!finalize_summary.dry_run);
+        assert_eq!(
+            finalize_summary.transaction_signature,
+            Some(sent_signature.to_string())
+        );
+        assert!(finalize_summary.posted_to_slack);
+    }
+
+    #[test]
+    fn test_finalized_signature_is_none_for_dry_run() {
+        let transaction = Transaction::new(Arc::new(Keypair::new()), true, false);
+
+        assert_eq!(
+            finalized_signature(&transaction, Some("Program log: simulated")),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_estimate_debts_skips_rpc_writes() {
+        let validator_id = "6WgdYhhGE53WrZ7ywJA15hBVkw7CRbQ8yDBBTwmBtAHN";
+        let validator_ids = vec![validator_id.to_string()];
+        let dz_epoch = 42;
+        let solana_epoch = 823;
+        let fixed_sol_amount = 5_000;
+
+        // Only read-only expectations are set. The mock panics if the
+        // estimate reaches for the Solana or DZ Ledger RPC clients, which
+        // is the only way to build a ledger record or send a transaction.
+        let mut mock_solana_debt_calculator = MockValidatorRewards::new();
+
+        let mock_rpc_vote_account_status = RpcVoteAccountStatus {
+            current: vec![RpcVoteAccountInfo {
+                vote_pubkey: "6WgdYhhGE53WrZ7ywJA15hBVkw7CRbQ8yDBBTwmBtABB".to_string(),
+                node_pubkey: validator_id.to_string(),
+                activated_stake: 4_200_000_000_000,
+                epoch_vote_account: true,
+                epoch_credits: vec![(812, 256, 128), (811, 128, 64)],
+                commission: 10,
+                last_vote: 123456789,
+                root_slot: 123456700,
+            }],
+            delinquent: vec![],
+        };
+        mock_solana_debt_calculator
+            .expect_get_vote_accounts_with_config()
+            .times(1)
+            .returning(move || Ok(mock_rpc_vote_account_status.clone()));
+
+        let mock_rpc_inflation_reward = vec![Some(RpcInflationReward {
+            epoch: solana_epoch,
+            effective_slot: 123456789,
+            amount: 5_500,
+            post_balance: 1_500_002_500,
+            commission: Some(1),
+        })];
+        mock_solana_debt_calculator
+            .expect_get_inflation_reward()
+            .times(1)
+            .returning(move |_, _| Ok(mock_rpc_inflation_reward.clone()));
+
+        let mut leader_schedule = HashMap::new();
+        leader_schedule.insert(validator_id.to_string(), vec![10]);
+        mock_solana_debt_calculator
+            .expect_get_leader_schedule()
+            .times(1)
+            .returning(move |_| Ok(leader_schedule.clone()));
+
+        let mock_block = UiConfirmedBlock {
+            num_reward_partitions: Some(1),
+            signatures: Some(vec!["One".to_string()]),
+            rewards: Some(vec![solana_transaction_status_client_types::Reward {
+                pubkey: validator_id.to_string(),
+                lamports: 40_000,
+                post_balance: 40_000,
+                reward_type: Some(Fee),
+                commission: None,
+            }]),
+            previous_blockhash: "".to_string(),
+            blockhash: "".to_string(),
+            parent_slot: 0,
+            transactions: None,
+            block_time: None,
+            block_height: None,
+        };
+        mock_solana_debt_calculator
+            .expect_get_block_with_config()
+            .times(1)
+            .returning(move |_| Ok(mock_block.clone()));
+
+        let mock_epoch_info = EpochInfo {
+            epoch: 824,
+            slot_index: 100000,
+            absolute_slot: 10000000,
+            block_height: 103030003,
+            slots_in_epoch: 5000000,
+            transaction_count: Some(1000),
+        };
+        mock_solana_debt_calculator
+            .expect_get_epoch_info()
+            .times(1)
+            .returning(move || Ok(mock_epoch_info.clone()));
+
+        mock_solana_debt_calculator
+            .expect_get::<JitoRewards>()
+            .times(1)
+            .returning(move |_| {
+                Ok(JitoRewards {
+                    total_count: 1000,
+                    rewards: vec![JitoReward {
+                        vote_account: validator_id.to_string(),
+                        mev_revenue: 10_000,
+                    }],
+                })
+            });
+
+        // All reward percentages are zero, so only the fixed fee is owed.
+        let mut distribution: Distribution = bytemuck::Zeroable::zeroed();
+        distribution
+            .solana_validator_fee_parameters
+            .fixed_sol_amount = fixed_sol_amount;
+
+        let summary = estimate_debts(
+            &mock_solana_debt_calculator,
+            &distribution,
+            &validator_ids,
+            dz_epoch,
+            solana_epoch,
+        )
+        .await
+        .unwrap();
+
+        assert!(summary.dry_run);
+        assert_eq!(summary.transaction_id, None);
+        assert_eq!(summary.dz_epoch, dz_epoch);
+        assert_eq!(summary.solana_epoch, solana_epoch);
+        assert_eq!(summary.total_validators, 1);
+        assert_eq!(summary.total_debt, fixed_sol_amount as u64);
+        assert_eq!(
+            summary.validator_summaries[0].validator_pubkey,
+            validator_id
+        );
+    }
+
+    #[test]
+    fn test_collection_summary() {
+        let mut distribution: Distribution = bytemuck::Zeroable::zeroed();
+        distribution.dz_epoch = DoubleZeroEpoch::new(42);
+        distribution.total_solana_validator_debt = 10_000;
+        distribution.collected_solana_validator_payments = 7_500;
+        distribution.uncollectible_sol_debt = 500;
+        distribution.total_solana_validators = 8;
+        distribution.solana_validator_payments_count = 6;
+
+        assert_eq!(
+            CollectionSummary::from(&distribution),
+            CollectionSummary {
+                dz_epoch: 42,
+                total_debt: 10_000,
+                collected: 7_500,
+                remaining_debt: 2_500,
+                uncollectible: 500,
+                processed_count: 6,
+                remaining_count: 2,
+            }
+        );
+
+        // Nothing is owed before debt is posted.
+        let distribution: Distribution = bytemuck::Zeroable::zeroed();
+        assert_eq!(
+            CollectionSummary::from(&distribution),
+            CollectionSummary::default()
+        );
+    }
+
+    fn debts_for_test() -> ComputedSolanaValidatorDebts {
+        ComputedSolanaValidatorDebts {
+            blockhash: Hash::new_unique(),
+            first_solana_epoch: 822,
+            last_solana_epoch: 822,
+            debts: (1..=5)
+                .map(|i| ComputedSolanaValidatorDebt {
+                    node_id: Pubkey::new_unique(),
+                    amount: i * 1_000_000,
+                })
+                .collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_debt_leaves_flags_tampered_leaf() {
+        let computed_debt = debts_for_test();
+        let root = computed_debt.merkle_root().unwrap();
+
+        // Local stand-in for the on-chain verification.
+        let verify = |proof: MerkleProof, leaf: SolanaValidatorDebt| async move {
+            let leaf = ComputedSolanaValidatorDebt {
+                node_id: leaf.node_id,
+                amount: leaf.amount,
+            };
+            ensure!(leaf.merkle_root(proof) == root, "root mismatch");
+            Ok(())
+        };
+
+        let verifications = verify_debt_leaves(&computed_debt, &computed_debt.debts, verify).await;
+        assert_eq!(verifications.len(), computed_debt.debts.len());
+        assert!(
+            verifications
+                .iter()
+                .all(|v| v.verified && v.error.is_empty())
+        );
+
+        let mut tampered_leaves = computed_debt.debts.clone();
+        tampered_leaves[2].amount += 1;
+
+        let verifications = verify_debt_leaves(&computed_debt, &tampered_leaves, verify).await;
+        let failed: Vec<_> = verifications.iter().filter(|v| !v.verified).collect();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].node_id, tampered_leaves[2].node_id.to_string());
+        assert_eq!(failed[0].amount, tampered_leaves[2].amount);
+        assert_eq!(failed[0].error, "root mismatch");
+    }
+
+    #[tokio::test]
+    async fn test_verify_debt_leaves_fails_unknown_node() {
+        let computed_debt = debts_for_test();
+        let unknown_leaf = ComputedSolanaValidatorDebt {
+            node_id: Pubkey::new_unique(),
+            amount: 1,
+        };
+
+        let verifications = verify_debt_leaves(&computed_debt, &[unknown_leaf], |_, _| async {
+            bail!("verify should not run without a proof")
+        })
+        .await;
+
+        assert_eq!(verifications.len(), 1);
+        assert!(!verifications[0].verified);
+        assert!(verifications[0].error.contains("No debt proof found"));
+    }
+
+    #[test]
+    fn test_build_debt_proof_export() {
+        let computed_debt = debts_for_test();
+        let root = computed_debt.merkle_root().unwrap();
+        let debt = computed_debt.debts[3];
+
+        let export = build_debt_proof_export(&computed_debt, 42, &debt.node_id, root).unwrap();
+        assert_eq!(export.dz_epoch, 42);
+        assert_eq!(export.node_id, debt.node_id.to_string());
+        assert_eq!(export.amount, debt.amount);
+        assert_eq!(export.leaf_index, 3);
+        assert_eq!(export.merkle_root, root.to_string());
+        assert!(export.verified);
+
+        // The exported leaf and proof alone reproduce the root.
+        let encoding = base64::engine::general_purpose::STANDARD;
+        let leaf = encoding.decode(&export.leaf).unwrap();
+        let proof: MerkleProof =
+            borsh::from_slice(&encoding.decode(&export.proof).unwrap()).unwrap();
+        assert_eq!(
+            borsh::from_slice::<ComputedSolanaValidatorDebt>(&leaf).unwrap(),
+            debt
+        );
+        assert_eq!(
+            proof.root_from_leaf(leaf.as_slice(), Some(export.leaf_prefix.as_bytes())),
+            root
+        );
+
+        // A root other than the record's does not verify.
+        let other_root = debts_for_test().merkle_root().unwrap();
+        let export =
+            build_debt_proof_export(&computed_debt, 42, &debt.node_id, other_root).unwrap();
+        assert!(!export.verified);
+
+        assert!(build_debt_proof_export(&computed_debt, 42, &Pubkey::new_unique(), root).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_debt_collection_details_skipped_when_disabled() {
+        let posted = std::sync::Mutex::new(Vec::new());
+        let options = SlackReportOptions {
+            slack_summary: true,
+            slack_details: false,
+            ..Default::default()
+        };
+
+        post_debt_collection_reports(
+            &options,
+            || async {
+                posted.lock().unwrap().push("summary");
+                Ok(())
+            },
+            || async {
+                posted.lock().unwrap().push("details");
+                Ok(())
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(*posted.lock().unwrap(), vec!["summary"]);
+    }
+
+    #[tokio::test]
+    async fn test_debt_collection_reports_default_posts_both() {
+        let posted = std::sync::Mutex::new(Vec::new());
+
+        post_debt_collection_reports(
+            &SlackReportOptions::default(),
+            || async {
+                posted.lock().unwrap().push("summary");
+                Ok(())
+            },
+            || async {
+                posted.lock().unwrap().push("details");
+                Ok(())
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(*posted.lock().unwrap(), vec!["summary", "details"]);
+    }
+
+    #[tokio::test]
+    async fn test_overlapping_epoch_with_force_zeroes_and_finalizes() {
+        let calls = std::sync::Mutex::new(Vec::new());
+
+        let outcome = handle_overlapping_epoch(
+            true,
+            false,
+            42,
+            823,
+            || async {
+                calls.lock().unwrap().push("zero_out_debt");
+                Ok(())
+            },
+            || async {
+                calls.lock().unwrap().push("finalize");
+                Ok(())
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(outcome, OverlappingEpochOutcome::ZeroedAndFinalized);
+        assert_eq!(*calls.lock().unwrap(), vec!["zero_out_debt", "finalize"]);
+    }
+
+    #[tokio::test]
+    async fn test_overlapping_epoch_without_force_requires_force() {
+        let calls = std::sync::Mutex::new(Vec::new());
+
+        let outcome = handle_overlapping_epoch(
+            false,
+            false,
+            42,
+            823,
+            || async {
+                calls.lock().unwrap().push("zero_out_debt");
+                Ok(())
+            },
+            || async {
+                calls.lock().unwrap().push("finalize");
+                Ok(())
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(outcome, OverlappingEpochOutcome::RequiresForce);
+        assert_eq!(*calls.lock().unwrap(), vec!["zero_out_debt"]);
+    }
+
+    #[tokio::test]
+    async fn test_overlapping_epoch_strict_fails() {
+        let run = |strict| async move {
+            let calls = std::sync::Mutex::new(Vec::new());
+            let outcome = handle_overlapping_epoch(
+                true,
+                strict,
+                42,
+                823,
+                || async {
+                    calls.lock().unwrap().push("zero_out_debt");
+                    Ok(())
+                },
+                || async {
+                    calls.lock().unwrap().push("finalize");
+                    Ok(())
+                },
+            )
+            .await;
+            (outcome, calls.into_inner().unwrap())
+        };
+
+        // Normal mode zeroes out the debt and continues.
+        let (outcome, calls) = run(false).await;
+        assert_eq!(
+            outcome.unwrap(),
+            OverlappingEpochOutcome::ZeroedAndFinalized
+        );
+        assert_eq!(calls, vec!["zero_out_debt", "finalize"]);
+
+        // Strict mode fails before writing anything.
+        let (outcome, calls) = run(true).await;
+        let err = outcome.unwrap_err().to_string();
+        assert!(err.contains("overlaps already charged Solana epoch 823"));
+        assert!(err.contains("--strict"));
+        assert!(calls.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_strict_does_not_overwrite_mismatched_record() {
+        let existing_computed_debt = debts_for_test();
+        let new_computed_debt = ComputedSolanaValidatorDebts {
+            blockhash: Hash::new_unique(),
+            debts: vec![ComputedSolanaValidatorDebt {
+                node_id: Pubkey::new_unique(),
+                amount: 1,
+            }],
+            ..existing_computed_debt.clone()
+        };
+        let writes = AtomicUsize::new(0);
+
+        let err = reconcile_ledger_record(
+            Some(existing_computed_debt.clone()),
+            &new_computed_debt,
+            true,
+            true,
+            false,
+            || async {
+                writes.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            },
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("--strict"));
+        assert_eq!(writes.load(Ordering::Relaxed), 0);
+
+        // Without strict, force overwrites the record.
+        reconcile_ledger_record(
+            Some(existing_computed_debt),
+            &new_computed_debt,
+            true,
+            false,
+            false,
+            || async {
+                writes.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(writes.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reprocess_overwrites_matching_record() {
+        let existing_computed_debt = debts_for_test();
+        let new_computed_debt = ComputedSolanaValidatorDebts {
+            blockhash: Hash::new_unique(),
+            ..existing_computed_debt.clone()
+        };
+        let writes = AtomicUsize::new(0);
+
+        // Without reprocess a matching record is reused as is
+        let record = reconcile_ledger_record(
+            Some(existing_computed_debt.clone()),
+            &new_computed_debt,
+            false,
+            false,
+            false,
+            || async {
+                writes.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(record.blockhash, existing_computed_debt.blockhash);
+        assert_eq!(writes.load(Ordering::Relaxed), 0);
+
+        let record = reconcile_ledger_record(
+            Some(existing_computed_debt),
+            &new_computed_debt,
+            false,
+            false,
+            true,
+            || async {
+                writes.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(record.blockhash, new_computed_debt.blockhash);
+        assert_eq!(record.debts, new_computed_debt.debts);
+        assert_eq!(writes.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reprocess_overwrites_mismatched_record() {
+        let existing_computed_debt = debts_for_test();
+        let new_computed_debt = ComputedSolanaValidatorDebts {
+            blockhash: Hash::new_unique(),
+            debts: vec![ComputedSolanaValidatorDebt {
+                node_id: Pubkey::new_unique(),
+                amount: 1,
+            }],
+            ..existing_computed_debt.clone()
+        };
+        let writes = AtomicUsize::new(0);
+
+        // Without force or reprocess a mismatched record is an error
+        let err = reconcile_ledger_record(
+            Some(existing_computed_debt.clone()),
+            &new_computed_debt,
+            false,
+            false,
+            false,
+            || async {
+                writes.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            },
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("does not match"));
+        assert_eq!(writes.load(Ordering::Relaxed), 0);
+
+        // Reprocess overwrites it without force, even with strict
+        let record = reconcile_ledger_record(
+            Some(existing_computed_debt),
+            &new_computed_debt,
+            false,
+            true,
+            true,
+            || async {
+                writes.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(record.debts, new_computed_debt.debts);
+        assert_eq!(writes.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_check_distribution_age() {
+        let allowed = 1_700_000_000;
+
+        // Within the threshold, or no threshold at all
+        check_distribution_age(42, allowed, allowed + 3_600, Some(3_600)).unwrap();
+        check_distribution_age(42, allowed, allowed + 1_000_000, None).unwrap();
+
+        let err = check_distribution_age(42, allowed, allowed + 3_601, Some(3_600)).unwrap_err();
+        assert!(err.to_string().contains("3601s ago"));
+    }
+
+    #[test]
+    fn test_debt_reconciliation() {
+        let reconciliation = |s3_validators, nonzero_debts, zero_debts| DebtReconciliation {
+            s3_validators,
+            nonzero_debts,
+            zero_debts,
+        };
+
+        // Every S3 validator has a debt, zero or not.
+        let exact = reconciliation(10, 7, 3);
+        assert!(exact.is_reconciled());
+        assert_eq!(exact.dropped_validators(), 0);
+
+        // Validators dropped between the S3 fetch and reward computation.
+        let dropped = reconciliation(10, 7, 1);
+        assert!(dropped.is_reconciled());
+        assert_eq!(dropped.dropped_validators(), 2);
+
+        // More debts than S3 validators.
+        let extra = reconciliation(10, 8, 3);
+        assert!(!extra.is_reconciled());
+        assert_eq!(extra.dropped_validators(), 0);
+
+        assert!(reconciliation(0, 0, 0).is_reconciled());
+
+        // Strict mode fails on dropped or extra validators.
+        exact.report(823, true).unwrap();
+        dropped.report(823, false).unwrap();
+        assert!(dropped.report(823, true).is_err());
+        assert!(extra.report(823, true).is_err());
+    }
+
+    #[test]
+    fn test_select_solana_epoch_prefers_last_overlapping_epoch() {
+        assert_eq!(select_solana_epoch(&(823..=823), 42), 823);
+        assert_eq!(select_solana_epoch(&(822..=823), 42), 823);
+    }
+}
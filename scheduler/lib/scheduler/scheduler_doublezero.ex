@@ -14,6 +14,8 @@ defmodule Scheduler.DoubleZero do
 
   def initialize_distribution(_solana_rpc), do: :erlang.nif_error(:nif_not_loaded)
 
+  def epochs_behind(_ledger_rpc, _solana_rpc), do: :erlang.nif_error(:nif_not_loaded)
+
   def calculate_distribution(_solana_rpc, _post_to_slack),
     do: :erlang.nif_error(:nif_not_loaded)
 
@@ -23,10 +23,25 @@ defmodule Scheduler.Worker.InitializeDistribution do
 
       {} ->
         Logger.info("initialize_distribution: completed")
+        check_epochs_behind()
         {:stop, :normal, state}
     end
   end
 
+  # initialize_distribution does nothing while the program lags the ledger
+  defp check_epochs_behind do
+    case Scheduler.DoubleZero.epochs_behind(ledger_rpc(), solana_rpc()) do
+      {:error, error} ->
+        Logger.error("epochs_behind: received error: #{inspect(error)}")
+
+      0 ->
+        :ok
+
+      epochs_behind ->
+        Logger.warning("initialize_distribution: program is #{epochs_behind} epochs behind the ledger")
+    end
+  end
+
   def handle_info(msg, state) do
     Logger.warning("Received unexpected msg: #{msg}")
     {:noreply, state}
@@ -35,4 +50,8 @@ defmodule Scheduler.Worker.InitializeDistribution do
   defp solana_rpc do
     Application.get_env(:scheduler, :solana_rpc)
   end
+
+  defp ledger_rpc do
+    Application.get_env(:scheduler, :ledger_rpc)
+  end
 end
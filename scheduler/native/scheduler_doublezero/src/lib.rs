@@ -3,16 +3,18 @@ use std::sync::Arc;
 use anyhow::Result;
 use doublezero_solana_client_tools::{
     payer::{SolanaPayerOptions, SolanaSignerOptions, Wallet, try_load_keypair},
-    rpc::{DoubleZeroLedgerConnection, SolanaConnectionOptions},
+    rpc::{DoubleZeroLedgerConnection, SolanaConnection, SolanaConnectionOptions},
 };
 use doublezero_solana_sdk::{NetworkEnvironment, revenue_distribution::fetch::try_fetch_config};
 use doublezero_solana_validator_debt::{
     rpc::SolanaValidatorDebtConnectionOptions,
+    s3_fetcher,
     solana_debt_calculator::SolanaDebtCalculator,
     transaction::{DebtCollectionResults, Transaction},
     worker,
 };
 use rustler::{Error as NifError, NifStruct};
+use slack_notifier::validator_debt::SlackTarget;
 use tokio::runtime::Runtime;
 use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -71,11 +73,22 @@ pub fn collect_epoch_debt(
             let dz_connection = get_dz_ledger(&wallet, None).await?;
             let (_, config) = try_fetch_config(&wallet.connection).await?;
 
-            let tx_results =
-                worker::pay_solana_validator_debt(&wallet, &dz_connection, dz_epoch, &config)
-                    .await?;
+            let tx_results = worker::pay_solana_validator_debt(
+                &wallet,
+                &dz_connection,
+                dz_epoch,
+                &config,
+                worker::PayDebtOptions::default(),
+            )
+            .await?;
 
-            worker::post_debt_collection_to_slack(tx_results.clone(), false, None).await?;
+            worker::post_debt_collection_to_slack(
+                tx_results.clone(),
+                false,
+                None,
+                SlackTarget::default(),
+            )
+            .await?;
 
             Ok::<DebtCollectionResults, anyhow::Error>(tx_results)
         })
@@ -108,6 +121,7 @@ pub fn initialize_distribution(solana_rpc_url: String) -> Result<(), NifError> {
                 None,    // dz_env
                 false,   // bypass_dz_epoch_check
                 None,    // record_accountant_key
+                worker::DEFAULT_WRITE_OFF_SETTLED_EPOCHS,
             )
             .await
         })
@@ -116,6 +130,21 @@ pub fn initialize_distribution(solana_rpc_url: String) -> Result<(), NifError> {
     Ok(())
 }
 
+/// Number of DZ epochs the Revenue Distribution program lags the DoubleZero
+/// Ledger. `initialize_distribution` does nothing while this is nonzero.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn epochs_behind(ledger_rpc_url: String, solana_rpc_url: String) -> Result<u64, NifError> {
+    Runtime::new()
+        .map_err(display_to_nif_error)?
+        .block_on(async {
+            let solana_connection = SolanaConnection::new(solana_rpc_url);
+            let dz_connection = DoubleZeroLedgerConnection::new(ledger_rpc_url);
+
+            worker::try_fetch_epochs_behind(&solana_connection, &dz_connection).await
+        })
+        .map_err(display_to_nif_error)
+}
+
 #[rustler::nif(schedule = "DirtyIo")]
 pub fn collect_all_debt(solana_rpc_url: String) -> Result<(), NifError> {
     Runtime::new()
@@ -128,7 +157,13 @@ pub fn collect_all_debt(solana_rpc_url: String) -> Result<(), NifError> {
 
             let dz_connection = get_dz_ledger(&wallet, None).await?;
 
-            worker::pay_all_solana_validator_debt(wallet, dz_connection).await
+            worker::pay_all_solana_validator_debt(
+                wallet,
+                dz_connection,
+                worker::SlackReportOptions::default(),
+                worker::PayDebtOptions::default(),
+            )
+            .await
         })
         .map_err(display_to_nif_error)?;
     Ok(())
@@ -155,12 +190,26 @@ pub fn calculate_distribution(solana_rpc_url: String, post_to_slack: bool) -> Re
             let keypair = try_load_keypair(None)?;
             let arc_keypair = Arc::new(keypair);
             let transaction = Transaction::new(arc_keypair, false, false);
-
-            let write_summary =
-                worker::calculate_distribution(&solana_debt_calculator, transaction, false).await?;
+            let network: s3_fetcher::Network = wallet
+                .connection
+                .try_network_environment()
+                .await?
+                .try_into()?;
+
+            let write_summary = worker::calculate_distribution(
+                &solana_debt_calculator,
+                transaction,
+                false, // post_to_ledger_only
+                false, // reprocess
+                None,  // max_distribution_age_secs
+                s3_fetcher::DEFAULT_MIN_HOURLY_APPEARANCES,
+                network,
+            )
+            .await?;
             if post_to_slack {
                 slack_notifier::validator_debt::post_distribution_to_slack(
                     None,
+                    SlackTarget::default(),
                     write_summary.solana_epoch,
                     write_summary.dz_epoch,
                     false,
@@ -201,7 +250,13 @@ pub fn finalize_distribution(dz_epoch: u64, solana_rpc_url: String) -> Result<()
             let arc_keypair = Arc::new(keypair);
             let transaction = Transaction::new(arc_keypair, false, false);
 
-            worker::finalize_distribution(&solana_debt_calculator, transaction, dz_epoch).await?;
+            worker::finalize_distribution(
+                &solana_debt_calculator,
+                transaction,
+                dz_epoch,
+                SlackTarget::default(),
+            )
+            .await?;
 
             Ok::<(), anyhow::Error>(())
         })